@@ -0,0 +1,34 @@
+//! Rough comparison of `Schedule::weekly_same_day` lookups against a daily rule with the same
+//! occurrences; run with `cargo bench --bench weekly_same_day`.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chrono::Datelike;
+use recurring_dates_rs::Schedule;
+
+const ITERATIONS: u64 = 1_000_000;
+
+fn timed(schedule: &Schedule) -> Duration {
+    let start_date = schedule.start_date();
+    let begin = Instant::now();
+    let mut date = start_date;
+
+    for _ in 0..ITERATIONS {
+        date = std::hint::black_box(schedule.next_after(std::hint::black_box(date)).unwrap());
+
+        if date.year() > 9000 {
+            date = start_date;
+        }
+    }
+
+    begin.elapsed()
+}
+
+fn main() {
+    let start_date = chrono::NaiveDate::from_str("2023-09-21").unwrap();
+    let same_day = Schedule::weekly_same_day(start_date, 2).unwrap();
+    let daily = Schedule::every_n_days(start_date, 14).unwrap();
+
+    println!("weekly same day: {:?}, every 14 days: {:?} for {} lookups", timed(&same_day), timed(&daily), ITERATIONS);
+}
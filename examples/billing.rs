@@ -0,0 +1,76 @@
+use core::str::FromStr;
+
+use recurring_dates_rs::{MissingDay, MonthDay, Repeating, Schedule};
+
+#[derive(Debug, PartialEq)]
+struct Invoice {
+    number: u32,
+    due: chrono::NaiveDate,
+    amount_cents: u64,
+}
+
+fn expand_invoices(schedule: &Schedule, amount_cents: u64) -> Vec<Invoice> {
+    // The schedule ends at its `until` date, so the iterator does too.
+    schedule.occurrences(chrono::NaiveDate::MIN)
+        .zip(1..)
+        .map(|(due, number)| Invoice { number, due, amount_cents })
+        .collect()
+}
+
+fn dues(invoices: &[Invoice]) -> Vec<chrono::NaiveDate> {
+    invoices.iter().map(|invoice| invoice.due).collect()
+}
+
+fn date(value: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_str(value).unwrap()
+}
+
+pub fn main() {
+    let start_date = date("2024-01-31");
+    let until = date("2025-02-28");
+
+    // Billed at the end of every month: months without a 31st bill on their last day.
+    let month_end = Schedule::monthly_on_day(start_date, MonthDay::new(31).unwrap(), MissingDay::Clamp).unwrap();
+    let billing = Schedule::builder().start(start_date).monthly_by(month_end.monthly_by().unwrap().clone());
+    let contract = billing.clone().monthly().until(until).build().unwrap();
+    let invoices = expand_invoices(&contract, 4_990);
+
+    for invoice in &invoices {
+        println!("invoice #{} due {} for {}.{:02}", invoice.number, invoice.due, invoice.amount_cents / 100, invoice.amount_cents % 100);
+    }
+
+    let expected_dues = [
+        "2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30", "2024-05-31", "2024-06-30", "2024-07-31",
+        "2024-08-31", "2024-09-30", "2024-10-31", "2024-11-30", "2024-12-31", "2025-01-31", "2025-02-28",
+    ];
+    assert_eq!(expected_dues.map(date).to_vec(), dues(&invoices));
+    assert_eq!(Invoice { number: 2, due: date("2024-02-29"), amount_cents: 4_990 }, invoices[1]);
+    assert_eq!(Invoice { number: 4, due: date("2024-04-30"), amount_cents: 4_990 }, invoices[3]);
+
+    // The open ended plan and the contract agree up to the contract's end.
+    assert_eq!(dues(&invoices), month_end.occurrences_between(start_date, until).into_vec());
+
+    // Every invoice must be recognized as part of the billing cycle, and nothing else.
+    assert!(invoices.iter().all(|invoice| contract.matches(invoice.due)));
+    assert!(!contract.matches(date("2024-02-28")));
+    assert!(!contract.matches(date("2025-03-31")));
+
+    let total: u64 = invoices.iter().map(|invoice| invoice.amount_cents).sum();
+    assert_eq!(14 * 4_990, total);
+
+    // A waived month is excluded; the later invoices keep their due dates.
+    let waived = billing.clone().monthly().until(date("2024-05-31")).exclude(date("2024-02-29")).build().unwrap();
+    assert_eq!(["2024-01-31", "2024-03-31", "2024-04-30", "2024-05-31"].map(date).to_vec(), dues(&expand_invoices(&waived, 4_990)));
+
+    // Quarterly billing bills at the end of every third month.
+    let quarterly = billing.quarterly().until(until).build().unwrap();
+    assert_eq!(["2024-01-31", "2024-04-30", "2024-07-31", "2024-10-31", "2025-01-31"].map(date).to_vec(), dues(&expand_invoices(&quarterly, 14_970)));
+    assert_eq!(Repeating::Quarterly, quarterly.repeat());
+
+    // Billing plans are stored as JSON.
+    let json = serde_json::to_string(&waived).unwrap();
+    println!("stored plan: {}", json);
+
+    assert!(json.contains(r#""until":"2024-05-31""#), "{}", json);
+    assert_eq!(waived, serde_json::from_str::<Schedule>(&json).unwrap());
+}
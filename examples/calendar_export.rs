@@ -0,0 +1,44 @@
+use core::str::FromStr;
+
+use recurring_dates_rs::{parse_ics_events, RruleError, Schedule, WeekdayFlags};
+
+fn date(value: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_str(value).unwrap()
+}
+
+pub fn main() {
+    let events = [
+        ("standup@example.com", "Standup", Schedule::builder().start(date("2023-09-18")).daily().on(WeekdayFlags::MIDWEEK).build().unwrap()),
+        ("one-on-one@example.com", "One on one", Schedule::every_n_weeks_on(date("2023-09-12"), 2, WeekdayFlags::TUE | WeekdayFlags::THU).unwrap()),
+        ("rent@example.com", "Rent", Schedule::builder().start(date("2023-09-05")).monthly().build().unwrap()),
+        ("review@example.com", "Quarterly review", Schedule::builder().start(date("2023-10-02")).quarterly().count(4).build().unwrap()),
+    ];
+    let stamp = date("2023-09-01").and_hms_opt(12, 0, 0).unwrap();
+
+    for (uid, summary, schedule) in &events {
+        let ics = schedule.to_ics_event(uid, summary, stamp).unwrap();
+        print!("{}", ics);
+
+        // Reading the file back must describe the same dates the crate computes.
+        let parsed = parse_ics_events(&ics);
+        assert_eq!(1, parsed.len());
+
+        let event = parsed[0].as_ref().unwrap();
+        assert_eq!(Some(*uid), event.uid.as_deref());
+        assert_eq!(Some(*summary), event.summary.as_deref());
+        assert!(schedule.occurrences(chrono::NaiveDate::MIN).take(50).eq(event.schedule.occurrences(chrono::NaiveDate::MIN).take(50)));
+    }
+
+    assert_eq!(Ok("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR".to_string()), events[0].2.to_rrule());
+    assert_eq!(Ok("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH".to_string()), events[1].2.to_rrule());
+    assert_eq!(Ok("FREQ=MONTHLY".to_string()), events[2].2.to_rrule());
+    // RFC 5545 has no quarterly frequency.
+    assert_eq!(Ok("FREQ=MONTHLY;INTERVAL=3;COUNT=4".to_string()), events[3].2.to_rrule());
+
+    let one_on_one: Vec<chrono::NaiveDate> = events[1].2.occurrences(chrono::NaiveDate::MIN).take(5).collect();
+    assert_eq!(["2023-09-12", "2023-09-14", "2023-09-26", "2023-09-28", "2023-10-10"].map(date).to_vec(), one_on_one);
+
+    // A skipped occurrence belongs in an EXDATE next to the RRULE, which this export does not write.
+    let rent_with_holiday = Schedule::builder().start(date("2023-09-05")).monthly().exclude(date("2023-12-05")).build().unwrap();
+    assert_eq!(Err(RruleError::NotExpressible { feature: "exclusions" }), rent_with_holiday.to_ics_event("rent@example.com", "Rent", stamp));
+}
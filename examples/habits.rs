@@ -0,0 +1,79 @@
+use core::str::FromStr;
+
+use recurring_dates_rs::{Schedule, WeekdayFlags};
+
+struct Habit {
+    schedule: Schedule,
+    completions: Vec<chrono::NaiveDate>,
+}
+
+impl Habit {
+    fn overdue(&self, today: chrono::NaiveDate) -> Vec<chrono::NaiveDate> {
+        let mut overdue: Vec<chrono::NaiveDate> = self.schedule.occurrences_before(today)
+            .filter(|date| !self.completions.contains(date))
+            .collect();
+        overdue.reverse();
+        overdue
+    }
+
+    fn streak(&self, today: chrono::NaiveDate) -> usize {
+        self.schedule.occurrences_before(today)
+            .take_while(|date| self.completions.contains(date))
+            .count()
+    }
+
+    fn is_due(&self, today: chrono::NaiveDate) -> bool {
+        self.schedule.matches(today) && !self.completions.contains(&today)
+    }
+}
+
+fn date(value: &str) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_str(value).unwrap()
+}
+
+fn dates(values: &[&str]) -> Vec<chrono::NaiveDate> {
+    values.iter().map(|value| date(value)).collect()
+}
+
+pub fn main() {
+    let weekdays = WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI;
+    let habit = Habit {
+        schedule: Schedule::weekly_on(date("2023-10-02"), weekdays).unwrap(),
+        completions: dates(&["2023-10-02", "2023-10-04", "2023-10-06", "2023-10-09", "2023-10-13", "2023-10-16", "2023-10-18"]),
+    };
+
+    let today = date("2023-10-20");
+
+    let overdue = habit.overdue(today);
+    let streak = habit.streak(today);
+
+    println!("overdue: {:?}", overdue);
+    println!("current streak: {}", streak);
+    println!("due today: {}", habit.is_due(today));
+
+    assert_eq!(dates(&["2023-10-11"]), overdue);
+    assert_eq!(3, streak);
+    assert!(habit.is_due(today));
+    assert!(!habit.is_due(date("2023-10-21")));
+
+    // Completing today extends the streak once today is in the past.
+    let mut habit = habit;
+    habit.completions.push(today);
+    let tomorrow = date("2023-10-21");
+
+    assert!(!habit.is_due(today));
+    assert_eq!(4, habit.streak(tomorrow));
+    assert_eq!(dates(&["2023-10-11"]), habit.overdue(tomorrow));
+
+    // A day off is excluded from the schedule instead of breaking the streak.
+    habit.schedule = Schedule::builder().start(date("2023-10-02")).weekly().on(weekdays).exclude(date("2023-10-11")).build().unwrap();
+
+    assert!(habit.overdue(tomorrow).is_empty());
+    assert_eq!(8, habit.streak(tomorrow));
+
+    // Habits are stored as JSON between sessions.
+    let json = serde_json::to_string(&habit.schedule).unwrap();
+    println!("stored schedule: {}", json);
+
+    assert_eq!(habit.schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+}
@@ -0,0 +1,369 @@
+//! Five field cron expressions, behind the `cron` feature.
+
+use std::collections::BTreeSet;
+
+use chrono::Datelike;
+
+use crate::{MissingDay, MonthFlags, MonthlyBy, Repeating, RepeatingDateError, Schedule, TimesOfDay, WeekdayFlags, WeekdayPolicy, SEARCH_DAYS};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CronError {
+    /// Not five whitespace separated fields.
+    FieldCount { count: usize },
+    /// A field, named as in `minute`, `hour`, `day of month`, `month` or `day of week`, whose
+    /// value does not parse or is out of range.
+    InvalidField { field: &'static str, value: String },
+    /// Both the day of month and the day of week are restricted: cron then runs on days matching
+    /// either, which a `Schedule` cannot represent.
+    DayOfMonthAndDayOfWeek,
+    /// The month is restricted but the day of month is not, as in every day of June.
+    MonthWithoutDayOfMonth,
+    /// The expression matches no date, e.g. `0 0 31 2 *`.
+    NeverMatches,
+    /// Every field parsed, but `Schedule` rejected the rule.
+    InvalidRule(RepeatingDateError),
+    /// The `Schedule` features, named as in their getters, that cron cannot express, e.g.
+    /// `interval` for every third week.
+    NotExpressible { features: Vec<&'static str> }
+}
+
+impl std::fmt::Display for CronError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CronError::FieldCount { count } => write!(f, "cron expression has {} fields, expected minute, hour, day of month, month and day of week", count),
+            CronError::InvalidField { field, value } => write!(f, "cron {} field `{}` is invalid", field, value),
+            CronError::DayOfMonthAndDayOfWeek => write!(f, "cron runs on days matching either the day of month or the day of week, which cannot be represented"),
+            CronError::MonthWithoutDayOfMonth => write!(f, "cron month is restricted without a day of month, which cannot be represented"),
+            CronError::NeverMatches => write!(f, "cron expression matches no date"),
+            CronError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            CronError::NotExpressible { features } => write!(f, "{} cannot be written as a cron expression", features.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for CronError {}
+
+/// Day of week names in cron order, Sunday being 0 (and 7).
+const CRON_DAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// The values of a field between `min` and `max`, both inclusive: `*`, numbers, `names` (the
+/// first naming `min`, case insensitive) and `a-b` ranges, each optionally with a `/step`, joined
+/// by commas. A single value with a step runs to `max`, as in `5/15`.
+fn parse_field(value: &str, min: u8, max: u8, names: &[&str]) -> Option<BTreeSet<u8>> {
+    let number = |entry: &str| -> Option<u8> {
+        let parsed = match names.iter().position(|name| name.eq_ignore_ascii_case(entry)) {
+            Some(index) => min + index as u8,
+            None => entry.parse::<u8>().ok()?,
+        };
+
+        (min..=max).contains(&parsed).then_some(parsed)
+    };
+
+    let mut values = BTreeSet::new();
+
+    for item in value.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u8>().ok().filter(|step| *step > 0)?)),
+            None => (item, None),
+        };
+
+        let (first, last) = match (range, range.split_once('-')) {
+            ("*", _) => (min, max),
+            (_, Some((first, last))) => (number(first)?, number(last)?),
+            (_, None) if step.is_some() => (number(range)?, max),
+            (_, None) => (number(range)?, number(range)?),
+        };
+
+        if first > last {
+            return None;
+        }
+
+        values.extend((first..=last).step_by(step.unwrap_or(1) as usize));
+    }
+
+    Some(values)
+}
+
+fn field(value: &str, name: &'static str, min: u8, max: u8, names: &[&str]) -> Result<BTreeSet<u8>, CronError> {
+    parse_field(value, min, max, names).ok_or_else(|| CronError::InvalidField { field: name, value: value.to_string() })
+}
+
+/// The weekday of a cron day of week, 0 and 7 being Sunday.
+fn cron_weekday(day: u8) -> chrono::Weekday {
+    chrono::Weekday::try_from((day + 6) % 7).unwrap()
+}
+
+/// Values in ascending order as a cron list, runs of three or more as ranges, or `*` when there are
+/// `all` of them.
+fn cron_list(values: &BTreeSet<u8>, all: usize) -> String {
+    if values.len() == all {
+        return "*".to_string();
+    }
+
+    let mut runs: Vec<(u8, u8)> = vec![];
+
+    for value in values {
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == *value => *last = *value,
+            _ => runs.push((*value, *value)),
+        }
+    }
+
+    runs.iter().flat_map(|(first, last)| match last - first {
+        0 => vec![first.to_string()],
+        1 => vec![first.to_string(), last.to_string()],
+        _ => vec![format!("{}-{}", first, last)],
+    }).collect::<Vec<String>>().join(",")
+}
+
+impl Schedule {
+    /// Reads a five field cron expression, `minute hour day-of-month month day-of-week`, as the
+    /// schedule starting on the first date on or after `from` it matches.
+    ///
+    /// The day fields decide the frequency: neither restricted is daily, a day of week alone is
+    /// weekly on those days, and a day of month is monthly on those days, or yearly in the
+    /// restricted months. A field is restricted unless it covers every value, so `0-6` is the same
+    /// as `*`. Minute and hour become the times of day, except that both `*` gives a rule on dates
+    /// alone, and so does `0 0`, as a schedule without times runs at midnight. Fields take lists,
+    /// ranges and steps; days of week and months also take names such as `MON` and `JAN`, and both
+    /// 0 and 7 are Sunday.
+    pub fn from_cron(expression: &str, from: chrono::NaiveDate) -> Result<Schedule, CronError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, month_day, month, weekday] = fields[..] else { return Err(CronError::FieldCount { count: fields.len() }) };
+
+        let minutes = field(minute, "minute", 0, 59, &[])?;
+        let hours = field(hour, "hour", 0, 23, &[])?;
+        let month_days = field(month_day, "day of month", 1, 31, &[])?;
+        let months = field(month, "month", 1, 12, &crate::month_set::MONTH_NAMES)?;
+        let weekdays = field(weekday, "day of week", 0, 7, &CRON_DAYS)?.into_iter()
+            .fold(WeekdayFlags::empty(), |weekdays, day| weekdays | WeekdayFlags::from_weekday(cron_weekday(day)));
+
+        let months = months.into_iter().fold(MonthFlags::empty(), |flags, month| flags | MonthFlags::from_month(chrono::Month::try_from(month).unwrap()));
+        let by_day_of_month = month_days.len() < 31;
+
+        match (by_day_of_month, !weekdays.is_all(), months != MonthFlags::ANY) {
+            (true, true, _) => return Err(CronError::DayOfMonthAndDayOfWeek),
+            (false, _, true) => return Err(CronError::MonthWithoutDayOfMonth),
+            _ => {},
+        }
+
+        let start_date = (0..SEARCH_DAYS)
+            .filter_map(|days| from.checked_add_days(chrono::Days::new(days)))
+            .find(|date| months.contains_month(date.month()) && month_days.contains(&(date.day() as u8)) && weekdays.contains(WeekdayFlags::from_weekday(date.weekday())))
+            .ok_or(CronError::NeverMatches)?;
+
+        let mut builder = Schedule::builder().start(start_date);
+
+        builder = match (by_day_of_month, months == MonthFlags::ANY) {
+            (false, _) if weekdays.is_all() => builder.daily(),
+            (false, _) => builder.weekly().on(weekdays),
+            (true, whole_year) => {
+                let days: Vec<i8> = month_days.iter().map(|day| *day as i8).collect();
+                let builder = builder.monthly_by(MonthlyBy::days(&days).unwrap());
+
+                if whole_year { builder.monthly() } else { builder.yearly().months(months) }
+            },
+        };
+
+        let times: BTreeSet<chrono::NaiveTime> = hours.iter().flat_map(|hour| minutes.iter().map(move |minute| chrono::NaiveTime::from_hms_opt(*hour as u32, *minute as u32, 0).unwrap())).collect();
+
+        if times.len() < 24 * 60 && times != BTreeSet::from([chrono::NaiveTime::MIN]) {
+            builder = builder.times_of_day(TimesOfDay::new(times).unwrap());
+        }
+
+        builder.build().map_err(CronError::InvalidRule)
+    }
+
+    /// Writes the rule as a five field cron expression, which `from_cron` reads back as an
+    /// equivalent rule from any date on or after the start. Days of week are numbers, Sunday
+    /// being 0, and lists use ranges for runs of three or more, as in `1-5` for weekdays. A rule
+    /// without times of day runs at midnight.
+    ///
+    /// Cron has no start, end or interval, so this covers daily and weekly rules every interval,
+    /// monthly rules on the start's day or on `MonthlyBy::Days` counted from the start of the month
+    /// whose interval divides 12, and yearly rules every year, with times of day that are every
+    /// combination of some minutes and hours. Anything else fails with
+    /// `CronError::NotExpressible`, naming every feature in the way.
+    pub fn to_cron(&self) -> Result<String, CronError> {
+        let (repeat, interval) = self.step();
+        let filtered = !self.weekdays().is_all();
+        let times = self.times_of_day().map(|times| times.times().clone()).unwrap_or_else(|| BTreeSet::from([chrono::NaiveTime::MIN]));
+        let hours: BTreeSet<u8> = times.iter().map(|time| chrono::Timelike::hour(time) as u8).collect();
+        let minutes: BTreeSet<u8> = times.iter().map(|time| chrono::Timelike::minute(time) as u8).collect();
+
+        let unsupported = [
+            ("interval", if repeat == Repeating::Monthly { 12 % interval != 0 } else { interval != 1 }),
+            ("weekday_policy", self.weekday_policy() != WeekdayPolicy::Skip),
+            ("shift_pattern", self.shift_pattern().is_some()),
+            ("monthly_by", match self.monthly_by() {
+                Some(MonthlyBy::Days { days, missing: MissingDay::Skip }) => days.iter().any(|day| day.get() < 0),
+                Some(_) => true,
+                None => false,
+            }),
+            ("weekdays", filtered && matches!(repeat, Repeating::Monthly | Repeating::Yearly)),
+            ("iso_weeks", !self.iso_weeks().is_empty()),
+            ("year_days", self.year_days().is_some()),
+            ("set_positions", !self.set_positions().is_empty()),
+            ("until", self.until().is_some()),
+            ("count", self.count().is_some()),
+            ("exclusions", !self.exclusions().is_empty()),
+            ("extra_dates", !self.extra_dates().is_empty()),
+            ("times_of_day", self.times_of_day().is_some_and(|times_of_day| WeekdayFlags::ANY.extract_weekdays().any(|weekday| times_of_day.weekday_time(weekday).is_some()))
+                || times.iter().any(|time| chrono::Timelike::second(time) != 0)
+                || times.len() != hours.len() * minutes.len()),
+        ];
+
+        let features: Vec<&'static str> = unsupported.iter().filter(|(_, unsupported)| *unsupported).map(|(feature, _)| *feature).collect();
+
+        if !features.is_empty() {
+            return Err(CronError::NotExpressible { features });
+        }
+
+        let start = self.start_date();
+        let month_days = match self.monthly_by() {
+            Some(MonthlyBy::Days { days, .. }) => days.iter().map(|day| day.get() as u8).collect(),
+            _ => BTreeSet::from([start.day() as u8]),
+        };
+
+        let (month_day, month) = match repeat {
+            Repeating::Daily | Repeating::Weekly => ("*".to_string(), "*".to_string()),
+            Repeating::Monthly => {
+                let months = (0..12 / interval as u32).map(|step| ((start.month0() + step * interval as u32) % 12 + 1) as u8).collect();
+
+                (cron_list(&month_days, 31), cron_list(&months, 12))
+            },
+            _ => {
+                let months = match self.months() {
+                    Some(months) => months.month_numbers().map(|month| month as u8).collect(),
+                    None => BTreeSet::from([start.month() as u8]),
+                };
+
+                (cron_list(&month_days, 31), cron_list(&months, 12))
+            },
+        };
+
+        let weekdays = self.weekdays().extract_weekdays().map(|weekday| weekday.num_days_from_sunday() as u8).collect();
+
+        Ok(format!("{} {} {} {} {}", cron_list(&minutes, 60), cron_list(&hours, 24), month_day, month, cron_list(&weekdays, 7)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    #[rstest]
+    // 2024-01-10 is a Wednesday.
+    #[case("0 9 * * MON-FRI", ["2024-01-10T09:00:00", "2024-01-11T09:00:00", "2024-01-12T09:00:00"])]
+    #[case("* * * * MON,WED", ["2024-01-10T00:00:00", "2024-01-15T00:00:00", "2024-01-17T00:00:00"])]
+    #[case("0 0 1 * *", ["2024-02-01T00:00:00", "2024-03-01T00:00:00", "2024-04-01T00:00:00"])]
+    #[case("30 18 * * *", ["2024-01-10T18:30:00", "2024-01-11T18:30:00", "2024-01-12T18:30:00"])]
+    #[case("0 8 * * */2", ["2024-01-11T08:00:00", "2024-01-13T08:00:00", "2024-01-14T08:00:00"])]
+    #[case("0 8 * * 0", ["2024-01-14T08:00:00", "2024-01-21T08:00:00", "2024-01-28T08:00:00"])]
+    #[case("0 8 * * 7", ["2024-01-14T08:00:00", "2024-01-21T08:00:00", "2024-01-28T08:00:00"])]
+    #[case("0 8 * * sat,Sun", ["2024-01-13T08:00:00", "2024-01-14T08:00:00", "2024-01-20T08:00:00"])]
+    #[case("0 8 * * 0-6", ["2024-01-10T08:00:00", "2024-01-11T08:00:00", "2024-01-12T08:00:00"])]
+    #[case("0 0 15,31 * *", ["2024-01-15T00:00:00", "2024-01-31T00:00:00", "2024-02-15T00:00:00"])]
+    #[case("0 0 */10 * *", ["2024-01-11T00:00:00", "2024-01-21T00:00:00", "2024-01-31T00:00:00"])]
+    #[case("0 12 1 JAN,JUL *", ["2024-07-01T12:00:00", "2025-01-01T12:00:00", "2025-07-01T12:00:00"])]
+    #[case("0 0 29 2 *", ["2024-02-29T00:00:00", "2028-02-29T00:00:00", "2032-02-29T00:00:00"])]
+    #[case("0,30 9 * * TUE", ["2024-01-16T09:00:00", "2024-01-16T09:30:00", "2024-01-23T09:00:00"])]
+    #[case("0 9-17/4 * * WED", ["2024-01-10T09:00:00", "2024-01-10T13:00:00", "2024-01-10T17:00:00"])]
+    fn first_three_occurrences(#[case] expression: &str, #[case] expected: [&str; 3]) {
+        let schedule = Schedule::from_cron(expression, date("2024-01-10")).unwrap();
+        let datetimes: Vec<chrono::NaiveDateTime> = schedule.datetimes(date("2024-01-09").and_hms_opt(23, 59, 59).unwrap()).take(3).collect();
+
+        assert_eq!(expected.map(|value| chrono::NaiveDateTime::from_str(value).unwrap()).to_vec(), datetimes);
+    }
+
+    #[test]
+    fn reads_the_rule_it_describes() {
+        assert_eq!(Schedule::from_str("weekly;days=MON,TUE,WED,THU,FRI;start=2024-01-10;times=09:00").unwrap(), Schedule::from_cron("0 9 * * 1-5", date("2024-01-10")).unwrap());
+        assert_eq!(Schedule::from_str("monthly;start=2024-02-01;monthdays=1").unwrap(), Schedule::from_cron("* * 1 * *", date("2024-01-10")).unwrap());
+    }
+
+    #[rstest]
+    #[case::monday(WeekdayFlags::MON, "1")]
+    #[case::tuesday(WeekdayFlags::TUE, "2")]
+    #[case::wednesday(WeekdayFlags::WED, "3")]
+    #[case::thursday(WeekdayFlags::THU, "4")]
+    #[case::friday(WeekdayFlags::FRI, "5")]
+    #[case::saturday(WeekdayFlags::SAT, "6")]
+    #[case::sunday(WeekdayFlags::SUN, "0")]
+    #[case::weekend(WeekdayFlags::SAT | WeekdayFlags::SUN, "0,6")]
+    #[case::business_days(WeekdayFlags::MIDWEEK, "1-5")]
+    #[case::sunday_to_tuesday(WeekdayFlags::SUN | WeekdayFlags::MON | WeekdayFlags::TUE, "0-2")]
+    #[case::every_other_day(WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI | WeekdayFlags::SUN, "0,1,3,5")]
+    fn day_of_week_numbers(#[case] weekdays: WeekdayFlags, #[case] expected: &str) {
+        let start = date("2024-01-07").iter_days().find(|date| weekdays.contains(WeekdayFlags::from_weekday(date.weekday()))).unwrap();
+        let schedule = Schedule::builder().start(start).weekly().on(weekdays).build().unwrap();
+        let cron = schedule.to_cron().unwrap();
+
+        assert_eq!(format!("0 0 * * {}", expected), cron);
+        assert_eq!(weekdays, Schedule::from_cron(&cron, date("2024-01-10")).unwrap().weekdays());
+    }
+
+    #[test]
+    fn seven_is_sunday_too() {
+        assert_eq!(WeekdayFlags::SUN, Schedule::from_cron("0 0 * * 7", date("2024-01-10")).unwrap().weekdays());
+        assert_eq!(WeekdayFlags::SAT | WeekdayFlags::SUN, Schedule::from_cron("0 0 * * 6-7", date("2024-01-10")).unwrap().weekdays());
+    }
+
+    #[rstest]
+    #[case::daily("daily;start=2024-01-10", "0 0 * * *")]
+    #[case::weekdays("daily;days=MON,TUE,WED,THU,FRI;start=2024-01-10;times=09:00", "0 9 * * 1-5")]
+    #[case::weekly("weekly;days=TUE,THU;start=2024-01-11;times=18:30", "30 18 * * 2,4")]
+    #[case::monthly("monthly;start=2024-01-31", "0 0 31 * *")]
+    #[case::monthly_days("monthly;start=2024-01-01;monthdays=1,2,3,15;times=06:00", "0 6 1-3,15 * *")]
+    #[case::quarterly("quarterly;start=2024-02-15", "0 0 15 2,5,8,11 *")]
+    #[case::every_other_month("monthly;interval=2;start=2024-11-05", "0 0 5 1,3,5,7,9,11 *")]
+    #[case::yearly("yearly;start=2024-07-04;times=12:00", "0 12 4 7 *")]
+    #[case::yearly_months("yearly;start=2024-01-01;months=JAN,JUL;monthdays=1", "0 0 1 1,7 *")]
+    #[case::hours_and_minutes("daily;start=2024-01-10;times=09:00,09:30,17:00,17:30", "0,30 9,17 * * *")]
+    fn writes(#[case] rule: &str, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let cron = schedule.to_cron().unwrap();
+
+        assert_eq!(expected, cron);
+        assert!(schedule.equivalent(&Schedule::from_cron(&cron, schedule.start_date()).unwrap()), "{}", cron);
+    }
+
+    #[rstest]
+    #[case::every_third_week("weekly;interval=3;days=MON;start=2024-01-01", &["interval"])]
+    #[case::every_five_months("monthly;interval=5;start=2024-01-01", &["interval"])]
+    #[case::every_other_year("yearly;interval=2;start=2024-01-01", &["interval"])]
+    #[case::ended("weekly;interval=2;days=MON;start=2024-01-01;count=5", &["interval", "count"])]
+    #[case::last_day("monthly;start=2024-01-31;monthdays=-1", &["monthly_by"])]
+    #[case::nth_weekday("monthly;start=2024-01-09;nth=2TUE", &["monthly_by"])]
+    #[case::filtered_month("monthly;days=FRI;start=2024-09-13", &["weekdays"])]
+    #[case::last_workday("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-31;setpos=-1", &["weekdays", "set_positions"])]
+    #[case::exceptions("daily;start=2024-01-01;until=2024-06-30;exdate=2024-01-02;rdate=2024-07-04", &["until", "exclusions", "extra_dates"])]
+    #[case::scattered_times("daily;start=2024-01-01;times=08:00,20:30", &["times_of_day"])]
+    #[case::seconds("daily;start=2024-01-01;times=08:00:30", &["times_of_day"])]
+    #[case::weekday_times("weekly;days=MON,THU;start=2024-01-01;times=MON@09:00,THU@14:00", &["times_of_day"])]
+    fn not_expressible(#[case] rule: &str, #[case] features: &[&'static str]) {
+        assert_eq!(Err(CronError::NotExpressible { features: features.to_vec() }), Schedule::from_str(rule).unwrap().to_cron());
+    }
+
+    #[rstest]
+    #[case::too_few("0 9 * *", CronError::FieldCount { count: 4 })]
+    #[case::too_many("0 9 * * * 2024", CronError::FieldCount { count: 6 })]
+    #[case::minute("60 9 * * *", CronError::InvalidField { field: "minute", value: "60".to_string() })]
+    #[case::hour("0 9-7 * * *", CronError::InvalidField { field: "hour", value: "9-7".to_string() })]
+    #[case::day_of_month("0 0 0 * *", CronError::InvalidField { field: "day of month", value: "0".to_string() })]
+    #[case::month("0 0 1 JANUARY *", CronError::InvalidField { field: "month", value: "JANUARY".to_string() })]
+    #[case::day_of_week("0 0 * * MON-", CronError::InvalidField { field: "day of week", value: "MON-".to_string() })]
+    #[case::zero_step("0 0 * * */0", CronError::InvalidField { field: "day of week", value: "*/0".to_string() })]
+    #[case::nonstandard("0 0 L * *", CronError::InvalidField { field: "day of month", value: "L".to_string() })]
+    #[case::either_day("0 0 1 * MON", CronError::DayOfMonthAndDayOfWeek)]
+    #[case::every_day_in_june("0 0 * 6 *", CronError::MonthWithoutDayOfMonth)]
+    #[case::mondays_in_june("0 0 * 6 MON", CronError::MonthWithoutDayOfMonth)]
+    #[case::thirtieth_of_february("0 0 30 2 *", CronError::NeverMatches)]
+    fn rejects(#[case] expression: &str, #[case] expected: CronError) {
+        assert_eq!(Err(expected), Schedule::from_cron(expression, date("2024-01-10")));
+    }
+}
@@ -0,0 +1,201 @@
+use chrono::Datelike;
+
+use crate::{days_until, find_next_date, match_repeating_date, Repeating, RepeatingDateError, WeekdayFlags};
+
+/// Longest run of steps searched for one on an allowed weekday: a week of minutely steps.
+const MAX_STEP_SKIP: u32 = 10_080;
+
+/// Length in seconds of one interval of a sub-daily frequency.
+fn step_seconds(repeat: Repeating, interval: u64) -> Result<i64, RepeatingDateError> {
+    let unit = match repeat {
+        Repeating::Hourly => 3600,
+        Repeating::Minutely => 60,
+        _ => return Err(RepeatingDateError::UnsupportedFrequency),
+    };
+
+    if interval == 0 {
+        return Err(RepeatingDateError::ZeroInterval);
+    }
+
+    i64::try_from(interval).ok().and_then(|interval| interval.checked_mul(unit)).ok_or(RepeatingDateError::DateOutOfRange)
+}
+
+fn allows(weekdays: WeekdayFlags, datetime: chrono::NaiveDateTime) -> bool {
+    weekdays.contains(WeekdayFlags::from_weekday(datetime.weekday()))
+}
+
+fn is_sub_daily(repeat: Repeating) -> bool {
+    matches!(repeat, Repeating::Hourly | Repeating::Minutely)
+}
+
+/// The first occurrence after `from_datetime` of a rule starting at `start_datetime`.
+///
+/// For `Repeating::Hourly` and `Repeating::Minutely` the steps stay on the grid of `interval`
+/// hours or minutes from the start: when one lands on a disallowed weekday, the search continues
+/// with the first step on the next allowed day, not at its midnight. Other frequencies pick dates
+/// as `find_next_date` does, each at the start's time of day; an occurrence date whose time has
+/// already passed on `from_datetime` is not the next one.
+pub fn find_next_datetime(from_datetime: chrono::NaiveDateTime, start_datetime: chrono::NaiveDateTime, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<chrono::NaiveDateTime, RepeatingDateError> {
+    if from_datetime < start_datetime {
+        return Err(RepeatingDateError::StartDateBeforeBound { from: from_datetime.date(), start: start_datetime.date() });
+    }
+
+    if !allows(weekdays, start_datetime) {
+        return Err(RepeatingDateError::WrongWeekday { start_weekday: start_datetime.weekday(), allowed: weekdays });
+    }
+
+    if !is_sub_daily(repeat) {
+        let time = start_datetime.time();
+
+        if from_datetime.time() < time && match_repeating_date(from_datetime.date(), start_datetime.date(), weekdays, repeat, interval) {
+            return Ok(from_datetime.date().and_time(time));
+        }
+
+        return find_next_date(from_datetime.date(), start_datetime.date(), weekdays, repeat, interval).map(|date| date.and_time(time));
+    }
+
+    let step = step_seconds(repeat, interval)?;
+    let step_at = |steps: i64| steps.checked_mul(step)
+        .and_then(chrono::TimeDelta::try_seconds)
+        .and_then(|offset| start_datetime.checked_add_signed(offset))
+        .ok_or(RepeatingDateError::DateOutOfRange);
+
+    let mut steps = (from_datetime - start_datetime).num_seconds() / step + 1;
+
+    for _ in 0..MAX_STEP_SKIP {
+        let candidate = step_at(steps)?;
+
+        if allows(weekdays, candidate) {
+            return Ok(candidate);
+        }
+
+        let days = match days_until(candidate.weekday(), weekdays.next_weekday(candidate.weekday()).ok_or(RepeatingDateError::EmptyWeekdaySet)?) {
+            0 => 7,
+            days => days as u64,
+        };
+        let next_day = candidate.date().checked_add_days(chrono::Days::new(days)).ok_or(RepeatingDateError::DateOutOfRange)?;
+        let until_next_day = (next_day.and_time(chrono::NaiveTime::MIN) - start_datetime).num_seconds();
+
+        steps = until_next_day / step + i64::from(until_next_day % step != 0);
+    }
+
+    Err(RepeatingDateError::NoOccurrenceFound)
+}
+
+/// Whether `datetime_to_check` is an occurrence: a whole number of hourly or minutely intervals
+/// after `start_datetime` on an allowed weekday, or for other frequencies the start's time of day
+/// on a date `match_repeating_date` accepts.
+pub fn match_repeating_datetime(datetime_to_check: chrono::NaiveDateTime, start_datetime: chrono::NaiveDateTime, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
+    if datetime_to_check < start_datetime || !allows(weekdays, datetime_to_check) {
+        return false;
+    }
+
+    if !is_sub_daily(repeat) {
+        return datetime_to_check.time() == start_datetime.time() && match_repeating_date(datetime_to_check.date(), start_datetime.date(), weekdays, repeat, interval);
+    }
+
+    let Ok(step) = step_seconds(repeat, interval) else { return false };
+    let elapsed = datetime_to_check - start_datetime;
+
+    elapsed.subsec_nanos() == 0 && elapsed.num_seconds() % step == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+
+    fn datetime(value: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_str(value).unwrap()
+    }
+
+    #[rstest]
+    #[case::same_day("2024-01-01T08:00:00", "2024-01-01T08:00:00", Repeating::Hourly, 6, "2024-01-01T14:00:00")]
+    #[case::between_steps("2024-01-01T08:00:00", "2024-01-01T15:59:59", Repeating::Hourly, 6, "2024-01-01T20:00:00")]
+    #[case::past_midnight("2024-01-01T08:00:00", "2024-01-01T20:00:00", Repeating::Hourly, 6, "2024-01-02T02:00:00")]
+    #[case::seven_hours_drift("2024-01-01T00:00:00", "2024-01-01T22:00:00", Repeating::Hourly, 7, "2024-01-02T04:00:00")]
+    #[case::seven_hours_realign_weekly("2024-01-01T00:00:00", "2024-01-07T23:59:00", Repeating::Hourly, 7, "2024-01-08T00:00:00")]
+    #[case::every_30_minutes("2024-01-01T08:15:00", "2024-01-01T08:44:00", Repeating::Minutely, 30, "2024-01-01T08:45:00")]
+    #[case::every_minute_at_midnight("2024-01-01T23:59:00", "2024-01-01T23:59:00", Repeating::Minutely, 1, "2024-01-02T00:00:00")]
+    fn next_on_any_day(#[case] start: &str, #[case] from: &str, #[case] repeat: Repeating, #[case] interval: u64, #[case] expected: &str) {
+        let (start, expected) = (datetime(start), datetime(expected));
+        let result = find_next_datetime(datetime(from), start, WeekdayFlags::ANY, repeat, interval);
+
+        assert_eq!(Ok(expected), result);
+        assert!(match_repeating_datetime(expected, start, WeekdayFlags::ANY, repeat, interval));
+    }
+
+    #[rstest]
+    // Friday 20:00 plus 56 hours is Monday 04:00; the Saturday and Sunday steps are skipped.
+    #[case::every_seven_hours("2024-01-05T20:00:00", "2024-01-06T01:00:00", Repeating::Hourly, 7, "2024-01-08T04:00:00")]
+    #[case::every_six_hours("2024-01-05T08:00:00", "2024-01-05T20:00:00", Repeating::Hourly, 6, "2024-01-08T02:00:00")]
+    #[case::every_30_minutes("2024-01-05T23:00:00", "2024-01-05T23:30:00", Repeating::Minutely, 30, "2024-01-08T00:00:00")]
+    #[case::every_25_hours("2024-01-05T10:00:00", "2024-01-05T10:00:00", Repeating::Hourly, 25, "2024-01-08T13:00:00")]
+    fn next_skips_the_weekend(#[case] start: &str, #[case] from: &str, #[case] repeat: Repeating, #[case] interval: u64, #[case] expected: &str) {
+        let (start, expected) = (datetime(start), datetime(expected));
+
+        assert_eq!(Ok(expected), find_next_datetime(datetime(from), start, WeekdayFlags::MIDWEEK, repeat, interval));
+        assert!(match_repeating_datetime(expected, start, WeekdayFlags::MIDWEEK, repeat, interval));
+    }
+
+    #[rstest]
+    #[case::on_the_grid("2024-01-08T04:00:00", true)]
+    #[case::start("2024-01-05T20:00:00", true)]
+    #[case::weekend_step("2024-01-06T03:00:00", false)]
+    #[case::off_the_grid("2024-01-08T05:00:00", false)]
+    #[case::off_by_a_second("2024-01-08T04:00:01", false)]
+    #[case::before_start("2024-01-05T13:00:00", false)]
+    fn match_every_seven_hours(#[case] check: &str, #[case] expected: bool) {
+        assert_eq!(expected, match_repeating_datetime(datetime(check), datetime("2024-01-05T20:00:00"), WeekdayFlags::MIDWEEK, Repeating::Hourly, 7));
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        let start = datetime("2024-01-06T08:00:00");
+
+        assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: start.date(), start: start.date() }), find_next_datetime(datetime("2024-01-06T07:00:00"), start, WeekdayFlags::ANY, Repeating::Hourly, 1));
+        assert_eq!(Err(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Sat, allowed: WeekdayFlags::MIDWEEK }), find_next_datetime(start, start, WeekdayFlags::MIDWEEK, Repeating::Hourly, 1));
+        assert_eq!(Err(RepeatingDateError::ZeroInterval), find_next_datetime(start, start, WeekdayFlags::ANY, Repeating::Minutely, 0));
+        assert_eq!(Err(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Sat, allowed: WeekdayFlags::MIDWEEK }), find_next_datetime(start, start, WeekdayFlags::MIDWEEK, Repeating::Weekly, 1));
+        assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: start.date(), start: start.date() }), find_next_datetime(datetime("2024-01-06T07:59:59"), start, WeekdayFlags::ANY, Repeating::Daily, 1));
+    }
+
+    // Tuesdays at 14:30 from January 2, 2024.
+    #[rstest]
+    #[case::exactly_at("2024-01-09T14:30:00", "2024-01-16T14:30:00")]
+    #[case::just_before("2024-01-09T14:29:59", "2024-01-09T14:30:00")]
+    #[case::just_after("2024-01-09T14:30:01", "2024-01-16T14:30:00")]
+    #[case::start("2024-01-02T14:30:00", "2024-01-09T14:30:00")]
+    #[case::morning_of_another_day("2024-01-10T08:00:00", "2024-01-16T14:30:00")]
+    fn next_keeps_the_start_time(#[case] from: &str, #[case] expected: &str) {
+        let start = datetime("2024-01-02T14:30:00");
+
+        assert_eq!(Ok(datetime(expected)), find_next_datetime(datetime(from), start, WeekdayFlags::TUE, Repeating::Weekly, 1));
+    }
+
+    #[rstest]
+    #[case::daily(Repeating::Daily, 1, "2024-01-31T09:00:00", "2024-02-01T09:00:00", "2024-02-02T09:00:00")]
+    // Months without a 31st have no occurrence.
+    #[case::monthly_before(Repeating::Monthly, 1, "2024-03-31T08:59:00", "2024-03-31T09:00:00", "2024-05-31T09:00:00")]
+    #[case::monthly_after(Repeating::Monthly, 1, "2024-03-31T09:00:01", "2024-05-31T09:00:00", "2024-07-31T09:00:00")]
+    #[case::yearly_before(Repeating::Yearly, 1, "2025-01-31T00:00:00", "2025-01-31T09:00:00", "2026-01-31T09:00:00")]
+    fn next_on_date_frequencies(#[case] repeat: Repeating, #[case] interval: u64, #[case] from: &str, #[case] expected: &str, #[case] after_that: &str) {
+        let start = datetime("2024-01-31T09:00:00");
+        let next = find_next_datetime(datetime(from), start, WeekdayFlags::ANY, repeat, interval).unwrap();
+
+        assert_eq!(datetime(expected), next);
+        assert_eq!(Ok(datetime(after_that)), find_next_datetime(next, start, WeekdayFlags::ANY, repeat, interval));
+    }
+
+    #[rstest]
+    #[case::at_the_time("2024-01-16T14:30:00", true)]
+    #[case::start("2024-01-02T14:30:00", true)]
+    #[case::other_time("2024-01-16T14:31:00", false)]
+    #[case::other_day("2024-01-17T14:30:00", false)]
+    #[case::before_start("2023-12-26T14:30:00", false)]
+    fn match_keeps_the_start_time(#[case] check: &str, #[case] expected: bool) {
+        assert_eq!(expected, match_repeating_datetime(datetime(check), datetime("2024-01-02T14:30:00"), WeekdayFlags::TUE, Repeating::Weekly, 1));
+    }
+}
@@ -0,0 +1,338 @@
+use chrono::Datelike;
+
+use crate::{MissingDay, MonthlyBy, Repeating, Schedule, WeekdayFlags, WeekdayPolicy, WeekendDefinition};
+
+const FULL_WEEKDAY_NAMES: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+fn ordinal(value: u64) -> String {
+    let suffix = match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", value, suffix)
+}
+
+/// An ordinal counting from the start when positive and from the end when negative, as in "2nd"
+/// and "2nd to last".
+fn from_end(value: i64) -> String {
+    match value {
+        -1 => "last".to_string(),
+        _ if value < 0 => format!("{} to last", ordinal(value.unsigned_abs())),
+        _ => ordinal(value as u64),
+    }
+}
+
+/// "a", "a and b", "a, b and c".
+fn join(items: impl IntoIterator<Item = String>, conjunction: &str) -> String {
+    let mut items: Vec<String> = items.into_iter().collect();
+
+    match items.pop() {
+        None => String::new(),
+        Some(last) if items.is_empty() => last,
+        Some(last) => format!("{} {} {}", items.join(", "), conjunction, last),
+    }
+}
+
+fn weekday_names(weekdays: WeekdayFlags) -> impl Iterator<Item = String> {
+    weekdays.extract_weekdays().map(|weekday| FULL_WEEKDAY_NAMES[weekday.num_days_from_monday() as usize].to_string())
+}
+
+/// The weekdays as the days something happens on: "weekdays", "Tuesday and Thursday".
+fn weekdays_plural(weekdays: WeekdayFlags, weekend: WeekendDefinition) -> String {
+    match weekdays {
+        _ if weekdays.is_all() => "every day".to_string(),
+        _ if weekdays == weekend.workdays() => "weekdays".to_string(),
+        _ if weekdays == weekend.weekend() => "weekends".to_string(),
+        _ => join(weekday_names(weekdays), "and"),
+    }
+}
+
+/// The weekdays as a kind of day: "weekday", "Monday or Friday".
+fn weekdays_singular(weekdays: WeekdayFlags, weekend: WeekendDefinition) -> String {
+    match weekdays {
+        _ if weekdays.is_all() => "day".to_string(),
+        _ if weekdays == weekend.workdays() => "weekday".to_string(),
+        _ if weekdays == weekend.weekend() => "weekend day".to_string(),
+        _ => join(weekday_names(weekdays), "or"),
+    }
+}
+
+fn unit(repeat: Repeating) -> &'static str {
+    match repeat {
+        Repeating::Daily => "day",
+        Repeating::Weekly => "week",
+        Repeating::Monthly => "month",
+        Repeating::Quarterly => "quarter",
+        Repeating::Yearly => "year",
+        Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+    }
+}
+
+fn every(repeat: Repeating, interval: u64) -> String {
+    match interval {
+        1 => format!("Every {}", unit(repeat)),
+        _ => format!("Every {} {}s", interval, unit(repeat)),
+    }
+}
+
+fn month_name(month: u32) -> &'static str {
+    chrono::Month::try_from(month as u8).unwrap().name()
+}
+
+fn short_date(date: chrono::NaiveDate) -> String {
+    date.format("%-d %b %Y").to_string()
+}
+
+fn time(time: chrono::NaiveTime) -> String {
+    match chrono::Timelike::second(&time) {
+        0 => time.format("%H:%M").to_string(),
+        _ => time.format("%H:%M:%S").to_string(),
+    }
+}
+
+/// Ordinals counting from the start in ascending order, then those counting from the end, as in
+/// "1st, 15th and last".
+fn ordinals(values: impl IntoIterator<Item = i64>) -> String {
+    let (mut from_start, mut from_finish): (Vec<i64>, Vec<i64>) = values.into_iter().partition(|value| *value > 0);
+    from_start.sort_unstable();
+    from_finish.sort_unstable();
+
+    join(from_start.into_iter().chain(from_finish).map(from_end), "and")
+}
+
+/// Month days as in "the 1st, 15th and last day".
+fn month_days(days: impl IntoIterator<Item = i64>) -> String {
+    let days: Vec<i64> = days.into_iter().collect();
+    let day = if days.iter().any(|day| *day < 0) { " day" } else { "" };
+
+    format!("the {}{}", ordinals(days), day)
+}
+
+impl Schedule {
+    /// The rule in English for display, as in "Every 2 weeks on Tuesday and Thursday, starting
+    /// 21 Sep 2023": the frequency and the days it picks, then times of day, set positions, the
+    /// start, the end, exclusions and extra dates. The wording is stable but not meant to be
+    /// parsed; use the text format of `Display` and `FromStr` for that. "Weekdays" and "weekends"
+    /// follow the Saturday/Sunday weekend, see `describe_with`.
+    pub fn describe(&self) -> String {
+        self.describe_with(WeekendDefinition::default())
+    }
+
+    /// Like `describe`, with the working days and the weekend of `weekend` named "weekdays" and
+    /// "weekends".
+    pub fn describe_with(&self, weekend: WeekendDefinition) -> String {
+        let weekdays = self.weekdays();
+        let filtered = !weekdays.is_all();
+        let mut description = match self.repeat() {
+            Repeating::Daily => self.describe_days(weekend),
+            Repeating::Weekly if filtered => format!("{} on {}", every(Repeating::Weekly, self.interval()), weekdays_plural(weekdays, weekend)),
+            Repeating::Weekly => format!("{} on every day of the week", every(Repeating::Weekly, self.interval())),
+            Repeating::Monthly | Repeating::Quarterly => format!("{} on {}", every(self.repeat(), self.interval()), self.describe_month_days(weekend)),
+            _ => format!("{} on {}", every(Repeating::Yearly, self.interval()), self.describe_year_days(weekend)),
+        };
+
+        let by_date = self.set_positions().is_empty() && !matches!(self.monthly_by(), Some(MonthlyBy::NthWeekday { .. })) && self.iso_weeks().is_empty();
+
+        if filtered && by_date && matches!(self.repeat(), Repeating::Monthly | Repeating::Quarterly | Repeating::Yearly) {
+            description.push_str(&format!(" when it falls on a {}", weekdays_singular(weekdays, weekend)));
+        }
+
+        if let Some(times) = self.times_of_day() {
+            let by_weekday = WeekdayFlags::ANY.extract_weekdays()
+                .filter_map(|weekday| times.weekday_time(weekday).map(|at| format!("{} on {}", time(at), FULL_WEEKDAY_NAMES[weekday.num_days_from_monday() as usize])));
+            let shared = join(times.times().iter().map(|at| time(*at)), "and");
+
+            description.push_str(&match (shared.is_empty(), join(by_weekday, "and")) {
+                (_, by_weekday) if by_weekday.is_empty() => format!(" at {}", shared),
+                (true, by_weekday) => format!(" at {}", by_weekday),
+                (false, by_weekday) => format!(" at {} ({})", shared, by_weekday),
+            });
+        }
+
+        if !self.set_positions().is_empty() {
+            let positions = ordinals(self.set_positions().iter().map(|position| *position as i64));
+
+            description.push_str(&format!(", keeping the {} of each {}", positions, unit(self.step().0)));
+        }
+
+        description.push_str(&format!(", starting {}", short_date(self.start_date())));
+
+        if let Some(until) = self.until() {
+            description.push_str(&format!(", until {}", short_date(until)));
+        }
+
+        match self.count() {
+            Some(1) => description.push_str(", once"),
+            Some(count) => description.push_str(&format!(", {} times", count)),
+            None => {},
+        }
+
+        if !self.exclusions().is_empty() {
+            description.push_str(&format!(", except {}", join(self.exclusions().iter().map(|date| short_date(*date)), "and")));
+        }
+
+        if !self.extra_dates().is_empty() {
+            description.push_str(&format!(", and also on {}", join(self.extra_dates().iter().map(|date| short_date(*date)), "and")));
+        }
+
+        description
+    }
+
+    fn describe_days(&self, weekend: WeekendDefinition) -> String {
+        let weekdays = self.weekdays();
+        let interval = self.interval();
+
+        if let Some(pattern) = self.shift_pattern() {
+            let cycle = if interval == 1 { String::new() } else { format!("{} ", ordinal(interval)) };
+            let on = if weekdays.is_all() { String::new() } else { format!(" on {}", weekdays_plural(weekdays, weekend)) };
+
+            return format!("On days {} of every {}{} day cycle{}", join(pattern.on_days().iter().map(|day| (day + 1).to_string()), "and"), cycle, pattern.cycle_days(), on);
+        }
+
+        match self.weekday_policy() {
+            _ if weekdays.is_all() => every(Repeating::Daily, interval),
+            WeekdayPolicy::Skip | WeekdayPolicy::Count if interval == 1 => format!("Every {}", weekdays_singular(weekdays, weekend)),
+            WeekdayPolicy::Skip => format!("{} on {}", every(Repeating::Daily, interval), weekdays_plural(weekdays, weekend)),
+            WeekdayPolicy::Slide => format!("{}, moved forward to the next {}", every(Repeating::Daily, interval), weekdays_singular(weekdays, weekend)),
+            WeekdayPolicy::Count => format!("Every {} {}", ordinal(interval), weekdays_singular(weekdays, weekend)),
+        }
+    }
+
+    /// The days of a monthly period, as in "the 19th" or "the 2nd and last Tuesday".
+    fn describe_month_days(&self, weekend: WeekendDefinition) -> String {
+        match self.monthly_by() {
+            Some(MonthlyBy::Days { days, missing }) => {
+                let clamped = *missing == MissingDay::Clamp && days.iter().any(|day| day.get().abs() > 28);
+
+                format!("{}{}", month_days(days.iter().map(|day| day.get() as i64)), if clamped { ", clamped to shorter months" } else { "" })
+            },
+            Some(MonthlyBy::NthWeekday { weekday, ordinals: nth }) => {
+                format!("the {} {}", ordinals(nth.iter().map(|ordinal| *ordinal as i64)), FULL_WEEKDAY_NAMES[weekday.num_days_from_monday() as usize])
+            },
+            None if !self.set_positions().is_empty() => weekdays_plural(self.weekdays(), weekend),
+            None => format!("the {}", ordinal(self.start_date().day() as u64)),
+        }
+    }
+
+    fn describe_year_days(&self, weekend: WeekendDefinition) -> String {
+        if !self.iso_weeks().is_empty() {
+            let weeks = if self.iso_weeks().len() == 1 { "week" } else { "weeks" };
+
+            return format!("{} in ISO {} {}", weekdays_plural(self.weekdays(), weekend), weeks, join(self.iso_weeks().iter().map(u8::to_string), "and"));
+        }
+
+        if let Some(year_days) = self.year_days() {
+            let days = if year_days.days().len() == 1 { "day" } else { "days" };
+            let clamped = if year_days.missing() == MissingDay::Clamp && year_days.days().contains(&366) { ", clamped to shorter years" } else { "" };
+
+            return format!("the {} {} of the year{}", join(year_days.days().iter().map(|day| ordinal(*day as u64)), "and"), days, clamped);
+        }
+
+        let months = match self.months() {
+            Some(months) => join(months.month_numbers().map(|month| month_name(month).to_string()), "and"),
+            None if self.monthly_by().is_none() && !self.set_positions().is_empty() => return weekdays_plural(self.weekdays(), weekend),
+            None if self.monthly_by().is_none() => return format!("{} {}", self.start_date().day(), month_name(self.start_date().month())),
+            None => month_name(self.start_date().month()).to_string(),
+        };
+
+        match self.monthly_by() {
+            None if !self.set_positions().is_empty() => format!("{} in {}", weekdays_plural(self.weekdays(), weekend), months),
+            _ => format!("{} of {}", self.describe_month_days(weekend), months),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, "1st")]
+    #[case(2, "2nd")]
+    #[case(3, "3rd")]
+    #[case(4, "4th")]
+    #[case(11, "11th")]
+    #[case(12, "12th")]
+    #[case(13, "13th")]
+    #[case(21, "21st")]
+    #[case(22, "22nd")]
+    #[case(101, "101st")]
+    #[case(111, "111th")]
+    fn ordinals(#[case] value: u64, #[case] expected: &str) {
+        assert_eq!(expected, ordinal(value));
+    }
+
+    #[test]
+    fn joins_without_an_oxford_comma() {
+        let words = |items: &[&str]| join(items.iter().map(|item| item.to_string()), "and");
+
+        assert_eq!("", words(&[]));
+        assert_eq!("Monday", words(&["Monday"]));
+        assert_eq!("Monday and Friday", words(&["Monday", "Friday"]));
+        assert_eq!("Monday, Wednesday and Friday", words(&["Monday", "Wednesday", "Friday"]));
+    }
+
+    #[test]
+    fn describes_the_documented_example() {
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 9, 21).unwrap();
+        let schedule = Schedule::new(start, WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2).unwrap();
+
+        assert_eq!("Every 2 weeks on Tuesday and Thursday, starting 21 Sep 2023", schedule.describe());
+    }
+
+    #[rstest]
+    #[case::daily("daily;start=2023-09-21", "Every day, starting 21 Sep 2023")]
+    #[case::every_3_days("daily;interval=3;start=2023-09-21", "Every 3 days, starting 21 Sep 2023")]
+    #[case::every_3_days_on_weekdays("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2023-09-21", "Every 3 days on weekdays, starting 21 Sep 2023")]
+    #[case::weekdays("daily;days=MON,TUE,WED,THU,FRI;start=2023-09-21", "Every weekday, starting 21 Sep 2023")]
+    #[case::some_days("daily;days=MON,WED,THU;start=2023-09-21", "Every Monday, Wednesday or Thursday, starting 21 Sep 2023")]
+    #[case::sliding("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2023-09-21;policy=slide", "Every 3 days, moved forward to the next weekday, starting 21 Sep 2023")]
+    #[case::counting("daily;interval=5;days=MON,TUE,WED,THU,FRI;start=2023-09-21;policy=count", "Every 5th weekday, starting 21 Sep 2023")]
+    #[case::shifts("daily;start=2023-09-21;shift=8:0,1,2,3", "On days 1, 2, 3 and 4 of every 8 day cycle, starting 21 Sep 2023")]
+    #[case::weekly("weekly;days=THU;start=2023-09-21", "Every week on Thursday, starting 21 Sep 2023")]
+    #[case::weekly_on_weekends("weekly;interval=3;days=SAT,SUN;start=2023-09-23", "Every 3 weeks on weekends, starting 23 Sep 2023")]
+    #[case::weekly_every_day("weekly;start=2023-09-21", "Every week on every day of the week, starting 21 Sep 2023")]
+    #[case::monthly("monthly;start=2023-09-19", "Every month on the 19th, starting 19 Sep 2023")]
+    #[case::monthly_on_a_friday("monthly;days=FRI;start=2023-10-13", "Every month on the 13th when it falls on a Friday, starting 13 Oct 2023")]
+    #[case::month_days("monthly;interval=2;start=2023-09-01;monthdays=1,15,-1", "Every 2 months on the 1st, 15th and last day, starting 1 Sep 2023")]
+    #[case::second_to_last("monthly;start=2023-09-29;monthdays=-2", "Every month on the 2nd to last day, starting 29 Sep 2023")]
+    #[case::clamped("monthly;start=2023-09-30;monthdays=31;missing=clamp", "Every month on the 31st, clamped to shorter months, starting 30 Sep 2023")]
+    #[case::nth_weekday("monthly;start=2023-09-12;nth=2TUE,-1TUE", "Every month on the 2nd and last Tuesday, starting 12 Sep 2023")]
+    #[case::last_workday("monthly;days=MON,TUE,WED,THU,FRI;start=2023-09-29;setpos=-1", "Every month on weekdays, keeping the last of each month, starting 29 Sep 2023")]
+    #[case::quarterly("quarterly;start=2023-09-30", "Every quarter on the 30th, starting 30 Sep 2023")]
+    #[case::every_2_quarters("quarterly;interval=2;start=2023-09-30", "Every 2 quarters on the 30th, starting 30 Sep 2023")]
+    #[case::yearly("yearly;interval=2;start=2023-09-12", "Every 2 years on 12 September, starting 12 Sep 2023")]
+    #[case::yearly_months("yearly;start=2024-01-01;months=JAN,JUL", "Every year on the 1st of January and July, starting 1 Jan 2024")]
+    #[case::mothers_day("yearly;start=2024-05-12;months=MAY;nth=2SUN", "Every year on the 2nd Sunday of May, starting 12 May 2024")]
+    #[case::yearly_days_without_months("yearly;start=2024-03-01;monthdays=1,-1", "Every year on the 1st and last day of March, starting 1 Mar 2024")]
+    #[case::iso_weeks("yearly;days=MON;start=2024-04-08;weeks=15,16", "Every year on Monday in ISO weeks 15 and 16, starting 8 Apr 2024")]
+    #[case::year_days("yearly;start=2024-04-09;yeardays=100", "Every year on the 100th day of the year, starting 9 Apr 2024")]
+    #[case::last_friday_of_the_year("yearly;days=FRI;start=2024-12-27;setpos=-1", "Every year on Friday, keeping the last of each year, starting 27 Dec 2024")]
+    #[case::times("daily;start=2023-09-21;times=08:00,20:00", "Every day at 08:00 and 20:00, starting 21 Sep 2023")]
+    #[case::seconds("daily;start=2023-09-21;times=08:00:30", "Every day at 08:00:30, starting 21 Sep 2023")]
+    #[case::weekday_times("weekly;days=MON,THU;start=2023-09-21;times=MON@09:00,THU@14:00", "Every week on Monday and Thursday at 09:00 on Monday and 14:00 on Thursday, starting 21 Sep 2023")]
+    #[case::mixed_times("weekly;days=MON,THU;start=2023-09-21;times=08:00,THU@14:00", "Every week on Monday and Thursday at 08:00 (14:00 on Thursday), starting 21 Sep 2023")]
+    #[case::until("weekly;days=THU;start=2023-09-21;until=2023-12-31", "Every week on Thursday, starting 21 Sep 2023, until 31 Dec 2023")]
+    #[case::count("monthly;start=2023-09-19;count=10", "Every month on the 19th, starting 19 Sep 2023, 10 times")]
+    #[case::once("monthly;start=2023-09-19;count=1", "Every month on the 19th, starting 19 Sep 2023, once")]
+    #[case::exceptions("daily;start=2023-12-20;exdate=2023-12-25,2024-01-01;rdate=2024-02-29", "Every day, starting 20 Dec 2023, except 25 Dec 2023 and 1 Jan 2024, and also on 29 Feb 2024")]
+    fn describes(#[case] rule: &str, #[case] expected: &str) {
+        assert_eq!(expected, Schedule::from_str(rule).unwrap().describe());
+    }
+
+    #[rstest]
+    #[case::weekend("weekly;days=FRI,SAT;start=2024-01-12", "Every week on weekends, starting 12 Jan 2024")]
+    #[case::workdays("weekly;days=MON,TUE,WED,THU,SUN;start=2024-01-14", "Every week on weekdays, starting 14 Jan 2024")]
+    #[case::every_workday("daily;days=MON,TUE,WED,THU,SUN;start=2024-01-14", "Every weekday, starting 14 Jan 2024")]
+    #[case::saturday_sunday("weekly;days=SAT,SUN;start=2024-01-13", "Every week on Saturday and Sunday, starting 13 Jan 2024")]
+    fn describes_with_a_friday_saturday_weekend(#[case] rule: &str, #[case] expected: &str) {
+        assert_eq!(expected, Schedule::from_str(rule).unwrap().describe_with(WeekendDefinition::FRIDAY_SATURDAY));
+    }
+}
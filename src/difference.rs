@@ -0,0 +1,185 @@
+use crate::{lcm, RepeatingDateError, Schedule};
+
+/// Longest search for a remaining date unless a horizon is set explicitly: one 400 year Gregorian
+/// cycle.
+const DEFAULT_MAX_HORIZON_DAYS: u64 = 146_097;
+
+/// The dates of one schedule that another schedule does not have, see `Schedule::minus`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleDifference {
+    minuend: Schedule,
+    subtrahend: Schedule,
+    horizon_days: u64
+}
+
+impl Schedule {
+    /// The dates of this schedule on which `other` does not occur.
+    ///
+    /// Lookups step through this schedule past every date `other` suppresses, giving up with
+    /// `HorizonExceeded` after `horizon_days` days without a remaining date. Those days are counted
+    /// from the latest exclusion, extra date or end of either schedule, after which the suppression
+    /// pattern repeats. The default horizon is one joint period of both rules, capped at 400 years,
+    /// see `ScheduleDifference::with_horizon`.
+    pub fn minus(&self, other: &Schedule) -> ScheduleDifference {
+        let joint_period = lcm(self.period_days(), other.period_days());
+        let horizon_days = u64::try_from(joint_period).unwrap_or(u64::MAX).min(DEFAULT_MAX_HORIZON_DAYS);
+
+        ScheduleDifference { minuend: self.clone(), subtrahend: other.clone(), horizon_days }
+    }
+}
+
+impl ScheduleDifference {
+    /// Searches up to `days` days past the starting point for a remaining date.
+    pub fn with_horizon(mut self, days: u64) -> ScheduleDifference {
+        self.horizon_days = days;
+        self
+    }
+
+    pub fn horizon_days(&self) -> u64 {
+        self.horizon_days
+    }
+
+    pub fn matches(&self, date: chrono::NaiveDate) -> bool {
+        self.minuend.matches(date) && !self.subtrahend.matches(date)
+    }
+
+    /// The first date after `from_date` on which the first schedule occurs and the second does not.
+    pub fn next_after(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        self.remaining_from(from_date, self.minuend.next_or_first(from_date)?)
+    }
+
+    fn remaining_from(&self, from_date: chrono::NaiveDate, mut date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        let one_offs = [&self.minuend, &self.subtrahend].into_iter()
+            .flat_map(|schedule| [schedule.exclusions().last().copied(), schedule.extra_dates().last().copied()])
+            .chain([self.subtrahend.last_occurrence()])
+            .flatten();
+
+        let anchor = one_offs.fold(from_date, chrono::NaiveDate::max);
+        let limit = anchor.checked_add_days(chrono::Days::new(self.horizon_days)).unwrap_or(chrono::NaiveDate::MAX);
+
+        while self.subtrahend.matches(date) {
+            if date > limit {
+                return Err(RepeatingDateError::HorizonExceeded);
+            }
+
+            date = self.minuend.next_after(date)?;
+        }
+
+        Ok(date)
+    }
+
+    /// The remaining dates in ascending order, ending once the first schedule ends or no remaining
+    /// date follows within the horizon.
+    pub fn iter(&self) -> ScheduleDifferenceIter<'_> {
+        ScheduleDifferenceIter { difference: self, last: None, done: false }
+    }
+}
+
+impl<'a> IntoIterator for &'a ScheduleDifference {
+    type Item = chrono::NaiveDate;
+    type IntoIter = ScheduleDifferenceIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleDifferenceIter<'a> {
+    difference: &'a ScheduleDifference,
+    last: Option<chrono::NaiveDate>,
+    done: bool
+}
+
+impl Iterator for ScheduleDifferenceIter<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let difference = self.difference;
+        let next = match self.last {
+            Some(last) => difference.next_after(last),
+            None => difference.minuend.first_occurrence()
+                .ok_or(RepeatingDateError::ScheduleEnded)
+                .and_then(|first| difference.remaining_from(first, first)),
+        };
+
+        self.last = next.ok();
+        self.done = self.last.is_none();
+        self.last
+    }
+}
+
+impl std::iter::FusedIterator for ScheduleDifferenceIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::test_util::date;
+
+    fn rule(value: &str) -> Schedule {
+        Schedule::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn workdays_without_all_hands() {
+        let workdays = rule("daily;days=MON,TUE,WED,THU,FRI;start=2023-09-04");
+        let difference = workdays.minus(&rule("weekly;interval=2;days=FRI;start=2023-09-08"));
+        let dates: Vec<chrono::NaiveDate> = difference.iter().take(10).collect();
+
+        let expected = ["2023-09-04", "2023-09-05", "2023-09-06", "2023-09-07", "2023-09-11", "2023-09-12", "2023-09-13", "2023-09-14", "2023-09-15", "2023-09-18"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+        assert_eq!(Ok(date("2023-09-25")), difference.next_after(date("2023-09-21")));
+        assert!(difference.matches(date("2023-09-15")));
+        assert!(!difference.matches(date("2023-09-22")));
+    }
+
+    #[test]
+    fn subtraction_empties_whole_weeks() {
+        let difference = rule("weekly;days=MON,TUE;start=2023-09-04").minus(&rule("weekly;interval=2;days=MON,TUE;start=2023-09-04"));
+        let dates: Vec<chrono::NaiveDate> = difference.iter().take(4).collect();
+
+        assert_eq!(["2023-09-11", "2023-09-12", "2023-09-25", "2023-09-26"].map(date).to_vec(), dates);
+        assert_eq!(Ok(date("2023-09-25")), difference.next_after(date("2023-09-12")));
+    }
+
+    #[test]
+    fn identical_schedules_leave_nothing() {
+        let daily = rule("daily;start=2023-09-01");
+        let difference = daily.minus(&daily);
+
+        assert_eq!(1, difference.horizon_days());
+        assert_eq!(Err(RepeatingDateError::HorizonExceeded), difference.next_after(date("2023-09-01")));
+        assert_eq!(None, difference.iter().next());
+        assert!(!difference.matches(date("2023-09-01")));
+
+        let monthly = rule("monthly;interval=5;days=TUE,WED;start=2024-01-31");
+        assert_eq!(Err(RepeatingDateError::HorizonExceeded), monthly.minus(&monthly).next_after(date("2024-02-01")));
+    }
+
+    #[test]
+    fn suppression_ending_after_a_long_run() {
+        let difference = rule("daily;start=2023-09-01").minus(&rule("daily;start=2023-09-01;until=2023-12-31"));
+
+        assert_eq!(Ok(date("2024-01-01")), difference.next_after(date("2023-09-01")));
+        assert_eq!(Some(date("2024-01-01")), difference.iter().next());
+        assert_eq!(Ok(date("2024-01-01")), difference.with_horizon(0).next_after(date("2023-09-01")));
+    }
+
+    #[test]
+    fn explicit_horizon_and_ended_minuend() {
+        let difference = rule("daily;start=2023-09-01").minus(&rule("daily;days=MON,TUE,WED,THU,FRI;start=2023-09-01"));
+
+        assert_eq!(Ok(date("2023-09-09")), difference.next_after(date("2023-09-04")));
+        assert_eq!(Err(RepeatingDateError::HorizonExceeded), difference.with_horizon(2).next_after(date("2023-09-04")));
+
+        let bounded = rule("daily;start=2023-09-01;count=5").minus(&rule("weekly;days=SAT,SUN;start=2023-09-02"));
+        assert_eq!(["2023-09-01", "2023-09-04", "2023-09-05"].map(date).to_vec(), bounded.iter().collect::<Vec<chrono::NaiveDate>>());
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), bounded.next_after(date("2023-09-05")));
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::Datelike;
+
+use crate::{lcm, Repeating, Schedule, WeekdayFlags, WeekdayPolicy};
+
+/// Days in the 400 year Gregorian cycle, after which dates repeat with the same weekday.
+const DAYS_PER_CYCLE: u128 = 146_097;
+const MONTHS_PER_CYCLE: u128 = 4800;
+
+/// Closed form of a rule that identifies its occurrence set independently of how it was spelled.
+#[derive(Debug, PartialEq, Eq)]
+enum Shape {
+    /// Exactly every `n` days.
+    Step(u64),
+    /// Every `interval` days, restricted to the weekdays.
+    Days { interval: u64, weekdays: WeekdayFlags },
+    Weeks { interval: u64, weekdays: WeekdayFlags },
+    /// The start's day of month every `months` months, restricted to the weekdays.
+    Months { months: u64, weekdays: WeekdayFlags },
+    /// Every `interval` days sliding to the next of the weekdays, or every `interval`th of them.
+    Slide { policy: WeekdayPolicy, interval: u64, weekdays: WeekdayFlags }
+}
+
+impl Schedule {
+    fn shape(&self) -> Shape {
+        let weekdays = WeekdayFlags::from_bits_masked(self.weekdays().bits());
+        let single_day = weekdays == WeekdayFlags::from_weekday(self.start_date().weekday());
+        let (repeat, interval) = self.step();
+
+        match repeat {
+            Repeating::Daily if weekdays.is_all() => Shape::Step(interval),
+            Repeating::Daily if self.slides() => Shape::Slide { policy: self.weekday_policy(), interval, weekdays },
+            Repeating::Daily if single_day => match interval % 7 {
+                0 => Shape::Step(interval),
+                _ => interval.checked_mul(7).map_or(Shape::Days { interval, weekdays }, Shape::Step),
+            },
+            Repeating::Daily => Shape::Days { interval, weekdays },
+            Repeating::Weekly if single_day => interval.checked_mul(7).map_or(Shape::Weeks { interval, weekdays }, Shape::Step),
+            Repeating::Weekly if interval == 1 && weekdays.is_all() => Shape::Step(1),
+            Repeating::Weekly if interval == 1 => Shape::Days { interval, weekdays },
+            Repeating::Weekly => Shape::Weeks { interval, weekdays },
+            Repeating::Monthly | Repeating::Quarterly => Shape::Months { months: interval, weekdays },
+            Repeating::Yearly => match interval.checked_mul(12) {
+                Some(months) => Shape::Months { months, weekdays },
+                None => Shape::Months { months: u64::MAX, weekdays },
+            },
+            Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        }
+    }
+
+    /// Length in days after which the rule's pattern repeats, saturating on overflow.
+    pub(crate) fn period_days(&self) -> u128 {
+        let (repeat, interval) = self.step();
+        let interval = interval as u128;
+
+        match repeat {
+            Repeating::Daily if self.is_expanded() => lcm(interval * self.cycle_days() as u128, 7),
+            Repeating::Daily if self.weekdays().is_all() => interval,
+            Repeating::Daily if self.slides() => self.slide_period_days() as u128,
+            Repeating::Daily => lcm(interval, 7),
+            Repeating::Weekly => interval * 7,
+            Repeating::Monthly | Repeating::Quarterly => lcm(interval, MONTHS_PER_CYCLE) / MONTHS_PER_CYCLE * DAYS_PER_CYCLE,
+            Repeating::Yearly => lcm(interval * 12, MONTHS_PER_CYCLE) / MONTHS_PER_CYCLE * DAYS_PER_CYCLE,
+            Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        }
+    }
+
+    /// Whether both rules produce the same occurrences, end conditions, exclusions, extra dates and
+    /// times of day included.
+    ///
+    /// Rules that only spell the same sequence differently (e.g. daily every 7 days and weekly on
+    /// the start's weekday, or yearly and monthly every 12 months) are recognised from their closed
+    /// form. Anything else is compared occurrence by occurrence over one full cycle, the least common
+    /// multiple of both rules' periods (a 400 year Gregorian cycle for monthly and yearly rules), after
+    /// which both patterns repeat; with exclusions or extra dates the cycle is counted from the latest
+    /// of those, and for sliding and counting rules from where their weekday pattern settles.
+    /// The comparison stops early at the first difference.
+    pub fn equivalent(&self, other: &Schedule) -> bool {
+        let first = self.first_occurrence();
+
+        if self.times_of_day() != other.times_of_day() || first != other.first_occurrence() || self.last_occurrence() != other.last_occurrence() {
+            return false;
+        }
+
+        let Some(first) = first else { return true };
+        let plain = [self, other].iter().all(|schedule| schedule.exclusions().is_empty() && schedule.extra_dates().is_empty())
+            && self.start_date() == other.start_date()
+            && self.monthly_by() == other.monthly_by()
+            && self.months() == other.months()
+            && self.weekday_policy() == other.weekday_policy()
+            && self.shift_pattern() == other.shift_pattern()
+            && self.set_positions() == other.set_positions()
+            && self.iso_weeks() == other.iso_weeks()
+            && self.year_days() == other.year_days();
+
+        let (shape, other_shape) = (self.shape(), other.shape());
+
+        if plain && shape == other_shape {
+            return true;
+        }
+
+        if let (true, Shape::Step(_), Shape::Step(_), None) = (plain, &shape, &other_shape, self.last_occurrence()) {
+            return false;
+        }
+
+        let latest_one_off = [self, other].iter().flat_map(|schedule| [schedule.exclusions().last(), schedule.extra_dates().last()]).flatten().max();
+        let cycle_start = latest_one_off.map_or(first, |date| first.max(*date)).max(self.pattern_start()).max(other.pattern_start());
+
+        let cycle = lcm(self.period_days(), other.period_days());
+        let horizon = u64::try_from(cycle).ok()
+            .and_then(|days| cycle_start.checked_add_days(chrono::Days::new(days)))
+            .unwrap_or(chrono::NaiveDate::MAX);
+
+        let mut current = first;
+
+        loop {
+            match (self.next_after(current), other.next_after(current)) {
+                (Ok(next), Ok(other_next)) if next == other_next => {
+                    if next >= horizon {
+                        return true;
+                    }
+
+                    current = next;
+                },
+                (Err(_), Err(_)) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+
+    fn rule(value: &str) -> Schedule {
+        Schedule::from_str(value).unwrap()
+    }
+
+    #[rstest]
+    #[case::identical("weekly;interval=2;days=TUE,THU;start=2023-09-12", "weekly;interval=2;days=TUE,THU;start=2023-09-12")]
+    #[case::reordered_days("weekly;interval=2;days=TUE,THU;start=2023-09-12", "weekly;interval=2;days=THU,TUE;start=2023-09-12")]
+    #[case::daily_7_weekly("daily;interval=7;start=2023-09-12", "weekly;interval=1;days=TUE;start=2023-09-12")]
+    #[case::daily_7_filtered_weekly("daily;interval=7;days=TUE,FRI;start=2023-09-12", "weekly;interval=1;days=TUE;start=2023-09-12")]
+    #[case::daily_one_day_weekly("daily;interval=1;days=TUE;start=2023-09-12", "weekly;interval=1;days=TUE;start=2023-09-12")]
+    #[case::daily_35_weekly_5("daily;interval=35;start=2023-09-12", "weekly;interval=5;days=TUE;start=2023-09-12")]
+    #[case::daily_filtered_weekly_1("daily;interval=1;days=TUE,THU;start=2023-09-12", "weekly;interval=1;days=TUE,THU;start=2023-09-12")]
+    #[case::all_days_weekly_1("daily;interval=1;start=2023-09-12", "weekly;interval=1;start=2023-09-12")]
+    #[case::yearly_monthly_12("yearly;interval=2;start=2023-09-12", "monthly;interval=24;start=2023-09-12")]
+    #[case::quarterly_monthly_3("quarterly;interval=2;days=TUE,SAT;start=2023-09-12", "monthly;interval=6;days=TUE,SAT;start=2023-09-12")]
+    #[case::quarterly_yearly("quarterly;interval=4;start=2024-02-29", "yearly;interval=1;start=2024-02-29")]
+    #[case::every_other_day_weekly_2("daily;interval=2;days=MON,WED,FRI;start=2023-09-11", "weekly;interval=2;days=MON,WED,FRI;start=2023-09-11")]
+    #[case::leap_day_yearly_4("yearly;interval=1;start=2024-02-29", "yearly;interval=4;start=2024-02-29")]
+    fn equal_pairs(#[case] left: &str, #[case] right: &str) {
+        assert!(rule(left).equivalent(&rule(right)));
+        assert!(rule(right).equivalent(&rule(left)));
+    }
+
+    #[rstest]
+    #[case::start_date("weekly;interval=2;days=TUE,THU;start=2023-09-12", "weekly;interval=2;days=TUE,THU;start=2023-09-26")]
+    #[case::daily_35_weekly_6("daily;interval=35;start=2023-09-12", "weekly;interval=6;days=TUE;start=2023-09-12")]
+    #[case::daily_42_weekly_5("daily;interval=42;start=2023-09-12", "weekly;interval=5;days=TUE;start=2023-09-12")]
+    // Identical until the first Sunday.
+    #[case::missing_sunday("daily;interval=1;days=MON,TUE,WED,THU,FRI,SAT;start=2023-09-11", "weekly;interval=1;start=2023-09-11")]
+    // Share the first on week, differ from the fourth week on.
+    #[case::week_3_week_6("weekly;interval=3;days=MON,TUE;start=2023-09-11", "weekly;interval=6;days=MON,TUE;start=2023-09-11")]
+    // Both skip February, April and June; they first differ on 2023-08-31.
+    #[case::day_31("monthly;interval=1;start=2023-01-31", "monthly;interval=2;start=2023-01-31")]
+    fn near_miss_pairs(#[case] left: &str, #[case] right: &str) {
+        assert!(!rule(left).equivalent(&rule(right)));
+        assert!(!rule(right).equivalent(&rule(left)));
+    }
+
+    #[rstest]
+    #[case::until_between("daily;interval=7;start=2023-09-12;until=2023-09-30", "weekly;interval=1;days=TUE;start=2023-09-12;until=2023-10-02")]
+    #[case::until_collapses_steps("daily;interval=7;start=2023-09-12;until=2023-09-13", "daily;interval=5;start=2023-09-12;until=2023-09-14")]
+    #[case::until_before_divergence("weekly;interval=3;days=MON,TUE;start=2023-09-11;until=2023-10-01", "weekly;interval=6;days=MON,TUE;start=2023-09-11;until=2023-10-02")]
+    #[case::count_until("weekly;interval=2;days=TUE,THU;start=2023-09-12;count=4", "weekly;interval=2;days=TUE,THU;start=2023-09-12;until=2023-10-09")]
+    fn bounded_equal_pairs(#[case] left: &str, #[case] right: &str) {
+        assert!(rule(left).equivalent(&rule(right)));
+        assert!(rule(right).equivalent(&rule(left)));
+    }
+
+    #[rstest]
+    #[case::one_bounded("daily;interval=7;start=2023-09-12;until=2023-09-30", "weekly;interval=1;days=TUE;start=2023-09-12")]
+    #[case::until_on_occurrence("daily;interval=7;start=2023-09-12;until=2023-09-30", "weekly;interval=1;days=TUE;start=2023-09-12;until=2023-10-03")]
+    fn bounded_near_miss_pairs(#[case] left: &str, #[case] right: &str) {
+        assert!(!rule(left).equivalent(&rule(right)));
+        assert!(!rule(right).equivalent(&rule(left)));
+    }
+
+    #[rstest]
+    #[case::same_exclusions("weekly;days=TUE;start=2023-09-12;exdate=2023-09-26", "daily;interval=7;start=2023-09-12;exdate=2023-09-26", true)]
+    #[case::exclusion_not_generated("weekly;days=TUE;start=2023-09-12;exdate=2023-09-26,2023-09-27", "daily;interval=7;start=2023-09-12;exdate=2023-09-26", true)]
+    #[case::one_excluded("weekly;days=TUE;start=2023-09-12;exdate=2023-09-26", "daily;interval=7;start=2023-09-12", false)]
+    #[case::excluded_start("weekly;days=TUE;start=2023-09-12;exdate=2023-09-12", "weekly;days=TUE;start=2023-09-19", true)]
+    #[case::excluded_odd_weeks("weekly;days=TUE;start=2023-09-12;count=4;exdate=2023-09-19,2023-10-03", "weekly;interval=2;days=TUE;start=2023-09-12;count=2", true)]
+    #[case::extra_date_fills_gap("weekly;interval=2;days=TUE;start=2023-09-12;count=2;rdate=2023-09-19", "weekly;days=TUE;start=2023-09-12;count=3", true)]
+    #[case::extra_date_on_occurrence("weekly;days=TUE;start=2023-09-12;rdate=2023-09-19", "daily;interval=7;start=2023-09-12", true)]
+    #[case::extra_date_only_one("weekly;days=TUE;start=2023-09-12;rdate=2023-09-20", "daily;interval=7;start=2023-09-12", false)]
+    fn one_off_dates(#[case] left: &str, #[case] right: &str, #[case] expected: bool) {
+        assert_eq!(expected, rule(left).equivalent(&rule(right)));
+        assert_eq!(expected, rule(right).equivalent(&rule(left)));
+    }
+
+    #[test]
+    fn near_miss_agrees_for_months() {
+        let take = |schedule: &Schedule| {
+            let mut result = vec![schedule.start_date()];
+
+            while result.len() < 5 {
+                result.push(schedule.next_after(*result.last().unwrap()).unwrap());
+            }
+
+            result
+        };
+
+        let left = take(&rule("monthly;interval=1;start=2023-01-31"));
+        let right = take(&rule("monthly;interval=2;start=2023-01-31"));
+
+        assert_eq!(left[..4], right[..4]);
+        assert_eq!(chrono::NaiveDate::from_ymd_opt(2023, 8, 31), Some(left[4]));
+        assert_eq!(chrono::NaiveDate::from_ymd_opt(2024, 1, 31), Some(right[4]));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use crate::{OccurrenceList, Schedule};
+
+/// What `Schedule::apply_exclusion_delta` changed about a schedule's occurrences, and which of the
+/// requested changes had nothing to do.
+///
+/// Exclusions never shift other occurrences. They are taken out after the rule, its weekday policy
+/// and its end are applied, and excluded dates still count towards `count`, occurrence indices and
+/// the gaps of `Slide` and `Count`. So there is deliberately no `shifted` set: the only dates that
+/// change are the listed ones, and a cached expansion is patched by removing `now_excluded` and
+/// inserting `now_included`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExclusionDelta {
+    /// Former occurrences the added exclusions removed.
+    pub now_excluded: OccurrenceList,
+    /// Dates that occur again now that their exclusion is removed.
+    pub now_included: OccurrenceList,
+    /// Added dates that were excluded already, or added more than once.
+    pub already_excluded: OccurrenceList,
+    /// Removed dates that were not excluded.
+    pub not_excluded: OccurrenceList
+}
+
+impl ExclusionDelta {
+    /// Whether the occurrences are unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.now_excluded.is_empty() && self.now_included.is_empty()
+    }
+}
+
+impl Schedule {
+    /// The schedule with `removed` taken out of its exclusions and then `added` put in, so a date
+    /// in both stays excluded, along with the occurrences that changed, see `ExclusionDelta`.
+    pub fn apply_exclusion_delta(&self, added: &[chrono::NaiveDate], removed: &[chrono::NaiveDate]) -> (Schedule, ExclusionDelta) {
+        let (mut already_excluded, mut not_excluded) = (BTreeSet::new(), BTreeSet::new());
+        let mut exclusions = self.exclusions().clone();
+
+        for date in removed {
+            if !exclusions.remove(date) {
+                not_excluded.insert(*date);
+            }
+        }
+
+        for date in added {
+            if !exclusions.insert(*date) {
+                already_excluded.insert(*date);
+            }
+        }
+
+        let schedule = self.clone().with_exclusions(exclusions);
+        let (mut now_excluded, mut now_included) = (BTreeSet::new(), BTreeSet::new());
+
+        for date in added.iter().chain(removed) {
+            match (self.matches(*date), schedule.matches(*date)) {
+                (true, false) => now_excluded.insert(*date),
+                (false, true) => now_included.insert(*date),
+                _ => false,
+            };
+        }
+
+        let list = |dates: BTreeSet<chrono::NaiveDate>| OccurrenceList::from_sorted_unchecked(dates.into_iter().collect());
+        let delta = ExclusionDelta { now_excluded: list(now_excluded), now_included: list(now_included), already_excluded: list(already_excluded), not_excluded: list(not_excluded) };
+
+        (schedule, delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{WeekdayFlags, WeekdayPolicy};
+    use crate::test_util::{date, dates};
+
+    /// Thursdays from 2024-01-04, with 2024-01-11 excluded.
+    fn thursdays() -> Schedule {
+        Schedule::builder().start(date("2024-01-04")).weekly().on(WeekdayFlags::THU).exclude(date("2024-01-11")).build().unwrap()
+    }
+
+    /// Every three days on weekdays from Monday 2024-01-01, sliding weekend dates to Monday and
+    /// counting on from there: 2024-01-04, 2024-01-08, 2024-01-11, 2024-01-15 and so on.
+    fn sliding() -> Schedule {
+        Schedule::builder().start(date("2024-01-01")).daily().interval(3).business_days().weekday_policy(WeekdayPolicy::Slide).count(20).build().unwrap()
+    }
+
+    /// Every second business day from Wednesday 2024-01-03, counted over weekdays only.
+    fn counting() -> Schedule {
+        Schedule::builder().start(date("2024-01-03")).daily().interval(2).business_days().weekday_policy(WeekdayPolicy::Count).count(20).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::excludes_an_occurrence(&["2024-01-18"], &[], &["2024-01-18"], &[])]
+    #[case::includes_an_occurrence(&[], &["2024-01-11"], &[], &["2024-01-11"])]
+    #[case::excludes_a_non_occurrence(&["2024-01-19"], &[], &[], &[])]
+    #[case::moves_an_exclusion(&["2024-01-25"], &["2024-01-11"], &["2024-01-25"], &["2024-01-11"])]
+    #[case::added_and_removed(&["2024-01-18"], &["2024-01-18"], &["2024-01-18"], &[])]
+    fn reports_changed_occurrences(#[case] added: &[&str], #[case] removed: &[&str], #[case] now_excluded: &[&str], #[case] now_included: &[&str]) {
+        let (schedule, delta) = thursdays().apply_exclusion_delta(&dates(added), &dates(removed));
+
+        assert_eq!(dates(now_excluded).as_slice(), delta.now_excluded.as_slice());
+        assert_eq!(dates(now_included).as_slice(), delta.now_included.as_slice());
+        assert!(dates(added).iter().all(|added| schedule.exclusions().contains(added)));
+    }
+
+    #[test]
+    fn reports_duplicates() {
+        let (schedule, delta) = thursdays().apply_exclusion_delta(&dates(&["2024-01-18", "2024-01-11", "2024-01-18"]), &[]);
+
+        assert_eq!(dates(&["2024-01-11", "2024-01-18"]).as_slice(), delta.already_excluded.as_slice());
+        assert_eq!(dates(&["2024-01-18"]).as_slice(), delta.now_excluded.as_slice());
+        assert_eq!(2, schedule.exclusions().len());
+    }
+
+    #[test]
+    fn reports_removing_an_absent_date() {
+        let (schedule, delta) = thursdays().apply_exclusion_delta(&[], &dates(&["2024-01-19", "2024-01-18"]));
+
+        assert_eq!(dates(&["2024-01-18", "2024-01-19"]).as_slice(), delta.not_excluded.as_slice());
+        assert!(delta.is_empty());
+        assert_eq!(thursdays(), schedule);
+    }
+
+    #[rstest]
+    #[case::slid_occurrence(sliding(), "2024-01-08")]
+    #[case::counted_occurrence(counting(), "2024-01-05")]
+    fn excluding_never_shifts_the_rest(#[case] schedule: Schedule, #[case] excluded: &str) {
+        let (patched, delta) = schedule.apply_exclusion_delta(&[date(excluded)], &[]);
+        let mut expected = schedule.occurrences(date("2023-12-31")).collect::<Vec<_>>();
+        expected.retain(|occurrence| *occurrence != date(excluded));
+
+        assert_eq!(dates(&[excluded]).as_slice(), delta.now_excluded.as_slice());
+        assert_eq!(expected, patched.occurrences(date("2023-12-31")).collect::<Vec<_>>());
+        assert_eq!(schedule.last_occurrence(), patched.last_occurrence());
+    }
+
+    /// Small deterministic generator so the equivalence checks are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        /// Up to seven dates in the first quarter of 2024, drawn from `occurrences` for every
+        /// other pick so most deltas touch real occurrences.
+        fn dates(&mut self, occurrences: &[chrono::NaiveDate]) -> Vec<chrono::NaiveDate> {
+            let len = self.next() % 8;
+
+            (0..len).map(|_| match self.next() % 2 {
+                0 if !occurrences.is_empty() => occurrences[self.next() as usize % occurrences.len()],
+                _ => date("2024-01-01") + chrono::Days::new(self.next() % 90),
+            }).collect()
+        }
+    }
+
+    #[rstest]
+    #[case::weekly(thursdays())]
+    #[case::sliding(sliding())]
+    #[case::counting(counting())]
+    fn patched_expansion_equals_recomputed_one(#[case] schedule: Schedule) {
+        let window = |schedule: &Schedule| schedule.occurrences_between(date("2024-01-01"), date("2024-04-30"));
+        let mut random = Lcg(7);
+        let mut current = schedule;
+
+        for _ in 0..200 {
+            let before = window(&current);
+            let (added, removed) = (random.dates(&before), random.dates(&before));
+            let (patched, delta) = current.apply_exclusion_delta(&added, &removed);
+
+            assert_eq!(before.difference(&delta.now_excluded).union(&delta.now_included), window(&patched), "added {:?}, removed {:?}", added, removed);
+            current = patched;
+        }
+    }
+}
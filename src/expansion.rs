@@ -0,0 +1,299 @@
+use chrono::Datelike;
+
+use crate::month_day::days_in_month;
+use crate::{day_after, get_months_since, week_start_day, weeks_between, MonthDay, MonthFlags, Repeating, RepeatingDateError, Schedule, WeekStart};
+
+// Rules with a `MonthlyBy`, a month set, ISO weeks, days of the year, a shift pattern or set
+// positions pick several dates per period (a shift cycle, week, month or year, following the
+// frequency; an ISO year for ISO weeks) instead of one date per step. Lookups resolve the picked dates of the on-periods in turn.
+impl Schedule {
+    /// Whether the rule picks its dates per period, see `period_dates`.
+    pub(crate) fn is_expanded(&self) -> bool {
+        self.monthly_by().is_some() || self.months().is_some() || !self.set_positions().is_empty() || !self.iso_weeks().is_empty() || self.year_days().is_some() || self.shift_pattern().is_some()
+    }
+
+    /// Longest run of on-periods searched for a picked date: one 400 year cycle, as for plain
+    /// monthly rules.
+    fn max_period_skip(&self) -> u64 {
+        match self.step().0 {
+            Repeating::Yearly => 400,
+            _ => 4800,
+        }
+    }
+
+    /// Number of whole periods from the start's to the one holding `date`, which is not before
+    /// the start.
+    pub(crate) fn periods_since(&self, date: chrono::NaiveDate) -> u64 {
+        match self.step().0 {
+            Repeating::Yearly if !self.iso_weeks().is_empty() => (date.iso_week().year() - self.start_date().iso_week().year()) as u64,
+            Repeating::Yearly => (date.year() - self.start_date().year()) as u64,
+            Repeating::Weekly => weeks_between(self.start_date(), date, WeekStart::Monday) as u64,
+            Repeating::Daily => (date - self.start_date()).num_days() as u64 / self.cycle_days(),
+            _ => get_months_since(date, self.start_date()) as u64,
+        }
+    }
+
+    /// Length of a daily period: the shift pattern's cycle.
+    pub(crate) fn cycle_days(&self) -> u64 {
+        self.shift_pattern().map_or(1, |pattern| pattern.cycle_days() as u64)
+    }
+
+    /// The months of a yearly period the rule picks from: the month set, else the start's month,
+    /// else all of them for set positions alone ("the last Friday of the year").
+    fn yearly_months(&self) -> MonthFlags {
+        match self.months() {
+            Some(months) => months,
+            None if self.monthly_by().is_none() && !self.set_positions().is_empty() => MonthFlags::ANY,
+            None => MonthFlags::from_bits_retain(1 << (self.start_date().month() - 1)),
+        }
+    }
+
+    /// The most dates the rule can pick in a single period.
+    pub(crate) fn max_per_period(&self) -> usize {
+        let positions = self.set_positions().len();
+        let per_month = match self.monthly_by() {
+            Some(rule) => rule.max_per_month(),
+            None if positions > 0 => 31,
+            None => 1,
+        };
+
+        let candidates = match self.step().0 {
+            Repeating::Yearly if !self.iso_weeks().is_empty() => self.iso_weeks().len() * 7,
+            Repeating::Yearly if self.year_days().is_some() => self.year_days().map_or(0, |year_days| year_days.days().len()),
+            Repeating::Yearly => self.yearly_months().month_numbers().count() * per_month,
+            Repeating::Weekly => 7,
+            Repeating::Daily => self.shift_pattern().map_or(1, |pattern| pattern.on_days().len()),
+            _ => per_month,
+        };
+
+        if positions > 0 { candidates.min(positions) } else { candidates }
+    }
+
+    /// The dates the rule picks in the period `periods` periods after the start's, ascending. With
+    /// set positions these are already narrowed by the weekday filter, which the positions count.
+    pub(crate) fn period_dates(&self, periods: u64) -> Option<Vec<chrono::NaiveDate>> {
+        let start_date = self.start_date();
+
+        let candidates: Vec<chrono::NaiveDate> = match self.step().0 {
+            Repeating::Yearly if !self.iso_weeks().is_empty() => {
+                let year = start_date.iso_week().year().checked_add(i32::try_from(periods).ok()?)?;
+
+                // Years with only 52 weeks have no week 53.
+                self.iso_weeks().iter()
+                    .filter_map(|week| chrono::NaiveDate::from_isoywd_opt(year, *week as u32, chrono::Weekday::Mon))
+                    .flat_map(|monday| monday.iter_days().take(7))
+                    .collect()
+            },
+            Repeating::Yearly => {
+                let year = start_date.year().checked_add(i32::try_from(periods).ok()?)?;
+
+                match self.year_days() {
+                    Some(year_days) => year_days.dates_in(year),
+                    None => self.yearly_months().month_numbers().flat_map(|month| self.month_dates(year, month)).collect(),
+                }
+            },
+            Repeating::Daily => {
+                let cycle_start = start_date.checked_add_days(chrono::Days::new(periods.checked_mul(self.cycle_days())?))?;
+
+                self.shift_pattern()?.dates_in(cycle_start)
+            },
+            Repeating::Weekly => {
+                let start_week = week_start_day(start_date, WeekStart::Monday);
+                let first_day = periods.checked_mul(7)?;
+
+                // The calendar's first week lacks its first days.
+                (first_day..first_day.saturating_add(7)).filter_map(|day| day_after(start_week, day)).collect()
+            },
+            _ => {
+                let months = chrono::Months::new(u32::try_from(periods).ok()?);
+                let first_of_month = start_date.with_day(1)?.checked_add_months(months)?;
+
+                self.month_dates(first_of_month.year(), first_of_month.month())
+            },
+        };
+
+        if self.set_positions().is_empty() {
+            return Some(candidates);
+        }
+
+        let allowed: Vec<chrono::NaiveDate> = candidates.into_iter().filter(|date| self.allows(*date)).collect();
+        let count = allowed.len() as i64;
+
+        let mut dates: Vec<chrono::NaiveDate> = self.set_positions().iter()
+            .filter_map(|position| {
+                let index = if *position > 0 { *position as i64 - 1 } else { count + *position as i64 };
+
+                usize::try_from(index).ok().and_then(|index| allowed.get(index).copied())
+            })
+            .collect();
+
+        dates.sort();
+        dates.dedup();
+        Some(dates)
+    }
+
+    fn month_dates(&self, year: i32, month: u32) -> Vec<chrono::NaiveDate> {
+        match self.monthly_by() {
+            Some(rule) => rule.dates_in(year, month),
+            None if !self.set_positions().is_empty() => {
+                let days = days_in_month(year, month).unwrap_or_default();
+
+                (1..=days).filter_map(|day| chrono::NaiveDate::from_ymd_opt(year, month, day)).collect()
+            },
+            None => MonthDay::of(self.start_date()).resolve(year, month).into_iter().collect(),
+        }
+    }
+
+    /// `find_next_date` for an expanded rule.
+    pub(crate) fn next_expanded(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        if from_date < self.start_date() {
+            return Err(RepeatingDateError::StartDateBeforeBound { from: from_date, start: self.start_date() });
+        }
+
+        let interval = self.step().1;
+        let periods_since = self.periods_since(from_date);
+        let mut periods = periods_since - periods_since % interval;
+
+        for _ in 0..self.max_period_skip() {
+            let dates = self.period_dates(periods).ok_or(RepeatingDateError::NoOccurrenceFound)?;
+
+            if let Some(date) = dates.into_iter().find(|date| *date > from_date && self.allows(*date)) {
+                return Ok(date);
+            }
+
+            periods = periods.checked_add(interval).ok_or(RepeatingDateError::NoOccurrenceFound)?;
+        }
+
+        Err(RepeatingDateError::NoOccurrenceFound)
+    }
+
+    /// `match_repeating_date` for an expanded rule.
+    pub(crate) fn matches_expanded(&self, date: chrono::NaiveDate) -> bool {
+        if date < self.start_date() || !self.allows(date) {
+            return false;
+        }
+
+        let periods = self.periods_since(date);
+
+        periods.is_multiple_of(self.step().1) && self.period_dates(periods).is_some_and(|dates| dates.contains(&date))
+    }
+
+    /// The latest picked date before `from_date`, which is after the start.
+    pub(crate) fn previous_expanded(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let interval = self.step().1;
+        let periods_since = self.periods_since(from_date);
+        let mut periods = periods_since - periods_since % interval;
+
+        for _ in 0..self.max_period_skip() {
+            let previous = self.period_dates(periods)?.into_iter().rev()
+                .find(|date| *date < from_date && *date >= self.start_date() && self.allows(*date));
+
+            if previous.is_some() {
+                return previous;
+            }
+
+            periods = periods.checked_sub(interval)?;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::WeekdayFlags;
+    use crate::test_util::date;
+
+    fn rule(value: &str) -> Schedule {
+        Schedule::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn last_workday_of_each_month() {
+        let schedule = rule("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-31;setpos=-1");
+
+        assert_eq!(["2024-02-29", "2024-03-29", "2024-04-30", "2024-05-31", "2024-06-28"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-31"), 5).unwrap().into_vec());
+        assert!(schedule.matches(date("2024-06-28")));
+        assert!(!schedule.matches(date("2024-06-27")));
+        assert!(!schedule.matches(date("2024-06-30")));
+        assert_eq!(Some(date("2024-05-31")), schedule.previous_before(date("2024-06-28")));
+    }
+
+    #[test]
+    fn second_occurrence_each_week() {
+        let schedule = Schedule::builder().start(date("2024-01-03")).weekly().on(WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI).set_positions([2]).build().unwrap();
+
+        assert_eq!(["2024-01-10", "2024-01-17"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-03"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-01-08")));
+        assert!(!schedule.matches(date("2024-01-12")));
+    }
+
+    #[test]
+    fn positions_past_the_candidates_leave_the_period_empty() {
+        let schedule = rule("monthly;days=MON;start=2024-01-29;setpos=5");
+
+        assert_eq!(["2024-04-29", "2024-07-29"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-29"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-02-26")));
+    }
+
+    #[test]
+    fn several_positions_at_once() {
+        let schedule = rule("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-01;setpos=1,-1");
+
+        assert_eq!(["2024-01-31", "2024-02-01", "2024-02-29", "2024-03-01", "2024-03-29"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 5).unwrap().into_vec());
+        assert_eq!(Some(3), schedule.occurrence_index(date("2024-02-29")));
+        assert_eq!(Ok(date("2024-03-29")), schedule.nth_occurrence(5));
+    }
+
+    #[test]
+    fn last_weekday_every_other_week() {
+        let schedule = rule("weekly;interval=2;days=MON,TUE,WED,THU,FRI;start=2024-01-05;setpos=-1");
+
+        assert_eq!(["2024-01-19", "2024-02-02"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-05"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-01-12")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2024-02-02")));
+    }
+
+    #[test]
+    fn last_friday_of_the_year() {
+        let schedule = rule("yearly;days=FRI;start=2024-12-27;setpos=-1");
+
+        assert_eq!(["2025-12-26", "2026-12-25"].map(date).to_vec(), schedule.next_n_dates(date("2024-12-27"), 2).unwrap().into_vec());
+        assert_eq!(Ok(date("2025-12-26")), schedule.next_after(date("2025-06-01")));
+        assert!(!schedule.matches(date("2025-12-19")));
+    }
+
+    #[test]
+    fn monday_of_iso_week_33() {
+        let schedule = rule("yearly;days=MON;start=2024-08-12;weeks=33");
+
+        assert_eq!(["2025-08-11", "2026-08-10"].map(date).to_vec(), schedule.next_n_dates(date("2024-08-12"), 2).unwrap().into_vec());
+        assert_eq!(Ok(date("2025-08-11")), schedule.next_after(date("2025-01-01")));
+        assert_eq!(Ok(date("2026-08-10")), schedule.next_after(date("2025-09-01")));
+        assert!(schedule.matches(date("2026-08-10")));
+        assert!(!schedule.matches(date("2026-08-11")));
+        assert!(!schedule.matches(date("2026-08-12")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2026-08-10")));
+    }
+
+    #[test]
+    fn week_53_skips_years_with_52_weeks() {
+        let schedule = rule("yearly;days=MON,FRI;start=2020-12-28;weeks=53");
+
+        assert_eq!(["2021-01-01", "2026-12-28", "2027-01-01"].map(date).to_vec(), schedule.next_n_dates(date("2020-12-28"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2021-12-27")));
+        assert_eq!(Some(date("2021-01-01")), schedule.previous_before(date("2026-12-28")));
+    }
+
+    #[test]
+    fn iso_years_cross_calendar_years() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).yearly().interval(2).on(WeekdayFlags::MON).iso_weeks([1]).build().unwrap();
+
+        // Week 1 of 2026 begins on December 29, 2025.
+        assert_eq!(["2025-12-29", "2028-01-03"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-12-30")));
+    }
+}
@@ -0,0 +1,223 @@
+//! Google Calendar API event resources, behind the `gcal` feature. An event's recurrence is its
+//! `start` object together with `recurrence`, an array of `RRULE`, `EXDATE` and `RDATE` lines.
+
+use std::collections::BTreeSet;
+
+use serde_json::{json, Value};
+
+use crate::ics::{date_values, parse_line};
+use crate::{RepeatingDateError, RruleError, Schedule, TimesOfDay};
+
+/// The recurrence of a Google Calendar event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcalRecurrence {
+    /// With the start's time as its single time of day for a `dateTime` start, and no times for
+    /// an all-day `date` start.
+    pub schedule: Schedule,
+    /// `start.timeZone`, the IANA zone the times of day are in.
+    pub time_zone: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GcalError {
+    /// A field, as a dotted path like `start.date`, that is absent or not of the expected JSON type.
+    MissingField { field: &'static str },
+    InvalidField { field: &'static str, value: String },
+    /// `recurrence` has no `RRULE` line.
+    MissingRule,
+    /// A `recurrence` line that is not an `RRULE`, `EXDATE` or `RDATE` of dates or date-times, or
+    /// a second `RRULE`.
+    UnsupportedLine { line: String },
+    InvalidLine { line: String },
+    Rrule(RruleError),
+    /// Every field parsed, but `Schedule` rejected the combination.
+    InvalidRule(RepeatingDateError),
+    /// Writing an event whose schedule has a time of day needs `time_zone`.
+    MissingTimeZone
+}
+
+impl std::fmt::Display for GcalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcalError::MissingField { field } => write!(f, "event field {} is missing", field),
+            GcalError::InvalidField { field, value } => write!(f, "event field {} value `{}` is invalid", field, value),
+            GcalError::MissingRule => write!(f, "event recurrence has no RRULE"),
+            GcalError::UnsupportedLine { line } => write!(f, "event recurrence line `{}` is not supported", line),
+            GcalError::InvalidLine { line } => write!(f, "event recurrence line `{}` is invalid", line),
+            GcalError::Rrule(error) => write!(f, "{}", error),
+            GcalError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            GcalError::MissingTimeZone => write!(f, "a time of day needs a time zone"),
+        }
+    }
+}
+
+impl std::error::Error for GcalError {}
+
+fn field<'a>(value: &'a Value, path: &'static str) -> Option<&'a str> {
+    path.split('.').try_fold(value, |value, key| value.get(key))?.as_str()
+}
+
+fn dates_line(name: &str, dates: &BTreeSet<chrono::NaiveDate>, time: Option<chrono::NaiveTime>, time_zone: Option<&str>) -> String {
+    let values = dates.iter().map(|date| match time {
+        Some(time) => date.and_time(time).format("%Y%m%dT%H%M%S").to_string(),
+        None => date.format("%Y%m%d").to_string(),
+    }).collect::<Vec<String>>().join(",");
+
+    match (time, time_zone) {
+        (Some(_), Some(zone)) => format!("{};TZID={}:{}", name, zone, values),
+        _ => format!("{};VALUE=DATE:{}", name, values),
+    }
+}
+
+impl GcalRecurrence {
+    /// Reads the recurrence of an event resource as the API returns it, e.g. from `events.get`:
+    /// `start.date` for an all-day event, otherwise `start.dateTime`, whose local time is kept
+    /// and offset dropped, with `start.timeZone`. `EXDATE` and `RDATE` lines become exclusions and
+    /// extra dates by their dates; other fields are ignored.
+    pub fn from_event(event: &Value) -> Result<GcalRecurrence, GcalError> {
+        let (start_date, time) = match (field(event, "start.date"), field(event, "start.dateTime")) {
+            (Some(date), _) => (chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| GcalError::InvalidField { field: "start.date", value: date.to_string() })?, None),
+            (None, Some(datetime)) => {
+                let local = chrono::DateTime::parse_from_rfc3339(datetime).map(|datetime| datetime.naive_local())
+                    .or_else(|_| chrono::NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S"))
+                    .map_err(|_| GcalError::InvalidField { field: "start.dateTime", value: datetime.to_string() })?;
+
+                (local.date(), Some(local.time()))
+            },
+            (None, None) => return Err(GcalError::MissingField { field: "start.date" }),
+        };
+
+        let lines = event.get("recurrence").and_then(Value::as_array).ok_or(GcalError::MissingField { field: "recurrence" })?;
+        let mut rrule = None;
+        let mut exclusions = BTreeSet::new();
+        let mut extra_dates = BTreeSet::new();
+
+        for line in lines {
+            let line = line.as_str().ok_or_else(|| GcalError::InvalidLine { line: line.to_string() })?;
+            let property = parse_line(line).ok_or_else(|| GcalError::InvalidLine { line: line.to_string() })?;
+            let dates = || match date_values(&property) {
+                Ok(values) => Ok(values.into_iter().map(|(date, _)| date)),
+                Err(crate::IcsErrorKind::Unsupported { .. }) => Err(GcalError::UnsupportedLine { line: line.to_string() }),
+                Err(_) => Err(GcalError::InvalidLine { line: line.to_string() }),
+            };
+
+            match property.name.as_str() {
+                "RRULE" if rrule.is_none() => rrule = Some(property.value.clone()),
+                "EXDATE" => exclusions.extend(dates()?),
+                "RDATE" => extra_dates.extend(dates()?),
+                _ => return Err(GcalError::UnsupportedLine { line: line.to_string() }),
+            }
+        }
+
+        let rrule = rrule.ok_or(GcalError::MissingRule)?;
+        let schedule = Schedule::from_rrule(&rrule, start_date).map_err(GcalError::Rrule)?
+            .with_exclusions(exclusions)
+            .with_extra_dates(extra_dates)
+            .and_then(|schedule| schedule.with_times_of_day(time.and_then(|time| TimesOfDay::new([time]).ok())))
+            .map_err(GcalError::InvalidRule)?;
+
+        Ok(GcalRecurrence { schedule, time_zone: field(event, "start.timeZone").map(str::to_string) })
+    }
+
+    /// The `start` and `recurrence` fields of an event to insert, as a JSON object to merge into
+    /// the request body along with `end`, `summary` and the rest: `start.date` without a time of
+    /// day, otherwise `start.dateTime` as a local time with `start.timeZone`, which it then needs.
+    /// Exclusions and extra dates are written as `EXDATE` and `RDATE` lines after the `RRULE`,
+    /// whose errors from `Schedule::to_rrule` it shares.
+    pub fn to_event(&self) -> Result<Value, GcalError> {
+        let rule = self.schedule.clone().with_exclusions(BTreeSet::new()).with_extra_dates(BTreeSet::new()).map_err(GcalError::InvalidRule)?;
+        let time = self.schedule.times_of_day().and_then(|times| times.times().first().copied());
+        let mut recurrence = vec![format!("RRULE:{}", rule.to_rrule().map_err(GcalError::Rrule)?)];
+
+        if !self.schedule.exclusions().is_empty() {
+            recurrence.push(dates_line("EXDATE", self.schedule.exclusions(), time, self.time_zone.as_deref()));
+        }
+
+        if !self.schedule.extra_dates().is_empty() {
+            recurrence.push(dates_line("RDATE", self.schedule.extra_dates(), time, self.time_zone.as_deref()));
+        }
+
+        let start = match (time, &self.time_zone) {
+            (None, _) => json!({ "date": self.schedule.start_date().format("%Y-%m-%d").to_string() }),
+            (Some(time), Some(zone)) => json!({ "dateTime": self.schedule.start_date().and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string(), "timeZone": zone }),
+            (Some(_), None) => return Err(GcalError::MissingTimeZone),
+        };
+
+        Ok(json!({ "start": start, "recurrence": recurrence }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+
+    const EVENT: &str = include_str!("../tests/fixtures/gcal_event.json");
+
+    fn recurrence(rule: &str, time_zone: Option<&str>) -> GcalRecurrence {
+        GcalRecurrence { schedule: Schedule::from_str(rule).unwrap(), time_zone: time_zone.map(str::to_string) }
+    }
+
+    #[test]
+    fn reads_a_captured_event() {
+        let event: Value = serde_json::from_str(EVENT).unwrap();
+
+        assert_eq!(Ok(recurrence("weekly;days=TUE,THU;start=2024-01-02;exdate=2024-01-11;times=10:00", Some("America/Sao_Paulo"))), GcalRecurrence::from_event(&event));
+    }
+
+    #[test]
+    fn reads_an_all_day_event() {
+        let event = json!({ "start": { "date": "2024-01-15" }, "end": { "date": "2024-01-16" }, "recurrence": ["RRULE:FREQ=MONTHLY;COUNT=12"] });
+
+        assert_eq!(Ok(recurrence("monthly;start=2024-01-15;count=12", None)), GcalRecurrence::from_event(&event));
+    }
+
+    #[test]
+    fn reads_an_offset_without_a_zone() {
+        let event = json!({ "start": { "dateTime": "2024-01-02T23:30:00+01:00" }, "recurrence": ["RRULE:FREQ=DAILY"] });
+
+        assert_eq!(Ok(recurrence("daily;start=2024-01-02;times=23:30", None)), GcalRecurrence::from_event(&event));
+    }
+
+    #[rstest]
+    #[case::all_day(recurrence("monthly;start=2024-01-15;count=12", None), json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=MONTHLY;COUNT=12"] }))]
+    #[case::timed(
+        recurrence("weekly;days=TUE,THU;start=2024-01-02;exdate=2024-01-11;rdate=2024-01-13;times=10:00", Some("America/Sao_Paulo")),
+        json!({
+            "start": { "dateTime": "2024-01-02T10:00:00", "timeZone": "America/Sao_Paulo" },
+            "recurrence": ["RRULE:FREQ=WEEKLY;BYDAY=TU,TH", "EXDATE;TZID=America/Sao_Paulo:20240111T100000", "RDATE;TZID=America/Sao_Paulo:20240113T100000"],
+        })
+    )]
+    #[case::all_day_exclusions(
+        recurrence("daily;start=2024-12-20;exdate=2024-12-25,2025-01-01", None),
+        json!({ "start": { "date": "2024-12-20" }, "recurrence": ["RRULE:FREQ=DAILY", "EXDATE;VALUE=DATE:20241225,20250101"] })
+    )]
+    fn writes_and_reads_back(#[case] recurrence: GcalRecurrence, #[case] expected: Value) {
+        let event = recurrence.to_event().unwrap();
+
+        assert_eq!(expected, event);
+        assert_eq!(Ok(recurrence), GcalRecurrence::from_event(&event));
+    }
+
+    #[rstest]
+    #[case::no_start(json!({ "recurrence": ["RRULE:FREQ=DAILY"] }), GcalError::MissingField { field: "start.date" })]
+    #[case::bad_date(json!({ "start": { "date": "15/01/2024" }, "recurrence": ["RRULE:FREQ=DAILY"] }), GcalError::InvalidField { field: "start.date", value: "15/01/2024".to_string() })]
+    #[case::bad_datetime(json!({ "start": { "dateTime": "10am" }, "recurrence": ["RRULE:FREQ=DAILY"] }), GcalError::InvalidField { field: "start.dateTime", value: "10am".to_string() })]
+    #[case::not_recurring(json!({ "start": { "date": "2024-01-15" } }), GcalError::MissingField { field: "recurrence" })]
+    #[case::no_rule(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["EXDATE;VALUE=DATE:20240116"] }), GcalError::MissingRule)]
+    #[case::two_rules(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=DAILY", "RRULE:FREQ=WEEKLY"] }), GcalError::UnsupportedLine { line: "RRULE:FREQ=WEEKLY".to_string() })]
+    #[case::exrule(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=DAILY", "EXRULE:FREQ=WEEKLY"] }), GcalError::UnsupportedLine { line: "EXRULE:FREQ=WEEKLY".to_string() })]
+    #[case::period(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=DAILY", "RDATE;VALUE=PERIOD:20240116T090000Z/PT1H"] }), GcalError::UnsupportedLine { line: "RDATE;VALUE=PERIOD:20240116T090000Z/PT1H".to_string() })]
+    #[case::bad_exdate(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=DAILY", "EXDATE:tomorrow"] }), GcalError::InvalidLine { line: "EXDATE:tomorrow".to_string() })]
+    #[case::bad_rule(json!({ "start": { "date": "2024-01-15" }, "recurrence": ["RRULE:FREQ=HOURLY"] }), GcalError::Rrule(RruleError::UnsupportedPart { part: "FREQ=HOURLY".to_string() }))]
+    fn rejects(#[case] event: Value, #[case] expected: GcalError) {
+        assert_eq!(Err(expected), GcalRecurrence::from_event(&event));
+    }
+
+    #[test]
+    fn timed_events_need_a_zone() {
+        assert_eq!(Err(GcalError::MissingTimeZone), recurrence("daily;start=2024-01-02;times=10:00", None).to_event());
+    }
+}
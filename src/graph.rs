@@ -0,0 +1,521 @@
+//! Microsoft Graph `patternedRecurrence` objects, as on Outlook events, behind the `graph` feature.
+
+use chrono::Datelike;
+
+use crate::{get_months_since, MissingDay, MonthFlags, MonthlyBy, Repeating, RepeatingDateError, Schedule, WeekdayFlags, WeekdayPolicy, SEARCH_DAYS};
+
+const DAY_NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+/// The `recurrence` of a Graph event: how it repeats and for how long.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GraphRecurrence {
+    pub pattern: GraphPattern,
+    pub range: GraphRange
+}
+
+/// A `recurrencePattern`. Fields a pattern type does not use are left out when written, and
+/// read as Graph's zero values when missing.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPattern {
+    #[serde(rename = "type")]
+    pub kind: GraphPatternType,
+    #[serde(default = "one")]
+    pub interval: u64,
+    /// 1 to 12, for yearly patterns.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub month: u32,
+    /// For absolute patterns.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub day_of_month: u32,
+    /// For weekly and relative patterns; names are read case insensitively and written lower case.
+    #[serde(default, with = "day_names", skip_serializing_if = "Vec::is_empty")]
+    pub days_of_week: Vec<chrono::Weekday>,
+    /// Which day weeks start on, Sunday when missing.
+    #[serde(default, with = "day_name", skip_serializing_if = "Option::is_none")]
+    pub first_day_of_week: Option<chrono::Weekday>,
+    /// For relative patterns, `First` when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<GraphWeekIndex>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphPatternType {
+    Daily,
+    Weekly,
+    /// On `day_of_month`.
+    AbsoluteMonthly,
+    /// On the `index`-th of `days_of_week` in the month.
+    RelativeMonthly,
+    /// On `day_of_month` of `month`.
+    AbsoluteYearly,
+    /// On the `index`-th of `days_of_week` in `month`.
+    RelativeYearly
+}
+
+/// Which of the matching days in the month a relative pattern falls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphWeekIndex {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Last
+}
+
+impl GraphWeekIndex {
+    fn ordinal(self) -> i8 {
+        match self {
+            GraphWeekIndex::First => 1,
+            GraphWeekIndex::Second => 2,
+            GraphWeekIndex::Third => 3,
+            GraphWeekIndex::Fourth => 4,
+            GraphWeekIndex::Last => -1,
+        }
+    }
+
+    fn from_ordinal(ordinal: i64) -> Option<GraphWeekIndex> {
+        match ordinal {
+            1 => Some(GraphWeekIndex::First),
+            2 => Some(GraphWeekIndex::Second),
+            3 => Some(GraphWeekIndex::Third),
+            4 => Some(GraphWeekIndex::Fourth),
+            -1 => Some(GraphWeekIndex::Last),
+            _ => None,
+        }
+    }
+}
+
+/// A `recurrenceRange`. Graph sends `endDate` as `0001-01-01` and `numberOfOccurrences` as 0
+/// where the range type does not use them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphRange {
+    #[serde(rename = "type")]
+    pub kind: GraphRangeType,
+    pub start_date: chrono::NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<chrono::NaiveDate>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub number_of_occurrences: u32,
+    /// A Windows or IANA zone name, kept as given; it does not change the dates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence_time_zone: Option<String>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphRangeType {
+    EndDate,
+    NoEnd,
+    Numbered
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// A field the pattern or range type needs that is missing or out of range, named as in Graph.
+    InvalidValue { field: &'static str, value: String },
+    /// A pattern whose meaning a `Schedule` lacks the capability for; `"week_start"` is weeks
+    /// starting on another day than Monday, which only matters for weekly patterns that skip
+    /// weeks and have days on both sides of the week start.
+    Unsupported { capability: &'static str },
+    /// No date within eight years of the range's start matches the pattern.
+    NeverMatches,
+    /// Every field read, but `Schedule` rejected the combination, e.g. an end before the start.
+    InvalidRule(RepeatingDateError),
+    /// The `Schedule` features, named as in their getters, that a Graph recurrence cannot hold.
+    NotExpressible { features: Vec<&'static str> }
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::InvalidValue { field, value } => write!(f, "recurrence {} value `{}` is invalid", field, value),
+            GraphError::Unsupported { capability } => write!(f, "recurrence needs {}, which is not supported", capability),
+            GraphError::NeverMatches => write!(f, "recurrence never matches"),
+            GraphError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            GraphError::NotExpressible { features } => write!(f, "{} cannot be written as a Graph recurrence", features.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+fn one() -> u64 {
+    1
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+fn day_named(name: &str) -> Option<chrono::Weekday> {
+    let index = DAY_NAMES.iter().position(|candidate| candidate.eq_ignore_ascii_case(name))?;
+
+    Some(chrono::Weekday::try_from(index as u8).unwrap())
+}
+
+mod day_names {
+    use super::{day_named, DAY_NAMES};
+
+    pub fn serialize<S: serde::Serializer>(days: &[chrono::Weekday], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(days.iter().map(|day| DAY_NAMES[day.num_days_from_monday() as usize]))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<chrono::Weekday>, D::Error> {
+        let names = <Option<Vec<String>> as serde::Deserialize>::deserialize(deserializer)?.unwrap_or_default();
+
+        names.iter().map(|name| day_named(name).ok_or_else(|| serde::de::Error::unknown_variant(name, &DAY_NAMES))).collect()
+    }
+}
+
+mod day_name {
+    use super::{day_named, DAY_NAMES};
+
+    pub fn serialize<S: serde::Serializer>(day: &Option<chrono::Weekday>, serializer: S) -> Result<S::Ok, S::Error> {
+        match day {
+            Some(day) => serializer.serialize_str(DAY_NAMES[day.num_days_from_monday() as usize]),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<chrono::Weekday>, D::Error> {
+        <Option<String> as serde::Deserialize>::deserialize(deserializer)?
+            .map(|name| day_named(&name).ok_or_else(|| serde::de::Error::unknown_variant(&name, &DAY_NAMES)))
+            .transpose()
+    }
+}
+
+/// The start of the week of `date`, weeks starting on `first_day`.
+fn week_of(date: chrono::NaiveDate, first_day: chrono::Weekday) -> chrono::NaiveDate {
+    date - chrono::Days::new(((date.weekday().num_days_from_monday() + 7 - first_day.num_days_from_monday()) % 7) as u64)
+}
+
+impl Schedule {
+    /// Reads a Graph recurrence. The start is the first date on or after `range.startDate` that
+    /// the pattern falls on, in an interval counted from the range's start as Graph does, and
+    /// the range becomes `until` or `count`.
+    ///
+    /// Daily and weekly patterns map to daily and weekly rules; absolute patterns to monthly and
+    /// yearly rules on the start's day; relative ones on a single day to a
+    /// `MonthlyBy::NthWeekday`, and on several days to a weekday filter narrowed by a set
+    /// position, in the pattern's month on yearly rules.
+    pub fn from_graph(recurrence: &GraphRecurrence) -> Result<Schedule, GraphError> {
+        let pattern = &recurrence.pattern;
+        let range = &recurrence.range;
+        let weekdays = pattern.days_of_week.iter().fold(WeekdayFlags::empty(), |weekdays, weekday| weekdays | WeekdayFlags::from_weekday(*weekday));
+        let first_day = pattern.first_day_of_week.unwrap_or(chrono::Weekday::Sun);
+        let ordinal = pattern.index.unwrap_or(GraphWeekIndex::First).ordinal();
+
+        if pattern.interval == 0 {
+            return Err(GraphError::InvalidValue { field: "interval", value: pattern.interval.to_string() });
+        }
+
+        if weekdays.is_empty() && matches!(pattern.kind, GraphPatternType::Weekly | GraphPatternType::RelativeMonthly | GraphPatternType::RelativeYearly) {
+            return Err(GraphError::InvalidValue { field: "daysOfWeek", value: "[]".to_string() });
+        }
+
+        if !(1..=12).contains(&pattern.month) && matches!(pattern.kind, GraphPatternType::AbsoluteYearly | GraphPatternType::RelativeYearly) {
+            return Err(GraphError::InvalidValue { field: "month", value: pattern.month.to_string() });
+        }
+
+        if !(1..=31).contains(&pattern.day_of_month) && matches!(pattern.kind, GraphPatternType::AbsoluteMonthly | GraphPatternType::AbsoluteYearly) {
+            return Err(GraphError::InvalidValue { field: "dayOfMonth", value: pattern.day_of_month.to_string() });
+        }
+
+        // Weeks group the days alike from any start as long as none of them straddle it.
+        let split = first_day.num_days_from_monday();
+        let straddles = weekdays.extract_weekdays().any(|weekday| weekday.num_days_from_monday() < split) && weekdays.extract_weekdays().any(|weekday| weekday.num_days_from_monday() >= split);
+
+        if pattern.kind == GraphPatternType::Weekly && pattern.interval > 1 && straddles {
+            return Err(GraphError::Unsupported { capability: "week_start" });
+        }
+
+        let (until, count) = match range.kind {
+            GraphRangeType::NoEnd => (None, None),
+            GraphRangeType::EndDate => (Some(range.end_date.ok_or_else(|| GraphError::InvalidValue { field: "endDate", value: String::new() })?), None),
+            GraphRangeType::Numbered if range.number_of_occurrences == 0 => return Err(GraphError::InvalidValue { field: "numberOfOccurrences", value: "0".to_string() }),
+            GraphRangeType::Numbered => (None, Some(range.number_of_occurrences)),
+        };
+
+        let on_grid = |date: &chrono::NaiveDate| match pattern.kind {
+            GraphPatternType::Daily => true,
+            GraphPatternType::Weekly => ((week_of(*date, first_day) - week_of(range.start_date, first_day)).num_weeks() as u64).is_multiple_of(pattern.interval),
+            GraphPatternType::AbsoluteMonthly | GraphPatternType::RelativeMonthly => (get_months_since(*date, range.start_date) as u64).is_multiple_of(pattern.interval),
+            GraphPatternType::AbsoluteYearly | GraphPatternType::RelativeYearly => ((date.year() - range.start_date.year()) as u64).is_multiple_of(pattern.interval),
+        };
+
+        let build = |start_date: chrono::NaiveDate| {
+            let in_month = start_date.month() == pattern.month;
+            let builder = Schedule::builder().start(start_date).interval(pattern.interval);
+            let mut builder = match pattern.kind {
+                GraphPatternType::Daily => builder.daily(),
+                GraphPatternType::Weekly => builder.weekly().on(weekdays),
+                GraphPatternType::AbsoluteMonthly if start_date.day() == pattern.day_of_month => builder.monthly(),
+                GraphPatternType::AbsoluteYearly if in_month && start_date.day() == pattern.day_of_month => builder.yearly(),
+                GraphPatternType::RelativeMonthly if pattern.days_of_week.len() == 1 => builder.monthly().monthly_by(MonthlyBy::nth_weekday(pattern.days_of_week[0], [ordinal])),
+                GraphPatternType::RelativeMonthly => builder.monthly().on(weekdays).set_positions([ordinal as i16]),
+                GraphPatternType::RelativeYearly if in_month && pattern.days_of_week.len() == 1 => builder.yearly().monthly_by(MonthlyBy::nth_weekday(pattern.days_of_week[0], [ordinal])),
+                GraphPatternType::RelativeYearly if in_month => builder.yearly().months(MonthFlags::from_bits_retain(1 << (pattern.month - 1))).on(weekdays).set_positions([ordinal as i16]),
+                _ => return Err(RepeatingDateError::StartNotInRule),
+            };
+
+            if let Some(until) = until {
+                builder = builder.until(until);
+            }
+
+            if let Some(count) = count {
+                builder = builder.count(count);
+            }
+
+            builder.build()
+        };
+
+        for start_date in range.start_date.iter_days().take(SEARCH_DAYS as usize).filter(on_grid) {
+            match build(start_date) {
+                Ok(schedule) => return Ok(schedule),
+                Err(RepeatingDateError::WrongWeekday { .. } | RepeatingDateError::StartNotInRule) => {},
+                Err(error) => return Err(GraphError::InvalidRule(error)),
+            }
+        }
+
+        Err(GraphError::NeverMatches)
+    }
+
+    /// Writes the rule as a Graph recurrence that `from_graph` reads back as an equivalent rule,
+    /// the range starting on the start date. A quarterly rule is monthly every three intervals,
+    /// and a daily rule on some weekdays every day is weekly on them. Weeks start on Monday.
+    ///
+    /// Monthly and yearly rules can fall on a single day of month, nth weekday (first to fourth,
+    /// or last) or set position over a weekday filter, yearly ones in a single month. Other
+    /// weekday policies than `Skip`, shift patterns, ISO weeks, days of the year, exclusions,
+    /// extra dates and more than one time of day fail with `GraphError::NotExpressible`, naming
+    /// every feature in the way; a single time of day belongs in the event's start.
+    pub fn to_graph(&self) -> Result<GraphRecurrence, GraphError> {
+        let (repeat, interval) = self.step();
+        let filtered = !self.weekdays().is_all();
+        let in_months = matches!(repeat, Repeating::Monthly | Repeating::Yearly);
+        let position = match self.set_positions().iter().collect::<Vec<&i16>>()[..] {
+            [position] => GraphWeekIndex::from_ordinal(*position as i64),
+            _ => None,
+        };
+        let single_month = self.months().is_none_or(|months| months.month_numbers().count() == 1);
+        let by_position = in_months && filtered && self.monthly_by().is_none() && position.is_some() && (repeat == Repeating::Monthly || self.months().is_some());
+
+        let unsupported = [
+            ("weekday_policy", self.weekday_policy() != WeekdayPolicy::Skip),
+            ("shift_pattern", self.shift_pattern().is_some()),
+            ("monthly_by", match self.monthly_by() {
+                Some(MonthlyBy::Days { days, missing: MissingDay::Skip }) => days.len() != 1 || days.iter().any(|day| day.get() < 0),
+                Some(MonthlyBy::Days { .. }) => true,
+                Some(MonthlyBy::NthWeekday { ordinals, .. }) => ordinals.len() != 1 || ordinals.iter().any(|ordinal| GraphWeekIndex::from_ordinal(*ordinal as i64).is_none()),
+                None => false,
+            }),
+            ("weekdays", filtered && if in_months { !by_position } else { repeat == Repeating::Daily && interval != 1 }),
+            ("months", !single_month),
+            ("iso_weeks", !self.iso_weeks().is_empty()),
+            ("year_days", self.year_days().is_some()),
+            ("set_positions", !self.set_positions().is_empty() && !by_position),
+            ("exclusions", !self.exclusions().is_empty()),
+            ("extra_dates", !self.extra_dates().is_empty()),
+            ("times_of_day", self.times_of_day().is_some_and(|times| times.times().len() != 1 || WeekdayFlags::ANY.extract_weekdays().any(|weekday| times.weekday_time(weekday).is_some()))),
+        ];
+
+        let features: Vec<&'static str> = unsupported.iter().filter(|(_, unsupported)| *unsupported).map(|(feature, _)| *feature).collect();
+
+        if !features.is_empty() {
+            return Err(GraphError::NotExpressible { features });
+        }
+
+        let start = self.start_date();
+        let mut pattern = GraphPattern { kind: GraphPatternType::Daily, interval, month: 0, day_of_month: 0, days_of_week: vec![], first_day_of_week: None, index: None };
+
+        if repeat == Repeating::Weekly || (repeat == Repeating::Daily && filtered) {
+            pattern.kind = GraphPatternType::Weekly;
+            pattern.days_of_week = self.weekdays().extract_weekdays().collect();
+            pattern.first_day_of_week = Some(chrono::Weekday::Mon);
+        }
+
+        if in_months {
+            let yearly = repeat == Repeating::Yearly;
+
+            match self.monthly_by() {
+                Some(MonthlyBy::NthWeekday { weekday, ordinals }) => {
+                    pattern.days_of_week = vec![*weekday];
+                    pattern.index = ordinals.iter().next().and_then(|ordinal| GraphWeekIndex::from_ordinal(*ordinal as i64));
+                },
+                _ if by_position => {
+                    pattern.days_of_week = self.weekdays().extract_weekdays().collect();
+                    pattern.index = position;
+                },
+                Some(MonthlyBy::Days { days, .. }) => pattern.day_of_month = days.iter().next().map_or(start.day(), |day| day.get() as u32),
+                None => pattern.day_of_month = start.day(),
+            }
+
+            pattern.kind = match (yearly, pattern.index.is_some()) {
+                (false, false) => GraphPatternType::AbsoluteMonthly,
+                (false, true) => GraphPatternType::RelativeMonthly,
+                (true, false) => GraphPatternType::AbsoluteYearly,
+                (true, true) => GraphPatternType::RelativeYearly,
+            };
+
+            if yearly {
+                pattern.month = self.months().and_then(|months| months.month_numbers().next()).unwrap_or(start.month());
+            }
+        }
+
+        let range = match (self.until(), self.count()) {
+            (Some(until), _) => GraphRange { kind: GraphRangeType::EndDate, start_date: start, end_date: Some(until), number_of_occurrences: 0, recurrence_time_zone: None },
+            (None, Some(count)) => GraphRange { kind: GraphRangeType::Numbered, start_date: start, end_date: None, number_of_occurrences: count, recurrence_time_zone: None },
+            (None, None) => GraphRange { kind: GraphRangeType::NoEnd, start_date: start, end_date: None, number_of_occurrences: 0, recurrence_time_zone: None },
+        };
+
+        Ok(GraphRecurrence { pattern, range })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    fn read(payload: Value) -> Result<Schedule, GraphError> {
+        Schedule::from_graph(&serde_json::from_value(payload).unwrap())
+    }
+
+    #[rstest]
+    #[case::weekly_until(
+        json!({ "pattern": { "type": "weekly", "interval": 1, "daysOfWeek": ["Monday"] }, "range": { "type": "endDate", "startDate": "2017-09-04", "endDate": "2017-12-31" } }),
+        "weekly;days=MON;start=2017-09-04;until=2017-12-31"
+    )]
+    #[case::daily_numbered(
+        json!({
+            "pattern": { "type": "daily", "interval": 1, "month": 0, "dayOfMonth": 0, "firstDayOfWeek": "sunday", "index": "first" },
+            "range": { "type": "numbered", "startDate": "2020-06-01", "endDate": "0001-01-01", "recurrenceTimeZone": "Pacific Standard Time", "numberOfOccurrences": 10 },
+        }),
+        "daily;start=2020-06-01;count=10"
+    )]
+    #[case::every_other_week(
+        json!({
+            "pattern": { "type": "weekly", "interval": 2, "month": 0, "dayOfMonth": 0, "daysOfWeek": ["monday", "wednesday", "friday"], "firstDayOfWeek": "sunday", "index": "first" },
+            "range": { "type": "noEnd", "startDate": "2024-01-10", "endDate": "0001-01-01", "recurrenceTimeZone": "W. Europe Standard Time", "numberOfOccurrences": 0 },
+        }),
+        "weekly;interval=2;days=MON,WED,FRI;start=2024-01-10"
+    )]
+    #[case::next_week_on_the_grid(
+        json!({ "pattern": { "type": "weekly", "interval": 2, "daysOfWeek": ["monday"], "firstDayOfWeek": "sunday" }, "range": { "type": "noEnd", "startDate": "2024-01-10" } }),
+        "weekly;interval=2;days=MON;start=2024-01-22"
+    )]
+    #[case::absolute_monthly(
+        json!({ "pattern": { "type": "absoluteMonthly", "interval": 2, "dayOfMonth": 5 }, "range": { "type": "noEnd", "startDate": "2024-01-10" } }),
+        "monthly;interval=2;start=2024-03-05"
+    )]
+    #[case::relative_monthly(
+        json!({ "pattern": { "type": "relativeMonthly", "interval": 1, "daysOfWeek": ["thursday"], "index": "second" }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }),
+        "monthly;start=2024-01-11;nth=2THU"
+    )]
+    #[case::last_workday(
+        json!({ "pattern": { "type": "relativeMonthly", "interval": 1, "daysOfWeek": ["monday", "tuesday", "wednesday", "thursday", "friday"], "index": "last" }, "range": { "type": "numbered", "startDate": "2024-03-01", "numberOfOccurrences": 6 } }),
+        "monthly;days=MON,TUE,WED,THU,FRI;start=2024-03-29;setpos=-1;count=6"
+    )]
+    #[case::absolute_yearly(
+        json!({ "pattern": { "type": "absoluteYearly", "interval": 1, "month": 7, "dayOfMonth": 4 }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }),
+        "yearly;start=2024-07-04"
+    )]
+    #[case::relative_yearly(
+        json!({ "pattern": { "type": "relativeYearly", "interval": 1, "month": 11, "daysOfWeek": ["thursday"], "index": "fourth" }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }),
+        "yearly;start=2024-11-28;nth=4THU"
+    )]
+    #[case::week_start_not_in_the_way(
+        json!({ "pattern": { "type": "weekly", "interval": 3, "daysOfWeek": ["saturday", "sunday"], "firstDayOfWeek": "saturday" }, "range": { "type": "noEnd", "startDate": "2024-01-06" } }),
+        "weekly;interval=3;days=SAT,SUN;start=2024-01-06"
+    )]
+    fn reads_graph_payloads(#[case] payload: Value, #[case] expected: &str) {
+        assert_eq!(Ok(Schedule::from_str(expected).unwrap()), read(payload));
+    }
+
+    #[rstest]
+    #[case::daily("daily;interval=3;start=2024-01-10;until=2024-06-30", json!({ "pattern": { "type": "daily", "interval": 3 }, "range": { "type": "endDate", "startDate": "2024-01-10", "endDate": "2024-06-30" } }))]
+    #[case::weekly(
+        "weekly;interval=2;days=TUE,THU;start=2024-01-02;count=8",
+        json!({ "pattern": { "type": "weekly", "interval": 2, "daysOfWeek": ["tuesday", "thursday"], "firstDayOfWeek": "monday" }, "range": { "type": "numbered", "startDate": "2024-01-02", "numberOfOccurrences": 8 } })
+    )]
+    #[case::monthly("monthly;interval=3;start=2024-01-15", json!({ "pattern": { "type": "absoluteMonthly", "interval": 3, "dayOfMonth": 15 }, "range": { "type": "noEnd", "startDate": "2024-01-15" } }))]
+    #[case::last_friday("monthly;start=2024-01-26;nth=-1FRI", json!({ "pattern": { "type": "relativeMonthly", "interval": 1, "daysOfWeek": ["friday"], "index": "last" }, "range": { "type": "noEnd", "startDate": "2024-01-26" } }))]
+    #[case::first_weekend_day(
+        "monthly;days=SAT,SUN;start=2024-01-06;setpos=1",
+        json!({ "pattern": { "type": "relativeMonthly", "interval": 1, "daysOfWeek": ["saturday", "sunday"], "index": "first" }, "range": { "type": "noEnd", "startDate": "2024-01-06" } })
+    )]
+    #[case::yearly("yearly;interval=4;start=2024-02-29", json!({ "pattern": { "type": "absoluteYearly", "interval": 4, "month": 2, "dayOfMonth": 29 }, "range": { "type": "noEnd", "startDate": "2024-02-29" } }))]
+    #[case::thanksgiving("yearly;start=2024-11-28;nth=4THU", json!({ "pattern": { "type": "relativeYearly", "interval": 1, "month": 11, "daysOfWeek": ["thursday"], "index": "fourth" }, "range": { "type": "noEnd", "startDate": "2024-11-28" } }))]
+    #[case::first_workday_of_september(
+        "yearly;days=MON,TUE,WED,THU,FRI;start=2024-09-02;months=SEP;setpos=1",
+        json!({ "pattern": { "type": "relativeYearly", "interval": 1, "month": 9, "daysOfWeek": ["monday", "tuesday", "wednesday", "thursday", "friday"], "index": "first" }, "range": { "type": "noEnd", "startDate": "2024-09-02" } })
+    )]
+    fn writes_and_reads_back(#[case] rule: &str, #[case] expected: Value) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let recurrence = schedule.to_graph().unwrap();
+
+        assert_eq!(expected, serde_json::to_value(&recurrence).unwrap());
+        assert_eq!(Ok(schedule), Schedule::from_graph(&recurrence));
+    }
+
+    #[rstest]
+    #[case::weekdays("daily;days=MON,TUE,WED,THU,FRI;start=2024-01-10", "weekly;days=MON,TUE,WED,THU,FRI;start=2024-01-10")]
+    #[case::quarterly("quarterly;start=2024-01-15", "monthly;interval=3;start=2024-01-15")]
+    #[case::month_day("monthly;start=2024-01-15;monthdays=15", "monthly;start=2024-01-15")]
+    #[case::month_set("yearly;start=2024-03-01;months=MAR;monthdays=1", "yearly;start=2024-03-01")]
+    fn reads_back_equivalent(#[case] rule: &str, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let read = Schedule::from_graph(&schedule.to_graph().unwrap()).unwrap();
+
+        assert_eq!(Schedule::from_str(expected).unwrap(), read);
+        assert!(schedule.equivalent(&read));
+    }
+
+    #[rstest]
+    #[case::daily_weekdays_skipping_days("daily;interval=2;days=MON,TUE,WED,THU,FRI;start=2024-01-10", vec!["weekdays"])]
+    #[case::several_month_days("monthly;start=2024-01-01;monthdays=1,15", vec!["monthly_by"])]
+    #[case::last_day("monthly;start=2024-01-31;monthdays=-1", vec!["monthly_by"])]
+    #[case::fifth_monday("monthly;start=2024-01-29;nth=5MON", vec!["monthly_by"])]
+    #[case::filtered_month_day("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-15", vec!["weekdays"])]
+    #[case::second_to_last("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-30;setpos=-2", vec!["weekdays", "set_positions"])]
+    #[case::positions_over_the_year("yearly;days=FRI;start=2024-12-27;setpos=-1", vec!["weekdays", "set_positions"])]
+    #[case::several_months("yearly;start=2024-01-01;months=JAN,JUL;monthdays=1", vec!["months"])]
+    #[case::everything("daily;start=2024-01-01;shift=4:0,1;exdate=2024-01-02;rdate=2024-01-03;times=08:00,20:00", vec!["shift_pattern", "exclusions", "extra_dates", "times_of_day"])]
+    fn not_expressible(#[case] rule: &str, #[case] features: Vec<&'static str>) {
+        assert_eq!(Err(GraphError::NotExpressible { features }), Schedule::from_str(rule).unwrap().to_graph());
+    }
+
+    #[rstest]
+    #[case::no_interval(json!({ "pattern": { "type": "daily", "interval": 0 }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }), GraphError::InvalidValue { field: "interval", value: "0".to_string() })]
+    #[case::no_days(json!({ "pattern": { "type": "weekly", "interval": 1, "daysOfWeek": [] }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }), GraphError::InvalidValue { field: "daysOfWeek", value: "[]".to_string() })]
+    #[case::no_month(json!({ "pattern": { "type": "absoluteYearly", "interval": 1, "dayOfMonth": 4 }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }), GraphError::InvalidValue { field: "month", value: "0".to_string() })]
+    #[case::no_day_of_month(json!({ "pattern": { "type": "absoluteMonthly", "interval": 1, "dayOfMonth": 32 }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }), GraphError::InvalidValue { field: "dayOfMonth", value: "32".to_string() })]
+    #[case::no_end_date(json!({ "pattern": { "type": "daily", "interval": 1 }, "range": { "type": "endDate", "startDate": "2024-01-01" } }), GraphError::InvalidValue { field: "endDate", value: String::new() })]
+    #[case::no_occurrences(json!({ "pattern": { "type": "daily", "interval": 1 }, "range": { "type": "numbered", "startDate": "2024-01-01", "numberOfOccurrences": 0 } }), GraphError::InvalidValue { field: "numberOfOccurrences", value: "0".to_string() })]
+    #[case::sunday_weeks(
+        json!({ "pattern": { "type": "weekly", "interval": 2, "daysOfWeek": ["sunday", "monday"], "firstDayOfWeek": "sunday" }, "range": { "type": "noEnd", "startDate": "2024-01-07" } }),
+        GraphError::Unsupported { capability: "week_start" }
+    )]
+    #[case::february_30th(json!({ "pattern": { "type": "absoluteYearly", "interval": 1, "month": 2, "dayOfMonth": 30 }, "range": { "type": "noEnd", "startDate": "2024-01-01" } }), GraphError::NeverMatches)]
+    #[case::ends_before_it_starts(
+        json!({ "pattern": { "type": "absoluteMonthly", "interval": 1, "dayOfMonth": 20 }, "range": { "type": "endDate", "startDate": "2024-01-25", "endDate": "2024-02-01" } }),
+        GraphError::InvalidRule(RepeatingDateError::UntilBeforeStart)
+    )]
+    fn rejects(#[case] payload: Value, #[case] expected: GraphError) {
+        assert_eq!(Err(expected), read(payload));
+    }
+
+    #[test]
+    fn rejects_unknown_day_names() {
+        let payload = json!({ "pattern": { "type": "weekly", "interval": 1, "daysOfWeek": ["funday"] }, "range": { "type": "noEnd", "startDate": "2024-01-01" } });
+
+        assert!(serde_json::from_value::<GraphRecurrence>(payload).is_err());
+    }
+}
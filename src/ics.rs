@@ -0,0 +1,376 @@
+//! Recurring events read from iCalendar files, behind the `rfc5545` feature. Only the recurrence
+//! relevant subset of a `VEVENT` is read: `UID`, `SUMMARY`, `DTSTART`, `RRULE`, `EXDATE` and
+//! `RDATE`.
+
+use std::collections::BTreeSet;
+
+use crate::{RepeatingDateError, RruleError, Schedule, TimesOfDay};
+
+/// A recurring `VEVENT`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsEvent {
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub schedule: Schedule
+}
+
+/// Why a `VEVENT` could not be read, naming the event by its position among the file's
+/// `VEVENT`s, counting from 0, and by its `UID` if it has one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsError {
+    pub event: usize,
+    pub uid: Option<String>,
+    /// The name of the property that failed or, for a line that is not a property, the line.
+    pub property: String,
+    pub kind: IcsErrorKind
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsErrorKind {
+    /// The line is not of the form `NAME;PARAM=value:value`.
+    MalformedLine,
+    /// A `VEVENT` left open by the end of the file or of its calendar.
+    Unterminated,
+    Missing,
+    Duplicate,
+    InvalidValue { value: String },
+    /// A property or value this crate cannot represent, e.g. `EXRULE` or a second `RRULE`.
+    Unsupported { value: String },
+    Rrule(RruleError),
+    /// Every property parsed, but `Schedule` rejected the combination, e.g. an `RDATE` before
+    /// `DTSTART`.
+    InvalidRule(RepeatingDateError)
+}
+
+impl std::fmt::Display for IcsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VEVENT {}", self.event)?;
+
+        if let Some(uid) = &self.uid {
+            write!(f, " (UID {})", uid)?;
+        }
+
+        match &self.kind {
+            IcsErrorKind::MalformedLine => write!(f, ": line `{}` is not a property", self.property),
+            IcsErrorKind::Unterminated => write!(f, ": no END:VEVENT"),
+            IcsErrorKind::Missing => write!(f, ": {} is missing", self.property),
+            IcsErrorKind::Duplicate => write!(f, ": {} is given more than once", self.property),
+            IcsErrorKind::InvalidValue { value } => write!(f, ": {} value `{}` is invalid", self.property, value),
+            IcsErrorKind::Unsupported { value } => write!(f, ": {} `{}` is not supported", self.property, value),
+            IcsErrorKind::Rrule(error) => write!(f, ": {}: {}", self.property, error),
+            IcsErrorKind::InvalidRule(error) => write!(f, ": {}: invalid rule: {}", self.property, error),
+        }
+    }
+}
+
+impl std::error::Error for IcsError {}
+
+/// A content line, its name and parameter names upper cased.
+pub(crate) struct Property {
+    pub(crate) name: String,
+    parameters: Vec<(String, String)>,
+    pub(crate) value: String
+}
+
+impl Property {
+    fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.iter().find(|(other, _)| other == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Splits `value` at each `separator` outside double quotes.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut pieces = vec![];
+    let mut quoted = false;
+    let mut begin = 0;
+
+    for (index, character) in value.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            _ if character == separator && !quoted => {
+                pieces.push(&value[begin..index]);
+                begin = index + 1;
+            },
+            _ => {},
+        }
+    }
+
+    pieces.push(&value[begin..]);
+    pieces
+}
+
+pub(crate) fn parse_line(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let colon = line.char_indices().find(|(_, character)| {
+        quoted ^= *character == '"';
+        *character == ':' && !quoted
+    })?.0;
+
+    let mut head = split_unquoted(&line[..colon], ';').into_iter();
+    let name = head.next().filter(|name| !name.is_empty())?.to_ascii_uppercase();
+    let parameters = head
+        .map(|parameter| parameter.split_once('=').map(|(name, value)| (name.to_ascii_uppercase(), value.trim_matches('"').to_string())))
+        .collect::<Option<Vec<(String, String)>>>()?;
+
+    Some(Property { name, parameters, value: line[colon + 1..].to_string() })
+}
+
+/// Joins each line starting with a space or tab to the one before it, dropping that character.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Reverses the escaping of a `TEXT` value, RFC 5545 section 3.3.11.
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        match (character, character == '\\') {
+            (_, true) => match characters.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(escaped) => unescaped.push(escaped),
+                None => unescaped.push('\\'),
+            },
+            _ => unescaped.push(character),
+        }
+    }
+
+    unescaped
+}
+
+/// The comma separated `DATE` or `DATE-TIME` values of a property, local or UTC, with the time of
+/// each date-time. A `TZID` is left out: times are read as wall-clock times in that zone.
+pub(crate) fn date_values(property: &Property) -> Result<Vec<(chrono::NaiveDate, Option<chrono::NaiveTime>)>, IcsErrorKind> {
+    let date_only = match property.parameter("VALUE").map(str::to_ascii_uppercase).as_deref() {
+        None | Some("DATE-TIME") => false,
+        Some("DATE") => true,
+        Some(value) => return Err(IcsErrorKind::Unsupported { value: format!("VALUE={}", value) }),
+    };
+
+    let invalid = || IcsErrorKind::InvalidValue { value: property.value.clone() };
+
+    property.value.split(',').map(str::trim).map(|value| {
+        let date = chrono::NaiveDate::parse_from_str(value.get(..8).ok_or_else(invalid)?, "%Y%m%d").map_err(|_| invalid())?;
+
+        match (&value[8..], date_only) {
+            ("", _) => Ok((date, None)),
+            (_, true) => Err(invalid()),
+            (time, false) => chrono::NaiveTime::parse_from_str(time.strip_suffix('Z').unwrap_or(time), "T%H%M%S")
+                .map(|time| (date, Some(time)))
+                .map_err(|_| invalid()),
+        }
+    }).collect()
+}
+
+fn uid(lines: &[Option<Property>]) -> Option<String> {
+    lines.iter().flatten().find(|property| property.name == "UID").map(|property| unescape_text(&property.value))
+}
+
+/// Reads a `VEVENT` from its own lines, those of nested components such as `VALARM` left out.
+/// `None` when it has no `RRULE`.
+fn read_event(event: usize, lines: &[Option<Property>], raw: &[String]) -> Option<Result<IcsEvent, IcsError>> {
+    let uid = uid(lines);
+    let fail = |property: &str, kind: IcsErrorKind| IcsError { event, uid: uid.clone(), property: property.to_string(), kind };
+
+    let mut summary = None;
+    let mut dtstart = None;
+    let mut rrule: Option<&str> = None;
+    let mut exclusions = BTreeSet::new();
+    let mut extra_dates = BTreeSet::new();
+
+    for (property, line) in lines.iter().zip(raw) {
+        let Some(property) = property else { return Some(Err(fail(line, IcsErrorKind::MalformedLine))) };
+        let dates = || date_values(property).map_err(|kind| fail(&property.name, kind));
+
+        match property.name.as_str() {
+            "SUMMARY" => summary = Some(unescape_text(&property.value)),
+            "DTSTART" if dtstart.is_some() => return Some(Err(fail("DTSTART", IcsErrorKind::Duplicate))),
+            "DTSTART" => match dates() {
+                Ok(values) if values.len() == 1 => dtstart = Some(values[0]),
+                Ok(_) => return Some(Err(fail("DTSTART", IcsErrorKind::InvalidValue { value: property.value.clone() }))),
+                Err(error) => return Some(Err(error)),
+            },
+            "RRULE" if rrule.is_some() => return Some(Err(fail("RRULE", IcsErrorKind::Unsupported { value: property.value.clone() }))),
+            "RRULE" => rrule = Some(&property.value),
+            "EXRULE" => return Some(Err(fail("EXRULE", IcsErrorKind::Unsupported { value: property.value.clone() }))),
+            "EXDATE" => match dates() {
+                Ok(values) => exclusions.extend(values.into_iter().map(|(date, _)| date)),
+                Err(error) => return Some(Err(error)),
+            },
+            "RDATE" => match dates() {
+                Ok(values) => extra_dates.extend(values.into_iter().map(|(date, _)| date)),
+                Err(error) => return Some(Err(error)),
+            },
+            _ => {},
+        }
+    }
+
+    let rrule = rrule?;
+    let Some((start_date, time)) = dtstart else { return Some(Err(fail("DTSTART", IcsErrorKind::Missing))) };
+
+    let schedule = Schedule::from_rrule(rrule, start_date)
+        .map_err(|error| fail("RRULE", IcsErrorKind::Rrule(error)))
+        .and_then(|schedule| schedule.with_exclusions(exclusions).with_extra_dates(extra_dates).map_err(|error| fail("RDATE", IcsErrorKind::InvalidRule(error))))
+        .and_then(|schedule| schedule.with_times_of_day(time.and_then(|time| TimesOfDay::new([time]).ok())).map_err(|error| fail("DTSTART", IcsErrorKind::InvalidRule(error))));
+
+    Some(schedule.map(|schedule| IcsEvent { uid: uid.clone(), summary, schedule }))
+}
+
+/// Reads the recurring `VEVENT`s of an iCalendar file, one result per event with an `RRULE`, in
+/// file order, so an event whose recurrence a `Schedule` cannot represent is reported without
+/// losing the others. Folded lines are unfolded and lines may end in CRLF or LF.
+///
+/// `DTSTART` may be a date or a date-time, which becomes the schedule's single time of day; its
+/// `TZID`, or the `Z` of a UTC time, is not applied, so times are wall-clock times in the zone
+/// they were written in. `EXDATE` and `RDATE` become exclusions and extra dates by their dates.
+/// Events without an `RRULE`, which includes single events and the moved occurrences of a
+/// recurring one (those with a `RECURRENCE-ID`), are skipped.
+pub fn parse_ics_events(ics: &str) -> Vec<Result<IcsEvent, IcsError>> {
+    let mut events = vec![];
+    let mut count = 0;
+    let mut open: Option<(Vec<Option<Property>>, Vec<String>)> = None;
+    let mut nested = 0;
+
+    for line in unfold(ics) {
+        let property = parse_line(&line);
+        let keyword = property.as_ref().filter(|property| property.name == "BEGIN" || property.name == "END")
+            .map(|property| (property.name == "BEGIN", property.value.trim().eq_ignore_ascii_case("VEVENT")));
+
+        match (open.as_mut(), keyword) {
+            (None, Some((true, true))) => open = Some((vec![], vec![])),
+            (None, _) => {},
+            (Some(_), Some((true, _))) => nested += 1,
+            (Some(_), Some((false, _))) if nested > 0 => nested -= 1,
+            (Some((lines, raw)), Some((false, true))) => {
+                events.extend(read_event(count, lines, raw));
+                open = None;
+                count += 1;
+            },
+            (Some((lines, _)), Some((false, false))) => {
+                events.push(Err(IcsError { event: count, uid: uid(lines), property: "VEVENT".to_string(), kind: IcsErrorKind::Unterminated }));
+                open = None;
+                count += 1;
+            },
+            (Some(_), None) if nested > 0 => {},
+            (Some((lines, raw)), None) => {
+                lines.push(property);
+                raw.push(line);
+            },
+        }
+    }
+
+    if let Some((lines, _)) = open {
+        events.push(Err(IcsError { event: count, uid: uid(&lines), property: "VEVENT".to_string(), kind: IcsErrorKind::Unterminated }));
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    const GOOGLE_CALENDAR: &str = include_str!("../tests/fixtures/google_calendar.ics");
+
+    fn event(uid: &str, summary: &str, schedule: &str) -> Result<IcsEvent, IcsError> {
+        Ok(IcsEvent { uid: Some(uid.to_string()), summary: Some(summary.to_string()), schedule: Schedule::from_str(schedule).unwrap() })
+    }
+
+    #[test]
+    fn reads_a_google_calendar_export() {
+        let expected = vec![
+            event("3j1k2l3m4n5o6p7q8r9s0t@google.com", "Team sync", "weekly;days=TUE,THU;start=2024-01-02;exdate=2024-01-11;times=10:00"),
+            event("7a8b9c0d1e2f3g4h5i6j7k@google.com", "Pay rent, water and electricity — transfer before noon so it clears the same day", "monthly;start=2024-01-15;monthdays=15;count=12"),
+            event("5e6f7g8h9i0j1k2l3m4n5o@google.com", "Clocks change", "yearly;start=2024-03-31;months=MAR;nth=-1SUN"),
+            Err(IcsError {
+                event: 5,
+                uid: Some("1q2w3e4r5t6y7u8i9o0p@google.com".to_string()),
+                property: "RRULE".to_string(),
+                kind: IcsErrorKind::Rrule(RruleError::UnsupportedPart { part: "BYDAY=TU".to_string() }),
+            }),
+            event("9z8x7c6v5b4n3m2l1k0j@google.com", "Run", "weekly;days=FRI;start=2024-01-05;until=2024-03-02;times=07:30"),
+        ];
+
+        assert_eq!(expected, parse_ics_events(GOOGLE_CALENDAR));
+    }
+
+    #[test]
+    fn imported_schedules_find_their_occurrences() {
+        let events = parse_ics_events(GOOGLE_CALENDAR);
+        let team_sync = &events[0].as_ref().unwrap().schedule;
+        let dates: Vec<chrono::NaiveDate> = team_sync.occurrences(date("2024-01-08")).take(3).collect();
+
+        assert_eq!(vec![date("2024-01-09"), date("2024-01-16"), date("2024-01-18")], dates);
+        assert_eq!(Ok(chrono::NaiveDateTime::from_str("2024-01-09T10:00:00").unwrap()), team_sync.next_datetime_after(chrono::NaiveDateTime::from_str("2024-01-08T12:00:00").unwrap()));
+    }
+
+    fn calendar(lines: &[&str]) -> String {
+        ["BEGIN:VCALENDAR", "BEGIN:VEVENT", "UID:a@example.com"].iter().chain(lines).chain(&["END:VEVENT", "END:VCALENDAR"]).map(|line| format!("{}\r\n", line)).collect()
+    }
+
+    #[test]
+    fn unfolds_with_lf_line_endings() {
+        let ics = calendar(&["DTSTART;VALUE=DATE:20240101", "RRULE:FREQ=DAILY;", " INTERVAL=2", "SUMMARY:Water", "\tthe plants"]).replace("\r\n", "\n");
+        let events = parse_ics_events(&ics);
+
+        assert_eq!(event("a@example.com", "Waterthe plants", "daily;interval=2;start=2024-01-01"), events[0]);
+    }
+
+    #[test]
+    fn reads_rdates_and_utc_times() {
+        let events = parse_ics_events(&calendar(&["DTSTART:20240101T090000Z", "RRULE:FREQ=WEEKLY", "RDATE;VALUE=DATE:20240103,20240110", "EXDATE:20240108T090000Z"]));
+
+        assert_eq!(Ok(Schedule::from_str("weekly;days=MON;start=2024-01-01;exdate=2024-01-08;rdate=2024-01-03,2024-01-10;times=09:00").unwrap()), events[0].clone().map(|event| event.schedule));
+    }
+
+    #[test]
+    fn skips_events_without_a_rule() {
+        assert_eq!(Vec::<Result<IcsEvent, IcsError>>::new(), parse_ics_events(&calendar(&["DTSTART:20240101T090000", "SUMMARY:Once"])));
+    }
+
+    #[rstest]
+    #[case::missing_start(&["RRULE:FREQ=DAILY"], "DTSTART", IcsErrorKind::Missing)]
+    #[case::two_starts(&["DTSTART:20240101T090000", "DTSTART:20240102T090000", "RRULE:FREQ=DAILY"], "DTSTART", IcsErrorKind::Duplicate)]
+    #[case::invalid_start(&["DTSTART:2024-01-01", "RRULE:FREQ=DAILY"], "DTSTART", IcsErrorKind::InvalidValue { value: "2024-01-01".to_string() })]
+    #[case::time_on_a_date(&["DTSTART;VALUE=DATE:20240101T090000", "RRULE:FREQ=DAILY"], "DTSTART", IcsErrorKind::InvalidValue { value: "20240101T090000".to_string() })]
+    #[case::two_rules(&["DTSTART:20240101T090000", "RRULE:FREQ=DAILY", "RRULE:FREQ=WEEKLY"], "RRULE", IcsErrorKind::Unsupported { value: "FREQ=WEEKLY".to_string() })]
+    #[case::exrule(&["DTSTART:20240101T090000", "RRULE:FREQ=DAILY", "EXRULE:FREQ=WEEKLY"], "EXRULE", IcsErrorKind::Unsupported { value: "FREQ=WEEKLY".to_string() })]
+    #[case::period(&["DTSTART:20240101T090000", "RRULE:FREQ=DAILY", "RDATE;VALUE=PERIOD:20240102T090000Z/PT1H"], "RDATE", IcsErrorKind::Unsupported { value: "VALUE=PERIOD".to_string() })]
+    #[case::rdate_before_the_start(&["DTSTART:20240101T090000", "RRULE:FREQ=DAILY", "RDATE:20231231T090000"], "RDATE", IcsErrorKind::InvalidRule(RepeatingDateError::ExtraDateBeforeStart))]
+    #[case::bad_rule(&["DTSTART:20240101T090000", "RRULE:FREQ=HOURLY"], "RRULE", IcsErrorKind::Rrule(RruleError::UnsupportedPart { part: "FREQ=HOURLY".to_string() }))]
+    #[case::not_a_property(&["RRULE:FREQ=DAILY", "DTSTART"], "DTSTART", IcsErrorKind::MalformedLine)]
+    fn reports_the_event_and_property(#[case] lines: &[&str], #[case] property: &str, #[case] kind: IcsErrorKind) {
+        let expected = IcsError { event: 0, uid: Some("a@example.com".to_string()), property: property.to_string(), kind };
+
+        assert_eq!(vec![Err(expected)], parse_ics_events(&calendar(lines)));
+    }
+
+    #[test]
+    fn reports_an_unterminated_event() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a@example.com\r\nEND:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20240101\r\n";
+        let unterminated = |event, uid: Option<&str>| Err(IcsError { event, uid: uid.map(str::to_string), property: "VEVENT".to_string(), kind: IcsErrorKind::Unterminated });
+
+        assert_eq!(vec![unterminated(0, Some("a@example.com")), unterminated(1, None)], parse_ics_events(ics));
+    }
+
+    #[test]
+    fn error_names_the_event() {
+        let error = IcsError { event: 5, uid: Some("b@example.com".to_string()), property: "RRULE".to_string(), kind: IcsErrorKind::Rrule(RruleError::MissingFrequency) };
+
+        assert_eq!("VEVENT 5 (UID b@example.com): RRULE: RRULE has no FREQ", error.to_string());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{lcm, RepeatingDateError, Schedule};
+
+/// Longest search for a common date unless a horizon is set explicitly: one 400 year Gregorian
+/// cycle.
+const DEFAULT_MAX_HORIZON_DAYS: u64 = 146_097;
+
+/// The dates on which two schedules both occur, see `Schedule::intersect`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleIntersection {
+    left: Schedule,
+    right: Schedule,
+    horizon_days: u64
+}
+
+impl Schedule {
+    /// The dates this schedule shares with `other`.
+    ///
+    /// Lookups step whichever schedule is behind until both land on the same date, giving up with
+    /// `HorizonExceeded` after `horizon_days` days without a common date. The default horizon is one
+    /// joint period of both rules, after which their pattern repeats, so schedules that never meet
+    /// fail fast; it is capped at 400 years, see `ScheduleIntersection::with_horizon`.
+    pub fn intersect(&self, other: &Schedule) -> ScheduleIntersection {
+        let joint_period = lcm(self.period_days(), other.period_days());
+        let horizon_days = u64::try_from(joint_period).unwrap_or(u64::MAX).min(DEFAULT_MAX_HORIZON_DAYS);
+
+        ScheduleIntersection { left: self.clone(), right: other.clone(), horizon_days }
+    }
+}
+
+impl ScheduleIntersection {
+    /// Searches up to `days` days past the starting point for a common date.
+    pub fn with_horizon(mut self, days: u64) -> ScheduleIntersection {
+        self.horizon_days = days;
+        self
+    }
+
+    pub fn horizon_days(&self) -> u64 {
+        self.horizon_days
+    }
+
+    pub fn matches(&self, date: chrono::NaiveDate) -> bool {
+        self.left.matches(date) && self.right.matches(date)
+    }
+
+    /// The first date after `from_date` on which both schedules occur.
+    pub fn next_common_after(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        self.common_from(from_date, self.left.next_or_first(from_date)?, self.right.next_or_first(from_date)?)
+    }
+
+    fn common_from(&self, from_date: chrono::NaiveDate, mut left: chrono::NaiveDate, mut right: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        let limit = from_date.checked_add_days(chrono::Days::new(self.horizon_days)).unwrap_or(chrono::NaiveDate::MAX);
+
+        while left != right {
+            if left.max(right) > limit {
+                return Err(RepeatingDateError::HorizonExceeded);
+            }
+
+            if left < right {
+                left = self.left.next_or_first(left)?;
+            } else {
+                right = self.right.next_or_first(right)?;
+            }
+        }
+
+        Ok(left)
+    }
+
+    /// The common dates in ascending order, ending once either schedule ends or no common date
+    /// follows within the horizon.
+    pub fn iter(&self) -> ScheduleIntersectionIter<'_> {
+        ScheduleIntersectionIter { intersection: self, last: None, done: false }
+    }
+}
+
+impl<'a> IntoIterator for &'a ScheduleIntersection {
+    type Item = chrono::NaiveDate;
+    type IntoIter = ScheduleIntersectionIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleIntersectionIter<'a> {
+    intersection: &'a ScheduleIntersection,
+    last: Option<chrono::NaiveDate>,
+    done: bool
+}
+
+impl Iterator for ScheduleIntersectionIter<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let intersection = self.intersection;
+        let next = match self.last {
+            Some(last) => intersection.next_common_after(last),
+            None => match (intersection.left.first_occurrence(), intersection.right.first_occurrence()) {
+                (Some(left), Some(right)) => intersection.common_from(left.min(right), left, right),
+                _ => Err(RepeatingDateError::ScheduleEnded),
+            },
+        };
+
+        self.last = next.ok();
+        self.done = self.last.is_none();
+        self.last
+    }
+}
+
+impl std::iter::FusedIterator for ScheduleIntersectionIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::test_util::date;
+
+    fn rule(value: &str) -> Schedule {
+        Schedule::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn disjoint_schedules_hit_the_horizon() {
+        let intersection = rule("weekly;days=TUE;start=2023-09-12").intersect(&rule("weekly;days=WED;start=2023-09-13"));
+
+        assert_eq!(7, intersection.horizon_days());
+        assert_eq!(Err(RepeatingDateError::HorizonExceeded), intersection.next_common_after(date("2023-09-01")));
+        assert_eq!(None, intersection.iter().next());
+        assert!(!intersection.matches(date("2023-09-12")));
+    }
+
+    #[test]
+    fn long_cycle_intersection() {
+        let intersection = rule("weekly;interval=3;days=MON;start=2023-09-04").intersect(&rule("weekly;interval=4;days=MON;start=2023-09-04"));
+        let dates: Vec<chrono::NaiveDate> = intersection.iter().take(3).collect();
+
+        assert_eq!(["2023-09-04", "2023-11-27", "2024-02-19"].map(date).to_vec(), dates);
+        assert_eq!(Ok(date("2023-11-27")), intersection.next_common_after(date("2023-09-04")));
+        assert!(intersection.matches(date("2024-02-19")));
+        assert!(!intersection.matches(date("2023-09-25")));
+    }
+
+    #[test]
+    fn different_starts_and_frequencies() {
+        // Every second Tuesday that is also the 12th of a month.
+        let intersection = rule("weekly;interval=2;days=TUE;start=2023-09-05").intersect(&rule("monthly;start=2023-01-12"));
+
+        assert_eq!(Ok(date("2023-12-12")), intersection.next_common_after(date("2023-09-01")));
+        assert_eq!(Some(date("2023-12-12")), intersection.iter().next());
+    }
+
+    #[test]
+    fn explicit_horizon_and_ended_schedules() {
+        let left = rule("weekly;interval=3;days=MON;start=2023-09-04");
+        let right = rule("weekly;interval=4;days=MON;start=2023-09-04");
+
+        assert_eq!(Err(RepeatingDateError::HorizonExceeded), left.intersect(&right).with_horizon(50).next_common_after(date("2023-09-04")));
+
+        let bounded = rule("weekly;interval=4;days=MON;start=2023-09-04;count=3");
+        let intersection = left.intersect(&bounded);
+
+        assert_eq!(vec![date("2023-09-04")], intersection.iter().collect::<Vec<chrono::NaiveDate>>());
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), intersection.next_common_after(date("2023-09-04")));
+    }
+}
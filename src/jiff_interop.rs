@@ -0,0 +1,105 @@
+//! `jiff` civil dates and weekdays for the date functions, behind the `jiff` feature. Dates
+//! convert by year, month and day, so each one maps to the same calendar date.
+
+use crate::{find_next_date, match_repeating_date, Repeating, RepeatingDateError, WeekdayFlags};
+
+fn from_jiff_date(date: jiff::civil::Date) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    chrono::NaiveDate::from_ymd_opt(date.year() as i32, date.month() as u32, date.day() as u32).ok_or(RepeatingDateError::DateOutOfRange)
+}
+
+fn to_jiff_date(date: chrono::NaiveDate) -> Result<jiff::civil::Date, RepeatingDateError> {
+    let year = i16::try_from(chrono::Datelike::year(&date)).map_err(|_| RepeatingDateError::DateOutOfRange)?;
+
+    jiff::civil::Date::new(year, chrono::Datelike::month(&date) as i8, chrono::Datelike::day(&date) as i8).map_err(|_| RepeatingDateError::DateOutOfRange)
+}
+
+impl WeekdayFlags {
+    pub fn from_jiff_weekday(weekday: jiff::civil::Weekday) -> WeekdayFlags {
+        match weekday {
+            jiff::civil::Weekday::Monday => WeekdayFlags::MON,
+            jiff::civil::Weekday::Tuesday => WeekdayFlags::TUE,
+            jiff::civil::Weekday::Wednesday => WeekdayFlags::WED,
+            jiff::civil::Weekday::Thursday => WeekdayFlags::THU,
+            jiff::civil::Weekday::Friday => WeekdayFlags::FRI,
+            jiff::civil::Weekday::Saturday => WeekdayFlags::SAT,
+            jiff::civil::Weekday::Sunday => WeekdayFlags::SUN,
+        }
+    }
+}
+
+/// `find_next_date` on `jiff::civil::Date`s; an occurrence past `jiff::civil::Date::MAX` is
+/// `RepeatingDateError::DateOutOfRange`.
+pub fn find_next_date_jiff(from_date: jiff::civil::Date, start_date: jiff::civil::Date, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<jiff::civil::Date, RepeatingDateError> {
+    to_jiff_date(find_next_date(from_jiff_date(from_date)?, from_jiff_date(start_date)?, weekdays, repeat, interval)?)
+}
+
+/// `match_repeating_date` on `jiff::civil::Date`s.
+pub fn match_repeating_date_jiff(date_to_check: jiff::civil::Date, start_date: jiff::civil::Date, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
+    match (from_jiff_date(date_to_check), from_jiff_date(start_date)) {
+        (Ok(date_to_check), Ok(start_date)) => match_repeating_date(date_to_check, start_date, weekdays, repeat, interval),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(year: i16, month: i8, day: i8) -> jiff::civil::Date {
+        jiff::civil::Date::new(year, month, day).unwrap()
+    }
+
+    #[rstest]
+    #[case::new_years_eve(date(2023, 12, 31), "2023-12-31")]
+    #[case::leap_day(date(2024, 2, 29), "2024-02-29")]
+    #[case::century_leap_day(date(2000, 2, 29), "2000-02-29")]
+    #[case::century_without_one(date(1900, 3, 1), "1900-03-01")]
+    #[case::first(jiff::civil::Date::MIN, "-9999-01-01")]
+    #[case::last(jiff::civil::Date::MAX, "9999-12-31")]
+    fn converts_exactly(#[case] date: jiff::civil::Date, #[case] expected: &str) {
+        assert_eq!(Ok(expected.parse::<chrono::NaiveDate>().unwrap()), from_jiff_date(date));
+    }
+
+    /// jiff -> chrono -> jiff is the identity on every date of four centuries and keeps the weekday,
+    /// which jiff numbers from Monday as 1 and chrono's `number_from_monday` likewise but
+    /// `num_days_from_sunday` from Sunday as 0.
+    #[test]
+    fn round_trips_every_date() {
+        let mut day = date(1800, 1, 1);
+
+        while day <= date(2200, 12, 31) {
+            let converted = from_jiff_date(day).unwrap();
+
+            assert_eq!(Ok(day), to_jiff_date(converted));
+            assert_eq!(WeekdayFlags::from_jiff_weekday(day.weekday()), WeekdayFlags::from_weekday(chrono::Datelike::weekday(&converted)));
+            day = day.tomorrow().unwrap();
+        }
+
+        for day in [jiff::civil::Date::MIN, jiff::civil::Date::MAX] {
+            assert_eq!(Ok(day), to_jiff_date(from_jiff_date(day).unwrap()));
+        }
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(WeekdayFlags::SUN, WeekdayFlags::from_jiff_weekday(date(2024, 1, 7).weekday()));
+        assert_eq!(WeekdayFlags::MON, WeekdayFlags::from_jiff_weekday(date(2024, 1, 8).weekday()));
+        assert_eq!(WeekdayFlags::SAT, WeekdayFlags::from_jiff_weekday(date(2024, 1, 13).weekday()));
+    }
+
+    #[test]
+    fn out_of_jiff_range() {
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), to_jiff_date(chrono::NaiveDate::from_ymd_opt(10000, 1, 1).unwrap()));
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), find_next_date_jiff(jiff::civil::Date::MAX, date(9999, 1, 1), WeekdayFlags::ANY, Repeating::Yearly, 1));
+    }
+
+    #[rstest]
+    #[case::across_new_year(date(2023, 12, 31), date(2023, 12, 1), WeekdayFlags::ANY, Repeating::Daily, date(2024, 1, 1))]
+    #[case::weekends(date(2024, 1, 8), date(2024, 1, 6), WeekdayFlags::SAT | WeekdayFlags::SUN, Repeating::Weekly, date(2024, 1, 13))]
+    #[case::yearly_leap_day(date(2024, 2, 29), date(2024, 2, 29), WeekdayFlags::ANY, Repeating::Yearly, date(2028, 2, 29))]
+    fn finds_next(#[case] from: jiff::civil::Date, #[case] start: jiff::civil::Date, #[case] weekdays: WeekdayFlags, #[case] repeat: Repeating, #[case] expected: jiff::civil::Date) {
+        assert_eq!(Ok(expected), find_next_date_jiff(from, start, weekdays, repeat, 1));
+        assert!(match_repeating_date_jiff(expected, start, weekdays, repeat, 1));
+    }
+}
@@ -1,18 +1,423 @@
+//! Recurring date calculations on top of chrono's `NaiveDate`.
+//!
+//! The entry point is `Schedule`, which bundles a rule's start date, allowed weekdays, frequency and
+//! interval and validates them once:
+//!
+//! ```
+//! use recurring_dates_rs::{Schedule, WeekdayFlags};
+//!
+//! let start = chrono::NaiveDate::from_ymd_opt(2023, 9, 12).unwrap();
+//! let schedule = Schedule::builder().start(start).weekly().interval(2).on(WeekdayFlags::TUE | WeekdayFlags::THU).build().unwrap();
+//!
+//! assert_eq!(chrono::NaiveDate::from_ymd_opt(2023, 9, 14), schedule.next_after(start).ok());
+//! assert!(schedule.matches(chrono::NaiveDate::from_ymd_opt(2023, 9, 26).unwrap()));
+//! ```
+//!
+//! The free functions `find_next_date` and `match_repeating_date` remain available and take the same
+//! parameters individually; end conditions (`until`, `count`), exclusions, extra dates, monthly
+//! patterns (`MonthlyBy`), month sets (`MonthFlags`), days of the year (`YearDays`), shift patterns
+//! (`ShiftPattern`), set positions and weekday policies (`WeekdayPolicy`) are only available on
+//! `Schedule`. Their monthly, quarterly and yearly rules skip months lacking the start's day of
+//! month; `find_next_date_with` and `match_repeating_date_with` take a `MonthDayPolicy` to clamp
+//! or roll over instead. Weekly rules count Monday-first weeks; `find_next_date_with_week_start`
+//! and `match_repeating_date_with_week_start` take a `WeekStart` for Sunday-first ones.
+//!
+//! Sub-daily rules (`Repeating::Hourly`, `Repeating::Minutely`) work on `NaiveDateTime` through
+//! `find_next_datetime` and `match_repeating_datetime`, with the weekday filter applied to the date
+//! part; `Schedule` and the date functions reject them. The datetime functions take the other
+//! frequencies too, placing each occurrence date at the start's time of day. On a `Schedule`,
+//! `TimesOfDay` expands each occurrence date into several times, see `Schedule::datetimes`, and an
+//! `OccurrenceWindow` gives those a duration. A `RecurringSpan` repeats a run of whole days
+//! instead.
+//!
+//! The domain of every function is the full proleptic Gregorian range of `NaiveDate`: anchors in the
+//! 1800s (or before the common era) follow exactly the same rules as modern ones, including century
+//! years such as 1900 not being leap years.
+//!
+//! Results are deterministic: every computation is integer calendar arithmetic on `NaiveDate`, with
+//! no floating point, hashing, system clock or locale state feeding into the dates produced, so the
+//! same inputs give identical outputs on every platform. `tests/determinism.rs` pins this against a
+//! checked-in fixture on each CI target.
+//!
+//! # Text format
+//!
+//! `Schedule` implements `Display` and `FromStr` for a compact single line form, broken here into
+//! one optional key per line:
+//!
+//! ```text
+//! <frequency>;interval=<n>;days=<DAY>[,<DAY>...];start=<YYYY-MM-DD>
+//!     [;policy=skip|slide|count]
+//!     [;shift=<n>:<n>[,...]]
+//!     [;months=<MON>[,...]]
+//!     [;weeks=<n>[,...]]
+//!     [;yeardays=<n>[,...][;missing=skip|clamp]]
+//!     [;monthdays=<n>[,...][;missing=skip|clamp]|;nth=<n><DAY>[,...]]
+//!     [;setpos=<n>[,...]]
+//!     [;until=<YYYY-MM-DD>]
+//!     [;count=<n>]
+//!     [;exdate=<YYYY-MM-DD>[,...]]
+//!     [;rdate=<YYYY-MM-DD>[,...]]
+//!     [;times=[<DAY>@]<HH:MM[:SS]>[,...]]
+//! ```
+//!
+//! - `frequency` comes first and is one of `daily`, `weekly`, `monthly`, `quarterly` or `yearly`.
+//! - The remaining fields are `key=value` pairs separated by `;`, in any order, each at most once.
+//! - `interval` is a positive decimal integer, 1 when omitted.
+//! - `days` is a comma separated list of `MON`, `TUE`, `WED`, `THU`, `FRI`, `SAT`, `SUN`, all
+//!   seven when omitted.
+//! - `start` is required and is an ISO 8601 calendar date as printed by chrono (years outside
+//!   `0..=9999` carry an explicit sign, e.g. `+12345-01-01`).
+//! - `policy` is optional and daily only: `skip` (the default) drops steps landing on a day outside
+//!   `days`, `slide` moves them to the next allowed day and `count` counts the interval in allowed
+//!   days only, see `WeekdayPolicy`.
+//! - `shift` is optional, daily only and needs the `skip` policy: a cycle length in days, a colon
+//!   and the comma separated zero based days of each cycle that are occurrences, e.g. `8:0,1,2,3`
+//!   for four on, four off; day `0` is the start, see `ShiftPattern`.
+//! - `months` is optional and yearly only, a comma separated list of `JAN`, `FEB`, ..., `DEC`:
+//!   the occurrences fall on the start's day of month (or the days `monthdays` or `nth` pick) in each
+//!   of those months, see `MonthFlags`.
+//! - `weeks` is optional, yearly only and excludes `months`, `monthdays` and `nth`: a comma
+//!   separated list of ISO week numbers `1..=53`; the occurrences fall on the allowed `days` of
+//!   those weeks of every on ISO year, see `Schedule::iso_weeks`.
+//! - `yeardays` is optional, yearly only and excludes `months`, `weeks`, `monthdays` and `nth`: a
+//!   comma separated list of days of the year `1..=366`; the occurrences fall on those days of each
+//!   on-year instead of on the start's month and day, see `YearDays`.
+//! - `monthdays` is optional and monthly, quarterly or yearly only, a comma separated list of days
+//!   of month `1..=31` (or `-31..=-1` counting from the end, `-1` being the last day): the
+//!   occurrences fall on those days of each on-month (of the start's month or each of `months` for
+//!   yearly rules) instead of on the start's day of month, see `MonthlyBy::Days`.
+//! - `missing` is optional and requires `monthdays` or `yeardays`: `skip` (the default) drops a day
+//!   in months or years lacking it, `clamp` moves it to the month's last day (first day when
+//!   counting from the end), or day 366 to December 31, see `MissingDay`.
+//! - `nth` is optional, monthly, quarterly or yearly only and excludes `monthdays`: a comma
+//!   separated list of ordinals `1..=5` (or `-5..=-1` counting from the end of the month) each
+//!   followed by the same day name, e.g. `nth=1MON,3MON` or `nth=-1FRI`, see
+//!   `MonthlyBy::NthWeekday`.
+//! - `setpos` is optional and not daily: a comma separated list of non-zero positions (negative
+//!   ones counting from the end) kept among each on-period's candidates, e.g. `setpos=-1` with
+//!   `days=MON,TUE,WED,THU,FRI` for the last workday of each month, see `Schedule::set_positions`.
+//! - `until` is optional, a date in the same form as `start`: the inclusive end of the series.
+//! - `count` is optional, a positive integer: the number of occurrences, the start included.
+//! - `exdate` is optional, a comma separated list of dates removed from the series.
+//! - `rdate` is optional, a comma separated list of one-off dates added to the series, none of
+//!   them before `start`.
+//! - `times` is optional, a comma separated list of times of day each occurrence date expands
+//!   into; an entry prefixed with a day name and `@`, as in `THU@14:00`, is that weekday's own
+//!   time, see `TimesOfDay`.
+//! - Names are case sensitive and no whitespace is allowed.
+//!
+//! `Display` always writes the four fields above in that order with the days Monday first, followed
+//! by `policy` unless `skip`, `shift`, `months`, `weeks`, `yeardays`, `monthdays` or `nth`
+//! (ascending), `missing` when `clamp`, `setpos` (ascending), `until`, `count`, `exdate` and
+//! `rdate` (dates ascending) and `times` (shared times ascending, then weekday times Monday first,
+//! with seconds) when set, so the output is canonical, e.g.
+//! `weekly;interval=2;days=TUE,THU;start=2023-09-21`. Parsing reports the offending field through
+//! `ParseScheduleError` and then applies the checks of `Schedule::new`.
+//! `Schedule::summary` is a shorter, one-way line for logs such as
+//! `weekly*2 on TUE,THU from 2023-09-12 except 3 dates`.
+//!
+//! # serde
+//!
+//! With the `serde` feature, `Repeating` (as `"daily"`, `"weekly"`, ...), `WeekdayFlags` (as a list
+//! of day names like `["MON","WED"]`, or its `u8` bitmask in formats that are not human-readable),
+//! `MonthFlags` (likewise, as `["MAR","JUN"]`), `MonthDay`, `MonthlyBy`, `MissingDay`, `YearDays`,
+//! `ShiftPattern`, `TimesOfDay`, `WeekdayPolicy` and `Schedule` implement `Serialize` and
+//! `Deserialize`. Deserializing a `Schedule` runs the same validation as `Schedule::new`.
+//!
+//! # RFC 5545
+//!
+//! With the `rfc5545` feature, `Schedule::from_rrule` reads an iCalendar `RRULE` value against a
+//! start date, reporting the parts a `Schedule` cannot represent instead of dropping them;
+//! `Schedule::parse_rrule_collecting` reports all of its errors, each with the byte range of its
+//! part, for editors. `Schedule::to_rrule` writes one back; `Schedule::to_ics_event` wraps it in a
+//! minimal iCalendar file. `parse_ics_events` reads the recurring events of an iCalendar file,
+//! reporting each one it cannot represent by its position, `UID` and failing property.
+//!
+//! # Google Calendar
+//!
+//! With the `gcal` feature, `GcalRecurrence::from_event` reads the `start` and `recurrence` fields
+//! of a Google Calendar API event resource as a `serde_json::Value`, and `GcalRecurrence::to_event`
+//! writes them for an insert request.
+//!
+//! # Microsoft Graph
+//!
+//! With the `graph` feature, `GraphRecurrence` is the `recurrence` object of a Graph (Outlook)
+//! event, which `Schedule::from_graph` reads and `Schedule::to_graph` writes, failing with a
+//! `GraphError` naming what the other side cannot represent.
+//!
+//! # Cron
+//!
+//! With the `cron` feature, `Schedule::from_cron` reads the day and time fields of a five field
+//! cron expression such as `0 9 * * MON-FRI`, failing with a `CronError` where cron's meaning has
+//! no `Schedule` equivalent, e.g. a day of month and a day of week together. `Schedule::to_cron`
+//! writes the rules cron can express back, naming every feature in the way of the others.
+//!
+//! # Phrases
+//!
+//! With the `phrases` feature, `Schedule::from_phrase` reads English phrases such as "every other
+//! friday" or "monthly on the last day" in the grammar it documents, pointing at the first word it
+//! does not recognise.
+//!
+//! # The time crate
+//!
+//! With the `time` feature, `find_next_time_date` and `match_repeating_time_date` take and return
+//! `time::Date`s, and `WeekdayFlags::from_time_weekday` builds a flag from a `time::Weekday`.
+//!
+//! # jiff
+//!
+//! With the `jiff` feature, `find_next_date_jiff` and `match_repeating_date_jiff` do the same with
+//! `jiff::civil::Date`s, and `WeekdayFlags::from_jiff_weekday` builds a flag from a
+//! `jiff::civil::Weekday`.
+//!
+//! # WebAssembly
+//!
+//! With the `wasm` feature, `WasmRule` is exported to JavaScript as `Rule`, taking and returning
+//! ISO 8601 date strings, and its errors name their `RepeatingDateError` variant. The crate builds
+//! for `wasm32-unknown-unknown` as chrono's clock is not used. It is an `rlib` only, so a wasm
+//! module is built by passing the crate type, e.g. `cargo rustc --lib --release --target
+//! wasm32-unknown-unknown --features wasm --crate-type cdylib`, and running `wasm-bindgen` on it.
+//!
+//! # Localized weekday names
+//!
+//! With the `locales` feature, `WeekdayFlags::parse_localized` reads lists of weekday names such
+//! as `"seg, qua, sex"` in a `WeekdayLocale`: English, Brazilian Portuguese, German or Spanish.
+//!
+//! # Test support
+//!
+//! With the `test-support` feature, `Simulation` walks a schedule forward day by day from a
+//! synthetic "today", tracking which occurrences were completed, for tests of reminder flows.
+//!
+//! # Time zones
+//!
+//! With the `chrono-tz` feature, `find_next_in_zone` and `match_repeating_in_zone` evaluate a rule
+//! whose start is a local datetime in a `chrono_tz::Tz` against instants in any zone. Dates and
+//! weekdays are those of the rule's zone; a local time repeated by a backward transition occurs at
+//! its earlier instant and one skipped by a forward transition is moved forward by the gap.
+
 use bitflags::bitflags;
 use chrono::Datelike;
 
-#[derive(Debug)]
+#[cfg(feature = "cron")]
+mod cron;
+mod datetime;
+mod description;
+mod difference;
+mod equivalence;
+mod exclusion_delta;
+mod expansion;
+#[cfg(feature = "gcal")]
+mod gcal;
+#[cfg(feature = "graph")]
+mod graph;
+#[cfg(feature = "rfc5545")]
+mod ics;
+mod intersection;
+#[cfg(feature = "jiff")]
+mod jiff_interop;
+mod month_day;
+mod month_set;
+mod monthly_by;
+mod occurrence_index;
+mod occurrence_list;
+mod occurrence_window;
+mod occurrences;
+#[cfg(feature = "phrases")]
+mod phrase;
+mod recurring_span;
+#[cfg(feature = "rfc5545")]
+mod rrule;
+mod rule_format;
+mod schedule;
+mod schedule_set;
+mod shift_pattern;
+#[cfg(feature = "test-support")]
+mod simulation;
+mod sliding;
+#[cfg(feature = "time")]
+mod time_interop;
+mod times_of_day;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "locales")]
+mod weekday_locale;
+mod year_day;
+#[cfg(feature = "chrono-tz")]
+mod zoned;
+
+#[cfg(feature = "cron")]
+pub use cron::CronError;
+pub use datetime::{find_next_datetime, match_repeating_datetime};
+pub use difference::{ScheduleDifference, ScheduleDifferenceIter};
+pub use exclusion_delta::ExclusionDelta;
+#[cfg(feature = "gcal")]
+pub use gcal::{GcalError, GcalRecurrence};
+#[cfg(feature = "graph")]
+pub use graph::{GraphError, GraphPattern, GraphPatternType, GraphRange, GraphRangeType, GraphRecurrence, GraphWeekIndex};
+#[cfg(feature = "rfc5545")]
+pub use ics::{parse_ics_events, IcsError, IcsErrorKind, IcsEvent};
+pub use intersection::{ScheduleIntersection, ScheduleIntersectionIter};
+#[cfg(feature = "jiff")]
+pub use jiff_interop::{find_next_date_jiff, match_repeating_date_jiff};
+pub use month_day::{InvalidMonthDay, MonthDay};
+pub use month_set::MonthFlags;
+pub use monthly_by::{MissingDay, MonthlyBy};
+pub use occurrence_list::{OccurrenceList, OccurrenceListError};
+pub use occurrence_window::OccurrenceWindow;
+pub use occurrences::{BoundedOccurrences, Occurrences, PastOccurrences};
+#[cfg(feature = "phrases")]
+pub use phrase::PhraseError;
+pub use recurring_span::RecurringSpan;
+#[cfg(feature = "rfc5545")]
+pub use rrule::{RruleError, SpannedRruleError};
+pub use rule_format::ParseScheduleError;
+pub use schedule::{Schedule, ScheduleBuilder};
+pub use schedule_set::{ScheduleSet, ScheduleSetError, ScheduleSetIter};
+pub use shift_pattern::{InvalidShiftPattern, ShiftPattern};
+#[cfg(feature = "test-support")]
+pub use simulation::Simulation;
+pub use sliding::WeekdayPolicy;
+#[cfg(feature = "time")]
+pub use time_interop::{find_next_time_date, match_repeating_time_date};
+pub use times_of_day::{Datetimes, EmptyTimesOfDay, TimesOfDay};
+#[cfg(feature = "wasm")]
+pub use wasm::WasmRule;
+#[cfg(feature = "locales")]
+pub use weekday_locale::{LocalizedWeekdayError, WeekdayLocale};
+pub use year_day::{InvalidYearDay, YearDays};
+#[cfg(feature = "chrono-tz")]
+pub use zoned::{find_next_in_zone, match_repeating_in_zone};
+
+/// Why a rule could not be built or searched.
+///
+/// New variants may be added in minor releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RepeatingDateError {
-    StartDateBeforeBound,
-    WrongWeekday
+    /// The search started at `from`, before the rule's `start`.
+    StartDateBeforeBound { from: chrono::NaiveDate, start: chrono::NaiveDate },
+    /// The start falls on `start_weekday`, which is not one of the `allowed` weekdays.
+    WrongWeekday { start_weekday: chrono::Weekday, allowed: WeekdayFlags },
+    NoOccurrenceFound,
+    MissingStartDate,
+    ZeroInterval,
+    EmptyWeekdaySet,
+    /// The next occurrence would fall after the schedule's `until` date.
+    ScheduleEnded,
+    UntilBeforeStart,
+    ZeroCount,
+    ExtraDateBeforeStart,
+    /// A search combining schedules found nothing within its horizon.
+    HorizonExceeded,
+    /// The requested occurrence would fall after `NaiveDate::MAX`.
+    DateOutOfRange,
+    /// An interval of more than `i32::MAX` days, months or years, longer than the whole calendar.
+    IntervalTooLarge,
+    /// A yearly search tried `searched_years` on-years without finding one on an allowed weekday,
+    /// see `find_next_date_with_limit`.
+    YearSearchExhausted { searched_years: u32 },
+    /// A `MonthlyBy` on a schedule that is not monthly, quarterly or yearly, or one that picks no
+    /// valid day.
+    InvalidMonthlyRule,
+    /// The start date is not one of the days the `MonthlyBy`, month set, ISO weeks, days of the year,
+    /// shift pattern or set positions pick.
+    StartNotInRule,
+    /// A month set on a schedule that is not yearly, or one without any month.
+    InvalidMonthSet,
+    /// `WeekdayPolicy::Slide` or `WeekdayPolicy::Count` on a schedule that is not daily.
+    InvalidWeekdayPolicy,
+    /// Set positions on a daily schedule, or a position that is 0 or beyond 366 either way.
+    InvalidSetPosition,
+    /// ISO weeks on a schedule that is not yearly or also picks months, or a week outside `1..=53`.
+    InvalidWeekNumber,
+    /// Days of the year on a schedule that is not yearly or also picks months or ISO weeks, or an
+    /// empty set of them.
+    InvalidYearDayRule,
+    /// A `ShiftPattern` on a schedule that is not daily or whose weekday policy is not `Skip`, or
+    /// one with on days outside its cycle.
+    InvalidShiftRule,
+    /// A deserialized `TimesOfDay` without any time.
+    InvalidTimesOfDay,
+    /// A `TimesOfDay` without shared times that lacks a time for a weekday the schedule can fall
+    /// on, extra dates included.
+    MissingWeekdayTime,
+    /// An `OccurrenceWindow` duration that is zero or negative.
+    InvalidDuration,
+    /// A `RecurringSpan` whose first span ends before the schedule's start.
+    SpanEndBeforeStart,
+    /// A frequency the function does not handle: `Hourly` and `Minutely` outside the datetime
+    /// functions.
+    UnsupportedFrequency
+}
+
+impl std::fmt::Display for RepeatingDateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepeatingDateError::StartDateBeforeBound { from, start } => write!(f, "{} is before the start date {}", from, start),
+            RepeatingDateError::WrongWeekday { start_weekday, allowed } => {
+                let allowed: Vec<&str> = allowed.extract_weekdays().map(weekday_name).collect();
+
+                write!(f, "the start falls on {}, which is not one of the allowed weekdays {}", weekday_name(*start_weekday), allowed.join(","))
+            },
+            RepeatingDateError::NoOccurrenceFound => write!(f, "no occurrence was found"),
+            RepeatingDateError::MissingStartDate => write!(f, "the schedule has no start date"),
+            RepeatingDateError::ZeroInterval => write!(f, "the interval must be at least 1"),
+            RepeatingDateError::EmptyWeekdaySet => write!(f, "no weekday is allowed"),
+            RepeatingDateError::ScheduleEnded => write!(f, "the schedule has ended"),
+            RepeatingDateError::UntilBeforeStart => write!(f, "the until date is before the start date"),
+            RepeatingDateError::ZeroCount => write!(f, "the count must be at least 1"),
+            RepeatingDateError::ExtraDateBeforeStart => write!(f, "an extra date is before the start date"),
+            RepeatingDateError::HorizonExceeded => write!(f, "no common date was found within the search horizon"),
+            RepeatingDateError::DateOutOfRange => write!(f, "the occurrence would fall after the last supported date"),
+            RepeatingDateError::IntervalTooLarge => write!(f, "the interval is longer than the supported calendar"),
+            RepeatingDateError::YearSearchExhausted { searched_years } => write!(f, "no occurrence on an allowed weekday within {} on-years", searched_years),
+            RepeatingDateError::InvalidMonthlyRule => write!(f, "a monthly pattern needs a monthly, quarterly or yearly schedule and a valid day"),
+            RepeatingDateError::StartNotInRule => write!(f, "the start date is not one of the days the rule picks"),
+            RepeatingDateError::InvalidMonthSet => write!(f, "a month set needs a yearly schedule and at least one month"),
+            RepeatingDateError::InvalidWeekdayPolicy => write!(f, "the slide and count weekday policies need a daily schedule"),
+            RepeatingDateError::InvalidSetPosition => write!(f, "set positions need a schedule that is not daily and must be non-zero and within 366 either way"),
+            RepeatingDateError::InvalidWeekNumber => write!(f, "ISO weeks need a yearly schedule without months and must be within 1 to 53"),
+            RepeatingDateError::InvalidYearDayRule => write!(f, "days of the year need a yearly schedule without months or ISO weeks and at least one day"),
+            RepeatingDateError::InvalidShiftRule => write!(f, "a shift pattern needs a daily schedule with the skip policy and on days within its cycle"),
+            RepeatingDateError::InvalidTimesOfDay => write!(f, "times of day need at least one time"),
+            RepeatingDateError::MissingWeekdayTime => write!(f, "the times of day lack a time for a weekday the schedule falls on"),
+            RepeatingDateError::InvalidDuration => write!(f, "the occurrence duration must be positive"),
+            RepeatingDateError::SpanEndBeforeStart => write!(f, "the first span ends before the schedule's start"),
+            RepeatingDateError::UnsupportedFrequency => write!(f, "hourly and minutely rules need the datetime functions"),
+        }
+    }
 }
 
-#[derive(Debug)]
+impl std::error::Error for RepeatingDateError {}
+
+/// How often a rule repeats, in steps of its interval.
+///
+/// New frequencies may be added in minor releases, so matches outside this crate need a wildcard
+/// arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+#[non_exhaustive]
 pub enum Repeating {
     Daily,
     Weekly,
     Monthly,
-    Yearly
+    /// Every three months: the same occurrences as `Monthly` with three times the interval, so a
+    /// `MonthlyBy` or set positions pick within the first month of each on-quarter.
+    Quarterly,
+    Yearly,
+    /// Every `interval` hours, for `find_next_datetime` and `match_repeating_datetime` only.
+    Hourly,
+    /// Every `interval` minutes, for `find_next_datetime` and `match_repeating_datetime` only.
+    Minutely
+}
+
+impl Repeating {
+    /// The frequency and interval the lookups run on; quarterly rules step by three months.
+    pub(crate) fn normalized(self, interval: u64) -> (Repeating, u64) {
+        match self {
+            Repeating::Quarterly => (Repeating::Monthly, interval.saturating_mul(3)),
+            repeat => (repeat, interval),
+        }
+    }
 }
 
 bitflags! {
@@ -43,7 +448,129 @@ bitflags! {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidWeekdayBits {
+    /// The bits outside of the seven weekday flags.
+    pub invalid_mask: u8
+}
+
+impl std::fmt::Display for InvalidWeekdayBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid weekday bits {:#04x}, only the lowest seven bits name weekdays", self.invalid_mask)
+    }
+}
+
+impl std::error::Error for InvalidWeekdayBits {}
+
+/// `WeekdayFlags` converted to a `chrono::Weekday` must hold exactly one weekday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotOneWeekday {
+    pub flags: WeekdayFlags
+}
+
+impl std::fmt::Display for NotOneWeekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "weekday bits {:#04x} do not name exactly one weekday", self.flags.bits())
+    }
+}
+
+impl std::error::Error for NotOneWeekday {}
+
+impl TryFrom<WeekdayFlags> for chrono::Weekday {
+    type Error = NotOneWeekday;
+
+    /// The weekday of a single flag; empty flags, several weekdays and bits outside of
+    /// `WeekdayFlags::ANY` are rejected.
+    fn try_from(flags: WeekdayFlags) -> Result<chrono::Weekday, NotOneWeekday> {
+        let bits = flags.bits();
+
+        if bits.count_ones() != 1 || bits & !WeekdayFlags::ANY.bits() != 0 {
+            return Err(NotOneWeekday { flags });
+        }
+
+        Ok(WEEKDAYS[bits.trailing_zeros() as usize])
+    }
+}
+
+impl From<chrono::Weekday> for WeekdayFlags {
+    fn from(weekday: chrono::Weekday) -> WeekdayFlags {
+        WeekdayFlags::from_weekday(weekday)
+    }
+}
+
+impl From<&[chrono::Weekday]> for WeekdayFlags {
+    fn from(weekdays: &[chrono::Weekday]) -> WeekdayFlags {
+        weekdays.iter().copied().collect()
+    }
+}
+
+/// The selected weekdays in Monday to Sunday order, as `WeekdayFlags::extract_weekdays` yields them.
+impl From<WeekdayFlags> for Vec<chrono::Weekday> {
+    fn from(weekdays: WeekdayFlags) -> Vec<chrono::Weekday> {
+        weekdays.extract_weekdays().collect()
+    }
+}
+
+/// The union of the weekdays, repeats included once.
+impl FromIterator<chrono::Weekday> for WeekdayFlags {
+    fn from_iter<I: IntoIterator<Item = chrono::Weekday>>(weekdays: I) -> WeekdayFlags {
+        let mut flags = WeekdayFlags::empty();
+        flags.extend(weekdays);
+        flags
+    }
+}
+
+impl Extend<chrono::Weekday> for WeekdayFlags {
+    fn extend<I: IntoIterator<Item = chrono::Weekday>>(&mut self, weekdays: I) {
+        for weekday in weekdays {
+            *self |= WeekdayFlags::from_weekday(weekday);
+        }
+    }
+}
+
+const WEEKDAYS: [chrono::Weekday; 7] = [chrono::Weekday::Mon, chrono::Weekday::Tue, chrono::Weekday::Wed, chrono::Weekday::Thu,
+    chrono::Weekday::Fri, chrono::Weekday::Sat, chrono::Weekday::Sun];
+
+/// Weekday of the highest set bit in a non-zero, masked weekday bitmask.
+fn highest_weekday(bits: u8) -> chrono::Weekday {
+    WEEKDAYS[7 - bits.leading_zeros() as usize]
+}
+
+/// The day a calendar week begins on, which decides the weeks a weekly rule's interval counts:
+/// Monday as in ISO 8601 and throughout this crate, or Sunday as in the US.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> chrono::Weekday {
+        match self {
+            WeekStart::Monday => chrono::Weekday::Mon,
+            WeekStart::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
 impl WeekdayFlags {
+    /// Strict constructor for raw bitmasks, rejecting anything outside of `WeekdayFlags::ANY`.
+    pub fn try_from_bits(bits: u8) -> Result<WeekdayFlags, InvalidWeekdayBits> {
+        let invalid_mask = bits & !WeekdayFlags::ANY.bits();
+
+        if invalid_mask != 0 {
+            return Err(InvalidWeekdayBits { invalid_mask });
+        }
+
+        Ok(WeekdayFlags::from_bits_retain(bits))
+    }
+
+    /// Lenient constructor for raw bitmasks, silently dropping anything outside of `WeekdayFlags::ANY`.
+    pub fn from_bits_masked(bits: u8) -> WeekdayFlags {
+        WeekdayFlags::from_bits_retain(bits & WeekdayFlags::ANY.bits())
+    }
+
     pub fn from_weekday(weekday: chrono::Weekday) -> WeekdayFlags {
         match weekday {
             chrono::Weekday::Mon => WeekdayFlags::MON,
@@ -56,82 +583,344 @@ impl WeekdayFlags {
         }
     }
 
+    #[deprecated(note = "panics unless exactly one weekday is set, use `chrono::Weekday::try_from` instead")]
     pub fn to_weekday(&self) -> chrono::Weekday {
-        match *self {
-            WeekdayFlags::MON => chrono::Weekday::Mon,
-            WeekdayFlags::TUE => chrono::Weekday::Tue,
-            WeekdayFlags::WED => chrono::Weekday::Wed,
-            WeekdayFlags::THU => chrono::Weekday::Thu,
-            WeekdayFlags::FRI => chrono::Weekday::Fri,
-            WeekdayFlags::SAT => chrono::Weekday::Sat,
-            WeekdayFlags::SUN => chrono::Weekday::Sun,
-            _ => panic!("What ?")
-        }
+        chrono::Weekday::try_from(*self).expect("exactly one weekday must be set")
     }
 
-    pub fn next_weekday(&self, current_dat: chrono::Weekday) -> chrono::Weekday {
-        let weekday_flags: Vec<WeekdayFlags> = self.iter().collect();
+    /// The selected weekday strictly after `current_dat` in the Monday-first week, wrapping around
+    /// to the earliest selected one (so a set holding only `current_dat` yields `current_dat`
+    /// itself, a week on), or `None` when no weekday is selected.
+    pub fn next_weekday(&self, current_dat: chrono::Weekday) -> Option<chrono::Weekday> {
+        let later_bits = self.bits() & !((WeekdayFlags::from_weekday(current_dat).bits() << 1) - 1);
 
-        for i in &weekday_flags {
-            let i_as_weekday = i.to_weekday();
+        WeekdayFlags::from_bits_masked(later_bits).extract_weekdays().next()
+            .or_else(|| self.extract_weekdays().next())
+    }
 
-            if i_as_weekday as u8 > current_dat as u8 {
-                return i_as_weekday;
-            }
+    /// The `n`-th selected weekday strictly after `current_dat`, counting from 1 so that `n = 1` is
+    /// `next_weekday`, with the calendar days advanced to reach it: for TUE and THU, the third after
+    /// a Thursday is the Tuesday twelve days on. `None` when no weekday is selected, `n` is zero or
+    /// the days do not fit in a `u32`.
+    pub fn nth_weekday_from(&self, current_dat: chrono::Weekday, n: u32) -> Option<(chrono::Weekday, u32)> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+
+        if bits == 0 || n == 0 {
+            return None;
         }
-        
-        weekday_flags[0].to_weekday()
+
+        let selected_count = bits.count_ones();
+        let (weeks, index) = ((n - 1) / selected_count, (n - 1) % selected_count);
+
+        let up_to_bits = (WeekdayFlags::from_weekday(current_dat).bits() << 1) - 1;
+        let weekday = SelectedWeekdays { bits: bits & !up_to_bits }.chain(SelectedWeekdays { bits: bits & up_to_bits }).nth(index as usize)?;
+        let days = match days_until(current_dat, weekday) {
+            0 => 7,
+            days => days as u32,
+        };
+
+        weeks.checked_mul(7)?.checked_add(days).map(|days| (weekday, days))
+    }
+
+    #[deprecated(note = "same as `next_weekday`")]
+    pub fn next_weekday_bitwise(&self, current_dat: chrono::Weekday) -> Option<chrono::Weekday> {
+        self.next_weekday(current_dat)
+    }
+
+    /// The earliest selected weekday of the Monday-first week up to and including `current_dat`,
+    /// or `None` when none of Monday to `current_dat` is selected. See
+    /// `first_valid_weekday_bitwise` for the earliest one in the whole week.
+    pub fn first_valid_weekday(&self, current_dat: chrono::Weekday) -> Option<chrono::Weekday> {
+        self.extract_weekdays().next().filter(|weekday| weekday.num_days_from_monday() <= current_dat.num_days_from_monday())
+    }
+
+    /// The earliest selected weekday in the week, or `None` when no weekday is selected.
+    pub fn first_valid_weekday_bitwise(&self) -> Option<chrono::Weekday> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+
+        chrono::Weekday::try_from(WeekdayFlags::from_bits_retain(bits & bits.wrapping_neg())).ok()
     }
 
-    pub fn next_weekday_bitwise(&self, current_dat: chrono::Weekday) -> chrono::Weekday {
-        let mut weekday_flag = WeekdayFlags::from_weekday(current_dat).bits() << 1;
-        let bits = self.bits();
+    /// The latest selected weekday strictly before `current_dat`, wrapping around to the previous
+    /// week (so a set holding only `current_dat` yields `current_dat` itself, a week back).
+    pub fn previous_weekday_strictly_before(&self, current_dat: chrono::Weekday) -> Option<chrono::Weekday> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+        let earlier_bits = bits & (WeekdayFlags::from_weekday(current_dat).bits() - 1);
 
-        while weekday_flag > 0 && (bits & weekday_flag) == 0 {
-            weekday_flag <<= 1;
+        if earlier_bits != 0 {
+            Some(highest_weekday(earlier_bits))
+        } else {
+            self.last_selected()
         }
-        
-        if weekday_flag > 0 {
-            WeekdayFlags::from_bits(weekday_flag).unwrap().to_weekday()
+    }
+
+    /// The latest selected weekday on or before `current_dat`: `current_dat` itself when it is
+    /// selected, otherwise `previous_weekday_strictly_before`.
+    pub fn previous_weekday(&self, current_dat: chrono::Weekday) -> Option<chrono::Weekday> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+        let up_to_bits = bits & ((WeekdayFlags::from_weekday(current_dat).bits() << 1) - 1);
+
+        if up_to_bits != 0 {
+            Some(highest_weekday(up_to_bits))
         } else {
-            self.first_valid_weekday_bitwise()
+            self.last_selected()
         }
     }
 
-    pub fn first_valid_weekday(&self, current_dat: chrono::Weekday) -> chrono::Weekday {
-        for i in self.into_iter() {
-            let i_as_weekday = i.to_weekday();
+    /// Days to walk back from `current_dat` to reach `previous_weekday_strictly_before`, in `1..=7`.
+    pub fn days_since_previous(&self, current_dat: chrono::Weekday) -> Option<u8> {
+        let previous = self.previous_weekday_strictly_before(current_dat)?;
+        let weekday_diff = current_dat.num_days_from_monday() as u8 + 7 - previous.num_days_from_monday() as u8;
 
-            if (i_as_weekday as u8) < (current_dat as u8) {
-                return i_as_weekday;
-            }
+        Some((weekday_diff - 1) % 7 + 1)
+    }
+
+    /// The selected weekday latest in the (Monday-first) week.
+    pub fn last_selected(&self) -> Option<chrono::Weekday> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+
+        if bits == 0 {
+            return None;
         }
 
-        current_dat
+        Some(highest_weekday(bits))
+    }
+
+    /// The selected weekdays in Monday to Sunday order, skipping bits outside of `WeekdayFlags::ANY`.
+    pub fn extract_weekdays(&self) -> SelectedWeekdays {
+        SelectedWeekdays { bits: WeekdayFlags::from_bits_masked(self.bits()).bits() }
+    }
+
+    /// The selected weekdays in the order of a week beginning on `week_start`, e.g. Sunday first.
+    pub fn extract_weekdays_in(&self, week_start: WeekStart) -> std::iter::Chain<SelectedWeekdays, SelectedWeekdays> {
+        let bits = WeekdayFlags::from_bits_masked(self.bits()).bits();
+        let earlier_bits = WeekdayFlags::from_weekday(week_start.weekday()).bits() - 1;
+
+        SelectedWeekdays { bits: bits & !earlier_bits }.chain(SelectedWeekdays { bits: bits & earlier_bits })
+    }
+
+    #[deprecated(note = "use `extract_weekdays`, which does not allocate, or `Vec::from`")]
+    pub fn extract_weekdays_vec(&self) -> Vec<chrono::Weekday> {
+        self.extract_weekdays().collect()
     }
+}
+
+/// Upper case day names shared by the text and serde representations, Monday first.
+pub(crate) const WEEKDAY_NAMES: [&str; 7] = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+
+pub(crate) fn weekday_name(weekday: chrono::Weekday) -> &'static str {
+    WEEKDAY_NAMES[weekday.num_days_from_monday() as usize]
+}
+
+pub(crate) fn weekday_flag_named(name: &str) -> Option<WeekdayFlags> {
+    let index = WEEKDAY_NAMES.iter().position(|candidate| *candidate == name)?;
+
+    Some(WeekdayFlags::from_weekday(WEEKDAYS[index]))
+}
+
+/// RFC 5545 two letter day codes, Monday first.
+pub(crate) const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// A token of a `WeekdayFlags` string that names no weekday or group of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidWeekdayName {
+    pub token: String,
+    /// The token's zero based index among the comma separated ones.
+    pub position: usize
+}
+
+impl std::fmt::Display for InvalidWeekdayName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` at position {} is not a weekday (MON..SUN or MO..SU) nor ANY, MIDWEEK or WEEKEND", self.token, self.position)
+    }
+}
+
+impl std::error::Error for InvalidWeekdayName {}
+
+/// The selected day names in Monday to Sunday order separated by commas, as in `MON,WED,FRI`;
+/// `ANY`, `MIDWEEK` and `WEEKEND` print as their names and no weekday as an empty string.
+impl std::fmt::Display for WeekdayFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match WeekdayFlags::from_bits_masked(self.bits()) {
+            WeekdayFlags::ANY => f.write_str("ANY"),
+            WeekdayFlags::MIDWEEK => f.write_str("MIDWEEK"),
+            WeekdayFlags::WEEKEND => f.write_str("WEEKEND"),
+            weekdays => f.write_str(&weekdays.extract_weekdays().map(weekday_name).collect::<Vec<&str>>().join(",")),
+        }
+    }
+}
+
+/// Comma separated day names in any case and order: three letter ones (`MON`), RFC 5545 codes
+/// (`MO`) and the groups `ANY`, `MIDWEEK` and `WEEKEND`, repeats allowed. An empty string is no
+/// weekday.
+impl std::str::FromStr for WeekdayFlags {
+    type Err = InvalidWeekdayName;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.trim().is_empty() {
+            return Ok(WeekdayFlags::empty());
+        }
+
+        value.split(',').enumerate().try_fold(WeekdayFlags::empty(), |weekdays, (position, token)| {
+            let name = token.trim().to_ascii_uppercase();
 
-    pub fn first_valid_weekday_bitwise(&self) -> chrono::Weekday {
-        let bits = self.bits();
-        WeekdayFlags::from_bits(bits &  !( bits - 1 )).unwrap().to_weekday()
+            let flags = match name.as_str() {
+                "ANY" => Some(WeekdayFlags::ANY),
+                "MIDWEEK" => Some(WeekdayFlags::MIDWEEK),
+                "WEEKEND" => Some(WeekdayFlags::WEEKEND),
+                code if code.len() == 2 => WEEKDAY_CODES.iter().position(|candidate| *candidate == code).map(|index| WeekdayFlags::from_weekday(WEEKDAYS[index])),
+                name => weekday_flag_named(name),
+            };
+
+            flags.map(|flags| weekdays | flags).ok_or_else(|| InvalidWeekdayName { token: token.trim().to_string(), position })
+        })
     }
+}
 
-    pub fn extract_weekdays(&self) -> Vec<chrono::Weekday> {
-        let mut result = vec![];
+/// Iterator over the weekdays of a `WeekdayFlags`, see `WeekdayFlags::extract_weekdays`.
+#[derive(Debug, Clone)]
+pub struct SelectedWeekdays {
+    bits: u8
+}
+
+impl Iterator for SelectedWeekdays {
+    type Item = chrono::Weekday;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let weekday = WEEKDAYS[self.bits.trailing_zeros() as usize];
+        self.bits &= self.bits - 1;
+
+        Some(weekday)
+    }
 
-        for i in self.iter() {
-            match i {
-                WeekdayFlags::MON => result.push(chrono::Weekday::Mon),
-                WeekdayFlags::TUE => result.push(chrono::Weekday::Tue),
-                WeekdayFlags::WED => result.push(chrono::Weekday::Wed),
-                WeekdayFlags::THU => result.push(chrono::Weekday::Thu),
-                WeekdayFlags::FRI => result.push(chrono::Weekday::Fri),
-                WeekdayFlags::SAT => result.push(chrono::Weekday::Sat),
-                WeekdayFlags::SUN => result.push(chrono::Weekday::Sun),
-                _ => panic!("What ?")
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bits.count_ones() as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for SelectedWeekdays {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let weekday = highest_weekday(self.bits);
+        self.bits &= !WeekdayFlags::from_weekday(weekday).bits();
+
+        Some(weekday)
+    }
+}
+
+impl ExactSizeIterator for SelectedWeekdays {}
+
+impl std::iter::FusedIterator for SelectedWeekdays {}
+
+/// Serialized as the list of selected day names in Monday to Sunday order, e.g. `["MON","WED"]`,
+/// in human-readable formats and as the `u8` bitmask in compact ones. Either shape deserializes.
+#[cfg(feature = "serde")]
+impl serde::Serialize for WeekdayFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u8(self.bits());
+        }
+
+        let weekdays = self.extract_weekdays();
+        let mut seq = serializer.serialize_seq(Some(weekdays.len()))?;
+
+        for weekday in weekdays {
+            seq.serialize_element(weekday_name(weekday))?;
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WeekdayFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct WeekdayListVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for WeekdayListVisitor {
+            type Value = WeekdayFlags;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a list of weekday names such as [\"MON\", \"WED\"] or a bitmask up to 0x7F")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, bits: u64) -> Result<Self::Value, E> {
+                u8::try_from(bits)
+                    .ok()
+                    .and_then(WeekdayFlags::from_bits)
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(bits), &"a weekday bitmask up to 0x7F"))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut result = WeekdayFlags::empty();
+
+                while let Some(name) = seq.next_element::<std::borrow::Cow<'de, str>>()? {
+                    let weekday = weekday_flag_named(&name)
+                        .ok_or_else(|| serde::de::Error::unknown_variant(&name, &WEEKDAY_NAMES))?;
+
+                    if result.contains(weekday) {
+                        return Err(serde::de::Error::custom(format_args!("duplicate weekday `{}`", name)));
+                    }
+
+                    result |= weekday;
+                }
+
+                Ok(result)
             }
         }
 
-        result
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(WeekdayListVisitor)
+        } else {
+            deserializer.deserialize_u8(WeekdayListVisitor)
+        }
+    }
+}
+
+/// Which weekdays count as the weekend when phrases like "every weekend" or "every weekday" are
+/// read or written, see `Schedule::from_phrase_with` and `Schedule::describe_with`.
+/// `WeekdayFlags::WEEKEND` and `WeekdayFlags::MIDWEEK` only describe the Saturday/Sunday
+/// convention, which is the default here too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekendDefinition {
+    weekend: WeekdayFlags
+}
+
+impl WeekendDefinition {
+    pub const SATURDAY_SUNDAY: WeekendDefinition = WeekendDefinition { weekend: WeekdayFlags::WEEKEND };
+    pub const FRIDAY_SATURDAY: WeekendDefinition = WeekendDefinition { weekend: WeekdayFlags::FRI.union(WeekdayFlags::SAT) };
+
+    pub fn new(weekend: WeekdayFlags) -> WeekendDefinition {
+        WeekendDefinition { weekend: WeekdayFlags::from_bits_masked(weekend.bits()) }
+    }
+
+    pub fn weekend(&self) -> WeekdayFlags {
+        self.weekend
+    }
+
+    /// The working days, i.e. every weekday outside of the weekend.
+    pub fn workdays(&self) -> WeekdayFlags {
+        WeekdayFlags::ANY.difference(self.weekend)
+    }
+
+    pub fn is_weekend(&self, weekday: chrono::Weekday) -> bool {
+        self.weekend.contains(WeekdayFlags::from_weekday(weekday))
+    }
+}
+
+impl Default for WeekendDefinition {
+    fn default() -> Self {
+        WeekendDefinition::SATURDAY_SUNDAY
     }
 }
 
@@ -141,154 +930,1148 @@ pub fn days_until(current_dat: chrono::Weekday, next_dat: chrono::Weekday) -> i3
     ((7 + weekday_diff) % 7) as i32
 }
 
+/// Days back from `current_dat` to the latest `previous_dat`, in `0..=6`; the mirror of `days_until`.
+pub fn days_since(current_dat: chrono::Weekday, previous_dat: chrono::Weekday) -> u32 {
+    days_until(previous_dat, current_dat) as u32
+}
+
+/// Which way `weekday_offset` and `strict_weekday_offset` count from the current weekday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward
+}
+
+/// Signed days from `current_dat` to the nearest `target_dat` in `direction`: `0..=6` forward and
+/// `-6..=0` backward, 0 being the same weekday.
+pub fn weekday_offset(current_dat: chrono::Weekday, target_dat: chrono::Weekday, direction: Direction) -> i32 {
+    match direction {
+        Direction::Forward => days_until(current_dat, target_dat),
+        Direction::Backward => -(days_since(current_dat, target_dat) as i32),
+    }
+}
+
+/// Like `weekday_offset`, but strictly after or before `current_dat`: the same weekday is a week
+/// away, so offsets are `1..=7` forward and `-7..=-1` backward.
+pub fn strict_weekday_offset(current_dat: chrono::Weekday, target_dat: chrono::Weekday, direction: Direction) -> i32 {
+    match weekday_offset(current_dat, target_dat, direction) {
+        0 if direction == Direction::Forward => 7,
+        0 => -7,
+        offset => offset,
+    }
+}
+
+/// Calendar months from `start_date`'s month to `from_date`'s, ignoring the days: January 31 to
+/// February 1 is 1. `find_next_date` and `match_repeating_date` align monthly intervals with it,
+/// as a monthly rule falls in every `interval`-th calendar month whatever day it lands on; see
+/// `complete_months_between` for elapsed months.
 pub fn get_months_since(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate) -> i32 {
-    let years_diff = from_date.year_ce().1 as i32 - start_date.year_ce().1 as i32;
+    let years_diff = from_date.year() - start_date.year();
 
     let years_months = years_diff * 12;
     
     from_date.month() as i32 - start_date.month() as i32 + years_months
 }
 
-pub fn find_next_weekstart(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, interval: u64) -> chrono::NaiveDate {
-    let date_diff = from_date - start_date;
-    let date_delta_days = date_diff.num_days() as u64;
+/// Whole months elapsed from `from` to `to`, negative when `to` is earlier: a month counts once
+/// `from`'s day of month is reached, or the month's last day when it is shorter, so January 31 to
+/// February 1 is 0 and January 31 to February 28, 2023 is 1.
+pub fn complete_months_between(from: chrono::NaiveDate, to: chrono::NaiveDate) -> i32 {
+    if to < from {
+        return -complete_months_between(to, from);
+    }
 
-    let is_any_weekday_valid = weekdays.is_all();
+    let months = get_months_since(to, from);
+    let day_reached = month_day::days_in_month(to.year(), to.month()).map_or(from.day(), |days| from.day().min(days));
 
-    let days_needed_weekly = interval * 7;
-    let days_needed_total = days_needed_weekly + date_delta_days;
-    let fix = days_needed_total % days_needed_weekly;
+    if to.day() < day_reached { months - 1 } else { months }
+}
+
+pub(crate) fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub(crate) fn lcm(a: u128, b: u128) -> u128 {
+    (a / gcd(a, b)).saturating_mul(b)
+}
+
+/// Day number, counted as by `num_days_from_ce`, of the first day of the week containing `date`.
+/// Weeks are counted in day numbers as the calendar begins on a Thursday, so the first day of its
+/// first week is before `NaiveDate::MIN`.
+fn week_start_day(date: chrono::NaiveDate, week_start: WeekStart) -> i64 {
+    date.num_days_from_ce() as i64 - days_since(date.weekday(), week_start.weekday()) as i64
+}
+
+/// The date `days` days after the day numbered `day`; `None` outside the calendar.
+fn day_after(day: i64, days: u64) -> Option<chrono::NaiveDate> {
+    let day = day.checked_add(i64::try_from(days).ok()?)?;
+
+    chrono::NaiveDate::from_num_days_from_ce_opt(i32::try_from(day).ok()?)
+}
+
+/// Whole weeks from the week containing `from` to the one containing `to`.
+fn weeks_between(from: chrono::NaiveDate, to: chrono::NaiveDate, week_start: WeekStart) -> i64 {
+    (week_start_day(to, week_start) - week_start_day(from, week_start)) / 7
+}
+
+/// A weekly rule is "on" during the week beginning on `week_start` containing `start_date` and
+/// every `interval`-th week after it; both the search and the matching go through this definition.
+fn is_on_week(date: chrono::NaiveDate, start_date: chrono::NaiveDate, interval: u64, week_start: WeekStart) -> bool {
+    let weeks_elapsed = weeks_between(start_date, date, week_start) as u64;
+
+    weeks_elapsed.is_multiple_of(interval)
+}
 
-    let day_offset = days_needed_total - fix;
+/// First day of the first on-week strictly after the week containing `from_date`, `None` past the
+/// end of the calendar.
+fn next_on_week_start(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, interval: u64, week_start: WeekStart) -> Option<chrono::NaiveDate> {
+    let weeks_elapsed = weeks_between(start_date, from_date, week_start) as u64;
 
-    let date = start_date.checked_add_days(chrono::Days::new(day_offset)).unwrap();
-    let result_weekday = date.weekday();
+    let next_on_week = (weeks_elapsed - (weeks_elapsed % interval)).checked_add(interval)?;
 
-    let previous_weekday = weekdays.first_valid_weekday_bitwise();
-    let weekdays_offset_abs = ((!is_any_weekday_valid) as u8) * ((result_weekday as u8) - (previous_weekday as u8));
+    day_after(week_start_day(start_date, week_start), next_on_week.checked_mul(7)?)
+}
+
+/// Weekly rule with every weekday selected: all days of the on-weeks are occurrences,
+/// so the next one is either tomorrow or the first day of the next on-week.
+fn find_next_day_in_on_week(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, interval: u64, week_start: WeekStart) -> Option<chrono::NaiveDate> {
+    let next_day = from_date.succ_opt()?;
+
+    if is_on_week(next_day, start_date, interval, week_start) {
+        Some(next_day)
+    } else {
+        next_on_week_start(next_day, start_date, interval, week_start)
+    }
+}
+
+/// Weekly rule on the start's weekday only: occurrences are exactly `start + k * interval` weeks.
+fn find_next_same_weekday(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, interval: u64) -> Option<chrono::NaiveDate> {
+    let period_days = interval.checked_mul(7)?;
+    let periods_elapsed = (from_date - start_date).num_days() as u64 / period_days;
+
+    start_date.checked_add_days(chrono::Days::new((periods_elapsed + 1).checked_mul(period_days)?))
+}
+
+fn matches_same_weekday(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, interval: u64) -> bool {
+    // Intervals too long to fit in days only ever match the start.
+    ((date_to_check - start_date).num_days() as u64).is_multiple_of(interval.saturating_mul(7))
+}
+
+fn find_next_weekly(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, interval: u64, week_start: WeekStart) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    let next_weekday = weekdays.next_weekday(from_date.weekday()).ok_or(RepeatingDateError::EmptyWeekdaySet)?;
+    let from_date_weekday = from_date.weekday();
+
+    let days_until_next_valid_weekday = days_until(from_date_weekday, next_weekday);
+
+    if let Some(date) = from_date.checked_add_days(chrono::Days::new(days_until_next_valid_weekday as u64)) {
+        if date > from_date && is_on_week(date, start_date, interval, week_start) {
+            return Ok(date);
+        }
+    }
 
-    date.checked_sub_days(chrono::Days::new(weekdays_offset_abs as u64)).unwrap()
+    next_weekstart(from_date, start_date, weekdays, interval, week_start)
 }
 
+/// Whether a step of `interval` is representable in the unit `repeat` counts in, `i32::MAX` days,
+/// months or years being far beyond the calendar either way.
+pub(crate) fn check_interval(repeat: Repeating, interval: u64) -> Result<(), RepeatingDateError> {
+    let units = match repeat {
+        Repeating::Weekly => interval.checked_mul(7),
+        Repeating::Quarterly => interval.checked_mul(3),
+        _ => Some(interval),
+    };
+
+    match units {
+        Some(units) if units <= i32::MAX as u64 => Ok(()),
+        _ => Err(RepeatingDateError::IntervalTooLarge),
+    }
+}
+
+/// The arguments every rule needs: some interval and some weekday.
+fn validate(weekdays: WeekdayFlags, interval: u64) -> Result<(), RepeatingDateError> {
+    if interval == 0 {
+        return Err(RepeatingDateError::ZeroInterval);
+    }
+
+    if WeekdayFlags::from_bits_masked(weekdays.bits()).is_empty() {
+        return Err(RepeatingDateError::EmptyWeekdaySet);
+    }
+
+    Ok(())
+}
+
+/// The first selected weekday of the first on-week strictly after the Monday-first week
+/// containing `from_date`; `ZeroInterval` or `EmptyWeekdaySet` for invalid arguments and
+/// `DateOutOfRange` past the end of the calendar.
+pub fn find_next_weekstart(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, interval: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    find_next_weekstart_with(from_date, start_date, weekdays, interval, WeekStart::default())
+}
+
+/// `find_next_weekstart` with weeks beginning on `week_start`.
+pub fn find_next_weekstart_with(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, interval: u64, week_start: WeekStart) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    validate(weekdays, interval)?;
+
+    next_weekstart(from_date, start_date, weekdays, interval, week_start)
+}
+
+fn next_weekstart(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, interval: u64, week_start: WeekStart) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    let next_week_start = next_on_week_start(from_date, start_date, interval, week_start).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+    if weekdays.is_all() {
+        return Ok(next_week_start);
+    }
+
+    let first_weekday = weekdays.extract_weekdays_in(week_start).next().ok_or(RepeatingDateError::EmptyWeekdaySet)?;
+    let weekdays_offset = days_until(week_start.weekday(), first_weekday);
+
+    next_week_start.checked_add_days(chrono::Days::new(weekdays_offset as u64)).ok_or(RepeatingDateError::DateOutOfRange)
+}
+
+/// Where a monthly, quarterly or yearly rule of the free functions falls in a month lacking its
+/// start's day of month, e.g. a January 31 start in February or a yearly February 29 start outside
+/// leap years. `Schedule` rules spell this as `MonthlyBy::Days` with a `MissingDay`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MonthDayPolicy {
+    /// That month has no occurrence, as in RFC 5545; a yearly February 29 rule only occurs in leap
+    /// years, so with an interval of 1 every four years at most.
+    #[default]
+    SkipShortMonths,
+    /// The month's last day is the occurrence instead: February 28 or 29 for a January 31 start.
+    ClampToMonthEnd,
+    /// The first of the following month is the occurrence instead: March 1 for a January 31 start.
+    RollToFirstOfNext
+}
+
+/// The occurrence a rule anchored on `start_date`'s day of month has in the given month, if any.
+fn start_day_in(start_date: chrono::NaiveDate, year: i32, month: u32, policy: MonthDayPolicy) -> Option<chrono::NaiveDate> {
+    let month_day = MonthDay::of(start_date);
+
+    match policy {
+        MonthDayPolicy::SkipShortMonths => month_day.resolve(year, month),
+        MonthDayPolicy::ClampToMonthEnd => month_day.resolve_clamped(year, month),
+        MonthDayPolicy::RollToFirstOfNext => month_day.resolve(year, month)
+            .or_else(|| month_day.resolve_clamped(year, month).and_then(|last| last.succ_opt())),
+    }
+}
+
+/// The first occurrence strictly after `from_date`, months lacking the start's day of month
+/// having none; see `find_next_date_with` for the other `MonthDayPolicy` choices.
 pub fn find_next_date(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    find_next_date_with(from_date, start_date, weekdays, repeat, interval, MonthDayPolicy::default())
+}
+
+/// `find_next_date`, but a `from_date` before the start gives the series' first occurrence, the
+/// start itself, instead of `StartDateBeforeBound`.
+pub fn find_next_date_or_first(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    if from_date >= start_date {
+        return find_next_date(from_date, start_date, weekdays, repeat, interval);
+    }
+
+    first_date(start_date, weekdays, repeat, interval)
+}
+
+/// The first occurrence on or after `from_date`: `from_date` itself when it is one, unlike
+/// `find_next_date`, and the start for any `from_date` up to it.
+pub fn find_next_date_on_or_after(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    match from_date.pred_opt() {
+        Some(day_before) if from_date > start_date => find_next_date(day_before, start_date, weekdays, repeat, interval),
+        _ => first_date(start_date, weekdays, repeat, interval),
+    }
+}
+
+/// The start, once the rule checks out as in `find_next_date`.
+fn first_date(start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    validate(weekdays, interval)?;
+
+    if !weekdays.contains(WeekdayFlags::from_weekday(start_date.weekday())) {
+        return Err(RepeatingDateError::WrongWeekday { start_weekday: start_date.weekday(), allowed: weekdays });
+    }
+
+    match repeat {
+        Repeating::Hourly | Repeating::Minutely => Err(RepeatingDateError::UnsupportedFrequency),
+        _ => Ok(start_date),
+    }
+}
+
+/// On-years a yearly search of `find_next_date` tries for an allowed weekday before failing with
+/// `YearSearchExhausted`.
+pub const DEFAULT_MAX_YEAR_SKIP: u32 = 100;
+
+/// Days the readers of other rule formats (cron, phrases, Graph) search for the first date of a
+/// rule: enough for the next 29 February across a century that is not a leap year.
+#[cfg(any(feature = "cron", feature = "phrases", feature = "graph"))]
+pub(crate) const SEARCH_DAYS: u64 = 8 * 366;
+
+/// `find_next_date`, with monthly, quarterly and yearly rules falling in months lacking the
+/// start's day of month as `policy` says.
+pub fn find_next_date_with(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    find_next_date_with_limit(from_date, start_date, weekdays, repeat, interval, policy, DEFAULT_MAX_YEAR_SKIP)
+}
+
+/// `find_next_date_with`, trying up to `max_year_skip` on-years for a yearly rule instead of
+/// `DEFAULT_MAX_YEAR_SKIP`.
+pub fn find_next_date_with_limit(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy, max_year_skip: u32) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    next_date(from_date, start_date, weekdays, repeat, interval, policy, max_year_skip, WeekStart::default())
+}
+
+/// `find_next_date`, with a weekly rule's interval counting weeks beginning on `week_start`. A
+/// bi-weekly Sunday and Monday rule starting on Sunday 2023-09-10 next occurs on Monday 09-18 with
+/// Monday-first weeks, the start being the last day of its week, but on Monday 09-11 with
+/// Sunday-first ones.
+pub fn find_next_date_with_week_start(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, week_start: WeekStart) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    next_date(from_date, start_date, weekdays, repeat, interval, MonthDayPolicy::default(), DEFAULT_MAX_YEAR_SKIP, week_start)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn next_date(from_date: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy, max_year_skip: u32, week_start: WeekStart) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    validate(weekdays, interval)?;
+    check_interval(repeat, interval)?;
+
     if from_date < start_date {
-        return Err(RepeatingDateError::StartDateBeforeBound);
+        return Err(RepeatingDateError::StartDateBeforeBound { from: from_date, start: start_date });
     }
     
     if !weekdays.contains(WeekdayFlags::from_weekday(start_date.weekday())) {
-        return Err(RepeatingDateError::WrongWeekday);
+        return Err(RepeatingDateError::WrongWeekday { start_weekday: start_date.weekday(), allowed: weekdays });
+    }
+
+    let date_diff = from_date - start_date;
+    let date_delta_days = date_diff.num_days() as u64;
+
+    match repeat {
+        Repeating::Hourly | Repeating::Minutely => Err(RepeatingDateError::UnsupportedFrequency),
+        Repeating::Daily => {
+            // The first step of the grid strictly after `from_date`.
+            let next_step = (date_delta_days - date_delta_days % interval).checked_add(interval).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+            let mut date = start_date.checked_add_days(chrono::Days::new(next_step)).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+            while !weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
+                date = date.checked_add_days(chrono::Days::new(interval)).ok_or(RepeatingDateError::DateOutOfRange)?;
+            }
+
+            Ok(date)
+        },
+        Repeating::Weekly => {
+            if weekdays.is_all() {
+                find_next_day_in_on_week(from_date, start_date, interval, week_start).ok_or(RepeatingDateError::DateOutOfRange)
+            } else if weekdays == WeekdayFlags::from_weekday(start_date.weekday()) {
+                find_next_same_weekday(from_date, start_date, interval).ok_or(RepeatingDateError::DateOutOfRange)
+            } else {
+                find_next_weekly(from_date, start_date, weekdays, interval, week_start)
+            }
+        },
+        Repeating::Quarterly => next_date(from_date, start_date, weekdays, Repeating::Monthly, interval.saturating_mul(3), policy, max_year_skip, week_start),
+        Repeating::Monthly => {
+            let max_month_skip = 4800;
+
+            let interval = i32::try_from(interval).map_err(|_| RepeatingDateError::IntervalTooLarge)?;
+            let month_diff = get_months_since(from_date, start_date);
+
+            let first_of_start_month = start_date.with_day(1).unwrap();
+            let mut months = month_diff - (month_diff % interval);
+
+            let mut counter = 0;
+            while counter < max_month_skip {
+                let first_of_month = first_of_start_month.checked_add_months(chrono::Months::new(months as u32)).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+                // Months lacking the start's day of month (e.g. the 31st) follow the policy.
+                if let Some(date) = start_day_in(start_date, first_of_month.year(), first_of_month.month(), policy) {
+                    if date > from_date && weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
+                        return Ok(date);
+                    }
+                }
+
+                months = months.checked_add(interval).ok_or(RepeatingDateError::DateOutOfRange)?;
+                counter += 1;
+            }
+
+            Err(RepeatingDateError::NoOccurrenceFound)
+        },
+        Repeating::Yearly => {
+            let interval = i32::try_from(interval).map_err(|_| RepeatingDateError::IntervalTooLarge)?;
+            let years_diff = from_date.year() - start_date.year();
+
+            let mut year = start_date.year() + years_diff - (years_diff % interval);
+
+            let mut counter = 0;
+            while counter < max_year_skip {
+                if year > chrono::NaiveDate::MAX.year() {
+                    return Err(RepeatingDateError::DateOutOfRange);
+                }
+
+                // Years lacking the start's day (Feb 29 outside of leap years) follow the policy.
+                if let Some(date) = start_day_in(start_date, year, start_date.month(), policy) {
+                    if date > from_date && weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
+                        return Ok(date);
+                    }
+                }
+
+                year = year.checked_add(interval).ok_or(RepeatingDateError::DateOutOfRange)?;
+                counter += 1;
+            }
+
+            Err(RepeatingDateError::YearSearchExhausted { searched_years: max_year_skip })
+        },
+    }
+}
+
+/// Whether `date_to_check` is an occurrence of the rule; a zero interval or no weekdays never
+/// match, see `try_match_repeating_date` to tell those apart.
+pub fn match_repeating_date(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
+    try_match_repeating_date(date_to_check, start_date, weekdays, repeat, interval).unwrap_or(false)
+}
+
+/// `match_repeating_date` for rules of `find_next_date_with` with the given `policy`.
+pub fn match_repeating_date_with(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy) -> bool {
+    try_match_repeating_date_with(date_to_check, start_date, weekdays, repeat, interval, policy).unwrap_or(false)
+}
+
+/// `match_repeating_date` for rules of `find_next_date_with_week_start` with the given `week_start`.
+pub fn match_repeating_date_with_week_start(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, week_start: WeekStart) -> bool {
+    validate(weekdays, interval).is_ok()
+        && matches_repeating_date(date_to_check, start_date, weekdays, repeat, interval, MonthDayPolicy::default(), week_start)
+}
+
+/// `match_repeating_date`, failing with `ZeroInterval` or `EmptyWeekdaySet` for invalid arguments.
+pub fn try_match_repeating_date(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<bool, RepeatingDateError> {
+    try_match_repeating_date_with(date_to_check, start_date, weekdays, repeat, interval, MonthDayPolicy::default())
+}
+
+/// `match_repeating_date_with`, failing with `ZeroInterval` or `EmptyWeekdaySet` for invalid
+/// arguments.
+pub fn try_match_repeating_date_with(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy) -> Result<bool, RepeatingDateError> {
+    validate(weekdays, interval)?;
+
+    Ok(matches_repeating_date(date_to_check, start_date, weekdays, repeat, interval, policy, WeekStart::default()))
+}
+
+fn matches_repeating_date(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64, policy: MonthDayPolicy, week_start: WeekStart) -> bool {
+    if date_to_check < start_date {
+        return false;
+    }
+    
+    if !weekdays.contains(WeekdayFlags::from_weekday(date_to_check.weekday())) {
+        return false;
+    }
+
+    let date_diff = date_to_check - start_date;
+
+    match repeat {
+        Repeating::Hourly | Repeating::Minutely => false,
+        Repeating::Daily => (date_diff.num_days() as u64).is_multiple_of(interval),
+        Repeating::Weekly if weekdays == WeekdayFlags::from_weekday(start_date.weekday()) => matches_same_weekday(date_to_check, start_date, interval),
+        Repeating::Weekly => is_on_week(date_to_check, start_date, interval, week_start),
+        Repeating::Monthly => {
+            let month_diff = get_months_since(date_to_check, start_date);
+            let first_of_start_month = start_date.with_day(1).unwrap();
+
+            // A rolled over occurrence belongs to the month before the one it lands in.
+            let months: &[i32] = if policy == MonthDayPolicy::RollToFirstOfNext { &[month_diff, month_diff - 1] } else { &[month_diff] };
+
+            months.iter().any(|&months| months >= 0 && (months as u64).is_multiple_of(interval)
+                && first_of_start_month.checked_add_months(chrono::Months::new(months as u32))
+                    .and_then(|first_of_month| start_day_in(start_date, first_of_month.year(), first_of_month.month(), policy)) == Some(date_to_check))
+        },
+        Repeating::Quarterly => matches_repeating_date(date_to_check, start_date, weekdays, Repeating::Monthly, interval.saturating_mul(3), policy, week_start),
+        Repeating::Yearly => {
+            let years = date_to_check.year() - start_date.year();
+
+            years >= 0 && (years as u64).is_multiple_of(interval)
+                && start_day_in(start_date, date_to_check.year(), start_date.month(), policy) == Some(date_to_check)
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use core::str::FromStr;
+
+    /// An ISO 8601 date, panicking on anything else.
+    pub(crate) fn date(value: &str) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_str(value).unwrap()
+    }
+
+    pub(crate) fn dates(values: &[&str]) -> Vec<chrono::NaiveDate> {
+        values.iter().map(|value| date(value)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use chrono::Datelike;
+    use crate::test_util::{date, dates};
+
+    #[rstest]
+    #[case::empty(&[], WeekdayFlags::empty())]
+    #[case::one_day(&[chrono::Weekday::Wed], WeekdayFlags::WED)]
+    #[case::duplicates(&[chrono::Weekday::Sat, chrono::Weekday::Sun, chrono::Weekday::Sat], WeekdayFlags::WEEKEND)]
+    #[case::all_seven(&WEEKDAYS, WeekdayFlags::ANY)]
+    #[case::any_order(&[chrono::Weekday::Fri, chrono::Weekday::Mon], WeekdayFlags::MON | WeekdayFlags::FRI)]
+    fn weekday_flags_from_weekdays(#[case] weekdays: &[chrono::Weekday], #[case] expected: WeekdayFlags) {
+        assert_eq!(expected, WeekdayFlags::from(weekdays));
+        assert_eq!(expected, weekdays.iter().copied().collect::<WeekdayFlags>());
+
+        let mut extended = WeekdayFlags::empty();
+        extended.extend(weekdays.iter().copied());
+        assert_eq!(expected, extended);
+    }
+
+    #[test]
+    fn weekday_flags_to_and_from_single_weekdays() {
+        for weekday in WEEKDAYS {
+            assert_eq!(WeekdayFlags::from_weekday(weekday), WeekdayFlags::from(weekday));
+        }
+
+        let mut weekdays = WeekdayFlags::MON;
+        weekdays.extend([chrono::Weekday::Mon, chrono::Weekday::Thu]);
+
+        assert_eq!(vec![chrono::Weekday::Mon, chrono::Weekday::Thu], Vec::from(weekdays));
+        assert_eq!(WEEKDAYS.to_vec(), Vec::from(WeekdayFlags::ANY));
+        assert_eq!(Vec::<chrono::Weekday>::new(), Vec::from(WeekdayFlags::empty()));
+    }
+
+    #[test]
+    fn weekday_flags_round_trip_through_strings() {
+        for bits in 1..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            assert_eq!(Ok(weekdays), weekdays.to_string().parse::<WeekdayFlags>(), "{}", weekdays);
+        }
+    }
+
+    #[rstest]
+    #[case::days(WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI, "MON,WED,FRI")]
+    #[case::one_day(WeekdayFlags::SUN, "SUN")]
+    #[case::any(WeekdayFlags::ANY, "ANY")]
+    #[case::midweek(WeekdayFlags::MIDWEEK, "MIDWEEK")]
+    #[case::weekend(WeekdayFlags::WEEKEND, "WEEKEND")]
+    #[case::neither_group(WeekdayFlags::MIDWEEK | WeekdayFlags::SAT, "MON,TUE,WED,THU,FRI,SAT")]
+    #[case::none(WeekdayFlags::empty(), "")]
+    fn weekday_flags_display(#[case] weekdays: WeekdayFlags, #[case] expected: &str) {
+        assert_eq!(expected, weekdays.to_string());
+    }
+
+    #[rstest]
+    #[case::any_case("mon,Wed,FRI", WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI)]
+    #[case::two_letter_codes("MO,we,Fr", WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI)]
+    #[case::groups("weekend,MIDWEEK", WeekdayFlags::ANY)]
+    #[case::repeats_and_spaces(" SAT , WEEKEND,su ", WeekdayFlags::WEEKEND)]
+    #[case::any_order("FRI,MON", WeekdayFlags::MON | WeekdayFlags::FRI)]
+    #[case::empty("", WeekdayFlags::empty())]
+    fn weekday_flags_from_str(#[case] value: &str, #[case] expected: WeekdayFlags) {
+        assert_eq!(Ok(expected), value.parse::<WeekdayFlags>());
+    }
+
+    #[rstest]
+    #[case::unknown("MON,XYZ", "XYZ", 1)]
+    #[case::full_name("Monday", "Monday", 0)]
+    #[case::empty_token("MON,,TUE", "", 1)]
+    #[case::one_letter("MON,TUE,W", "W", 2)]
+    fn weekday_flags_from_str_rejects(#[case] value: &str, #[case] token: &str, #[case] position: usize) {
+        let error = value.parse::<WeekdayFlags>().unwrap_err();
+
+        assert_eq!(InvalidWeekdayName { token: token.to_string(), position }, error);
+        assert!(error.to_string().starts_with(&format!("`{}` at position {}", token, position)), "{}", error);
+    }
+
+    #[test]
+    fn try_from_bits_rejects_contaminated_bits() {
+        assert_eq!(Ok(WeekdayFlags::MON | WeekdayFlags::WED), WeekdayFlags::try_from_bits(0x05));
+        assert_eq!(Ok(WeekdayFlags::ANY), WeekdayFlags::try_from_bits(0x7F));
+        assert_eq!(Ok(WeekdayFlags::empty()), WeekdayFlags::try_from_bits(0));
+
+        assert_eq!(Err(InvalidWeekdayBits { invalid_mask: 0x80 }), WeekdayFlags::try_from_bits(0x85));
+        assert_eq!(Err(InvalidWeekdayBits { invalid_mask: 0x80 }), WeekdayFlags::try_from_bits(0xFF));
+    }
+
+    #[test]
+    fn from_bits_masked_drops_contaminated_bits() {
+        assert_eq!(WeekdayFlags::MON | WeekdayFlags::WED, WeekdayFlags::from_bits_masked(0x85));
+        assert_eq!(WeekdayFlags::ANY, WeekdayFlags::from_bits_masked(0xFF));
+        assert_eq!(WeekdayFlags::empty(), WeekdayFlags::from_bits_masked(0x80));
+    }
+
+    #[rstest]
+    #[case(WeekdayFlags::MON, chrono::Weekday::Mon)]
+    #[case(WeekdayFlags::THU, chrono::Weekday::Thu)]
+    #[case(WeekdayFlags::SUN, chrono::Weekday::Sun)]
+    fn single_flag_to_weekday(#[case] flags: WeekdayFlags, #[case] expected: chrono::Weekday) {
+        assert_eq!(Ok(expected), chrono::Weekday::try_from(flags));
+        assert_eq!(flags, WeekdayFlags::from_weekday(expected));
+    }
+
+    #[rstest]
+    #[case::empty(WeekdayFlags::empty())]
+    #[case::any(WeekdayFlags::ANY)]
+    #[case::two(WeekdayFlags::SAT | WeekdayFlags::SUN)]
+    #[case::unknown_bit(WeekdayFlags::from_bits_retain(0x80))]
+    fn other_flags_are_not_a_weekday(#[case] flags: WeekdayFlags) {
+        assert_eq!(Err(NotOneWeekday { flags }), chrono::Weekday::try_from(flags));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_to_weekday() {
+        assert_eq!(chrono::Weekday::Wed, WeekdayFlags::WED.to_weekday());
+        assert!(std::panic::catch_unwind(|| WeekdayFlags::ANY.to_weekday()).is_err());
+    }
+
+    #[rstest]
+    #[case::monday_any(WeekStart::Monday, WeekdayFlags::ANY, WEEKDAYS.to_vec())]
+    #[case::sunday_weekend(WeekStart::Sunday, WeekdayFlags::WEEKEND, vec![chrono::Weekday::Sun, chrono::Weekday::Sat])]
+    #[case::sunday_mixed(WeekStart::Sunday, WeekdayFlags::MON | WeekdayFlags::FRI | WeekdayFlags::SUN, vec![chrono::Weekday::Sun, chrono::Weekday::Mon, chrono::Weekday::Fri])]
+    #[case::sunday_empty(WeekStart::Sunday, WeekdayFlags::from_bits_retain(0x80), vec![])]
+    fn extracts_weekdays_in_week_order(#[case] week_start: WeekStart, #[case] weekdays: WeekdayFlags, #[case] expected: Vec<chrono::Weekday>) {
+        assert_eq!(expected, weekdays.extract_weekdays_in(week_start).collect::<Vec<chrono::Weekday>>());
+    }
+
+    #[rstest]
+    #[case::any(WeekdayFlags::ANY, WEEKDAYS.to_vec())]
+    #[case::weekend(WeekdayFlags::WEEKEND, vec![chrono::Weekday::Sat, chrono::Weekday::Sun])]
+    #[case::single(WeekdayFlags::THU, vec![chrono::Weekday::Thu])]
+    #[case::unknown_bit(WeekdayFlags::from_bits_retain(0xFF), WEEKDAYS.to_vec())]
+    #[case::only_unknown_bits(WeekdayFlags::from_bits_retain(0x80), vec![])]
+    fn extracts_weekdays(#[case] weekdays: WeekdayFlags, #[case] expected: Vec<chrono::Weekday>) {
+        #[allow(deprecated)]
+        let collected = weekdays.extract_weekdays_vec();
+
+        assert_eq!(expected, weekdays.extract_weekdays().collect::<Vec<chrono::Weekday>>());
+        assert_eq!(expected, collected);
+    }
+
+    #[test]
+    fn contaminated_flags_do_not_panic_helpers() {
+        let weekdays = WeekdayFlags::from_bits_retain(0x80 | WeekdayFlags::TUE.bits());
+
+        assert_eq!(Some(chrono::Weekday::Tue), weekdays.next_weekday(chrono::Weekday::Sun));
+        assert_eq!(Some(chrono::Weekday::Tue), weekdays.next_weekday(chrono::Weekday::Wed));
+        assert_eq!(Some(chrono::Weekday::Tue), weekdays.first_valid_weekday(chrono::Weekday::Fri));
+        assert_eq!(Some(chrono::Weekday::Tue), weekdays.first_valid_weekday_bitwise());
+        assert_eq!(vec![chrono::Weekday::Tue], weekdays.extract_weekdays().collect::<Vec<chrono::Weekday>>());
+
+        let start_date = chrono::NaiveDate::from_str("2023-09-12").unwrap();
+        let result = find_next_date(start_date, start_date, weekdays, Repeating::Weekly, 1).unwrap();
+        assert_eq!(chrono::NaiveDate::from_str("2023-09-19").unwrap(), result);
+        assert_eq!(chrono::NaiveDate::from_str("2023-09-19").unwrap(), find_next_weekstart(start_date, start_date, weekdays, 1).unwrap());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn empty_flags_do_not_panic_helpers() {
+        let weekdays = WeekdayFlags::empty();
+
+        for current in WEEKDAYS {
+            assert_eq!(None, weekdays.next_weekday(current));
+            assert_eq!(None, weekdays.next_weekday_bitwise(current));
+            assert_eq!(None, weekdays.first_valid_weekday(current));
+            assert_eq!(None, weekdays.previous_weekday(current));
+            assert_eq!(None, weekdays.previous_weekday_strictly_before(current));
+            assert_eq!(None, weekdays.days_since_previous(current));
+        }
+
+        assert_eq!(None, weekdays.first_valid_weekday_bitwise());
+        assert_eq!(None, weekdays.last_selected());
+        assert_eq!(0, weekdays.extract_weekdays().len());
+
+        let start_date = chrono::NaiveDate::from_str("2023-09-12").unwrap();
+        assert_eq!(Err(RepeatingDateError::EmptyWeekdaySet), find_next_weekly(start_date, start_date, weekdays, 1, WeekStart::Monday));
+        assert_eq!(Err(RepeatingDateError::EmptyWeekdaySet), next_weekstart(start_date, start_date, weekdays, 1, WeekStart::Monday));
+        assert_eq!(Err(RepeatingDateError::EmptyWeekdaySet), find_next_weekstart(start_date, start_date, weekdays, 1));
+        assert_eq!(Err(RepeatingDateError::EmptyWeekdaySet), find_next_date(start_date, start_date, weekdays, Repeating::Weekly, 1));
+    }
+
+    /// Every current weekday against every combination of flags, including one with a bit outside
+    /// of `WeekdayFlags::ANY`.
+    #[test]
+    #[allow(deprecated)]
+    fn next_weekday_names_agree() {
+        for bits in (1..=WeekdayFlags::ANY.bits()).chain([0x80 | WeekdayFlags::FRI.bits()]) {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            for current in WEEKDAYS {
+                let expected = std::iter::successors(Some(current.succ()), |weekday| Some(weekday.succ())).take(7)
+                    .find(|weekday| weekdays.contains(WeekdayFlags::from_weekday(*weekday)))
+                    .unwrap();
+
+                assert_eq!(Some(expected), weekdays.next_weekday(current), "{:?} after {:?}", weekdays, current);
+                assert_eq!(Some(expected), weekdays.next_weekday_bitwise(current), "{:?} after {:?}", weekdays, current);
+            }
+        }
+    }
+
+    #[test]
+    fn nth_weekday_from_matches_repeated_next_weekday() {
+        for bits in 1..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            for current in WEEKDAYS {
+                let mut expected = (current, 0);
+
+                for n in 1..=3 * bits.count_ones() + 2 {
+                    let next = weekdays.next_weekday(expected.0).unwrap();
+                    expected = (next, expected.1 + match days_until(expected.0, next) { 0 => 7, days => days as u32 });
+
+                    assert_eq!(Some(expected), weekdays.nth_weekday_from(current, n), "{:?} {} after {:?}", weekdays, n, current);
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::wraps_weeks(WeekdayFlags::TUE | WeekdayFlags::THU, chrono::Weekday::Thu, 3, Some((chrono::Weekday::Tue, 12)))]
+    #[case::third_after_wednesday(WeekdayFlags::MIDWEEK, chrono::Weekday::Wed, 3, Some((chrono::Weekday::Mon, 5)))]
+    #[case::single_day_from_itself(WeekdayFlags::FRI, chrono::Weekday::Fri, 4, Some((chrono::Weekday::Fri, 28)))]
+    #[case::single_day_from_another(WeekdayFlags::FRI, chrono::Weekday::Sat, 2, Some((chrono::Weekday::Fri, 13)))]
+    #[case::many_weeks(WeekdayFlags::WEEKEND, chrono::Weekday::Mon, 1_000, Some((chrono::Weekday::Sun, 3_499)))]
+    #[case::every_day(WeekdayFlags::ANY, chrono::Weekday::Sun, 10, Some((chrono::Weekday::Wed, 10)))]
+    #[case::zero(WeekdayFlags::ANY, chrono::Weekday::Sun, 0, None)]
+    #[case::empty(WeekdayFlags::empty(), chrono::Weekday::Sun, 1, None)]
+    #[case::only_unknown_bits(WeekdayFlags::from_bits_retain(0x80), chrono::Weekday::Sun, 1, None)]
+    #[case::too_many_days(WeekdayFlags::MON, chrono::Weekday::Mon, u32::MAX, None)]
+    #[case::most_days(WeekdayFlags::ANY, chrono::Weekday::Mon, u32::MAX, Some((chrono::Weekday::Thu, u32::MAX)))]
+    fn nth_weekday_from_cases(#[case] weekdays: WeekdayFlags, #[case] current: chrono::Weekday, #[case] n: u32, #[case] expected: Option<(chrono::Weekday, u32)>) {
+        assert_eq!(expected, weekdays.nth_weekday_from(current, n));
+    }
+
+    #[test]
+    fn first_valid_weekday_exhaustive() {
+        for bits in 0..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            for current in WEEKDAYS {
+                let expected = WEEKDAYS[..=current.num_days_from_monday() as usize].iter().copied().find(|weekday| weekdays.contains(WeekdayFlags::from_weekday(*weekday)));
+
+                assert_eq!(expected, weekdays.first_valid_weekday(current), "{:?} up to {:?}", weekdays, current);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::current_selected(WeekdayFlags::WED, chrono::Weekday::Wed, Some(chrono::Weekday::Wed))]
+    #[case::current_not_selected(WeekdayFlags::FRI, chrono::Weekday::Wed, None)]
+    #[case::earlier(WeekdayFlags::MON | WeekdayFlags::THU, chrono::Weekday::Sun, Some(chrono::Weekday::Mon))]
+    #[case::empty(WeekdayFlags::empty(), chrono::Weekday::Sun, None)]
+    fn first_valid_weekday_cases(#[case] weekdays: WeekdayFlags, #[case] current: chrono::Weekday, #[case] expected: Option<chrono::Weekday>) {
+        assert_eq!(expected, weekdays.first_valid_weekday(current));
+    }
+
+    #[test]
+    fn weekday_helpers_match_a_plain_walk() {
+        for bits in 1..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+            let selected: Vec<chrono::Weekday> = WEEKDAYS.into_iter().filter(|weekday| weekdays.contains(WeekdayFlags::from_weekday(*weekday))).collect();
+
+            assert_eq!(selected, weekdays.extract_weekdays().collect::<Vec<chrono::Weekday>>());
+            assert_eq!(selected.len(), weekdays.extract_weekdays().len());
+            assert_eq!(selected.iter().rev().copied().collect::<Vec<chrono::Weekday>>(), weekdays.extract_weekdays().rev().collect::<Vec<chrono::Weekday>>());
+
+            for current in WEEKDAYS {
+                let expected = selected.iter().find(|weekday| weekday.num_days_from_monday() > current.num_days_from_monday()).unwrap_or(&selected[0]);
+
+                assert_eq!(Some(*expected), weekdays.next_weekday(current), "{:?} after {:?}", weekdays, current);
+            }
+        }
+    }
+
+    #[test]
+    fn previous_weekday_exhaustive() {
+        for bits in 0..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            for current in WEEKDAYS {
+                let mut expected = None;
+                let mut walked = current;
+                for days_back in 1..=7u8 {
+                    walked = walked.pred();
+
+                    if weekdays.contains(WeekdayFlags::from_weekday(walked)) {
+                        expected = Some((walked, days_back));
+                        break;
+                    }
+                }
+
+                let expected_on_or_before = Some(current).filter(|current| weekdays.contains(WeekdayFlags::from_weekday(*current))).or(expected.map(|(weekday, _)| weekday));
+
+                assert_eq!(expected.map(|(weekday, _)| weekday), weekdays.previous_weekday_strictly_before(current), "{:?} before {:?}", weekdays, current);
+                assert_eq!(expected_on_or_before, weekdays.previous_weekday(current), "{:?} up to {:?}", weekdays, current);
+                assert_eq!(expected.map(|(_, days)| days), weekdays.days_since_previous(current), "{:?} before {:?}", weekdays, current);
+            }
+
+            let expected_last = WEEKDAYS.into_iter().rev().find(|weekday| weekdays.contains(WeekdayFlags::from_weekday(*weekday)));
+            assert_eq!(expected_last, weekdays.last_selected());
+        }
+    }
+
+    #[test]
+    fn previous_weekday_mirrors_next_weekday() {
+        for bits in 1..=WeekdayFlags::ANY.bits() {
+            let weekdays = WeekdayFlags::from_bits_retain(bits);
+
+            for current in weekdays.extract_weekdays() {
+                let previous = weekdays.previous_weekday_strictly_before(current).unwrap();
+
+                assert_eq!(Some(current), weekdays.next_weekday(previous), "{:?} around {:?}", weekdays, current);
+                assert_eq!(previous, weekdays.previous_weekday_strictly_before(weekdays.next_weekday(previous).unwrap()).unwrap());
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::wrap_from_monday(WeekdayFlags::SAT, chrono::Weekday::Mon, chrono::Weekday::Sat, 2)]
+    #[case::only_current(WeekdayFlags::WED, chrono::Weekday::Wed, chrono::Weekday::Wed, 7)]
+    #[case::same_week(WeekdayFlags::TUE | WeekdayFlags::THU, chrono::Weekday::Fri, chrono::Weekday::Thu, 1)]
+    #[case::sunday_from_monday(WeekdayFlags::SUN, chrono::Weekday::Mon, chrono::Weekday::Sun, 1)]
+    fn previous_weekday_strictly_before_cases(#[case] weekdays: WeekdayFlags, #[case] current: chrono::Weekday, #[case] expected_weekday: chrono::Weekday, #[case] expected_days: u8) {
+        assert_eq!(Some(expected_weekday), weekdays.previous_weekday_strictly_before(current));
+        assert_eq!(Some(expected_days), weekdays.days_since_previous(current));
+    }
+
+    #[rstest]
+    #[case::wrap_from_monday(WeekdayFlags::SAT, chrono::Weekday::Mon, Some(chrono::Weekday::Sat))]
+    #[case::current_selected(WeekdayFlags::MON | WeekdayFlags::WED, chrono::Weekday::Wed, Some(chrono::Weekday::Wed))]
+    #[case::earlier_in_week(WeekdayFlags::MON | WeekdayFlags::WED, chrono::Weekday::Tue, Some(chrono::Weekday::Mon))]
+    #[case::sunday(WeekdayFlags::SUN, chrono::Weekday::Sun, Some(chrono::Weekday::Sun))]
+    #[case::empty(WeekdayFlags::empty(), chrono::Weekday::Thu, None)]
+    #[case::only_unknown_bits(WeekdayFlags::from_bits_retain(0x80), chrono::Weekday::Sun, None)]
+    fn previous_weekday_cases(#[case] weekdays: WeekdayFlags, #[case] current: chrono::Weekday, #[case] expected: Option<chrono::Weekday>) {
+        assert_eq!(expected, weekdays.previous_weekday(current));
+    }
+
+    #[test]
+    fn previous_weekday_ignores_contaminated_bits() {
+        let weekdays = WeekdayFlags::from_bits_retain(0x80 | WeekdayFlags::MON.bits());
+
+        assert_eq!(Some(chrono::Weekday::Mon), weekdays.previous_weekday_strictly_before(chrono::Weekday::Sun));
+        assert_eq!(Some(chrono::Weekday::Mon), weekdays.last_selected());
+        assert_eq!(None, WeekdayFlags::from_bits_retain(0x80).last_selected());
+    }
+
+    /// Walks day by day from every weekday to every other one.
+    #[test]
+    fn weekday_offsets_exhaustive() {
+        for current in WEEKDAYS {
+            for target in WEEKDAYS {
+                let forward = (0..7).find(|days| (0..*days).fold(current, |weekday, _| weekday.succ()) == target).unwrap();
+                let backward = (0..7).find(|days| (0..*days).fold(current, |weekday, _| weekday.pred()) == target).unwrap();
+
+                assert_eq!(forward, days_until(current, target), "{:?} to {:?}", current, target);
+                assert_eq!(backward as u32, days_since(current, target), "{:?} back to {:?}", current, target);
+                assert_eq!(forward, weekday_offset(current, target, Direction::Forward));
+                assert_eq!(-backward, weekday_offset(current, target, Direction::Backward));
+                assert_eq!(if forward == 0 { 7 } else { forward }, strict_weekday_offset(current, target, Direction::Forward));
+                assert_eq!(if backward == 0 { -7 } else { -backward }, strict_weekday_offset(current, target, Direction::Backward));
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::forward(Direction::Forward, 0, 7)]
+    #[case::backward(Direction::Backward, 0, -7)]
+    fn same_weekday_offsets(#[case] direction: Direction, #[case] expected: i32, #[case] expected_strict: i32) {
+        for weekday in WEEKDAYS {
+            assert_eq!(expected, weekday_offset(weekday, weekday, direction));
+            assert_eq!(expected_strict, strict_weekday_offset(weekday, weekday, direction));
+        }
+
+        assert_eq!(0, days_since(chrono::Weekday::Tue, chrono::Weekday::Tue));
+    }
+
+    #[test]
+    fn last_tuesday() {
+        assert_eq!(1, days_since(chrono::Weekday::Wed, chrono::Weekday::Tue));
+        assert_eq!(6, days_since(chrono::Weekday::Mon, chrono::Weekday::Tue));
+        assert_eq!(-6, weekday_offset(chrono::Weekday::Mon, chrono::Weekday::Tue, Direction::Backward));
+    }
+
+    #[rstest]
+    #[case::not_a_day_short(date("2024-01-31"), date("2024-02-01"), 0)]
+    #[case::clamped_to_february(date("2023-01-31"), date("2023-02-28"), 1)]
+    #[case::clamped_to_a_leap_february(date("2024-01-31"), date("2024-02-29"), 1)]
+    #[case::a_day_short_of_a_leap_february(date("2024-01-31"), date("2024-02-28"), 0)]
+    #[case::a_day_short(date("2024-01-15"), date("2024-02-14"), 0)]
+    #[case::day_reached(date("2024-01-15"), date("2024-02-15"), 1)]
+    #[case::month_end_to_a_longer_month(date("2024-01-31"), date("2024-03-30"), 1)]
+    #[case::month_end_reached(date("2024-01-31"), date("2024-03-31"), 2)]
+    #[case::thirtieth_to_month_end(date("2024-04-30"), date("2024-05-30"), 1)]
+    #[case::across_new_year(date("2023-12-15"), date("2024-01-14"), 0)]
+    #[case::a_year(date("2023-02-28"), date("2024-02-28"), 12)]
+    #[case::same_month(date("2024-01-01"), date("2024-01-31"), 0)]
+    #[case::same_date(date("2024-01-31"), date("2024-01-31"), 0)]
+    #[case::backwards(date("2024-03-15"), date("2024-01-15"), -2)]
+    #[case::backwards_short(date("2024-02-01"), date("2024-01-31"), 0)]
+    fn complete_months(#[case] from: chrono::NaiveDate, #[case] to: chrono::NaiveDate, #[case] expected: i32) {
+        assert_eq!(expected, complete_months_between(from, to));
+    }
+
+    #[test]
+    fn calendar_months_ignore_the_day() {
+        assert_eq!(1, get_months_since(date("2024-02-01"), date("2024-01-31")));
+        assert_eq!(0, get_months_since(date("2024-01-31"), date("2024-01-01")));
     }
 
-    let date_diff = from_date - start_date;
-    let date_delta_days = date_diff.num_days() as u64;
+    /// A bi-weekly Sunday and Monday rule starting on a Sunday: with Monday-first weeks the start is
+    /// the end of its on-week and the Monday after is off, with Sunday-first weeks the start opens
+    /// its on-week and the Monday after is on.
+    #[rstest]
+    #[case::monday(WeekStart::Monday, ["2023-09-18", "2023-09-24", "2023-10-02", "2023-10-08", "2023-10-16"])]
+    #[case::sunday(WeekStart::Sunday, ["2023-09-11", "2023-09-24", "2023-09-25", "2023-10-08", "2023-10-09"])]
+    fn week_start_decides_the_on_weeks(#[case] week_start: WeekStart, #[case] expected: [&str; 5]) {
+        let start = date("2023-09-10");
+        let weekdays = WeekdayFlags::SUN | WeekdayFlags::MON;
+        let expected = expected.map(date);
+
+        let dates: Vec<chrono::NaiveDate> = std::iter::successors(Some(start), |from| find_next_date_with_week_start(*from, start, weekdays, Repeating::Weekly, 2, week_start).ok())
+            .skip(1)
+            .take(5)
+            .collect();
+        assert_eq!(expected.to_vec(), dates);
+
+        let matched: Vec<chrono::NaiveDate> = start.iter_days().take_while(|day| *day <= expected[4])
+            .filter(|day| match_repeating_date_with_week_start(*day, start, weekdays, Repeating::Weekly, 2, week_start))
+            .collect();
+        assert_eq!([&[start][..], &expected[..]].concat(), matched);
+    }
 
-    match repeat {
-        Repeating::Daily => {
-            let date_delta_mod = date_delta_days % interval;
+    #[rstest]
+    #[case::monday(WeekStart::Monday, WeekdayFlags::SUN | WeekdayFlags::MON, "2023-09-18")]
+    #[case::sunday(WeekStart::Sunday, WeekdayFlags::SUN | WeekdayFlags::MON, "2023-09-24")]
+    #[case::sunday_every_day(WeekStart::Sunday, WeekdayFlags::ANY, "2023-09-24")]
+    #[case::sunday_midweek(WeekStart::Sunday, WeekdayFlags::MIDWEEK, "2023-09-25")]
+    fn next_weekstart_with_week_start(#[case] week_start: WeekStart, #[case] weekdays: WeekdayFlags, #[case] expected: &str) {
+        assert_eq!(Ok(date(expected)), find_next_weekstart_with(date("2023-09-10"), date("2023-09-10"), weekdays, 2, week_start));
+    }
 
-            let mut date = start_date.checked_add_days(chrono::Days::new(date_delta_mod + date_delta_days + interval)).unwrap();
+    /// The calendar begins on a Thursday, so its first week, whichever day weeks start on, lacks
+    /// its first days.
+    #[rstest]
+    #[case::monday(WeekStart::Monday, [2, 3, 14, 16, 17], 14)]
+    #[case::sunday(WeekStart::Sunday, [2, 10, 14, 16, 24], 10)]
+    fn weeks_at_the_start_of_the_calendar(#[case] week_start: WeekStart, #[case] expected: [u64; 5], #[case] expected_week_start: u64) {
+        let start = chrono::NaiveDate::MIN;
+        let weekdays = WeekdayFlags::THU | WeekdayFlags::SAT | WeekdayFlags::SUN;
+        let expected = expected.map(|days| start + chrono::Days::new(days));
+
+        let dates: Vec<chrono::NaiveDate> = std::iter::successors(Some(start), |from| find_next_date_with_week_start(*from, start, weekdays, Repeating::Weekly, 2, week_start).ok())
+            .skip(1)
+            .take(5)
+            .collect();
+        assert_eq!(expected.to_vec(), dates);
+
+        let matched: Vec<chrono::NaiveDate> = start.iter_days().take_while(|day| *day <= expected[4])
+            .filter(|day| match_repeating_date_with_week_start(*day, start, weekdays, Repeating::Weekly, 2, week_start))
+            .collect();
+        assert_eq!([&[start][..], &expected[..]].concat(), matched);
+
+        assert_eq!(Ok(start + chrono::Days::new(expected_week_start)), find_next_weekstart_with(start, start, weekdays, 2, week_start));
+    }
 
-            while !weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
-                date = date.checked_add_days(chrono::Days::new(interval)).unwrap();
-            }
+    #[test]
+    fn monday_week_start_is_the_default() {
+        let start = date("2023-09-10");
+        let weekdays = WeekdayFlags::SUN | WeekdayFlags::TUE;
 
-            Ok(date)
-        },
-        Repeating::Weekly => {
-            let next_weekday = weekdays.next_weekday_bitwise(from_date.weekday());
-            let from_date_weekday = from_date.weekday();
+        for from in start.iter_days().take(30) {
+            assert_eq!(find_next_date(from, start, weekdays, Repeating::Weekly, 3), find_next_date_with_week_start(from, start, weekdays, Repeating::Weekly, 3, WeekStart::default()));
+            assert_eq!(match_repeating_date(from, start, weekdays, Repeating::Weekly, 3), match_repeating_date_with_week_start(from, start, weekdays, Repeating::Weekly, 3, WeekStart::default()));
+        }
+    }
 
-            let is_any_weekday_valid = weekdays.is_all();
+    #[rstest]
+    #[case::zero_interval(WeekdayFlags::ANY, 0, RepeatingDateError::ZeroInterval)]
+    #[case::zero_interval_on_some_days(WeekdayFlags::TUE, 0, RepeatingDateError::ZeroInterval)]
+    #[case::no_weekdays(WeekdayFlags::empty(), 1, RepeatingDateError::EmptyWeekdaySet)]
+    #[case::only_unknown_bits(WeekdayFlags::from_bits_retain(0x80), 2, RepeatingDateError::EmptyWeekdaySet)]
+    fn invalid_arguments_do_not_panic(#[case] weekdays: WeekdayFlags, #[case] interval: u64, #[case] expected: RepeatingDateError) {
+        let start = date("2023-09-12");
+        let from = date("2024-01-10");
+
+        for repeat in [Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Quarterly, Repeating::Yearly] {
+            assert_eq!(Err(expected), find_next_date(from, start, weekdays, repeat, interval), "{:?}", repeat);
+            assert_eq!(Err(expected), try_match_repeating_date(from, start, weekdays, repeat, interval), "{:?}", repeat);
+            assert!(!match_repeating_date(from, start, weekdays, repeat, interval), "{:?}", repeat);
+        }
 
-            let days_until_next_valid_weekday = ((!is_any_weekday_valid) as i32) * days_until(from_date_weekday, next_weekday);
+        assert_eq!(Err(expected), find_next_weekstart(from, start, weekdays, interval));
+    }
 
-            let date = from_date.checked_add_days(chrono::Days::new(days_until_next_valid_weekday as u64)).unwrap();
+    #[test]
+    fn next_occurrence_past_the_calendar_end() {
+        let start = chrono::NaiveDate::MAX - chrono::Days::new(10);
+        let weekdays = [WeekdayFlags::ANY, WeekdayFlags::from_weekday(start.weekday()), WeekdayFlags::from_weekday(start.weekday()) | WeekdayFlags::from_weekday(start.weekday().succ())];
 
-            if date > from_date && match_repeating_date(date, start_date, weekdays, repeat, interval) {
-                return Ok(date);
+        for repeat in [Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Quarterly, Repeating::Yearly] {
+            for weekdays in weekdays {
+                assert_eq!(Err(RepeatingDateError::DateOutOfRange), find_next_date(chrono::NaiveDate::MAX, start, weekdays, repeat, 1), "{:?} on {:?}", repeat, weekdays);
             }
+        }
 
-            Ok(find_next_weekstart(from_date, start_date, weekdays, interval))
-        },
-        Repeating::Monthly => {
-            let interval = interval as i32;
-            let month_diff = get_months_since(from_date, start_date);
-
-            let delta = interval + (((month_diff % interval == 0) as i32) * month_diff);
-
-            let mut date = start_date.checked_add_months(chrono::Months::new(delta as u32)).unwrap();
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), find_next_weekstart(chrono::NaiveDate::MAX, start, WeekdayFlags::ANY, 1));
+        assert_eq!(Ok(chrono::NaiveDate::MAX), find_next_date(chrono::NaiveDate::MAX.pred_opt().unwrap(), start, WeekdayFlags::ANY, Repeating::Daily, 1));
+    }
 
-            while !weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
-                date = date.checked_add_months(chrono::Months::new(interval as u32)).unwrap();
+    #[rstest]
+    #[case::daily(Repeating::Daily)]
+    #[case::weekly(Repeating::Weekly)]
+    #[case::monthly(Repeating::Monthly)]
+    #[case::quarterly(Repeating::Quarterly)]
+    #[case::yearly(Repeating::Yearly)]
+    fn huge_intervals_run_past_the_calendar_end(#[case] repeat: Repeating) {
+        let start = date("2023-09-12");
+        // The Sunday closing the start's week, leaving no weekly occurrences in it either.
+        let from = date("2023-09-17");
+
+        for (interval, expected) in [(u64::MAX, RepeatingDateError::IntervalTooLarge), (i64::MAX as u64 + 1, RepeatingDateError::IntervalTooLarge),
+            (i32::MAX as u64 + 1, RepeatingDateError::IntervalTooLarge), (1 << 40, RepeatingDateError::IntervalTooLarge), (i32::MAX as u64 / 7, RepeatingDateError::DateOutOfRange)] {
+            for weekdays in [WeekdayFlags::ANY, WeekdayFlags::TUE, WeekdayFlags::TUE | WeekdayFlags::THU] {
+                assert_eq!(Err(expected), find_next_date(from, start, weekdays, repeat, interval), "every {} on {:?}", interval, weekdays);
             }
 
-            Ok(date)
-        },
-        Repeating::Yearly => {
-            let max_year_skip = 100;
-
-            let interval = interval as i32;
-            let years_delta = from_date.years_since(start_date).unwrap() as i32;
+            assert!(!match_repeating_date(date("2023-09-18"), start, WeekdayFlags::ANY, repeat, interval), "every {}", interval);
+            assert!(!match_repeating_date(date("2024-09-12"), start, WeekdayFlags::ANY, repeat, interval), "every {}", interval);
+        }
+    }
 
-            let (_, current_year) = start_date.year_ce();
-            let mut date = start_date.with_year(current_year as i32 + years_delta + interval).unwrap();
+    #[rstest]
+    #[case::daily(Repeating::Daily)]
+    #[case::weekly(Repeating::Weekly)]
+    #[case::monthly(Repeating::Monthly)]
+    #[case::quarterly(Repeating::Quarterly)]
+    #[case::yearly(Repeating::Yearly)]
+    fn next_or_first_before_the_start(#[case] repeat: Repeating) {
+        let start = date("2023-09-12");
+        let weekdays = WeekdayFlags::TUE | WeekdayFlags::THU;
+
+        for from in [date("2019-02-03"), date("2023-09-11")] {
+            assert_eq!(Ok(start), find_next_date_or_first(from, start, weekdays, repeat, 2), "from {}", from);
+        }
 
-            let mut counter = 0;
-            while counter < max_year_skip && !weekdays.contains(WeekdayFlags::from_weekday(date.weekday())) {
-                let (_, cur_year) = start_date.year_ce();
-                date = date.with_year(cur_year as i32 + interval).unwrap();
+        for from in [start, date("2025-01-01")] {
+            assert_eq!(find_next_date(from, start, weekdays, repeat, 2), find_next_date_or_first(from, start, weekdays, repeat, 2), "from {}", from);
+        }
+    }
 
-                counter += 1;
+    #[rstest]
+    #[case::daily(Repeating::Daily, true)]
+    #[case::weekly(Repeating::Weekly, true)]
+    #[case::monthly(Repeating::Monthly, true)]
+    #[case::quarterly(Repeating::Quarterly, true)]
+    #[case::yearly(Repeating::Yearly, true)]
+    #[case::hourly_needs_the_datetime_functions(Repeating::Hourly, false)]
+    #[case::minutely_needs_the_datetime_functions(Repeating::Minutely, false)]
+    fn start_is_the_first_occurrence(#[case] repeat: Repeating, #[case] expected: bool) {
+        let start = date("2024-01-31");
+
+        for (weekdays, interval) in [(WeekdayFlags::ANY, 1), (WeekdayFlags::WED, 3), (WeekdayFlags::WED | WeekdayFlags::FRI, 2)] {
+            assert_eq!(expected, match_repeating_date(start, start, weekdays, repeat, interval), "every {} on {:?}", interval, weekdays);
+
+            for policy in [MonthDayPolicy::SkipShortMonths, MonthDayPolicy::ClampToMonthEnd, MonthDayPolicy::RollToFirstOfNext] {
+                assert_eq!(expected, match_repeating_date_with(start, start, weekdays, repeat, interval, policy), "every {} on {:?} with {:?}", interval, weekdays, policy);
             }
+        }
+    }
 
-            Ok(date)
-        },
+    #[rstest]
+    #[case::daily(Repeating::Daily, 1)]
+    #[case::bi_daily(Repeating::Daily, 2)]
+    #[case::weekly(Repeating::Weekly, 1)]
+    #[case::bi_weekly(Repeating::Weekly, 2)]
+    #[case::monthly(Repeating::Monthly, 1)]
+    #[case::bi_monthly(Repeating::Monthly, 2)]
+    #[case::quarterly(Repeating::Quarterly, 1)]
+    #[case::bi_quarterly(Repeating::Quarterly, 2)]
+    #[case::yearly(Repeating::Yearly, 1)]
+    #[case::bi_yearly(Repeating::Yearly, 2)]
+    fn on_or_after_and_strictly_after_an_occurrence(#[case] repeat: Repeating, #[case] interval: u64) {
+        let start = date("2023-09-12");
+        let weekdays = WeekdayFlags::TUE | WeekdayFlags::THU | WeekdayFlags::SAT;
+
+        let occurrence = find_next_date(start, start, weekdays, repeat, interval).unwrap();
+        let following = find_next_date(occurrence, start, weekdays, repeat, interval).unwrap();
+
+        assert_eq!(Ok(start), find_next_date_on_or_after(start, start, weekdays, repeat, interval));
+        assert_eq!(Ok(occurrence), find_next_date_on_or_after(occurrence, start, weekdays, repeat, interval));
+        assert_eq!(Ok(following), find_next_date_on_or_after(occurrence.succ_opt().unwrap(), start, weekdays, repeat, interval));
+        assert!(following > occurrence);
+        assert!(match_repeating_date(occurrence, start, weekdays, repeat, interval));
     }
-}
 
-pub fn match_repeating_date(date_to_check: chrono::NaiveDate, start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
-    if date_to_check < start_date {
-        return false;
+    #[test]
+    fn on_or_after_before_the_start() {
+        let start = date("2023-09-12");
+
+        assert_eq!(Ok(start), find_next_date_on_or_after(date("2020-01-01"), start, WeekdayFlags::ANY, Repeating::Monthly, 1));
+        assert_eq!(Err(RepeatingDateError::ZeroInterval), find_next_date_on_or_after(start, start, WeekdayFlags::ANY, Repeating::Monthly, 0));
+        assert_eq!(Ok(chrono::NaiveDate::MIN), find_next_date_on_or_after(chrono::NaiveDate::MIN, chrono::NaiveDate::MIN, WeekdayFlags::ANY, Repeating::Daily, 1));
     }
-    
-    if !weekdays.contains(WeekdayFlags::from_weekday(date_to_check.weekday())) {
-        return false;
+
+    #[rstest]
+    #[case::zero_interval(WeekdayFlags::TUE, Repeating::Daily, 0, RepeatingDateError::ZeroInterval)]
+    #[case::no_weekdays(WeekdayFlags::empty(), Repeating::Daily, 1, RepeatingDateError::EmptyWeekdaySet)]
+    #[case::wrong_weekday(WeekdayFlags::MON, Repeating::Weekly, 1, RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Tue, allowed: WeekdayFlags::MON })]
+    #[case::sub_daily(WeekdayFlags::ANY, Repeating::Hourly, 1, RepeatingDateError::UnsupportedFrequency)]
+    fn next_or_first_checks_the_rule_before_the_start(#[case] weekdays: WeekdayFlags, #[case] repeat: Repeating, #[case] interval: u64, #[case] expected: RepeatingDateError) {
+        assert_eq!(Err(expected), find_next_date_or_first(date("2023-09-11"), date("2023-09-12"), weekdays, repeat, interval));
     }
 
-    let date_diff = date_to_check - start_date;
+    #[rstest]
+    #[case::before_start(RepeatingDateError::StartDateBeforeBound { from: date("2023-09-01"), start: date("2023-09-12") }, "2023-09-01 is before the start date 2023-09-12")]
+    #[case::wrong_weekday(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Wed, allowed: WeekdayFlags::TUE | WeekdayFlags::THU },
+        "the start falls on WED, which is not one of the allowed weekdays TUE,THU")]
+    #[case::zero_interval(RepeatingDateError::ZeroInterval, "the interval must be at least 1")]
+    #[case::interval_too_large(RepeatingDateError::IntervalTooLarge, "the interval is longer than the supported calendar")]
+    #[case::year_search(RepeatingDateError::YearSearchExhausted { searched_years: 100 }, "no occurrence on an allowed weekday within 100 on-years")]
+    fn error_messages(#[case] error: RepeatingDateError, #[case] expected: &str) {
+        assert_eq!(expected, error.to_string());
+    }
 
-    match repeat {
-        Repeating::Daily => date_diff.num_days() % interval as i64 == 0,
-        Repeating::Weekly => {
-            let date_to_check_from_monday = date_to_check.weekday().number_from_monday();
-            let start_date_from_monday = start_date.weekday().number_from_monday();
+    #[test]
+    fn errors_carry_context() {
+        let start = date("2023-09-13");
 
-            let is_new_week = date_to_check_from_monday < start_date_from_monday;
+        assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: date("2023-09-01"), start }), find_next_date(date("2023-09-01"), start, WeekdayFlags::ANY, Repeating::Daily, 1));
+        assert_eq!(Err(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Wed, allowed: WeekdayFlags::TUE }), find_next_date(start, start, WeekdayFlags::TUE, Repeating::Weekly, 1));
+    }
 
-            (date_diff.num_weeks() + (is_new_week as i64)) % interval as i64 == 0
-        },
-        Repeating::Monthly => {
-            let month_diff = get_months_since(date_to_check, start_date);
+    #[test]
+    fn errors_are_std_errors() {
+        fn assert_error<E: std::error::Error + Send + Sync + 'static>() {}
+        assert_error::<RepeatingDateError>();
 
-            date_to_check.day0() == start_date.day0() 
-                && month_diff > 0 && month_diff % interval as i32 == 0
-        },
-        Repeating::Yearly => if let Some(years) = date_to_check.years_since(start_date) {
-            date_to_check.day0() == start_date.day0() && date_to_check.month() == start_date.month()
-                && years > 0 && years % interval as u32 == 0
-        } else {
-            false
-        }
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(RepeatingDateError::EmptyWeekdaySet);
+        assert_eq!("no weekday is allowed", boxed.to_string());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use core::str::FromStr;
+    #[test]
+    fn try_match_on_valid_arguments() {
+        assert_eq!(Ok(true), try_match_repeating_date(date("2023-09-15"), date("2023-09-12"), WeekdayFlags::ANY, Repeating::Daily, 3));
+        assert_eq!(Ok(false), try_match_repeating_date(date("2023-09-14"), date("2023-09-12"), WeekdayFlags::ANY, Repeating::Daily, 3));
+    }
 
-    use super::*;
-    use rstest::rstest;
-    use chrono::Datelike;
+    #[test]
+    fn default_weekend_matches_presets() {
+        let weekend = WeekendDefinition::default();
+
+        assert_eq!(WeekdayFlags::WEEKEND, weekend.weekend());
+        assert_eq!(WeekdayFlags::MIDWEEK, weekend.workdays());
+        assert!(weekend.is_weekend(chrono::Weekday::Sun));
+        assert!(!weekend.is_weekend(chrono::Weekday::Fri));
+    }
+
+    #[test]
+    fn friday_saturday_weekend() {
+        let weekend = WeekendDefinition::FRIDAY_SATURDAY;
+
+        assert_eq!(WeekdayFlags::FRI | WeekdayFlags::SAT, weekend.weekend());
+        assert_eq!(WeekdayFlags::SUN | WeekdayFlags::MON | WeekdayFlags::TUE | WeekdayFlags::WED | WeekdayFlags::THU, weekend.workdays());
+        assert!(weekend.is_weekend(chrono::Weekday::Fri));
+        assert!(!weekend.is_weekend(chrono::Weekday::Sun));
+        assert_eq!(weekend, WeekendDefinition::new(WeekdayFlags::FRI | WeekdayFlags::SAT));
+    }
 
     #[test]
     fn daily_schedule_10_repeats() {
@@ -343,20 +2126,16 @@ mod tests {
         let start_date = chrono::NaiveDate::from_str("2023-09-21").unwrap();
         let dates_in_range = ["2023-09-28", "2023-10-05", "2023-10-12", "2023-10-19", "2023-10-26", "2023-11-02", "2023-11-09", "2023-11-16", "2023-11-23", "2023-11-30", "2023-12-07", "2023-12-14", "2023-12-21"];
 
-        let mut counter = 1;
-        let mut result = start_date;
-        for expected_date_string in dates_in_range {
-            result = find_next_date(result, start_date, weekdays, Repeating::Weekly, 1).unwrap();
+        let schedule = Schedule::new(start_date, weekdays, Repeating::Weekly, 1).unwrap();
+        let results = schedule.next_n_dates(start_date, limit - 1).unwrap();
 
+        assert_eq!(dates_in_range.len(), results.len());
+        for (expected_date_string, result) in dates_in_range.into_iter().zip(results) {
             let expected_result = chrono::NaiveDate::from_str(expected_date_string).unwrap();
             assert_eq!(expected_result, result);
             assert!(weekdays.contains(WeekdayFlags::from_weekday(result.weekday())));
             assert!(match_repeating_date(result, start_date, weekdays, Repeating::Weekly, 1));
-
-            counter += 1;
         }
-
-        assert_eq!(limit, counter);
     }
 
     #[test]
@@ -405,6 +2184,17 @@ mod tests {
         assert_eq!(limit, counter);
     }
 
+    #[test]
+    fn weekly_schedule_moredays_occurrences() {
+        let weekdays = WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI | WeekdayFlags::SAT;
+        let today = chrono::NaiveDate::from_str("2023-10-11").unwrap();
+        let schedule = Schedule::new(today, weekdays, Repeating::Weekly, 1).unwrap();
+        let dates_in_range = ["2023-10-13", "2023-10-14", "2023-10-16", "2023-10-18", "2023-10-20", "2023-10-21", "2023-10-23", "2023-10-25", "2023-10-27", "2023-10-28"];
+
+        let expected: Vec<chrono::NaiveDate> = dates_in_range.iter().map(|date| chrono::NaiveDate::from_str(date).unwrap()).collect();
+        assert_eq!(expected, schedule.occurrences(today).take(10).collect::<Vec<_>>());
+    }
+
     #[test]
     fn weekly_schedule_3_weeks_moredays() {
         let limit = 5;
@@ -491,6 +2281,38 @@ mod tests {
         assert!(match_repeating_date(result, start, weekdays, Repeating::Daily, interval));
     }
 
+    #[rstest]
+    #[case::off_grid("2023-9-12", "2023-9-13", WeekdayFlags::ANY, 3, "2023-9-15")]
+    #[case::off_grid_filtered("2023-9-12", "2023-9-13", WeekdayFlags::TUE | WeekdayFlags::THU, 3, "2023-9-21")]
+    #[case::months_later("2023-9-12", "2024-1-10", WeekdayFlags::ANY, 7, "2024-1-16")]
+    #[case::a_year_later("2023-9-12", "2024-9-12", WeekdayFlags::ANY, 3, "2024-9-15")]
+    #[case::a_year_later_filtered("2023-9-12", "2024-9-12", WeekdayFlags::MIDWEEK, 4, "2024-9-18")]
+    #[case::years_later("2023-9-12", "2030-2-1", WeekdayFlags::ANY, 10, "2030-2-7")]
+    fn next_daily_far_from_the_start(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate, #[case] weekdays: WeekdayFlags,
+        #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_date(from, start, weekdays, Repeating::Daily, interval).unwrap();
+        let walked = from.iter_days().skip(1).find(|date| match_repeating_date(*date, start, weekdays, Repeating::Daily, interval)).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert_eq!(walked, result);
+    }
+
+    #[test]
+    fn next_daily_agrees_with_matching() {
+        let start = chrono::NaiveDate::from_str("2023-9-12").unwrap();
+
+        for interval in [1, 2, 3, 5, 7, 10, 30] {
+            for weekdays in [WeekdayFlags::ANY, WeekdayFlags::MIDWEEK, WeekdayFlags::TUE | WeekdayFlags::SAT] {
+                for days in (0..800).step_by(13) {
+                    let from = start + chrono::Days::new(days);
+                    let walked = from.iter_days().skip(1).find(|date| match_repeating_date(*date, start, weekdays, Repeating::Daily, interval));
+
+                    assert_eq!(walked, find_next_date(from, start, weekdays, Repeating::Daily, interval).ok(), "every {} days on {:?} from {}", interval, weekdays, from);
+                }
+            }
+        }
+    }
+
     #[rstest]
     #[case::same_week_two_days("2023-9-12", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 2, "2023-9-14")]
     #[case::same_week_two_days("2023-9-12", "2023-9-14", WeekdayFlags::TUE | WeekdayFlags::THU, 2, "2023-9-26")]
@@ -513,6 +2335,139 @@ mod tests {
         assert!(match_repeating_date(result, start, weekdays, Repeating::Weekly, interval));
     }
 
+    #[rstest]
+    #[case::start_mon("2023-10-09", WeekdayFlags::MON | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-14", "2023-10-15", "2023-10-30", "2023-11-04", "2023-11-05", "2023-11-20", "2023-11-25"])]
+    #[case::start_tue("2023-10-10", WeekdayFlags::MON | WeekdayFlags::TUE | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-14", "2023-10-15", "2023-10-30", "2023-10-31", "2023-11-04", "2023-11-05", "2023-11-20"])]
+    #[case::start_wed("2023-10-11", WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-14", "2023-10-15", "2023-10-30", "2023-11-01", "2023-11-04", "2023-11-05", "2023-11-20"])]
+    #[case::start_thu("2023-10-12", WeekdayFlags::MON | WeekdayFlags::THU | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-14", "2023-10-15", "2023-10-30", "2023-11-02", "2023-11-04", "2023-11-05", "2023-11-20"])]
+    #[case::start_fri("2023-10-13", WeekdayFlags::MON | WeekdayFlags::FRI | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-14", "2023-10-15", "2023-10-30", "2023-11-03", "2023-11-04", "2023-11-05", "2023-11-20"])]
+    #[case::start_sat("2023-10-14", WeekdayFlags::MON | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-15", "2023-10-30", "2023-11-04", "2023-11-05", "2023-11-20", "2023-11-25", "2023-11-26"])]
+    #[case::start_sun("2023-10-15", WeekdayFlags::MON | WeekdayFlags::SAT | WeekdayFlags::SUN, &["2023-10-30", "2023-11-04", "2023-11-05", "2023-11-20", "2023-11-25", "2023-11-26", "2023-12-11"])]
+    fn tri_weekly_on_week_contains_start(#[case] start: chrono::NaiveDate, #[case] weekdays: WeekdayFlags, #[case] dates_in_range: &[&str]) {
+        let mut result = start;
+        for expected_date_string in dates_in_range {
+            let previous = result;
+            result = find_next_date(result, start, weekdays, Repeating::Weekly, 3).unwrap();
+
+            let expected_result = chrono::NaiveDate::from_str(expected_date_string).unwrap();
+            assert_eq!(expected_result, result);
+            assert!(match_repeating_date(result, start, weekdays, Repeating::Weekly, 3));
+
+            for skipped in previous.iter_days().skip(1).take_while(|date| *date < result) {
+                assert!(!match_repeating_date(skipped, start, weekdays, Repeating::Weekly, 3), "{} should not match", skipped);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::start_week_sunday("2023-10-15", true)]
+    #[case::off_week_monday("2023-10-16", false)]
+    #[case::off_week_sunday("2023-10-29", false)]
+    #[case::on_week_monday("2023-10-30", true)]
+    #[case::on_week_saturday("2023-11-04", true)]
+    fn tri_weekly_saturday_start_boundaries(#[case] check: chrono::NaiveDate, #[case] expected_result: bool) {
+        let start_date = chrono::NaiveDate::from_str("2023-10-14").unwrap();
+        let weekdays = WeekdayFlags::MON | WeekdayFlags::SAT | WeekdayFlags::SUN;
+
+        assert_eq!(expected_result, match_repeating_date(check, start_date, weekdays, Repeating::Weekly, 3));
+    }
+
+    #[test]
+    fn weekly_same_day_agrees_with_general_path() {
+        let first_start = chrono::NaiveDate::from_str("2023-09-11").unwrap();
+
+        for start_offset in 0..7 {
+            let start_date = first_start.checked_add_days(chrono::Days::new(start_offset)).unwrap();
+            let weekdays = WeekdayFlags::from_weekday(start_date.weekday());
+
+            for interval in 1..=5 {
+                for from_offset in 0..(3 * 365) {
+                    let from_date = start_date.checked_add_days(chrono::Days::new(from_offset)).unwrap();
+
+                    assert_eq!(find_next_weekly(from_date, start_date, weekdays, interval, WeekStart::Monday).ok(), find_next_same_weekday(from_date, start_date, interval),
+                        "start {} from {} interval {}", start_date, from_date, interval);
+                    assert_eq!(is_on_week(from_date, start_date, interval, WeekStart::Monday) && from_date.weekday() == start_date.weekday(),
+                        matches_same_weekday(from_date, start_date, interval), "start {} check {} interval {}", start_date, from_date, interval);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weekly_same_day_long_horizon() {
+        let start_date = chrono::NaiveDate::from_str("2023-09-21").unwrap();
+        let far_from = chrono::NaiveDate::from_str("2123-09-20").unwrap();
+
+        let result = find_next_date(far_from, start_date, WeekdayFlags::THU, Repeating::Weekly, 3).unwrap();
+
+        assert_eq!(chrono::Weekday::Thu, result.weekday());
+        assert!(result > far_from && (result - far_from).num_days() <= 21);
+        assert!(match_repeating_date(result, start_date, WeekdayFlags::THU, Repeating::Weekly, 3));
+        assert_eq!(0, (result - start_date).num_days() % 21);
+    }
+
+    #[rstest]
+    #[case::every_week_same_week("2023-9-11", "2023-9-11", 1, "2023-9-12")]
+    #[case::every_week_last_day("2023-9-11", "2023-9-17", 1, "2023-9-18")]
+    #[case::alternating_same_week("2023-9-11", "2023-9-14", 2, "2023-9-15")]
+    #[case::alternating_last_day("2023-9-11", "2023-9-17", 2, "2023-9-25")]
+    #[case::alternating_off_week("2023-9-11", "2023-9-20", 2, "2023-9-25")]
+    #[case::alternating_mid_week_start("2023-9-13", "2023-9-17", 2, "2023-9-25")]
+    #[case::alternating_mid_week_start_off_week("2023-9-13", "2023-9-24", 2, "2023-9-25")]
+    #[case::three_week_last_day("2023-9-13", "2023-9-17", 3, "2023-10-2")]
+    #[case::three_week_different_month("2023-9-13", "2023-10-8", 3, "2023-10-23")]
+    fn next_weekly_all_days(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate,
+        #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_date(from, start, WeekdayFlags::ANY, Repeating::Weekly, interval).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert!(match_repeating_date(result, start, WeekdayFlags::ANY, Repeating::Weekly, interval));
+    }
+
+    #[test]
+    fn weekly_all_days_alternating_weeks() {
+        let start_date = chrono::NaiveDate::from_str("2023-09-13").unwrap();
+        let dates_in_range = ["2023-09-14", "2023-09-15", "2023-09-16", "2023-09-17", "2023-09-25", "2023-09-26", "2023-09-27",
+            "2023-09-28", "2023-09-29", "2023-09-30", "2023-10-01", "2023-10-09"];
+
+        let mut result = start_date;
+        for expected_date_string in dates_in_range {
+            result = find_next_date(result, start_date, WeekdayFlags::ANY, Repeating::Weekly, 2).unwrap();
+
+            let expected_result = chrono::NaiveDate::from_str(expected_date_string).unwrap();
+            assert_eq!(expected_result, result);
+            assert!(match_repeating_date(result, start_date, WeekdayFlags::ANY, Repeating::Weekly, 2));
+        }
+    }
+
+    #[rstest]
+    #[case("2023-9-13", "2023-9-17", 2, "2023-9-25")]
+    #[case("2023-9-13", "2023-9-13", 1, "2023-9-18")]
+    #[case("2023-9-11", "2023-9-25", 2, "2023-10-9")]
+    fn next_weekstart_all_days(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate,
+        #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_weekstart(from, start, WeekdayFlags::ANY, interval).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert_eq!(chrono::Weekday::Mon, result.weekday());
+    }
+
+    /// Sets whose earliest weekday comes after the start's, where the week-start offset used to be
+    /// a subtraction that underflowed.
+    #[rstest]
+    #[case::weekend_after_thursday("2023-9-14", "2023-9-14", WeekdayFlags::SAT | WeekdayFlags::SUN, 2, "2023-9-30")]
+    #[case::sunday_after_thursday("2023-9-14", "2023-9-20", WeekdayFlags::SUN, 3, "2023-10-8")]
+    #[case::friday_after_wednesday("2023-9-13", "2023-9-13", WeekdayFlags::FRI, 1, "2023-9-22")]
+    #[case::weekend_after_monday("2023-9-11", "2023-9-28", WeekdayFlags::WEEKEND, 4, "2023-10-14")]
+    fn next_weekstart_with_later_weekdays(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate, #[case] weekdays: WeekdayFlags,
+        #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_weekstart(from, start, weekdays, interval).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert_eq!(weekdays.first_valid_weekday_bitwise(), Some( result.weekday()));
+        assert!((result - from).num_days() <= interval as i64 * 7 + 6);
+    }
+
     #[rstest]
     #[case("2023-9-12", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 1, "2023-10-12")]
     #[case("2023-9-12", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU | WeekdayFlags::SUN, 2, "2023-11-12")]
@@ -529,11 +2484,56 @@ mod tests {
         assert!(match_repeating_date(result, start, weekdays, Repeating::Monthly, interval));
     }
 
+    #[test]
+    fn quarterly_schedule_until_date() {
+        let weekdays = WeekdayFlags::ANY;
+        let start_date = chrono::NaiveDate::from_str("2023-08-31").unwrap();
+        // November and February lack the 31st.
+        let dates_in_range = ["2024-05-31", "2024-08-31", "2025-05-31"];
+        let final_date = chrono::NaiveDate::from_str("2025-05-31").unwrap();
+
+        let mut counter = 0;
+        let mut result = start_date;
+        while result < final_date {
+            result = find_next_date(result, start_date, weekdays, Repeating::Quarterly, 1).unwrap();
+
+            let expected_result = chrono::NaiveDate::from_str(dates_in_range[counter]).unwrap();
+            assert_eq!(expected_result, result);
+            assert!(match_repeating_date(result, start_date, weekdays, Repeating::Quarterly, 1));
+            assert!(match_repeating_date(result, start_date, weekdays, Repeating::Monthly, 3));
+
+            counter += 1;
+        }
+
+        assert_eq!(dates_in_range.len(), counter);
+    }
+
+    #[rstest]
+    #[case("2023-10-12", "2023-10-12", WeekdayFlags::ANY, 1, "2024-1-12")]
+    #[case("2023-10-12", "2023-11-30", WeekdayFlags::ANY, 1, "2024-1-12")]
+    #[case("2023-10-12", "2023-10-12", WeekdayFlags::ANY, 2, "2024-4-12")]
+    #[case::weekday_filter("2024-3-12", "2024-3-12", WeekdayFlags::TUE | WeekdayFlags::THU, 1, "2024-9-12")]
+    #[case::yearly("2023-11-12", "2023-11-12", WeekdayFlags::ANY, 4, "2024-11-12")]
+    fn next_quarterly(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate, #[case] weekdays: WeekdayFlags,
+        #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_date(from, start, weekdays, Repeating::Quarterly, interval).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert!(weekdays.contains(WeekdayFlags::from_weekday(result.weekday())));
+        assert!(match_repeating_date(result, start, weekdays, Repeating::Quarterly, interval));
+    }
+
     #[rstest]
     #[case("2023-9-12", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 1, "2024-9-12")]
     #[case("2023-9-12", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU | WeekdayFlags::FRI, 2, "2025-9-12")]
     #[case("2023-9-12", "2023-9-12", WeekdayFlags::ANY, 2, "2025-9-12")]
     #[case("2023-9-12", "2023-12-12", WeekdayFlags::ANY, 3, "2026-9-12")]
+    #[case::several_steps_to_a_monday("2023-9-12", "2023-9-12", WeekdayFlags::MON | WeekdayFlags::TUE, 1, "2028-9-12")]
+    #[case::several_biennial_steps_to_a_monday("2023-9-12", "2023-9-12", WeekdayFlags::MON | WeekdayFlags::TUE, 2, "2033-9-12")]
+    #[case::several_triennial_steps_to_a_monday("2023-9-12", "2023-9-12", WeekdayFlags::MON | WeekdayFlags::TUE, 3, "2044-9-12")]
+    #[case::from_between_steps_to_a_monday("2023-9-12", "2030-1-1", WeekdayFlags::MON | WeekdayFlags::TUE, 3, "2044-9-12")]
+    #[case::leap_day_on_a_saturday("1896-2-29", "1896-2-29", WeekdayFlags::SAT, 1, "1908-2-29")]
+    #[case::sexennial_to_a_monday_or_tuesday("2023-9-12", "2023-9-12", WeekdayFlags::MON | WeekdayFlags::TUE, 6, "2089-9-12")]
     fn next_yearly(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate, #[case] weekdays: WeekdayFlags, 
         #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
         let result = find_next_date(from, start, weekdays, Repeating::Yearly, interval).unwrap();
@@ -543,6 +2543,188 @@ mod tests {
         assert!(match_repeating_date(result, start, weekdays, Repeating::Yearly, interval));
     }
 
+    #[test]
+    fn monthly_weekday_filtered_results_match_their_rule() {
+        // Every partial weekday set holding the start's weekday, for starts on every day of month.
+        for start in date("2023-01-01").iter_days().step_by(3).take(244) {
+            let start_weekday = WeekdayFlags::from_weekday(start.weekday());
+            let others: Vec<WeekdayFlags> = (WeekdayFlags::ANY - start_weekday).iter().collect();
+
+            for subset in 0..(1u32 << others.len()) - 1 {
+                let weekdays = others.iter().enumerate().filter(|(index, _)| subset & (1 << index) != 0).fold(start_weekday, |weekdays, (_, flag)| weekdays | *flag);
+
+                for (repeat, interval) in [(Repeating::Monthly, 1), (Repeating::Monthly, 2), (Repeating::Monthly, 5), (Repeating::Quarterly, 1)] {
+                    for from in [start, start + chrono::Days::new(40), start + chrono::Days::new(400)] {
+                        let next = find_next_date(from, start, weekdays, repeat, interval).unwrap();
+
+                        assert!(next > from && match_repeating_date(next, start, weekdays, repeat, interval), "{:?} every {} on {:?} from {}: {}", repeat, interval, weekdays, from, next);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn monthly_weekday_filtered_results_are_the_first_match() {
+        let start = date("2023-01-12");
+        let weekdays = WeekdayFlags::THU | WeekdayFlags::SAT;
+
+        for interval in 1..=3 {
+            let mut from = start;
+
+            for _ in 0..12 {
+                let next = find_next_date(from, start, weekdays, Repeating::Monthly, interval).unwrap();
+                let walked = from.iter_days().skip(1).find(|date| match_repeating_date(*date, start, weekdays, Repeating::Monthly, interval));
+
+                assert_eq!(walked, Some(next), "every {} months from {}", interval, from);
+                from = next;
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::skip_leap(MonthDayPolicy::SkipShortMonths, "2024-1-31",
+        &["2024-3-31", "2024-5-31", "2024-7-31", "2024-8-31", "2024-10-31", "2024-12-31", "2025-1-31"])]
+    #[case::skip_non_leap(MonthDayPolicy::SkipShortMonths, "2023-1-31",
+        &["2023-3-31", "2023-5-31", "2023-7-31", "2023-8-31", "2023-10-31", "2023-12-31", "2024-1-31"])]
+    #[case::clamp_leap(MonthDayPolicy::ClampToMonthEnd, "2024-1-31",
+        &["2024-2-29", "2024-3-31", "2024-4-30", "2024-5-31", "2024-6-30", "2024-7-31", "2024-8-31", "2024-9-30", "2024-10-31", "2024-11-30", "2024-12-31", "2025-1-31"])]
+    #[case::clamp_non_leap(MonthDayPolicy::ClampToMonthEnd, "2023-1-31",
+        &["2023-2-28", "2023-3-31", "2023-4-30", "2023-5-31", "2023-6-30", "2023-7-31", "2023-8-31", "2023-9-30", "2023-10-31", "2023-11-30", "2023-12-31", "2024-1-31"])]
+    #[case::roll_leap(MonthDayPolicy::RollToFirstOfNext, "2024-1-31",
+        &["2024-3-1", "2024-3-31", "2024-5-1", "2024-5-31", "2024-7-1", "2024-7-31", "2024-8-31", "2024-10-1", "2024-10-31", "2024-12-1", "2024-12-31", "2025-1-31"])]
+    #[case::roll_non_leap(MonthDayPolicy::RollToFirstOfNext, "2023-1-31",
+        &["2023-3-1", "2023-3-31", "2023-5-1", "2023-5-31", "2023-7-1", "2023-7-31", "2023-8-31", "2023-10-1", "2023-10-31", "2023-12-1", "2023-12-31", "2024-1-31"])]
+    fn monthly_day_policies_over_a_year(#[case] policy: MonthDayPolicy, #[case] start: chrono::NaiveDate, #[case] expected: &[&str]) {
+        let expected = dates(expected);
+        let end = *expected.last().unwrap();
+
+        let found: Vec<chrono::NaiveDate> = std::iter::successors(Some(start), |from| find_next_date_with(*from, start, WeekdayFlags::ANY, Repeating::Monthly, 1, policy).ok())
+            .skip(1).take_while(|date| *date <= end).collect();
+        let matched: Vec<chrono::NaiveDate> = start.iter_days().skip(1).take_while(|date| *date <= end)
+            .filter(|date| match_repeating_date_with(*date, start, WeekdayFlags::ANY, Repeating::Monthly, 1, policy)).collect();
+
+        assert_eq!(expected, found);
+        assert_eq!(expected, matched);
+    }
+
+    #[rstest]
+    #[case::leap_years_only(MonthDayPolicy::SkipShortMonths, 1, &["2028-2-29", "2032-2-29"])]
+    #[case::leap_years_only_biennial(MonthDayPolicy::SkipShortMonths, 2, &["2028-2-29", "2032-2-29"])]
+    #[case::leap_years_only_triennial(MonthDayPolicy::SkipShortMonths, 3, &[])]
+    #[case::feb_28(MonthDayPolicy::ClampToMonthEnd, 1,
+        &["2025-2-28", "2026-2-28", "2027-2-28", "2028-2-29", "2029-2-28", "2030-2-28", "2031-2-28", "2032-2-29"])]
+    #[case::feb_28_biennial(MonthDayPolicy::ClampToMonthEnd, 2, &["2026-2-28", "2028-2-29", "2030-2-28", "2032-2-29"])]
+    #[case::feb_28_triennial(MonthDayPolicy::ClampToMonthEnd, 3, &["2027-2-28", "2030-2-28"])]
+    #[case::mar_1(MonthDayPolicy::RollToFirstOfNext, 1,
+        &["2025-3-1", "2026-3-1", "2027-3-1", "2028-2-29", "2029-3-1", "2030-3-1", "2031-3-1", "2032-2-29"])]
+    #[case::mar_1_biennial(MonthDayPolicy::RollToFirstOfNext, 2, &["2026-3-1", "2028-2-29", "2030-3-1", "2032-2-29"])]
+    #[case::mar_1_triennial(MonthDayPolicy::RollToFirstOfNext, 3, &["2027-3-1", "2030-3-1"])]
+    fn yearly_leap_day_policies_2024_to_2032(#[case] policy: MonthDayPolicy, #[case] interval: u64, #[case] expected: &[&str]) {
+        let start = date("2024-2-29");
+        let end = date("2032-12-31");
+        let expected = dates(expected);
+
+        let found: Vec<chrono::NaiveDate> = std::iter::successors(Some(start), |from| find_next_date_with(*from, start, WeekdayFlags::ANY, Repeating::Yearly, interval, policy).ok())
+            .skip(1).take_while(|date| *date <= end).collect();
+        let matched: Vec<chrono::NaiveDate> = start.iter_days().skip(1).take_while(|date| *date <= end)
+            .filter(|date| match_repeating_date_with(*date, start, WeekdayFlags::ANY, Repeating::Yearly, interval, policy)).collect();
+
+        assert_eq!(expected, found);
+        assert_eq!(expected, matched);
+    }
+
+    #[rstest]
+    #[case::too_few_years(5, Err(RepeatingDateError::YearSearchExhausted { searched_years: 5 }))]
+    #[case::just_enough_years(6, Ok(date("2028-9-12")))]
+    #[case::no_years(0, Err(RepeatingDateError::YearSearchExhausted { searched_years: 0 }))]
+    fn yearly_search_limit(#[case] max_year_skip: u32, #[case] expected: Result<chrono::NaiveDate, RepeatingDateError>) {
+        // The 12th of September next falls on a Tuesday five years on; the search tries 2023 too.
+        let start = date("2023-9-12");
+        let result = find_next_date_with_limit(start, start, WeekdayFlags::TUE, Repeating::Yearly, 1, MonthDayPolicy::SkipShortMonths, max_year_skip);
+
+        assert_eq!(expected, result);
+        assert!(result.is_err() || match_repeating_date(result.unwrap(), start, WeekdayFlags::TUE, Repeating::Yearly, 1));
+    }
+
+    #[rstest]
+    #[case::skip(MonthDayPolicy::SkipShortMonths, Repeating::Yearly, "2024-2-29", "2028-2-29")]
+    #[case::clamp(MonthDayPolicy::ClampToMonthEnd, Repeating::Yearly, "2024-2-29", "2025-2-28")]
+    #[case::roll(MonthDayPolicy::RollToFirstOfNext, Repeating::Yearly, "2024-2-29", "2025-3-1")]
+    #[case::quarterly_skip(MonthDayPolicy::SkipShortMonths, Repeating::Quarterly, "2024-1-31", "2024-7-31")]
+    #[case::quarterly_clamp(MonthDayPolicy::ClampToMonthEnd, Repeating::Quarterly, "2024-1-31", "2024-4-30")]
+    #[case::quarterly_roll(MonthDayPolicy::RollToFirstOfNext, Repeating::Quarterly, "2024-1-31", "2024-5-1")]
+    fn day_policies_beyond_monthly(#[case] policy: MonthDayPolicy, #[case] repeat: Repeating, #[case] start: chrono::NaiveDate, #[case] expected_result: chrono::NaiveDate) {
+        assert_eq!(Ok(expected_result), find_next_date_with(start, start, WeekdayFlags::ANY, repeat, 1, policy));
+        assert!(match_repeating_date_with(expected_result, start, WeekdayFlags::ANY, repeat, 1, policy));
+        assert!(start.iter_days().skip(1).take_while(|date| *date < expected_result)
+            .all(|date| !match_repeating_date_with(date, start, WeekdayFlags::ANY, repeat, 1, policy)));
+    }
+
+    #[test]
+    fn day_policies_default_to_skipping() {
+        let start = date("2024-1-31");
+
+        assert_eq!(MonthDayPolicy::SkipShortMonths, MonthDayPolicy::default());
+        assert_eq!(find_next_date_with(start, start, WeekdayFlags::ANY, Repeating::Monthly, 1, MonthDayPolicy::SkipShortMonths), find_next_date(start, start, WeekdayFlags::ANY, Repeating::Monthly, 1));
+        assert!(!match_repeating_date(date("2024-2-29"), start, WeekdayFlags::ANY, Repeating::Monthly, 1));
+    }
+
+    #[rstest]
+    #[case::daily("1850-3-4", "1850-3-4", WeekdayFlags::MIDWEEK, Repeating::Daily, 1, "1850-3-5")]
+    #[case::bi_daily_weekend("1850-3-4", "1850-3-8", WeekdayFlags::MIDWEEK, Repeating::Daily, 2, "1850-3-12")]
+    #[case::weekly_new_century("1799-12-31", "1799-12-31", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2, "1800-1-2")]
+    #[case::weekly_off_week("1799-12-31", "1800-1-2", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2, "1800-1-14")]
+    #[case::monthly_day_31("1850-1-31", "1850-1-31", WeekdayFlags::ANY, Repeating::Monthly, 1, "1850-3-31")]
+    #[case::bi_monthly_day_31("1850-1-31", "1850-7-31", WeekdayFlags::ANY, Repeating::Monthly, 2, "1851-1-31")]
+    #[case::yearly_century("1899-6-15", "1899-6-15", WeekdayFlags::ANY, Repeating::Yearly, 1, "1900-6-15")]
+    #[case::yearly_leap_day("1896-2-29", "1896-2-29", WeekdayFlags::ANY, Repeating::Yearly, 1, "1904-2-29")]
+    #[case::bi_yearly_leap_day("1896-2-29", "1896-2-29", WeekdayFlags::ANY, Repeating::Yearly, 2, "1904-2-29")]
+    #[case::quadrennial_leap_day("1896-2-29", "1896-2-29", WeekdayFlags::ANY, Repeating::Yearly, 4, "1904-2-29")]
+    #[case::quadrennial_leap_day_from_between("1896-2-29", "1897-3-1", WeekdayFlags::ANY, Repeating::Yearly, 4, "1904-2-29")]
+    #[case::triennial_leap_day("1896-2-29", "1896-2-29", WeekdayFlags::ANY, Repeating::Yearly, 3, "1908-2-29")]
+    fn next_before_1900(#[case] start: chrono::NaiveDate, #[case] from: chrono::NaiveDate, #[case] weekdays: WeekdayFlags,
+        #[case] repeat: Repeating, #[case] interval: u64, #[case] expected_result: chrono::NaiveDate) {
+        let result = find_next_date(from, start, weekdays, repeat, interval).unwrap();
+
+        assert_eq!(expected_result, result);
+        assert!(match_repeating_date(result, start, weekdays, repeat, interval));
+    }
+
+    #[test]
+    fn monthly_day_31_through_1800s() {
+        let start_date = chrono::NaiveDate::from_str("1850-01-31").unwrap();
+        let dates_in_range = ["1850-03-31", "1850-05-31", "1850-07-31", "1850-08-31", "1850-10-31", "1850-12-31", "1851-01-31"];
+
+        let mut result = start_date;
+        for expected_date_string in dates_in_range {
+            result = find_next_date(result, start_date, WeekdayFlags::ANY, Repeating::Monthly, 1).unwrap();
+
+            let expected_result = chrono::NaiveDate::from_str(expected_date_string).unwrap();
+            assert_eq!(expected_result, result);
+            assert!(match_repeating_date(result, start_date, WeekdayFlags::ANY, Repeating::Monthly, 1));
+        }
+    }
+
+    #[rstest]
+    #[case::not_a_leap_year("1900-2-28", false)]
+    #[case::day_after("1900-3-1", false)]
+    #[case::next_leap_year("1904-2-29", true)]
+    #[case::following_leap_year("1908-2-29", true)]
+    fn yearly_leap_day_across_1900_match(#[case] check: chrono::NaiveDate, #[case] expected_result: bool) {
+        let start_date = chrono::NaiveDate::from_str("1896-02-29").unwrap();
+
+        assert_eq!(expected_result, match_repeating_date(check, start_date, WeekdayFlags::ANY, Repeating::Yearly, 1));
+    }
+
+    #[test]
+    fn months_since_across_common_era() {
+        let from_date = chrono::NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let start_date = chrono::NaiveDate::from_ymd_opt(-1, 12, 1).unwrap();
+
+        assert_eq!(13, get_months_since(from_date, start_date));
+    }
+
     #[rstest]
     #[case::two_week("2023-9-26", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 2, true)]
     #[case::same_week("2023-9-14", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 2, true)]
@@ -557,6 +2739,41 @@ mod tests {
         assert_eq!(expected_result, result);
     }
 
+    #[rstest]
+    #[case::earlier_in_the_start_week("2023-9-12", 2, false)]
+    #[case::later_in_the_start_week("2023-9-14", 2, true)]
+    #[case::earlier_in_the_off_week("2023-9-19", 2, false)]
+    #[case::later_in_the_off_week("2023-9-21", 2, false)]
+    #[case::earlier_in_the_next_on_week("2023-9-26", 2, true)]
+    #[case::later_in_the_next_on_week("2023-9-28", 2, true)]
+    #[case::earlier_one_week_later("2023-9-19", 1, true)]
+    #[case::earlier_three_weeks_later("2023-10-3", 3, true)]
+    #[case::later_two_weeks_later("2023-9-28", 3, false)]
+    fn weekly_mid_week_start_match(#[case] check: chrono::NaiveDate, #[case] interval: u64, #[case] expected_result: bool) {
+        let start = date("2023-9-13");
+        let weekdays = WeekdayFlags::TUE | WeekdayFlags::WED | WeekdayFlags::THU;
+
+        assert_eq!(expected_result, match_repeating_date(check, start, weekdays, Repeating::Weekly, interval));
+    }
+
+    #[test]
+    fn weekly_match_counts_calendar_weeks() {
+        // 2024-01-08 to 2024-12-22 share an ISO year, so the ISO week numbers count calendar weeks.
+        for start in date("2024-1-8").iter_days().take(7) {
+            let start_weekday = WeekdayFlags::from_weekday(start.weekday());
+            let weekdays = start_weekday | WeekdayFlags::from_weekday(start.weekday().pred()) | WeekdayFlags::from_weekday(start.weekday().succ());
+
+            for interval in 1..=3 {
+                for check in start.iter_days().take(7 * 40) {
+                    let weeks = check.iso_week().week() - start.iso_week().week();
+                    let expected = weekdays.contains(WeekdayFlags::from_weekday(check.weekday())) && weeks % interval as u32 == 0;
+
+                    assert_eq!(expected, match_repeating_date(check, start, weekdays, Repeating::Weekly, interval), "every {} weeks from {} on {}", interval, start, check);
+                }
+            }
+        }
+    }
+
     #[rstest]
     #[case("2023-9-13", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::WED, 1, true)]
     #[case::every_two_days("2023-9-14", "2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, 2, true)]
@@ -585,6 +2802,19 @@ mod tests {
         assert_eq!(expected_result, result);
     }
 
+    #[rstest]
+    #[case::next_quarter("2023-12-12", "2023-9-12", 1, true)]
+    #[case::next_year("2024-3-12", "2023-9-12", 1, true)]
+    #[case::off_quarter("2023-10-12", "2023-9-12", 1, false)]
+    #[case::skipped_quarter("2023-12-12", "2023-9-12", 2, false)]
+    #[case::every_two_quarters("2024-3-12", "2023-9-12", 2, true)]
+    #[case::wrong_day("2023-12-13", "2023-9-12", 1, false)]
+    fn quarterly_repeat_match(#[case] check: chrono::NaiveDate, #[case] start: chrono::NaiveDate, #[case] interval: u64, #[case] expected_result: bool) {
+        let result = match_repeating_date(check, start, WeekdayFlags::ANY, Repeating::Quarterly, interval);
+
+        assert_eq!(expected_result, result);
+    }
+
     #[rstest]
     #[case::same_year("2023-9-14", "2023-9-12", 1, false)]
     #[case::every_two_year("2025-9-12", "2023-9-12", 2, true)]
@@ -0,0 +1,204 @@
+use chrono::Datelike;
+
+/// A validated day of month: `1..=31` counts from the start of the month, `-31..=-1` from its end
+/// (`-1` being the last day).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MonthDay(i8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidMonthDay {
+    pub value: i8
+}
+
+impl std::fmt::Display for InvalidMonthDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid day of month {}, expected 1..=31 or -31..=-1", self.value)
+    }
+}
+
+impl std::error::Error for InvalidMonthDay {}
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    (28..=31).rev().find(|day| chrono::NaiveDate::from_ymd_opt(year, month, *day).is_some())
+}
+
+impl MonthDay {
+    pub const FIRST: MonthDay = MonthDay(1);
+    pub const LAST: MonthDay = MonthDay(-1);
+
+    pub fn new(day: i8) -> Result<MonthDay, InvalidMonthDay> {
+        if (1..=31).contains(&day) || (-31..=-1).contains(&day) {
+            Ok(MonthDay(day))
+        } else {
+            Err(InvalidMonthDay { value: day })
+        }
+    }
+
+    /// The day of month the given date falls on, counted from the start of the month.
+    pub fn of(date: chrono::NaiveDate) -> MonthDay {
+        MonthDay(date.day() as i8)
+    }
+
+    pub fn get(&self) -> i8 {
+        self.0
+    }
+
+    /// The date this day falls on in the given month, or `None` when the month is too short
+    /// (e.g. the 31st of April, or `-30` in February).
+    pub fn resolve(&self, year: i32, month: u32) -> Option<chrono::NaiveDate> {
+        if self.0 > 0 {
+            return chrono::NaiveDate::from_ymd_opt(year, month, self.0 as u32);
+        }
+
+        let day = days_in_month(year, month)? as i32 + self.0 as i32 + 1;
+
+        if day < 1 {
+            return None;
+        }
+
+        chrono::NaiveDate::from_ymd_opt(year, month, day as u32)
+    }
+
+    /// Like `resolve`, but a day beyond the month's end falls on its last day and one before its
+    /// start on its first (the 31st of April is April 30).
+    pub fn resolve_clamped(&self, year: i32, month: u32) -> Option<chrono::NaiveDate> {
+        let days = days_in_month(year, month)? as i32;
+        let day = if self.0 > 0 { (self.0 as i32).min(days) } else { (days + self.0 as i32 + 1).max(1) };
+
+        chrono::NaiveDate::from_ymd_opt(year, month, day as u32)
+    }
+
+    pub fn exists_in(&self, year: i32, month: u32) -> bool {
+        self.resolve(year, month).is_some()
+    }
+}
+
+impl TryFrom<i8> for MonthDay {
+    type Error = InvalidMonthDay;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        MonthDay::new(value)
+    }
+}
+
+impl From<MonthDay> for i8 {
+    fn from(value: MonthDay) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MonthDay {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i8(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MonthDay {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <i8 as serde::Deserialize>::deserialize(deserializer)?;
+
+        MonthDay::new(value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0)]
+    #[case(32)]
+    #[case(-32)]
+    #[case(45)]
+    #[case(i8::MIN)]
+    #[case(i8::MAX)]
+    fn rejects_out_of_range(#[case] value: i8) {
+        assert_eq!(Err(InvalidMonthDay { value }), MonthDay::new(value));
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(31)]
+    #[case(-1)]
+    #[case(-31)]
+    fn accepts_in_range(#[case] value: i8) {
+        assert_eq!(value, MonthDay::new(value).unwrap().get());
+    }
+
+    #[rstest]
+    #[case(2023, 1, Some(31))]
+    #[case(2023, 2, None)]
+    #[case(2024, 2, None)]
+    #[case(2023, 3, Some(31))]
+    #[case(2023, 4, None)]
+    #[case(2023, 5, Some(31))]
+    #[case(2023, 6, None)]
+    #[case(2023, 7, Some(31))]
+    #[case(2023, 8, Some(31))]
+    #[case(2023, 9, None)]
+    #[case(2023, 10, Some(31))]
+    #[case(2023, 11, None)]
+    #[case(2023, 12, Some(31))]
+    fn day_31_per_month(#[case] year: i32, #[case] month: u32, #[case] expected_day: Option<u32>) {
+        let day = MonthDay::new(31).unwrap();
+
+        assert_eq!(expected_day.map(|d| chrono::NaiveDate::from_ymd_opt(year, month, d).unwrap()), day.resolve(year, month));
+        assert_eq!(expected_day.is_some(), day.exists_in(year, month));
+    }
+
+    #[rstest]
+    #[case(2023, 1, Some(1))]
+    #[case(2023, 2, None)]
+    #[case(2024, 2, None)]
+    #[case(2023, 3, Some(1))]
+    #[case(2023, 4, None)]
+    #[case(2023, 5, Some(1))]
+    #[case(2023, 6, None)]
+    #[case(2023, 7, Some(1))]
+    #[case(2023, 8, Some(1))]
+    #[case(2023, 9, None)]
+    #[case(2023, 10, Some(1))]
+    #[case(2023, 11, None)]
+    #[case(2023, 12, Some(1))]
+    fn day_minus_31_per_month(#[case] year: i32, #[case] month: u32, #[case] expected_day: Option<u32>) {
+        let day = MonthDay::new(-31).unwrap();
+
+        assert_eq!(expected_day.map(|d| chrono::NaiveDate::from_ymd_opt(year, month, d).unwrap()), day.resolve(year, month));
+        assert_eq!(expected_day.is_some(), day.exists_in(year, month));
+    }
+
+    #[rstest]
+    #[case(2023, 2, -1, Some(28))]
+    #[case(2024, 2, -1, Some(29))]
+    #[case(2023, 2, 29, None)]
+    #[case(2024, 2, 29, Some(29))]
+    #[case(2023, 2, -28, Some(1))]
+    #[case(2024, 2, -29, Some(1))]
+    #[case(2023, 2, -29, None)]
+    #[case(1900, 2, 29, None)]
+    #[case(2000, 2, 29, Some(29))]
+    #[case(2023, 4, -1, Some(30))]
+    fn february_leap_states(#[case] year: i32, #[case] month: u32, #[case] value: i8, #[case] expected_day: Option<u32>) {
+        let day = MonthDay::new(value).unwrap();
+
+        assert_eq!(expected_day.map(|d| chrono::NaiveDate::from_ymd_opt(year, month, d).unwrap()), day.resolve(year, month));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_signed_integer() {
+        for value in [1i8, 15, 31, -1, -31] {
+            let day = MonthDay::new(value).unwrap();
+            let json = serde_json::to_string(&day).unwrap();
+
+            assert_eq!(value.to_string(), json);
+            assert_eq!(day, serde_json::from_str::<MonthDay>(&json).unwrap());
+        }
+
+        assert!(serde_json::from_str::<MonthDay>("0").is_err());
+        assert!(serde_json::from_str::<MonthDay>("32").is_err());
+    }
+}
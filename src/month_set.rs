@@ -0,0 +1,159 @@
+bitflags::bitflags! {
+    /// A set of months of the year, restricting a yearly schedule to them.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
+    pub struct MonthFlags: u16 {
+        const JAN = 1;
+        const FEB = 1 << 1;
+        const MAR = 1 << 2;
+        const APR = 1 << 3;
+        const MAY = 1 << 4;
+        const JUN = 1 << 5;
+        const JUL = 1 << 6;
+        const AUG = 1 << 7;
+        const SEP = 1 << 8;
+        const OCT = 1 << 9;
+        const NOV = 1 << 10;
+        const DEC = 1 << 11;
+
+        const ANY = (1 << 12) - 1;
+
+        /// The last month of each quarter.
+        const QUARTER_ENDS = MonthFlags::MAR.bits() | MonthFlags::JUN.bits() | MonthFlags::SEP.bits() | MonthFlags::DEC.bits();
+    }
+}
+
+/// Upper case month names shared by the text and serde representations, January first.
+pub(crate) const MONTH_NAMES: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+pub(crate) fn month_flag_named(name: &str) -> Option<MonthFlags> {
+    let index = MONTH_NAMES.iter().position(|candidate| *candidate == name)?;
+
+    Some(MonthFlags::from_bits_retain(1 << index))
+}
+
+impl MonthFlags {
+    pub fn from_month(month: chrono::Month) -> MonthFlags {
+        MonthFlags::from_bits_retain(1 << month.number_from_month().saturating_sub(1))
+    }
+
+    /// Whether the month numbered `1..=12` is in the set.
+    pub fn contains_month(&self, month: u32) -> bool {
+        (1..=12).contains(&month) && self.bits() & (1 << (month - 1)) != 0
+    }
+
+    /// The numbers `1..=12` of the months in the set, ascending.
+    pub(crate) fn month_numbers(&self) -> impl Iterator<Item = u32> + '_ {
+        (1..=12).filter(|month| self.contains_month(*month))
+    }
+
+    pub(crate) fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.month_numbers().map(|month| MONTH_NAMES[month as usize - 1])
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MonthFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.names())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MonthFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut result = MonthFlags::empty();
+
+        for name in <Vec<String> as serde::Deserialize>::deserialize(deserializer)? {
+            let month = month_flag_named(&name).ok_or_else(|| serde::de::Error::unknown_variant(&name, &MONTH_NAMES))?;
+
+            if result.contains(month) {
+                return Err(serde::de::Error::custom(format_args!("duplicate month `{}`", name)));
+            }
+
+            result |= month;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RepeatingDateError, Schedule};
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    fn yearly_in(start: &str, interval: u64, months: MonthFlags) -> Schedule {
+        Schedule::builder().start(date(start)).yearly().interval(interval).months(months).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::january(chrono::Month::January, MonthFlags::JAN, 1)]
+    #[case::june(chrono::Month::June, MonthFlags::JUN, 6)]
+    #[case::december(chrono::Month::December, MonthFlags::DEC, 12)]
+    fn months_by_number(#[case] month: chrono::Month, #[case] expected: MonthFlags, #[case] number: u32) {
+        assert_eq!(expected, MonthFlags::from_month(month));
+        assert!(expected.contains_month(number));
+        assert_eq!(vec![number], expected.month_numbers().collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn names_are_ascending() {
+        assert_eq!(vec!["MAR", "JUN", "SEP", "DEC"], MonthFlags::QUARTER_ENDS.names().collect::<Vec<&str>>());
+        assert_eq!(Some(MonthFlags::SEP), month_flag_named("SEP"));
+        assert_eq!(None, month_flag_named("Sep"));
+        assert!(!MonthFlags::ANY.contains_month(13));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        assert_eq!(r#"["FEB","NOV"]"#, serde_json::to_string(&(MonthFlags::NOV | MonthFlags::FEB)).unwrap());
+        assert_eq!(MonthFlags::QUARTER_ENDS, serde_json::from_str::<MonthFlags>(r#"["DEC","MAR","JUN","SEP"]"#).unwrap());
+        assert!(serde_json::from_str::<MonthFlags>(r#"["MAR","MAR"]"#).is_err());
+        assert!(serde_json::from_str::<MonthFlags>(r#"["MARCH"]"#).is_err());
+    }
+
+    #[test]
+    fn every_quarter_end() {
+        let schedule = yearly_in("2024-03-12", 1, MonthFlags::QUARTER_ENDS);
+
+        assert_eq!(["2024-06-12", "2024-09-12", "2024-12-12", "2025-03-12"].map(date).to_vec(), schedule.next_n_dates(date("2024-03-12"), 4).unwrap().into_vec());
+        assert_eq!(Ok(date("2024-09-12")), schedule.next_after(date("2024-07-01")));
+        assert_eq!(Some(date("2024-06-12")), schedule.previous_before(date("2024-09-12")));
+        assert!(schedule.matches(date("2024-06-12")));
+        assert!(!schedule.matches(date("2024-04-12")));
+        assert!(!schedule.matches(date("2024-06-13")));
+    }
+
+    #[test]
+    fn skipped_years_produce_nothing() {
+        let schedule = yearly_in("2024-03-12", 2, MonthFlags::MAR | MonthFlags::SEP);
+
+        assert_eq!(["2024-09-12", "2026-03-12", "2026-09-12"].map(date).to_vec(), schedule.next_n_dates(date("2024-03-12"), 3).unwrap().into_vec());
+        assert_eq!(Ok(date("2026-03-12")), schedule.next_after(date("2024-10-01")));
+        assert!(!schedule.matches(date("2025-03-12")));
+        assert_eq!(Some(date("2024-09-12")), schedule.previous_before(date("2026-03-12")));
+        assert_eq!(Some(3), schedule.occurrence_index(date("2026-09-12")));
+        assert_eq!(Ok(date("2026-09-12")), schedule.nth_occurrence(3));
+    }
+
+    #[test]
+    fn months_too_short_are_skipped() {
+        let schedule = yearly_in("2024-01-31", 1, MonthFlags::JAN | MonthFlags::APR | MonthFlags::JUL | MonthFlags::OCT);
+
+        assert_eq!(["2024-07-31", "2024-10-31", "2025-01-31"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-31"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-04-30")));
+    }
+
+    #[test]
+    fn builder_validates_month_set() {
+        let builder = Schedule::builder().start(date("2024-03-12"));
+
+        assert_eq!(Err(RepeatingDateError::InvalidMonthSet), builder.clone().monthly().months(MonthFlags::MAR).build());
+        assert_eq!(Err(RepeatingDateError::InvalidMonthSet), builder.clone().yearly().months(MonthFlags::empty()).build());
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), builder.clone().yearly().months(MonthFlags::JUN).build());
+        assert_eq!(Some(MonthFlags::MAR), builder.yearly().months(MonthFlags::MAR).build().unwrap().months());
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::BTreeSet;
+
+use chrono::Datelike;
+
+use crate::month_day::days_in_month;
+use crate::{InvalidMonthDay, MonthDay};
+
+/// Which days of each on-month a monthly schedule falls on, in place of the start's day of month.
+///
+/// On a yearly schedule the rule applies to the start's month, or to each of its `months`, so e.g.
+/// the fourth Thursday of November is a yearly rule starting on one with `nth_weekday(Thu, [4])`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum MonthlyBy {
+    /// The `ordinals`-th occurrences of `weekday` in the month, `1..=5` counting from its start
+    /// (the second Tuesday is ordinal 2) and `-5..=-1` from its end (the last Friday is ordinal
+    /// -1). Months without a fifth such weekday have no occurrence for ordinals 5 and -5.
+    NthWeekday {
+        #[cfg_attr(feature = "serde", serde(with = "weekday_by_name"))]
+        weekday: chrono::Weekday,
+        ordinals: BTreeSet<i8>
+    },
+    /// The given days of month, see `MonthDay`; `MonthDay::LAST` is the last day of every month,
+    /// February 29 in leap years. `missing` decides what happens in months too short for a day,
+    /// and days resolving to the same date occur once. As with every rule, the weekday filter
+    /// applies to the resolved dates.
+    Days {
+        days: BTreeSet<MonthDay>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MissingDay::is_skip"))]
+        missing: MissingDay
+    }
+}
+
+/// What a `MonthlyBy::Days` or `YearDays` rule does with a day that a month or year lacks, e.g. the
+/// 31st in April or day 366 outside leap years.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum MissingDay {
+    /// That month has no occurrence for the day, as for plain monthly rules.
+    #[default]
+    Skip,
+    /// The day falls on the month's last day instead (its first one for days counted from the end),
+    /// or day 366 on December 31.
+    Clamp
+}
+
+impl MissingDay {
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_skip(&self) -> bool {
+        *self == MissingDay::Skip
+    }
+}
+
+impl MonthlyBy {
+    pub fn nth_weekday(weekday: chrono::Weekday, ordinals: impl IntoIterator<Item = i8>) -> MonthlyBy {
+        MonthlyBy::NthWeekday { weekday, ordinals: ordinals.into_iter().collect() }
+    }
+
+    /// The last `weekday` of every month.
+    pub fn last_weekday(weekday: chrono::Weekday) -> MonthlyBy {
+        MonthlyBy::nth_weekday(weekday, [-1])
+    }
+
+    /// The given days of every month, e.g. `&[1, 15]` for the 1st and the 15th; days a month lacks
+    /// are skipped, see `with_missing_days` to clamp them instead.
+    pub fn days(days: &[i8]) -> Result<MonthlyBy, InvalidMonthDay> {
+        let days = days.iter().map(|day| MonthDay::new(*day)).collect::<Result<BTreeSet<MonthDay>, InvalidMonthDay>>()?;
+
+        Ok(MonthlyBy::Days { days, missing: MissingDay::Skip })
+    }
+
+    /// The last day of every month.
+    pub fn last_day() -> MonthlyBy {
+        MonthlyBy::Days { days: BTreeSet::from([MonthDay::LAST]), missing: MissingDay::Skip }
+    }
+
+    /// Sets the policy for days a month lacks; rules on weekdays are returned unchanged.
+    pub fn with_missing_days(self, policy: MissingDay) -> MonthlyBy {
+        match self {
+            MonthlyBy::Days { days, .. } => MonthlyBy::Days { days, missing: policy },
+            rule => rule,
+        }
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            MonthlyBy::NthWeekday { ordinals, .. } => !ordinals.is_empty() && ordinals.iter().all(|ordinal| (1..=5).contains(ordinal) || (-5..=-1).contains(ordinal)),
+            MonthlyBy::Days { days, .. } => !days.is_empty(),
+        }
+    }
+
+    /// The most dates the rule can pick in a single month.
+    pub(crate) fn max_per_month(&self) -> usize {
+        match self {
+            MonthlyBy::NthWeekday { ordinals, .. } => ordinals.len(),
+            MonthlyBy::Days { days, .. } => days.len(),
+        }
+    }
+
+    /// The dates the rule picks in the given month, ascending.
+    pub(crate) fn dates_in(&self, year: i32, month: u32) -> Vec<chrono::NaiveDate> {
+        let mut dates: Vec<chrono::NaiveDate> = match self {
+            MonthlyBy::NthWeekday { weekday, ordinals } => ordinals.iter().filter_map(|ordinal| nth_weekday_of_month(year, month, *weekday, *ordinal)).collect(),
+            MonthlyBy::Days { days, missing: MissingDay::Skip } => days.iter().filter_map(|day| day.resolve(year, month)).collect(),
+            MonthlyBy::Days { days, missing: MissingDay::Clamp } => days.iter().filter_map(|day| day.resolve_clamped(year, month)).collect(),
+        };
+
+        // Counting from both ends can pick a date twice, e.g. the fifth and the last Friday.
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+}
+
+fn nth_weekday_of_month(year: i32, month: u32, weekday: chrono::Weekday, ordinal: i8) -> Option<chrono::NaiveDate> {
+    if ordinal > 0 {
+        return chrono::NaiveDate::from_weekday_of_month_opt(year, month, weekday, ordinal as u8);
+    }
+
+    let last_day = chrono::NaiveDate::from_ymd_opt(year, month, days_in_month(year, month)?)?;
+    let days_back = (last_day.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    let weeks_back = (-ordinal - 1) as u32;
+    let day = last_day.day().checked_sub(days_back + weeks_back * 7).filter(|day| *day >= 1)?;
+
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(feature = "serde")]
+mod weekday_by_name {
+    use crate::{weekday_flag_named, weekday_name, WEEKDAY_NAMES};
+
+    pub fn serialize<S: serde::Serializer>(weekday: &chrono::Weekday, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(weekday_name(*weekday))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<chrono::Weekday, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+        weekday_flag_named(&name).and_then(|flag| chrono::Weekday::try_from(flag).ok())
+            .ok_or_else(|| serde::de::Error::unknown_variant(&name, &WEEKDAY_NAMES))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MonthFlags, Repeating, RepeatingDateError, Schedule, WeekdayFlags};
+    use crate::test_util::date;
+
+    #[test]
+    fn nth_weekday_dates_ascending() {
+        let rule = MonthlyBy::nth_weekday(chrono::Weekday::Mon, [3, 1]);
+
+        assert_eq!(vec![date("2024-01-01"), date("2024-01-15")], rule.dates_in(2024, 1));
+        assert_eq!(2, rule.max_per_month());
+    }
+
+    #[test]
+    fn fifth_weekday_only_where_it_exists() {
+        let rule = MonthlyBy::nth_weekday(chrono::Weekday::Thu, [5]);
+
+        assert_eq!(vec![date("2024-02-29")], rule.dates_in(2024, 2));
+        assert!(rule.dates_in(2023, 2).is_empty());
+    }
+
+    #[test]
+    fn counting_from_the_end() {
+        let rule = MonthlyBy::nth_weekday(chrono::Weekday::Fri, [-1, -2, -5]);
+
+        assert_eq!(vec![date("2024-05-03"), date("2024-05-24"), date("2024-05-31")], rule.dates_in(2024, 5));
+        assert_eq!(vec![date("2024-10-18"), date("2024-10-25")], rule.dates_in(2024, 10));
+    }
+
+    #[test]
+    fn fifth_and_last_coincide() {
+        let rule = MonthlyBy::nth_weekday(chrono::Weekday::Fri, [5, -1]);
+
+        assert_eq!(vec![date("2024-05-31")], rule.dates_in(2024, 5));
+        assert_eq!(vec![date("2024-10-25")], rule.dates_in(2024, 10));
+    }
+
+    #[test]
+    fn ordinals_must_be_1_to_5() {
+        assert!(MonthlyBy::nth_weekday(chrono::Weekday::Tue, [1, 5]).is_valid());
+        assert!(MonthlyBy::nth_weekday(chrono::Weekday::Tue, [-1, -5]).is_valid());
+        assert!(!MonthlyBy::nth_weekday(chrono::Weekday::Tue, [-6]).is_valid());
+        assert!(!MonthlyBy::nth_weekday(chrono::Weekday::Tue, [0]).is_valid());
+        assert!(!MonthlyBy::nth_weekday(chrono::Weekday::Tue, [6]).is_valid());
+        assert!(!MonthlyBy::nth_weekday(chrono::Weekday::Tue, []).is_valid());
+    }
+
+    fn nth(start: &str, interval: u64, weekday: chrono::Weekday, ordinals: &[i8]) -> Schedule {
+        Schedule::builder().start(date(start)).monthly().interval(interval).monthly_by(MonthlyBy::nth_weekday(weekday, ordinals.iter().copied())).build().unwrap()
+    }
+
+    #[test]
+    fn second_tuesday_of_every_month() {
+        let schedule = nth("2024-01-09", 1, chrono::Weekday::Tue, &[2]);
+        let dates: Vec<chrono::NaiveDate> = schedule.occurrences(date("2024-01-01")).take(12).collect();
+
+        // February, and October 2024 which starts on a Tuesday.
+        let expected = ["2024-01-09", "2024-02-13", "2024-03-12", "2024-04-09", "2024-05-14", "2024-06-11", "2024-07-09", "2024-08-13", "2024-09-10", "2024-10-08", "2024-11-12", "2024-12-10"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+        assert!(schedule.matches(date("2024-10-08")));
+        assert!(!schedule.matches(date("2024-10-01")));
+        assert_eq!(Some(date("2024-09-10")), schedule.previous_before(date("2024-10-08")));
+    }
+
+    #[test]
+    fn months_without_a_fifth_weekday_are_skipped() {
+        let schedule = nth("2024-02-29", 1, chrono::Weekday::Thu, &[5]);
+
+        assert_eq!(["2024-02-29", "2024-05-30", "2024-08-29"].map(date).to_vec(), schedule.occurrences(date("2024-01-01")).take(3).collect::<Vec<chrono::NaiveDate>>());
+        assert!(!schedule.matches(date("2024-03-28")));
+        assert_eq!(Some(date("2024-02-29")), schedule.previous_before(date("2024-05-30")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2024-08-29")));
+    }
+
+    #[test]
+    fn several_ordinals_with_interval() {
+        let schedule = nth("2024-01-01", 2, chrono::Weekday::Mon, &[1, 3]);
+        let dates: Vec<chrono::NaiveDate> = schedule.occurrences(date("2024-01-01")).take(5).collect();
+
+        assert_eq!(["2024-01-15", "2024-03-04", "2024-03-18", "2024-05-06", "2024-05-20"].map(date).to_vec(), dates);
+        assert!(!schedule.matches(date("2024-02-05")));
+        assert_eq!(Ok(date("2024-05-20")), schedule.nth_occurrence(5));
+    }
+
+    #[test]
+    fn start_after_earlier_picks_in_its_month() {
+        let schedule = nth("2024-01-15", 1, chrono::Weekday::Mon, &[1, 3]);
+
+        assert_eq!(Some(date("2024-01-15")), schedule.first_occurrence());
+        assert!(!schedule.matches(date("2024-01-01")));
+        assert_eq!(Some(0), schedule.occurrence_index(date("2024-01-15")));
+        assert_eq!(Ok(date("2024-02-05")), schedule.nth_occurrence(1));
+    }
+
+    #[test]
+    fn weekday_filter_still_applies() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).monthly().on(WeekdayFlags::MON)
+            .monthly_by(MonthlyBy::nth_weekday(chrono::Weekday::Mon, [1])).build().unwrap();
+
+        assert_eq!(Ok(date("2024-02-05")), schedule.next_after(date("2024-01-01")));
+    }
+
+    #[test]
+    fn builder_validates_the_rule() {
+        let builder = Schedule::builder().start(date("2024-01-09")).monthly();
+
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), builder.clone().monthly_by(MonthlyBy::nth_weekday(chrono::Weekday::Tue, [1])).build());
+        assert_eq!(Err(RepeatingDateError::InvalidMonthlyRule), builder.clone().monthly_by(MonthlyBy::nth_weekday(chrono::Weekday::Tue, [6])).build());
+        assert_eq!(Err(RepeatingDateError::InvalidMonthlyRule), builder.repeat(Repeating::Weekly).monthly_by(MonthlyBy::nth_weekday(chrono::Weekday::Tue, [2])).build());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let schedule = nth("2024-01-01", 2, chrono::Weekday::Mon, &[1, 3]);
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert!(json.contains(r#""monthly_by":{"nth_weekday":{"weekday":"MON","ordinals":[1,3]}}"#), "{}", json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+    }
+
+    #[test]
+    fn last_friday_through_a_year() {
+        let schedule = Schedule::builder().start(date("2024-01-26")).monthly().monthly_by(MonthlyBy::last_weekday(chrono::Weekday::Fri)).build().unwrap();
+        let dates: Vec<chrono::NaiveDate> = schedule.occurrences(date("2024-01-01")).take(14).collect();
+
+        let expected = ["2024-01-26", "2024-02-23", "2024-03-29", "2024-04-26", "2024-05-31", "2024-06-28", "2024-07-26",
+            "2024-08-30", "2024-09-27", "2024-10-25", "2024-11-29", "2024-12-27", "2025-01-31", "2025-02-28"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+        assert!(schedule.matches(date("2024-05-31")));
+        assert!(!schedule.matches(date("2024-05-24")));
+        assert_eq!(Some(date("2024-12-27")), schedule.previous_before(date("2025-01-31")));
+    }
+
+    #[test]
+    fn last_friday_every_third_month() {
+        let schedule = Schedule::builder().start(date("2024-11-29")).monthly().interval(3).monthly_by(MonthlyBy::last_weekday(chrono::Weekday::Fri)).build().unwrap();
+
+        assert_eq!(["2025-02-28", "2025-05-30", "2025-08-29"].map(date).to_vec(), schedule.next_n_dates(date("2024-11-29"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-12-27")));
+        assert_eq!(Some(3), schedule.occurrence_index(date("2025-08-29")));
+    }
+
+    fn last_day(start: &str, interval: u64) -> Schedule {
+        Schedule::builder().start(date(start)).monthly().interval(interval).monthly_by(MonthlyBy::last_day()).build().unwrap()
+    }
+
+    #[test]
+    fn last_day_of_every_month() {
+        let schedule = last_day("2024-01-31", 1);
+        let dates: Vec<chrono::NaiveDate> = schedule.occurrences(date("2024-01-01")).take(14).collect();
+
+        let expected = ["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30", "2024-05-31", "2024-06-30", "2024-07-31",
+            "2024-08-31", "2024-09-30", "2024-10-31", "2024-11-30", "2024-12-31", "2025-01-31", "2025-02-28"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+        assert!(schedule.matches(date("2025-02-28")));
+        assert!(!schedule.matches(date("2024-02-28")));
+        assert_eq!(Some(date("2024-02-29")), schedule.previous_before(date("2024-03-31")));
+    }
+
+    #[test]
+    fn last_day_of_every_third_month() {
+        let schedule = last_day("2023-11-30", 3);
+
+        assert_eq!(["2024-02-29", "2024-05-31", "2024-08-31", "2024-11-30", "2025-02-28"].map(date).to_vec(), schedule.next_n_dates(date("2023-11-30"), 5).unwrap().into_vec());
+        assert_eq!(Ok(date("2025-02-28")), schedule.nth_occurrence(5));
+        assert!(!schedule.matches(date("2024-03-31")));
+    }
+
+    #[test]
+    fn last_day_with_weekday_filter() {
+        let schedule = Schedule::builder().start(date("2024-01-31")).monthly().on(WeekdayFlags::MIDWEEK).monthly_by(MonthlyBy::last_day()).build().unwrap();
+
+        // 2024-03-31 is a Sunday.
+        assert_eq!(["2024-02-29", "2024-04-30", "2024-05-31"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-31"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-03-31")));
+    }
+
+    #[test]
+    fn last_day_requires_a_month_end_start() {
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), Schedule::builder().start(date("2024-02-28")).monthly().monthly_by(MonthlyBy::last_day()).build());
+        assert_eq!(Err(RepeatingDateError::InvalidMonthlyRule), Schedule::builder().start(date("2024-02-29")).monthly().monthly_by(MonthlyBy::days(&[]).unwrap()).build());
+    }
+
+    fn payroll(start: &str, interval: u64, days: &[i8], missing: MissingDay) -> Schedule {
+        let rule = MonthlyBy::days(days).unwrap().with_missing_days(missing);
+
+        Schedule::builder().start(date(start)).monthly().interval(interval).monthly_by(rule).build().unwrap()
+    }
+
+    #[test]
+    fn first_and_fifteenth() {
+        let schedule = payroll("2024-01-01", 1, &[15, 1], MissingDay::Skip);
+
+        assert_eq!(["2024-01-15", "2024-02-01", "2024-02-15", "2024-03-01"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 4).unwrap().into_vec());
+        assert_eq!(Ok(date("2024-02-01")), schedule.next_after(date("2024-01-20")));
+        assert!(schedule.matches(date("2024-03-15")));
+        assert!(!schedule.matches(date("2024-03-14")));
+        assert_eq!(Some(date("2024-02-15")), schedule.previous_before(date("2024-03-01")));
+    }
+
+    #[test]
+    fn both_days_fire_in_every_on_month() {
+        let schedule = payroll("2024-01-15", 2, &[1, 15], MissingDay::Skip);
+
+        assert_eq!(["2024-03-01", "2024-03-15", "2024-05-01", "2024-05-15"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-15"), 4).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-02-01")));
+        assert!(!schedule.matches(date("2024-01-01")));
+        assert_eq!(Some(4), schedule.occurrence_index(date("2024-05-15")));
+    }
+
+    #[test]
+    fn missing_days_skip_or_clamp() {
+        let skipping = payroll("2024-03-15", 1, &[15, 31], MissingDay::Skip);
+        let clamping = payroll("2024-03-15", 1, &[15, 31], MissingDay::Clamp);
+
+        assert_eq!(["2024-03-31", "2024-04-15", "2024-05-15"].map(date).to_vec(), skipping.next_n_dates(date("2024-03-15"), 3).unwrap().into_vec());
+        assert_eq!(["2024-03-31", "2024-04-15", "2024-04-30"].map(date).to_vec(), clamping.next_n_dates(date("2024-03-15"), 3).unwrap().into_vec());
+        assert!(clamping.matches(date("2025-02-28")));
+        assert!(!skipping.matches(date("2025-02-28")));
+    }
+
+    #[test]
+    fn clamped_days_landing_together_occur_once() {
+        let schedule = payroll("2024-01-30", 1, &[30, 31], MissingDay::Clamp);
+
+        assert_eq!(["2024-01-31", "2024-02-29", "2024-03-30", "2024-03-31"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-30"), 4).unwrap().into_vec());
+        assert_eq!(Some(2), schedule.occurrence_index(date("2024-02-29")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn missing_day_policy_serde() {
+        let json = serde_json::to_string(&payroll("2024-01-31", 1, &[31], MissingDay::Clamp)).unwrap();
+
+        assert!(json.contains(r#""monthly_by":{"days":{"days":[31],"missing":"clamp"}}"#), "{}", json);
+        assert_eq!(payroll("2024-01-31", 1, &[31], MissingDay::Clamp), serde_json::from_str::<Schedule>(&json).unwrap());
+        assert!(!serde_json::to_string(&payroll("2024-01-31", 1, &[31], MissingDay::Skip)).unwrap().contains("missing"));
+    }
+
+    #[test]
+    fn days_rejects_invalid_days() {
+        assert_eq!(Err(InvalidMonthDay { value: 32 }), MonthlyBy::days(&[1, 32]));
+        assert_eq!(Err(InvalidMonthDay { value: 0 }), MonthlyBy::days(&[0]));
+    }
+
+    fn thanksgiving(start: &str, interval: u64) -> Schedule {
+        Schedule::builder().start(date(start)).yearly().interval(interval).monthly_by(MonthlyBy::nth_weekday(chrono::Weekday::Thu, [4])).build().unwrap()
+    }
+
+    #[test]
+    fn thanksgiving_through_a_decade() {
+        let schedule = thanksgiving("2020-11-26", 1);
+
+        let expected = ["2021-11-25", "2022-11-24", "2023-11-23", "2024-11-28", "2025-11-27", "2026-11-26", "2027-11-25", "2028-11-23", "2029-11-22", "2030-11-28"];
+        assert_eq!(expected.map(date).to_vec(), schedule.next_n_dates(date("2020-11-26"), 10).unwrap().into_vec());
+        assert_eq!(Ok(date("2024-11-28")), schedule.next_after(date("2024-06-01")));
+        assert_eq!(Ok(date("2025-11-27")), schedule.next_after(date("2024-11-28")));
+        assert_eq!(Some(date("2023-11-23")), schedule.previous_before(date("2024-11-28")));
+        assert!(schedule.matches(date("2028-11-23")));
+        assert!(!schedule.matches(date("2028-11-30")));
+        assert!(!schedule.matches(date("2028-12-28")));
+    }
+
+    #[test]
+    fn yearly_nth_weekday_every_fourth_year() {
+        let schedule = thanksgiving("2020-11-26", 4);
+
+        assert_eq!(["2024-11-28", "2028-11-23"].map(date).to_vec(), schedule.next_n_dates(date("2021-01-01"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2021-11-25")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2028-11-23")));
+    }
+
+    #[test]
+    fn yearly_last_weekday_in_several_months() {
+        let rule = MonthlyBy::last_weekday(chrono::Weekday::Mon);
+        let schedule = Schedule::builder().start(date("2024-05-27")).yearly().months(MonthFlags::MAY | MonthFlags::SEP).monthly_by(rule).build().unwrap();
+
+        assert_eq!(["2024-09-30", "2025-05-26", "2025-09-29"].map(date).to_vec(), schedule.next_n_dates(date("2024-05-27"), 3).unwrap().into_vec());
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), Schedule::builder().start(date("2024-05-20")).yearly().monthly_by(MonthlyBy::last_weekday(chrono::Weekday::Mon)).build());
+    }
+
+    #[test]
+    fn third_to_last_day_per_month() {
+        let schedule = payroll("2024-01-29", 1, &[-3], MissingDay::Skip);
+        let dates = schedule.next_n_dates(date("2024-01-29"), 12).unwrap();
+
+        assert_eq!(date("2024-02-27"), dates[0]);
+        assert_eq!(date("2024-04-28"), dates[2]);
+        assert_eq!(date("2024-12-29"), dates[10]);
+        assert_eq!(date("2025-01-29"), dates[11]);
+        assert_eq!(Ok(date("2025-02-26")), schedule.next_after(date("2025-01-29")));
+        assert!(schedule.matches(date("2028-02-27")));
+        assert!(!schedule.matches(date("2027-02-27")));
+    }
+
+    #[test]
+    fn negative_days_every_other_month() {
+        let schedule = payroll("2023-12-30", 2, &[-2], MissingDay::Skip);
+
+        assert_eq!(["2024-02-28", "2024-04-29", "2024-06-29"].map(date).to_vec(), schedule.next_n_dates(date("2023-12-30"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-03-30")));
+        assert_eq!(Some(date("2024-02-28")), schedule.previous_before(date("2024-04-29")));
+    }
+
+    #[test]
+    fn negative_days_resolve_before_the_weekday_filter() {
+        let rule = MonthlyBy::days(&[-3]).unwrap();
+        let schedule = Schedule::builder().start(date("2024-01-29")).monthly().on(WeekdayFlags::MIDWEEK).monthly_by(rule).build().unwrap();
+
+        // April 28 and December 29, 2024 are Sundays; the filter drops them rather than moving them.
+        assert_eq!(["2024-02-27", "2024-03-29", "2024-05-29"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-29"), 3).unwrap().into_vec());
+        assert_eq!(Ok(date("2025-01-29")), schedule.next_after(date("2024-11-28")));
+    }
+}
@@ -0,0 +1,329 @@
+use std::ops::Range;
+
+use chrono::Datelike;
+
+use crate::{day_after, gcd, get_months_since, week_start_day, weeks_between, MonthDay, Repeating, RepeatingDateError, Schedule, WeekStart};
+
+// Every rule is laid out as numbered slots: candidate dates at fixed steps from the start (the
+// interval's days, months or years; every day of each on week for weekly rules). A slot holds an
+// occurrence when its date exists and is on an allowed weekday. Which slots are occupied repeats
+// with a short period (at most seven slots, a 400 year cycle for monthly and yearly rules), so
+// indices are counted per period instead of by walking the whole series. Sliding and counting
+// daily rules have one slot per occurrence, see `WeekdayPolicy`.
+impl Schedule {
+    fn slot_period(&self) -> u64 {
+        let (repeat, interval) = self.step();
+        let interval = interval as u128;
+
+        match repeat {
+            Repeating::Daily if self.slides() => 1,
+            Repeating::Daily if self.is_expanded() => 7 * self.slots_per_period(),
+            Repeating::Daily if self.weekdays().is_all() || interval.is_multiple_of(7) => 1,
+            Repeating::Weekly if self.is_expanded() => self.slots_per_period(),
+            Repeating::Daily | Repeating::Weekly => 7,
+            Repeating::Monthly | Repeating::Quarterly => (4800 / gcd(interval, 4800)) as u64 * self.slots_per_period(),
+            Repeating::Yearly => (400 / gcd(interval, 400)) as u64 * self.slots_per_period(),
+            Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        }
+    }
+
+    /// Expanded rules give each week, month or year one slot per date it can pick.
+    fn slots_per_period(&self) -> u64 {
+        if self.is_expanded() { self.max_per_period() as u64 } else { 1 }
+    }
+
+    fn slot_date(&self, slot: u64) -> Option<chrono::NaiveDate> {
+        let start_date = self.start_date();
+        let (repeat, interval) = self.step();
+
+        let date = match repeat {
+            _ if self.is_expanded() => {
+                let per_period = self.slots_per_period();
+                let dates = self.period_dates((slot / per_period).checked_mul(interval)?)?;
+
+                dates.get((slot % per_period) as usize).copied().filter(|date| *date >= start_date)?
+            },
+            Repeating::Daily if self.slides() => self.slide_date(slot)?,
+            Repeating::Daily => start_date.checked_add_days(chrono::Days::new(slot.checked_mul(interval)?))?,
+            Repeating::Weekly => {
+                let days = (slot / 7).checked_mul(interval)?.checked_mul(7)?.checked_add(slot % 7)?;
+                let date = day_after(week_start_day(start_date, WeekStart::Monday), days)?;
+
+                if date < start_date {
+                    return None;
+                }
+
+                date
+            },
+            Repeating::Monthly | Repeating::Quarterly => {
+                let months = u32::try_from(slot.checked_mul(interval)?).ok()?;
+                let first_of_month = start_date.with_day(1)?.checked_add_months(chrono::Months::new(months))?;
+
+                MonthDay::of(start_date).resolve(first_of_month.year(), first_of_month.month())?
+            },
+            Repeating::Yearly => {
+                let years = i32::try_from(slot.checked_mul(interval)?).ok()?;
+
+                start_date.with_year(start_date.year().checked_add(years)?)?
+            },
+            Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        };
+
+        Some(date).filter(|date| self.allows(*date))
+    }
+
+    /// The slot whose candidate date would be `date`, whether or not it holds an occurrence.
+    fn slot_of(&self, date: chrono::NaiveDate) -> Option<u64> {
+        let start_date = self.start_date();
+        let (repeat, interval) = self.step();
+
+        if date < start_date {
+            return None;
+        }
+
+        if self.slides() {
+            return self.slide_index(date);
+        }
+
+        let (steps, offset) = match repeat {
+            _ if self.is_expanded() => (self.periods_since(date), 0),
+            Repeating::Daily => ((date - start_date).num_days() as u64, 0),
+            Repeating::Weekly => (weeks_between(start_date, date, WeekStart::Monday) as u64, date.weekday().num_days_from_monday() as u64),
+            Repeating::Monthly | Repeating::Quarterly => (get_months_since(date, start_date) as u64, 0),
+            Repeating::Yearly => ((date.year() - start_date.year()) as u64, 0),
+            Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        };
+
+        if !steps.is_multiple_of(interval) {
+            return None;
+        }
+
+        if self.is_expanded() {
+            let position = self.period_dates(steps)?.iter().position(|candidate| *candidate == date)?;
+
+            return (steps / interval).checked_mul(self.slots_per_period())?.checked_add(position as u64);
+        }
+
+        match repeat {
+            Repeating::Weekly => (steps / interval).checked_mul(7)?.checked_add(offset),
+            _ => Some(steps / interval),
+        }
+    }
+
+    fn occupied_slots(&self, slots: Range<u64>) -> impl Iterator<Item = chrono::NaiveDate> + '_ {
+        slots.filter_map(|slot| self.slot_date(slot))
+    }
+
+    /// Number of occurrences in the slots before `slot`.
+    fn occurrences_before_slot(&self, slot: u64) -> u64 {
+        let period = self.slot_period();
+
+        if slot <= period * 2 {
+            return self.occupied_slots(0..slot).count() as u64;
+        }
+
+        // The first period can differ from the others: weekly rules drop the days before the start.
+        let first = self.occupied_slots(0..period).count() as u64;
+        let per_period = self.occupied_slots(period..period * 2).count() as u64;
+        let periods = slot / period;
+
+        first + (periods - 1) * per_period + self.occupied_slots(periods * period..slot).count() as u64
+    }
+
+    /// Zero based position of `date` in the series, ignoring `until` and `count`.
+    pub(crate) fn index_unbounded(&self, date: chrono::NaiveDate) -> Option<u64> {
+        let slot = self.slot_of(date)?;
+
+        if self.slot_date(slot) != Some(date) {
+            return None;
+        }
+
+        Some(self.occurrences_before_slot(slot))
+    }
+
+    /// The occurrence at zero based position `index`, ignoring `until` and `count`.
+    pub(crate) fn nth_unbounded(&self, index: u64) -> Option<chrono::NaiveDate> {
+        let period = self.slot_period();
+        let first = self.occupied_slots(0..period).count() as u64;
+
+        if index < first {
+            return self.occupied_slots(0..period).nth(index as usize);
+        }
+
+        let per_period = self.occupied_slots(period..period.checked_mul(2)?).count() as u64;
+
+        if per_period == 0 {
+            return None;
+        }
+
+        let remaining = index - first;
+        let base = (remaining / per_period + 1).checked_mul(period)?;
+
+        self.occupied_slots(base..base.checked_add(period)?).nth((remaining % per_period) as usize)
+    }
+
+    /// Number of dates up to `date` inclusive that the rule generates, ignoring `until` and
+    /// `count`.
+    fn generated_through(&self, date: chrono::NaiveDate) -> u64 {
+        let last = match self.index_unbounded(date) {
+            Some(index) => return index + 1,
+            None => self.previous_unbounded(date),
+        };
+
+        last.and_then(|last| self.index_unbounded(last)).map_or(0, |index| index + 1)
+    }
+
+    /// Number of occurrences from `start` to `end`, both inclusive, extra dates included.
+    pub(crate) fn count_between(&self, start: chrono::NaiveDate, end: chrono::NaiveDate) -> u64 {
+        if end < start {
+            return 0;
+        }
+
+        let rule_end = self.last_generated().map_or(end, |last| last.min(end));
+        let is_generated = |date: chrono::NaiveDate| date <= rule_end && self.index_unbounded(date).is_some();
+
+        let (generated, excluded) = if rule_end < start {
+            (0, 0)
+        } else {
+            let before = start.pred_opt().map_or(0, |day_before| self.generated_through(day_before));
+            let excluded = self.exclusions().range(start..=rule_end).filter(|date| is_generated(**date)).count();
+
+            (self.generated_through(rule_end) - before, excluded as u64)
+        };
+
+        let extra = self.extra_dates().range(start..=end).filter(|date| !self.is_excluded(**date) && !is_generated(**date)).count();
+
+        generated - excluded + extra as u64
+    }
+
+    /// Zero based position of `date` in the series, the start being 0; `None` when `date` is not
+    /// an occurrence, is excluded or comes after the schedule's end. Excluded dates keep their
+    /// position, so the dates after them are not renumbered.
+    pub fn occurrence_index(&self, date: chrono::NaiveDate) -> Option<u64> {
+        self.index_unbounded(date).filter(|_| !self.is_past_end(date) && !self.is_excluded(date))
+    }
+
+    /// The occurrence at zero based position `index`, the start being 0.
+    ///
+    /// Computed from the slot layout rather than by stepping through the series: daily and weekly
+    /// rules repeat every seven slots, so only a couple of weeks are inspected. Monthly and yearly
+    /// rules with a weekday filter have no closed form and count their slots over one 400 year cycle
+    /// (at most 4800 candidate dates) instead. Fails with `ScheduleEnded` past the schedule's end,
+    /// `NoOccurrenceFound` when that occurrence is excluded and `DateOutOfRange` when it would fall
+    /// after `NaiveDate::MAX`.
+    pub fn nth_occurrence(&self, index: u64) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        if self.count().is_some_and(|count| index >= count as u64) {
+            return Err(RepeatingDateError::ScheduleEnded);
+        }
+
+        let date = self.nth_unbounded(index).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+        if self.is_past_end(date) {
+            return Err(RepeatingDateError::ScheduleEnded);
+        }
+
+        if self.is_excluded(date) {
+            return Err(RepeatingDateError::NoOccurrenceFound);
+        }
+
+        Ok(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::WeekdayFlags;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    #[rstest]
+    #[case::daily(Repeating::Daily, 1, WeekdayFlags::ANY)]
+    #[case::daily_stretched(Repeating::Daily, 3, WeekdayFlags::TUE | WeekdayFlags::THU | WeekdayFlags::SAT)]
+    #[case::daily_multiple_of_7(Repeating::Daily, 14, WeekdayFlags::TUE | WeekdayFlags::FRI)]
+    #[case::weekly(Repeating::Weekly, 1, WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::weekly_mid_week_start(Repeating::Weekly, 3, WeekdayFlags::MON | WeekdayFlags::TUE | WeekdayFlags::SUN)]
+    #[case::weekly_same_day(Repeating::Weekly, 2, WeekdayFlags::TUE)]
+    #[case::monthly(Repeating::Monthly, 1, WeekdayFlags::ANY)]
+    #[case::monthly_filtered(Repeating::Monthly, 5, WeekdayFlags::TUE | WeekdayFlags::WED)]
+    #[case::yearly(Repeating::Yearly, 1, WeekdayFlags::ANY)]
+    #[case::yearly_filtered(Repeating::Yearly, 3, WeekdayFlags::MIDWEEK)]
+    fn index_and_nth_agree_with_iteration(#[case] repeat: Repeating, #[case] interval: u64, #[case] weekdays: WeekdayFlags) {
+        for start in [date("2023-09-12"), date("2024-01-31"), date("2024-02-29")] {
+            let Ok(schedule) = Schedule::new(start, weekdays, repeat, interval) else { continue };
+            let mut current = start;
+
+            for index in 0..60 {
+                assert_eq!(Some(index), schedule.occurrence_index(current), "{} {}", schedule, current);
+                assert_eq!(Ok(current), schedule.nth_occurrence(index), "{} {}", schedule, index);
+
+                let next = schedule.next_after(current).unwrap();
+
+                for skipped in current.iter_days().skip(1).take_while(|day| *day < next) {
+                    assert_eq!(None, schedule.occurrence_index(skipped), "{} {}", schedule, skipped);
+                }
+
+                current = next;
+            }
+        }
+    }
+
+    #[rstest]
+    fn kth_yielded_date_has_index_k(#[values(Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Quarterly, Repeating::Yearly)] repeat: Repeating, #[values(1, 2, 3, 7)] interval: u64) {
+        let starts = date("2023-12-25").iter_days().take(7);
+
+        for (start, bits) in starts.flat_map(|start| (1..=0x7f).map(move |bits| (start, bits))) {
+            let Ok(schedule) = Schedule::new(start, WeekdayFlags::from_bits_masked(bits), repeat, interval) else { continue };
+
+            for (k, date) in schedule.occurrences(start.pred_opt().unwrap()).take(15).enumerate() {
+                assert_eq!(Some(k as u64), schedule.occurrence_index(date), "{} {}", schedule, date);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::daily("daily;interval=1;start=2023-09-12", 1_000_000, "4761-08-09")]
+    #[case::weekly("weekly;interval=2;days=TUE,THU;start=2023-09-12", 1041, "2043-08-20")]
+    #[case::day_31("monthly;interval=1;start=2024-01-31", 6, "2024-12-31")]
+    #[case::leap_day("yearly;interval=1;start=2024-02-29", 24, "2124-02-29")]
+    fn nth_far_away(#[case] rule: &str, #[case] index: u64, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+
+        assert_eq!(Ok(date(expected)), schedule.nth_occurrence(index));
+        assert_eq!(Some(index), schedule.occurrence_index(date(expected)));
+    }
+
+    #[test]
+    fn before_start_has_no_index() {
+        let schedule = Schedule::from_str("daily;start=2023-09-12").unwrap();
+
+        assert_eq!(None, schedule.occurrence_index(date("2023-09-11")));
+        assert_eq!(Some(0), schedule.occurrence_index(date("2023-09-12")));
+    }
+
+    #[test]
+    fn nth_past_the_date_range() {
+        let daily = Schedule::from_str("daily;start=2023-09-12").unwrap();
+        let yearly = Schedule::from_str("yearly;interval=1000;start=2023-09-12").unwrap();
+
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), daily.nth_occurrence(u64::MAX));
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), daily.nth_occurrence(1_000_000_000));
+        assert_eq!(Ok(date("2023-09-12")), yearly.nth_occurrence(0));
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), yearly.nth_occurrence(300));
+    }
+
+    #[test]
+    fn nth_respects_end_and_exclusions() {
+        let schedule = Schedule::from_str("weekly;days=TUE,THU;start=2023-09-12;count=4;exdate=2023-09-19").unwrap();
+        let until = Schedule::from_str("monthly;start=2023-09-12;until=2023-11-30").unwrap();
+
+        assert_eq!(Ok(date("2023-09-14")), schedule.nth_occurrence(1));
+        assert_eq!(Err(RepeatingDateError::NoOccurrenceFound), schedule.nth_occurrence(2));
+        assert_eq!(Ok(date("2023-09-21")), schedule.nth_occurrence(3));
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.nth_occurrence(4));
+        assert_eq!(Ok(date("2023-11-12")), until.nth_occurrence(2));
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), until.nth_occurrence(3));
+    }
+}
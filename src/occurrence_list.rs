@@ -0,0 +1,236 @@
+use std::cmp::Ordering;
+
+/// Occurrence dates that are guaranteed to be sorted ascending and free of duplicates.
+///
+/// Outside of this crate the only way to build one is the checked `try_from_vec` (or the set
+/// operations on existing lists), so binary searches over it are always sound.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct OccurrenceList(Vec<chrono::NaiveDate>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccurrenceListError {
+    /// The date at `index` comes before the one preceding it.
+    Unsorted { index: usize },
+    /// The date at `index` repeats the one preceding it.
+    Duplicate { index: usize }
+}
+
+impl std::fmt::Display for OccurrenceListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OccurrenceListError::Unsorted { index } => write!(f, "occurrence at index {} is earlier than the previous one", index),
+            OccurrenceListError::Duplicate { index } => write!(f, "occurrence at index {} duplicates the previous one", index),
+        }
+    }
+}
+
+impl std::error::Error for OccurrenceListError {}
+
+impl OccurrenceList {
+    pub fn new() -> OccurrenceList {
+        OccurrenceList(vec![])
+    }
+
+    pub fn try_from_vec(dates: Vec<chrono::NaiveDate>) -> Result<OccurrenceList, OccurrenceListError> {
+        for index in 1..dates.len() {
+            match dates[index].cmp(&dates[index - 1]) {
+                Ordering::Less => return Err(OccurrenceListError::Unsorted { index }),
+                Ordering::Equal => return Err(OccurrenceListError::Duplicate { index }),
+                Ordering::Greater => {}
+            }
+        }
+
+        Ok(OccurrenceList(dates))
+    }
+
+    /// For dates produced in ascending order without repeats, like a schedule's occurrences.
+    pub(crate) fn from_sorted_unchecked(dates: Vec<chrono::NaiveDate>) -> OccurrenceList {
+        debug_assert!(dates.windows(2).all(|pair| pair[0] < pair[1]), "unsorted occurrences {:?}", dates);
+
+        OccurrenceList(dates)
+    }
+
+    pub fn as_slice(&self) -> &[chrono::NaiveDate] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<chrono::NaiveDate> {
+        self.0
+    }
+
+    /// Binary search membership check.
+    pub fn contains(&self, date: &chrono::NaiveDate) -> bool {
+        self.0.binary_search(date).is_ok()
+    }
+
+    /// Dates present in both lists.
+    pub fn intersect(&self, other: &OccurrenceList) -> OccurrenceList {
+        self.merge(other, true, false, false)
+    }
+
+    /// Dates present in either list.
+    pub fn union(&self, other: &OccurrenceList) -> OccurrenceList {
+        self.merge(other, true, true, true)
+    }
+
+    /// Dates present in `self` but not in `other`.
+    pub fn difference(&self, other: &OccurrenceList) -> OccurrenceList {
+        self.merge(other, false, true, false)
+    }
+
+    fn merge(&self, other: &OccurrenceList, keep_both: bool, keep_left: bool, keep_right: bool) -> OccurrenceList {
+        let mut result = Vec::with_capacity(self.len().max(other.len()));
+
+        let mut left = self.0.iter().peekable();
+        let mut right = other.0.iter().peekable();
+
+        loop {
+            match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) => match l.cmp(r) {
+                    Ordering::Less => {
+                        if keep_left { result.push(**l); }
+                        left.next();
+                    },
+                    Ordering::Greater => {
+                        if keep_right { result.push(**r); }
+                        right.next();
+                    },
+                    Ordering::Equal => {
+                        if keep_both { result.push(**l); }
+                        left.next();
+                        right.next();
+                    },
+                },
+                (Some(l), None) => {
+                    if !keep_left { break; }
+                    result.push(**l);
+                    left.next();
+                },
+                (None, Some(r)) => {
+                    if !keep_right { break; }
+                    result.push(**r);
+                    right.next();
+                },
+                (None, None) => break,
+            }
+        }
+
+        OccurrenceList(result)
+    }
+}
+
+impl std::ops::Deref for OccurrenceList {
+    type Target = [chrono::NaiveDate];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[chrono::NaiveDate]> for OccurrenceList {
+    fn as_ref(&self) -> &[chrono::NaiveDate] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<chrono::NaiveDate>> for OccurrenceList {
+    type Error = OccurrenceListError;
+
+    fn try_from(dates: Vec<chrono::NaiveDate>) -> Result<Self, Self::Error> {
+        OccurrenceList::try_from_vec(dates)
+    }
+}
+
+impl From<OccurrenceList> for Vec<chrono::NaiveDate> {
+    fn from(list: OccurrenceList) -> Self {
+        list.0
+    }
+}
+
+impl IntoIterator for OccurrenceList {
+    type Item = chrono::NaiveDate;
+    type IntoIter = std::vec::IntoIter<chrono::NaiveDate>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a OccurrenceList {
+    type Item = &'a chrono::NaiveDate;
+    type IntoIter = std::slice::Iter<'a, chrono::NaiveDate>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use super::*;
+    use crate::test_util::date;
+
+    /// Small deterministic generator so the set operation checks are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn dates(&mut self) -> BTreeSet<chrono::NaiveDate> {
+            let base = date("2024-01-01");
+            let len = self.next() % 40;
+
+            (0..len).map(|_| base + chrono::Days::new(self.next() % 60)).collect()
+        }
+    }
+
+    fn list(set: &BTreeSet<chrono::NaiveDate>) -> OccurrenceList {
+        OccurrenceList::try_from_vec(set.iter().copied().collect()).unwrap()
+    }
+
+    #[test]
+    fn try_from_vec_validates_order_and_uniqueness() {
+        assert!(OccurrenceList::try_from_vec(vec![]).unwrap().is_empty());
+        assert_eq!(2, OccurrenceList::try_from_vec(vec![date("2024-01-01"), date("2024-01-02")]).unwrap().len());
+
+        assert_eq!(Err(OccurrenceListError::Unsorted { index: 2 }),
+            OccurrenceList::try_from_vec(vec![date("2024-01-01"), date("2024-01-03"), date("2024-01-02")]));
+        assert_eq!(Err(OccurrenceListError::Duplicate { index: 1 }),
+            OccurrenceList::try_from_vec(vec![date("2024-01-01"), date("2024-01-01")]));
+    }
+
+    #[test]
+    fn derefs_to_slice() {
+        let list = OccurrenceList::try_from_vec(vec![date("2024-01-01"), date("2024-02-01")]).unwrap();
+
+        assert_eq!(Some(&date("2024-02-01")), list.last());
+        assert_eq!(&[date("2024-01-01"), date("2024-02-01")], &list[..]);
+        assert!(list.contains(&date("2024-01-01")));
+        assert!(!list.contains(&date("2024-01-15")));
+    }
+
+    #[test]
+    fn set_operations_agree_with_btreeset() {
+        let mut rng = Lcg(0x5eed);
+
+        for _ in 0..500 {
+            let a = rng.dates();
+            let b = rng.dates();
+
+            let (list_a, list_b) = (list(&a), list(&b));
+
+            assert_eq!(list(&a.intersection(&b).copied().collect()), list_a.intersect(&list_b));
+            assert_eq!(list(&a.union(&b).copied().collect()), list_a.union(&list_b));
+            assert_eq!(list(&a.difference(&b).copied().collect()), list_a.difference(&list_b));
+            assert_eq!(list(&b.difference(&a).copied().collect()), list_b.difference(&list_a));
+
+            for probe in rng.dates() {
+                assert_eq!(a.contains(&probe), list_a.contains(&probe));
+            }
+        }
+    }
+}
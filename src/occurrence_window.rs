@@ -0,0 +1,110 @@
+use crate::{RepeatingDateError, Schedule};
+
+/// A schedule whose occurrences last for `duration` from each of their datetimes (see
+/// `Schedule::datetimes`), e.g. a two hour meeting. A window includes its start and excludes its
+/// end, and may run past midnight into a day the schedule does not fall on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccurrenceWindow {
+    schedule: Schedule,
+    duration: chrono::TimeDelta
+}
+
+impl OccurrenceWindow {
+    /// Fails with `RepeatingDateError::InvalidDuration` unless `duration` is positive.
+    pub fn new(schedule: Schedule, duration: chrono::TimeDelta) -> Result<OccurrenceWindow, RepeatingDateError> {
+        if duration <= chrono::TimeDelta::zero() {
+            return Err(RepeatingDateError::InvalidDuration);
+        }
+
+        Ok(OccurrenceWindow { schedule, duration })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn duration(&self) -> chrono::TimeDelta {
+        self.duration
+    }
+
+    fn window_from(&self, start: chrono::NaiveDateTime) -> (chrono::NaiveDateTime, chrono::NaiveDateTime) {
+        (start, start.checked_add_signed(self.duration).unwrap_or(chrono::NaiveDateTime::MAX))
+    }
+
+    /// The window `datetime` falls in, as its start and end. Windows all last as long, so the one
+    /// starting last at or before `datetime` is the only candidate.
+    pub fn occurrence_containing(&self, datetime: chrono::NaiveDateTime) -> Option<(chrono::NaiveDateTime, chrono::NaiveDateTime)> {
+        let start = if self.schedule.matches_datetime(datetime) { Some(datetime) } else { self.schedule.previous_datetime_before(datetime) };
+
+        start.map(|start| self.window_from(start)).filter(|(_, end)| datetime < *end)
+    }
+
+    /// Every window overlapping `from..to`, in ascending order, including one already running at
+    /// `from`.
+    pub fn windows_between(&self, from: chrono::NaiveDateTime, to: chrono::NaiveDateTime) -> Vec<(chrono::NaiveDateTime, chrono::NaiveDateTime)> {
+        if from >= to {
+            return vec![];
+        }
+
+        let running = self.occurrence_containing(from);
+        let later = self.schedule.datetimes(from).take_while(|start| *start < to).map(|start| self.window_from(start));
+
+        running.into_iter().chain(later).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{TimesOfDay, WeekdayFlags};
+    use rstest::rstest;
+
+    fn datetime(value: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_str(value).unwrap()
+    }
+
+    fn weekly_at(start: &str, weekday: WeekdayFlags, time: &str, hours: i64) -> OccurrenceWindow {
+        let times = TimesOfDay::new([chrono::NaiveTime::from_str(time).unwrap()]).unwrap();
+        let schedule = Schedule::builder().start(chrono::NaiveDate::from_str(start).unwrap()).weekly().on(weekday).times_of_day(times).build().unwrap();
+
+        OccurrenceWindow::new(schedule, chrono::TimeDelta::hours(hours)).unwrap()
+    }
+
+    fn two_hour_meeting() -> OccurrenceWindow {
+        weekly_at("2024-03-07", WeekdayFlags::THU, "14:00", 2)
+    }
+
+    #[rstest]
+    #[case::inside("2024-03-14T15:00:00", Some(("2024-03-14T14:00:00", "2024-03-14T16:00:00")))]
+    #[case::at_the_start("2024-03-14T14:00:00", Some(("2024-03-14T14:00:00", "2024-03-14T16:00:00")))]
+    #[case::at_the_end("2024-03-14T16:00:00", None)]
+    #[case::before("2024-03-14T13:59:59", None)]
+    #[case::before_the_first("2024-03-01T15:00:00", None)]
+    fn containing(#[case] query: &str, #[case] expected: Option<(&str, &str)>) {
+        assert_eq!(expected.map(|(start, end)| (datetime(start), datetime(end))), two_hour_meeting().occurrence_containing(datetime(query)));
+    }
+
+    #[test]
+    fn windows_spanning_midnight() {
+        // Friday 23:00 for three hours runs into Saturday, which the schedule does not fall on.
+        let late_shift = weekly_at("2024-03-08", WeekdayFlags::FRI, "23:00", 3);
+        let first = (datetime("2024-03-08T23:00:00"), datetime("2024-03-09T02:00:00"));
+        let second = (datetime("2024-03-15T23:00:00"), datetime("2024-03-16T02:00:00"));
+
+        assert_eq!(Some(first), late_shift.occurrence_containing(datetime("2024-03-09T01:00:00")));
+        assert_eq!(None, late_shift.occurrence_containing(datetime("2024-03-09T02:00:00")));
+        assert_eq!(vec![first, second], late_shift.windows_between(datetime("2024-03-09T00:00:00"), datetime("2024-03-16T00:00:00")));
+        assert_eq!(vec![second], late_shift.windows_between(datetime("2024-03-09T02:00:00"), datetime("2024-03-16T02:00:00")));
+        assert!(late_shift.windows_between(datetime("2024-03-10T00:00:00"), datetime("2024-03-10T00:00:00")).is_empty());
+    }
+
+    #[test]
+    fn rejects_non_positive_durations() {
+        let schedule = two_hour_meeting().schedule().clone();
+
+        assert_eq!(Err(RepeatingDateError::InvalidDuration), OccurrenceWindow::new(schedule.clone(), chrono::TimeDelta::zero()));
+        assert_eq!(Err(RepeatingDateError::InvalidDuration), OccurrenceWindow::new(schedule, chrono::TimeDelta::minutes(-30)));
+    }
+}
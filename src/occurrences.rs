@@ -0,0 +1,363 @@
+use crate::{OccurrenceList, RepeatingDateError, Schedule};
+
+impl Schedule {
+    /// The occurrences strictly after `from_date` in ascending order, starting with the first
+    /// occurrence when `from_date` is before the start; ends with the schedule.
+    pub fn occurrences(&self, from_date: chrono::NaiveDate) -> Occurrences<'_> {
+        Occurrences { schedule: self, from_date: Some(from_date), done: false }
+    }
+
+    /// The `n` occurrences strictly after `from_date`, each searched from the previous one. A schedule
+    /// that ends first gives a shorter list; `from_date` before the start fails like `next_after`.
+    pub fn next_n_dates(&self, from_date: chrono::NaiveDate, n: usize) -> Result<OccurrenceList, RepeatingDateError> {
+        let mut dates = vec![];
+        let mut current = from_date;
+
+        while dates.len() < n {
+            match self.next_after(current) {
+                Ok(date) => {
+                    dates.push(date);
+                    current = date;
+                },
+                Err(RepeatingDateError::ScheduleEnded) => break,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(OccurrenceList::from_sorted_unchecked(dates))
+    }
+
+    /// Every occurrence from `start` to `end`, both inclusive, in ascending order. A range that
+    /// begins before the schedule's start is clamped to it; an empty range gives an empty list.
+    pub fn occurrences_between(&self, start: chrono::NaiveDate, end: chrono::NaiveDate) -> OccurrenceList {
+        OccurrenceList::from_sorted_unchecked(self.occurrences_in(start, end).collect())
+    }
+
+    /// Iterator form of `occurrences_between`, which knows its exact length up front.
+    pub fn occurrences_in(&self, start: chrono::NaiveDate, end: chrono::NaiveDate) -> BoundedOccurrences<'_> {
+        // Stepping on from an occurrence rather than from the day before `start` keeps the search on
+        // the rule's grid.
+        let occurrences = Occurrences { schedule: self, from_date: self.previous_before(start), done: false };
+        let remaining = usize::try_from(self.count_between(start, end)).unwrap_or(usize::MAX);
+
+        BoundedOccurrences { occurrences, end, remaining }
+    }
+
+    /// The occurrences strictly before `from_date` in descending order, ending with the first
+    /// occurrence; see `previous_before`.
+    pub fn occurrences_before(&self, from_date: chrono::NaiveDate) -> PastOccurrences<'_> {
+        PastOccurrences { schedule: self, from_date: Some(from_date) }
+    }
+}
+
+/// Iterator over a schedule's occurrences, see `Schedule::occurrences`.
+#[derive(Debug, Clone)]
+pub struct Occurrences<'a> {
+    schedule: &'a Schedule,
+    /// The previous date yielded, `None` before the first occurrence.
+    from_date: Option<chrono::NaiveDate>,
+    done: bool
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = match self.from_date {
+            Some(from_date) => self.schedule.next_or_first(from_date),
+            None => self.schedule.first_occurrence().ok_or(RepeatingDateError::ScheduleEnded),
+        };
+
+        match next {
+            Ok(date) => {
+                self.from_date = Some(date);
+                Some(date)
+            },
+            Err(_) => {
+                self.done = true;
+                None
+            },
+        }
+    }
+
+    /// Exact for schedules with an end, unknown for open ended ones.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        let Some(last) = self.schedule.last_occurrence() else { return (0, None) };
+        let start = match self.from_date {
+            Some(from_date) => match from_date.succ_opt() {
+                Some(start) => start,
+                None => return (0, Some(0)),
+            },
+            None => chrono::NaiveDate::MIN,
+        };
+
+        let remaining = usize::try_from(self.schedule.count_between(start, last)).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+impl std::iter::FusedIterator for Occurrences<'_> {}
+
+/// Iterator over a schedule's occurrences within a date range, see `Schedule::occurrences_in`.
+#[derive(Debug, Clone)]
+pub struct BoundedOccurrences<'a> {
+    occurrences: Occurrences<'a>,
+    end: chrono::NaiveDate,
+    remaining: usize
+}
+
+impl Iterator for BoundedOccurrences<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = self.end;
+
+        match self.occurrences.next().filter(|date| *date <= end) {
+            Some(date) => {
+                self.remaining = self.remaining.saturating_sub(1);
+                Some(date)
+            },
+            None => {
+                self.occurrences.done = true;
+                self.remaining = 0;
+                None
+            },
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for BoundedOccurrences<'_> {}
+
+impl std::iter::FusedIterator for BoundedOccurrences<'_> {}
+
+/// Iterator over a schedule's earlier occurrences, latest first, see `Schedule::occurrences_before`.
+#[derive(Debug, Clone)]
+pub struct PastOccurrences<'a> {
+    schedule: &'a Schedule,
+    /// The previous date yielded, `None` once the first occurrence has been passed.
+    from_date: Option<chrono::NaiveDate>
+}
+
+impl Iterator for PastOccurrences<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.from_date = self.schedule.previous_before(self.from_date?);
+        self.from_date
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.from_date.and_then(|from_date| from_date.pred_opt())
+            .map_or(0, |end| self.schedule.count_between(chrono::NaiveDate::MIN, end));
+
+        let remaining = usize::try_from(remaining).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PastOccurrences<'_> {}
+
+impl std::iter::FusedIterator for PastOccurrences<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use chrono::Datelike;
+
+    use super::*;
+    use crate::{Repeating, WeekdayFlags};
+    use rstest::rstest;
+    use crate::test_util::{date, dates};
+
+    #[rstest]
+    #[case::daily("daily;interval=3;days=TUE,THU,SAT;start=2023-09-12")]
+    #[case::weekly("weekly;interval=2;days=MON,WED,SUN;start=2023-09-13")]
+    #[case::monthly("monthly;interval=5;days=TUE,WED;start=2024-01-31")]
+    #[case::yearly("yearly;days=MON,TUE,WED,THU,FRI;start=2024-02-29")]
+    fn strictly_increasing_on_allowed_weekdays(#[case] rule: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let dates: Vec<chrono::NaiveDate> = schedule.occurrences(date("2020-01-01")).take(40).collect();
+
+        assert_eq!(40, dates.len());
+        assert_eq!(schedule.start_date(), dates[0]);
+        assert!(dates.windows(2).all(|pair| pair[0] < pair[1]), "{}", schedule);
+        assert!(dates.iter().all(|date| schedule.weekdays().contains(WeekdayFlags::from_weekday(date.weekday()))), "{}", schedule);
+    }
+
+    #[test]
+    fn starts_strictly_after_from() {
+        let schedule = Schedule::from_str("weekly;days=TUE,THU;start=2023-09-12").unwrap();
+
+        assert_eq!(Some(date("2023-09-14")), schedule.occurrences(date("2023-09-12")).next());
+        assert_eq!(Some(date("2023-09-19")), schedule.occurrences(date("2023-09-15")).next());
+    }
+
+    #[test]
+    fn fused_after_the_end() {
+        let schedule = Schedule::from_str("daily;start=2023-09-12;count=3").unwrap();
+        let mut occurrences = schedule.occurrences(date("2023-09-01"));
+
+        assert_eq!(["2023-09-12", "2023-09-13", "2023-09-14"].map(date).to_vec(), occurrences.by_ref().collect::<Vec<chrono::NaiveDate>>());
+        assert_eq!(None, occurrences.next());
+        assert_eq!(None, occurrences.next());
+    }
+
+    #[test]
+    fn clones_continue_independently() {
+        let schedule = Schedule::from_str("monthly;start=2024-01-31").unwrap();
+        let mut occurrences = schedule.occurrences(date("2024-01-31"));
+
+        assert_eq!(Some(date("2024-03-31")), occurrences.next());
+
+        let mut copy = occurrences.clone();
+        assert_eq!(Some(date("2024-05-31")), occurrences.next());
+        assert_eq!(Some(date("2024-05-31")), copy.next());
+    }
+
+    #[rstest]
+    #[case::daily(Repeating::Daily, 2, WeekdayFlags::ANY, "2024-03-01", "2024-03-07", &["2024-03-01", "2024-03-03", "2024-03-05", "2024-03-07"])]
+    #[case::weekly(Repeating::Weekly, 1, WeekdayFlags::WED, "2024-03-06", "2024-03-20", &["2024-03-06", "2024-03-13", "2024-03-20"])]
+    #[case::monthly(Repeating::Monthly, 1, WeekdayFlags::ANY, "2024-03-01", "2024-05-01", &["2024-03-01", "2024-04-01", "2024-05-01"])]
+    #[case::yearly(Repeating::Yearly, 1, WeekdayFlags::ANY, "2024-03-01", "2026-03-01", &["2024-03-01", "2025-03-01", "2026-03-01"])]
+    fn between_includes_both_bounds(#[case] repeat: Repeating, #[case] interval: u64, #[case] weekdays: WeekdayFlags, #[case] start: &str, #[case] end: &str, #[case] expected: &[&str]) {
+        let schedule = Schedule::new(date("2023-03-01"), weekdays, repeat, interval).unwrap();
+        let expected = dates(expected);
+
+        // Starting exactly on an occurrence, then ending exactly on one.
+        assert_eq!(expected, schedule.occurrences_between(date(start), date(end)).into_vec());
+        assert_eq!(expected[1..], schedule.occurrences_between(date(start).succ_opt().unwrap(), date(end)).into_vec());
+        assert_eq!(expected[..expected.len() - 1], schedule.occurrences_between(date(start), date(end).pred_opt().unwrap()).into_vec());
+    }
+
+    #[test]
+    fn between_clamps_to_the_start() {
+        let schedule = Schedule::from_str("weekly;days=TUE,THU;start=2024-03-05").unwrap();
+
+        assert_eq!(["2024-03-05", "2024-03-07", "2024-03-12"].map(date).to_vec(), schedule.occurrences_between(date("2024-02-01"), date("2024-03-12")).into_vec());
+        assert!(schedule.occurrences_between(date("2024-01-01"), date("2024-02-29")).is_empty());
+    }
+
+    #[test]
+    fn between_without_occurrences() {
+        let schedule = Schedule::from_str("monthly;start=2024-01-31").unwrap();
+
+        assert!(schedule.occurrences_between(date("2024-04-01"), date("2024-04-30")).is_empty());
+        assert!(schedule.occurrences_between(date("2024-03-31"), date("2024-03-01")).is_empty());
+        assert_eq!(vec![date("2024-03-31")], schedule.occurrences_between(date("2024-03-01"), date("2024-03-31")).into_vec());
+    }
+
+    #[rstest]
+    #[case::daily("daily;interval=3;days=TUE,THU,SAT;start=2023-09-12")]
+    #[case::weekly("weekly;interval=2;days=MON,WED,SUN;start=2023-09-13")]
+    #[case::monthly("monthly;interval=5;days=TUE,WED;start=2024-01-31")]
+    #[case::monthly_day_31("monthly;start=2023-08-31")]
+    #[case::yearly("yearly;start=2024-02-29")]
+    #[case::bounded("weekly;days=TUE,THU;start=2023-09-12;count=7;exdate=2023-09-19;rdate=2023-12-25")]
+    fn reverse_matches_forward(#[case] rule: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let forward: Vec<chrono::NaiveDate> = schedule.occurrences(date("2000-01-01")).take(25).collect();
+        let end = forward.last().unwrap().succ_opt().unwrap();
+
+        let mut backward: Vec<chrono::NaiveDate> = schedule.occurrences_before(end).collect();
+        assert!(backward.windows(2).all(|pair| pair[0] > pair[1]), "{}", schedule);
+
+        backward.reverse();
+        assert_eq!(forward, backward, "{}", schedule);
+    }
+
+    #[test]
+    fn before_stops_at_the_start() {
+        let schedule = Schedule::from_str("weekly;days=TUE,THU;start=2023-09-12").unwrap();
+        let mut past = schedule.occurrences_before(date("2023-09-26"));
+
+        assert_eq!(["2023-09-21", "2023-09-19", "2023-09-14", "2023-09-12"].map(date).to_vec(), past.by_ref().collect::<Vec<chrono::NaiveDate>>());
+        assert_eq!(None, past.next());
+        assert_eq!(None, schedule.occurrences_before(date("2023-09-12")).next());
+        assert_eq!(vec![date("2023-09-21"), date("2023-09-19")], schedule.occurrences_before(date("2023-09-22")).take(2).collect::<Vec<chrono::NaiveDate>>());
+    }
+
+    #[test]
+    fn next_n_dates_stops_with_the_schedule() {
+        let schedule = Schedule::from_str("weekly;days=TUE,THU;start=2023-09-12;count=5").unwrap();
+
+        assert_eq!(Ok(["2023-09-14", "2023-09-19"].map(date).to_vec()), schedule.next_n_dates(date("2023-09-12"), 2).map(Vec::from));
+        assert_eq!(Ok(["2023-09-19", "2023-09-21", "2023-09-26"].map(date).to_vec()), schedule.next_n_dates(date("2023-09-15"), 10).map(Vec::from));
+        assert_eq!(Ok(vec![]), schedule.next_n_dates(date("2023-09-12"), 0).map(Vec::from));
+        assert_eq!(Ok(vec![]), schedule.next_n_dates(date("2023-09-26"), 3).map(Vec::from));
+        assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: date("2023-09-01"), start: date("2023-09-12") }), schedule.next_n_dates(date("2023-09-01"), 3));
+    }
+
+    #[test]
+    fn lists_combine_as_sets() {
+        let tuesdays = Schedule::from_str("weekly;days=TUE;start=2023-09-12").unwrap();
+        let fortnightly = Schedule::from_str("weekly;interval=2;days=TUE,THU;start=2023-09-12").unwrap();
+        let weekly = tuesdays.occurrences_between(date("2023-09-12"), date("2023-10-10"));
+        let every_other = fortnightly.next_n_dates(date("2023-09-12"), 5).unwrap();
+
+        assert_eq!(["2023-09-26", "2023-10-10"].map(date).as_slice(), weekly.intersect(&every_other).as_slice());
+        assert_eq!(["2023-09-12", "2023-09-19", "2023-10-03"].map(date).as_slice(), weekly.difference(&every_other).as_slice());
+    }
+
+    #[rstest]
+    fn len_agrees_with_count(
+        #[values(Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Quarterly, Repeating::Yearly)] repeat: Repeating,
+        #[values(1, 2, 3)] interval: u64,
+        #[values(WeekdayFlags::ANY, WeekdayFlags::WED, WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::SAT, WeekdayFlags::MIDWEEK)] weekdays: WeekdayFlags,
+    ) {
+        let schedule = Schedule::new(date("2023-09-13"), weekdays, repeat, interval).unwrap();
+        let ranges = [("2023-09-01", "2023-09-13"), ("2023-09-14", "2023-10-01"), ("2023-09-10", "2026-02-15"), ("2024-02-29", "2031-03-02"), ("2024-05-05", "2024-05-04")];
+
+        for (start, end) in ranges.map(|(start, end)| (date(start), date(end))) {
+            let occurrences = schedule.occurrences_in(start, end);
+
+            assert_eq!(occurrences.len(), occurrences.clone().count(), "{} {} {}", schedule, start, end);
+        }
+    }
+
+    #[rstest]
+    #[case::count("weekly;interval=2;days=MON,WED,SUN;start=2023-09-13;count=20")]
+    #[case::until("daily;interval=3;days=TUE,THU,SAT;start=2023-09-12;until=2024-03-01")]
+    #[case::monthly("monthly;start=2024-01-31;count=9")]
+    #[case::one_offs("weekly;days=TUE,THU;start=2023-09-12;count=7;exdate=2023-09-19,2023-09-21;rdate=2023-09-14,2023-09-16,2023-12-25")]
+    fn bounded_schedules_report_remaining(#[case] rule: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let mut occurrences = schedule.occurrences(date("2023-01-01"));
+
+        loop {
+            let (lower, upper) = occurrences.size_hint();
+
+            assert_eq!(Some(lower), upper, "{}", schedule);
+            assert_eq!(lower, occurrences.clone().count(), "{}", schedule);
+
+            if occurrences.next().is_none() {
+                break;
+            }
+        }
+
+        let last = schedule.last_occurrence().unwrap().succ_opt().unwrap();
+        let past = schedule.occurrences_before(last);
+        assert_eq!(past.len(), past.clone().count(), "{}", schedule);
+    }
+
+    #[test]
+    fn open_ended_size_hint() {
+        let schedule = Schedule::from_str("daily;start=2023-09-12").unwrap();
+
+        assert_eq!((0, None), schedule.occurrences(date("2023-09-12")).size_hint());
+        assert_eq!(30, schedule.occurrences_in(date("2023-09-01"), date("2023-10-11")).len());
+        assert_eq!(11, schedule.occurrences_before(date("2023-09-23")).len());
+    }
+}
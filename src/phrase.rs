@@ -0,0 +1,476 @@
+//! Recurrence phrases in English such as "every other friday", behind the `phrases` feature.
+
+use crate::{MonthlyBy, Repeating, RepeatingDateError, Schedule, WeekdayFlags, WeekendDefinition, SEARCH_DAYS};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhraseError {
+    /// A word, or a character that is not part of one, that the grammar does not allow where it
+    /// appears, lower cased, with its byte offset in the phrase.
+    Unexpected { token: String, offset: usize },
+    /// The phrase ends where the grammar expects more, as in "every 3".
+    UnexpectedEnd,
+    /// The phrase names no date, as in "yearly on february 30".
+    NeverMatches,
+    /// The phrase parsed, but `Schedule` rejected the rule.
+    InvalidRule(RepeatingDateError)
+}
+
+impl std::fmt::Display for PhraseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhraseError::Unexpected { token, offset } => write!(f, "unexpected `{}` at offset {}", token, offset),
+            PhraseError::UnexpectedEnd => write!(f, "the phrase ends too early"),
+            PhraseError::NeverMatches => write!(f, "the phrase names no date"),
+            PhraseError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PhraseError {}
+
+const MONTH_WORDS: [(&str, &str); 12] = [
+    ("january", "jan"), ("february", "feb"), ("march", "mar"), ("april", "apr"), ("may", "may"), ("june", "jun"),
+    ("july", "jul"), ("august", "aug"), ("september", "sep"), ("october", "oct"), ("november", "nov"), ("december", "dec"),
+];
+
+const WEEKDAY_WORDS: [(&str, &str); 7] = [("monday", "mon"), ("tuesday", "tue"), ("wednesday", "wed"), ("thursday", "thu"), ("friday", "fri"), ("saturday", "sat"), ("sunday", "sun")];
+
+const COUNT_WORDS: [&str; 12] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve"];
+
+const ORDINAL_WORDS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// Words with their byte offsets; commas and ampersands are tokens of their own and other
+/// characters outside words stand alone so they can be reported.
+fn tokenize(phrase: &str) -> Vec<(usize, String)> {
+    let mut tokens: Vec<(usize, String)> = vec![];
+    let mut in_word = false;
+
+    for (offset, character) in phrase.char_indices() {
+        match character {
+            _ if character.is_alphanumeric() => match tokens.last_mut() {
+                Some((_, word)) if in_word => word.extend(character.to_lowercase()),
+                _ => tokens.push((offset, character.to_lowercase().collect())),
+            },
+            _ if character.is_whitespace() => {},
+            _ => tokens.push((offset, character.to_string())),
+        }
+
+        in_word = character.is_alphanumeric();
+    }
+
+    tokens
+}
+
+fn weekday_word(word: &str, weekend: WeekendDefinition) -> Option<WeekdayFlags> {
+    let singular = word.strip_suffix('s').filter(|stem| stem.len() >= 3).unwrap_or(word);
+
+    match singular {
+        "weekday" => Some(weekend.workdays()),
+        "weekend" => Some(weekend.weekend()),
+        "tues" | "thur" | "thurs" => weekday_word(&singular[..3], weekend),
+        _ => WEEKDAY_WORDS.iter().position(|(name, short)| *name == singular || *short == singular || *name == word)
+            .map(|index| WeekdayFlags::from_weekday(chrono::Weekday::try_from(index as u8).unwrap())),
+    }
+}
+
+fn month_word(word: &str) -> Option<u32> {
+    let word = if word == "sept" { "sep" } else { word };
+
+    MONTH_WORDS.iter().position(|(name, short)| *name == word || *short == word).map(|index| index as u32 + 1)
+}
+
+/// "3", "three", "other" (two).
+fn count_word(word: &str) -> Option<u64> {
+    match word {
+        "other" => Some(2),
+        _ => word.parse::<u64>().ok().filter(|count| *count > 0)
+            .or_else(|| COUNT_WORDS.iter().position(|name| *name == word).map(|index| index as u64 + 1)),
+    }
+}
+
+/// "15th", "1st", "15", "first".
+fn ordinal_word(word: &str) -> Option<i8> {
+    let digits = word.trim_end_matches(|character: char| character.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    let expected = |value: i8| match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    match digits.parse::<i8>() {
+        Ok(value) if value > 0 && (suffix.is_empty() || suffix == expected(value)) => Some(value),
+        _ => ORDINAL_WORDS.iter().position(|name| *name == word).map(|index| index as i8 + 1),
+    }
+}
+
+/// The days of a monthly or yearly rule.
+enum Days {
+    Month(Vec<i8>),
+    Nth(chrono::Weekday, Vec<i8>)
+}
+
+struct Phrase {
+    repeat: Repeating,
+    interval: u64,
+    weekdays: Option<WeekdayFlags>,
+    days: Option<Days>,
+    month: Option<u32>
+}
+
+struct Parser {
+    tokens: Vec<(usize, String)>,
+    position: usize,
+    weekend: WeekendDefinition
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(|(_, word)| word.as_str())
+    }
+
+    fn next(&mut self) -> Result<&str, PhraseError> {
+        let (_, word) = self.tokens.get(self.position).ok_or(PhraseError::UnexpectedEnd)?;
+        self.position += 1;
+
+        Ok(word)
+    }
+
+    /// The error for the token just read, or the end.
+    fn unexpected(&self) -> PhraseError {
+        match self.tokens.get(self.position.saturating_sub(1)) {
+            Some((offset, token)) => PhraseError::Unexpected { token: token.clone(), offset: *offset },
+            None => PhraseError::UnexpectedEnd,
+        }
+    }
+
+    /// The error for the token about to be read, or the end.
+    fn unexpected_here(&self) -> PhraseError {
+        match self.tokens.get(self.position) {
+            Some((offset, token)) => PhraseError::Unexpected { token: token.clone(), offset: *offset },
+            None => PhraseError::UnexpectedEnd,
+        }
+    }
+
+    fn accept(&mut self, words: &[&str]) -> bool {
+        let found = self.peek().is_some_and(|word| words.contains(&word));
+        self.position += found as usize;
+        found
+    }
+
+    fn list_separator(&mut self) -> bool {
+        self.accept(&[",", "and", "&", "or"])
+    }
+
+    /// `day { separator day }`, a day being a weekday, "weekdays" or "weekends".
+    fn weekdays(&mut self) -> Result<WeekdayFlags, PhraseError> {
+        let mut weekdays = WeekdayFlags::empty();
+
+        loop {
+            let weekend = self.weekend;
+            let word = self.next()?;
+            weekdays |= weekday_word(word, weekend).ok_or_else(|| self.unexpected())?;
+
+            if !self.list_separator() {
+                return Ok(weekdays);
+            }
+        }
+    }
+
+    /// "15th", "last" or "2nd to last".
+    fn ordinal(&mut self) -> Result<i8, PhraseError> {
+        let word = self.next()?;
+
+        let ordinal = match word {
+            "last" => -1,
+            _ => {
+                let ordinal = ordinal_word(word).ok_or_else(|| self.unexpected())?;
+
+                match self.accept(&["to"]) {
+                    true if self.next()? == "last" => -ordinal,
+                    true => return Err(self.unexpected()),
+                    false => ordinal,
+                }
+            },
+        };
+
+        Ok(ordinal)
+    }
+
+    /// `[the] ordinal { separator ordinal } (day | weekday)`.
+    fn days(&mut self) -> Result<Days, PhraseError> {
+        self.accept(&["the"]);
+
+        let mut ordinals = vec![self.ordinal()?];
+
+        while self.list_separator() {
+            self.accept(&["the"]);
+            ordinals.push(self.ordinal()?);
+        }
+
+        if self.accept(&["day", "days"]) {
+            return Ok(Days::Month(ordinals));
+        }
+
+        match self.peek().and_then(|word| weekday_word(word, self.weekend)) {
+            Some(weekday) if weekday.bits().count_ones() == 1 => {
+                self.position += 1;
+
+                Ok(Days::Nth(weekday.extract_weekdays().next().unwrap(), ordinals))
+            },
+            Some(_) => Err(self.unexpected_here()),
+            None => Ok(Days::Month(ordinals)),
+        }
+    }
+
+    /// The frequency, and the days when given with it as in "every friday".
+    fn frequency(&mut self) -> Result<Phrase, PhraseError> {
+        let phrase = |repeat, interval| Phrase { repeat, interval, weekdays: None, days: None, month: None };
+
+        match self.next()? {
+            "daily" => return Ok(phrase(Repeating::Daily, 1)),
+            "weekly" => return Ok(phrase(Repeating::Weekly, 1)),
+            "biweekly" | "fortnightly" => return Ok(phrase(Repeating::Weekly, 2)),
+            "monthly" => return Ok(phrase(Repeating::Monthly, 1)),
+            "quarterly" => return Ok(phrase(Repeating::Quarterly, 1)),
+            "yearly" | "annually" => return Ok(phrase(Repeating::Yearly, 1)),
+            "every" | "each" => {},
+            _ => return Err(self.unexpected()),
+        }
+
+        let count = self.peek().and_then(count_word);
+        self.position += count.is_some() as usize;
+        let count = count.unwrap_or(1);
+
+        let word = self.next()?;
+        let (repeat, interval) = match word.strip_suffix('s').unwrap_or(word) {
+            "day" => (Repeating::Daily, count),
+            "week" => (Repeating::Weekly, count),
+            "fortnight" => (Repeating::Weekly, count.saturating_mul(2)),
+            "month" => (Repeating::Monthly, count),
+            "quarter" => (Repeating::Quarterly, count),
+            "year" => (Repeating::Yearly, count),
+            _ => {
+                self.position -= 1;
+
+                return Ok(Phrase { weekdays: Some(self.weekdays()?), ..phrase(Repeating::Weekly, count) });
+            },
+        };
+
+        Ok(phrase(repeat, interval))
+    }
+
+    /// `frequency [on days]`.
+    fn phrase(&mut self) -> Result<Phrase, PhraseError> {
+        let mut phrase = self.frequency()?;
+
+        if phrase.weekdays.is_none() && self.accept(&["on"]) {
+            match phrase.repeat {
+                Repeating::Daily | Repeating::Weekly => phrase.weekdays = Some(self.weekdays()?),
+                Repeating::Monthly | Repeating::Quarterly => phrase.days = Some(self.days()?),
+                _ => self.year_days(&mut phrase)?,
+            }
+        }
+
+        match self.peek() {
+            None => Ok(phrase),
+            Some(_) => Err(self.unexpected_here()),
+        }
+    }
+
+    /// `month day`, `day month` or `days of month`.
+    fn year_days(&mut self, phrase: &mut Phrase) -> Result<(), PhraseError> {
+        if let Some(month) = self.peek().and_then(month_word) {
+            self.position += 1;
+            phrase.month = Some(month);
+            phrase.days = Some(Days::Month(vec![self.ordinal()?]));
+
+            return Ok(());
+        }
+
+        let days = self.days()?;
+
+        if !self.accept(&["of"]) {
+            match (&days, self.peek().and_then(month_word)) {
+                (Days::Month(_), Some(month)) => {
+                    self.position += 1;
+                    phrase.month = Some(month);
+                    phrase.days = Some(days);
+
+                    return Ok(());
+                },
+                _ => return Err(self.unexpected_here()),
+            }
+        }
+
+        let word = self.next()?;
+        phrase.month = Some(month_word(word).ok_or_else(|| self.unexpected())?);
+        phrase.days = Some(days);
+
+        Ok(())
+    }
+}
+
+impl Phrase {
+    fn build(&self, start_date: chrono::NaiveDate) -> Result<Schedule, RepeatingDateError> {
+        let mut builder = Schedule::builder().start(start_date).repeat(self.repeat).interval(self.interval);
+
+        if let Some(weekdays) = self.weekdays {
+            builder = builder.on(weekdays);
+        } else if self.repeat == Repeating::Weekly {
+            builder = builder.on(WeekdayFlags::from_weekday(chrono::Datelike::weekday(&start_date)));
+        }
+
+        match &self.days {
+            // A single day from the start of the month is the start's own day.
+            Some(Days::Month(days)) if days.len() == 1 && days[0] > 0 && chrono::Datelike::day(&start_date) != days[0] as u32 => return Err(RepeatingDateError::StartNotInRule),
+            Some(Days::Month(days)) if days.len() == 1 && days[0] > 0 => {},
+            Some(Days::Month(days)) => builder = builder.monthly_by(MonthlyBy::days(days).map_err(|_| RepeatingDateError::InvalidMonthlyRule)?),
+            Some(Days::Nth(weekday, ordinals)) => builder = builder.monthly_by(MonthlyBy::nth_weekday(*weekday, ordinals.iter().copied())),
+            None => {},
+        }
+
+        if let Some(month) = self.month {
+            if chrono::Datelike::month(&start_date) != month {
+                return Err(RepeatingDateError::StartNotInRule);
+            }
+
+            if self.days.as_ref().is_some_and(|days| matches!(days, Days::Nth(..)) || matches!(days, Days::Month(values) if values.len() > 1 || values[0] < 0)) {
+                builder = builder.months(crate::MonthFlags::from_month(chrono::Month::try_from(month as u8).unwrap()));
+            }
+        }
+
+        builder.build()
+    }
+}
+
+impl Schedule {
+    /// Reads an English recurrence phrase, case insensitive, as the schedule starting on the first
+    /// date on or after `from` it allows. The grammar, one phrase per line:
+    ///
+    /// ```text
+    /// frequency [on weekdays]     for daily and weekly frequencies
+    /// frequency [on month-days]   for monthly and quarterly ones
+    /// frequency [on year-days]    for yearly ones
+    /// every [count] weekdays      weekly on those days: "every friday", "every other mon and wed"
+    ///
+    /// frequency   = daily | weekly | biweekly | fortnightly | monthly | quarterly | yearly
+    ///             | annually | (every | each) [count] (day | week | fortnight | month | quarter
+    ///             | year)[s]
+    /// count       = 3 | three (up to twelve) | other
+    /// weekdays    = day {(, | and | & | or) day}; day = monday | mon | mondays | ... | weekdays
+    ///             | weekends
+    /// month-days  = [the] ordinal {separator [the] ordinal} [day | days | weekday]
+    /// ordinal     = 15th | 15 | first (up to fifth) | last | ordinal to last
+    /// year-days   = month ordinal | month-days [of] month
+    /// ```
+    ///
+    /// A month day list followed by a weekday, as in "the first and third monday", picks the nth
+    /// weekdays. Without days, monthly and yearly rules fall on `from`'s day, and weekly ones on
+    /// its weekday. "weekdays" and "weekends" follow the Saturday/Sunday weekend, see
+    /// `from_phrase_with`.
+    pub fn from_phrase(phrase: &str, from: chrono::NaiveDate) -> Result<Schedule, PhraseError> {
+        Schedule::from_phrase_with(phrase, from, WeekendDefinition::default())
+    }
+
+    /// Like `from_phrase`, with "weekdays" and "weekends" read as the working days and the weekend
+    /// of `weekend`.
+    pub fn from_phrase_with(phrase: &str, from: chrono::NaiveDate, weekend: WeekendDefinition) -> Result<Schedule, PhraseError> {
+        let parsed = Parser { tokens: tokenize(phrase), position: 0, weekend }.phrase()?;
+        let mut last_error = None;
+
+        for start_date in from.iter_days().take(SEARCH_DAYS as usize) {
+            match parsed.build(start_date) {
+                Ok(schedule) => return Ok(schedule),
+                Err(RepeatingDateError::WrongWeekday { .. } | RepeatingDateError::StartNotInRule) => {},
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.map_or(PhraseError::NeverMatches, PhraseError::InvalidRule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+
+    fn from() -> chrono::NaiveDate {
+        // A Wednesday.
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 10).unwrap()
+    }
+
+    #[rstest]
+    #[case("daily", "daily;start=2024-01-10")]
+    #[case("every day", "daily;start=2024-01-10")]
+    #[case("Each Day", "daily;start=2024-01-10")]
+    #[case("every 3 days", "daily;interval=3;start=2024-01-10")]
+    #[case("every three days on weekdays", "daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-10")]
+    #[case("daily on weekends", "daily;days=SAT,SUN;start=2024-01-13")]
+    #[case("weekly", "weekly;days=WED;start=2024-01-10")]
+    #[case("every week", "weekly;days=WED;start=2024-01-10")]
+    #[case("biweekly", "weekly;interval=2;days=WED;start=2024-01-10")]
+    #[case("fortnightly on monday", "weekly;interval=2;days=MON;start=2024-01-15")]
+    #[case("every fortnight", "weekly;interval=2;days=WED;start=2024-01-10")]
+    #[case("every other friday", "weekly;interval=2;days=FRI;start=2024-01-12")]
+    #[case("every friday", "weekly;days=FRI;start=2024-01-12")]
+    #[case("every weekday", "weekly;days=MON,TUE,WED,THU,FRI;start=2024-01-10")]
+    #[case("every weekend", "weekly;days=SAT,SUN;start=2024-01-13")]
+    #[case("every mon, wed and fri", "weekly;days=MON,WED,FRI;start=2024-01-10")]
+    #[case("every 3 weeks on mon and wed", "weekly;interval=3;days=MON,WED;start=2024-01-10")]
+    #[case("every 2 weeks on Tues & Thurs", "weekly;interval=2;days=TUE,THU;start=2024-01-11")]
+    #[case("weekly on mondays or fridays", "weekly;days=MON,FRI;start=2024-01-12")]
+    #[case("monthly", "monthly;start=2024-01-10")]
+    #[case("monthly on the 15th", "monthly;start=2024-01-15")]
+    #[case("every month on the 5th", "monthly;start=2024-02-05")]
+    #[case("every 2 months on the 1st and 15th", "monthly;interval=2;start=2024-01-15;monthdays=1,15")]
+    #[case("monthly on the last day", "monthly;start=2024-01-31;monthdays=-1")]
+    #[case("monthly on the 2nd to last day", "monthly;start=2024-01-30;monthdays=-2")]
+    #[case("monthly on the first monday", "monthly;start=2024-02-05;nth=1MON")]
+    #[case("monthly on the second and fourth tuesday", "monthly;start=2024-01-23;nth=2TUE,4TUE")]
+    #[case("every month on the last friday", "monthly;start=2024-01-26;nth=-1FRI")]
+    #[case("quarterly on the 1st", "quarterly;start=2024-02-01")]
+    #[case("every other month", "monthly;interval=2;start=2024-01-10")]
+    #[case("yearly", "yearly;start=2024-01-10")]
+    #[case("annually on september 12", "yearly;start=2024-09-12")]
+    #[case("every 2 years on 12 september", "yearly;interval=2;start=2024-09-12")]
+    #[case("every year on the 29th of feb", "yearly;start=2024-02-29")]
+    #[case("yearly on the second sunday of may", "yearly;start=2024-05-12;months=MAY;nth=2SUN")]
+    #[case("every year on the last day of december", "yearly;start=2024-12-31;months=DEC;monthdays=-1")]
+    fn parses(#[case] phrase: &str, #[case] expected: &str) {
+        assert_eq!(Schedule::from_str(expected).unwrap(), Schedule::from_phrase(phrase, from()).unwrap());
+    }
+
+    #[rstest]
+    #[case("every weekend", WeekdayFlags::FRI | WeekdayFlags::SAT, "Every week on weekends, starting 12 Jan 2024")]
+    #[case("every weekday", WeekdayFlags::SUN | WeekdayFlags::MON | WeekdayFlags::TUE | WeekdayFlags::WED | WeekdayFlags::THU, "Every week on weekdays, starting 10 Jan 2024")]
+    #[case("daily on weekends", WeekdayFlags::FRI | WeekdayFlags::SAT, "Every weekend day, starting 12 Jan 2024")]
+    fn friday_saturday_weekend_both_ways(#[case] phrase: &str, #[case] weekdays: WeekdayFlags, #[case] description: &str) {
+        let weekend = WeekendDefinition::FRIDAY_SATURDAY;
+        let schedule = Schedule::from_phrase_with(phrase, from(), weekend).unwrap();
+
+        assert_eq!(weekdays, schedule.weekdays());
+        assert_eq!(description, schedule.describe_with(weekend));
+    }
+
+    #[rstest]
+    #[case::unknown_frequency("sometimes", PhraseError::Unexpected { token: "sometimes".to_string(), offset: 0 })]
+    #[case::unknown_day("every 2 weeks on mon and funday", PhraseError::Unexpected { token: "funday".to_string(), offset: 25 })]
+    #[case::trailing("every day please", PhraseError::Unexpected { token: "please".to_string(), offset: 10 })]
+    #[case::punctuation("every day!", PhraseError::Unexpected { token: "!".to_string(), offset: 9 })]
+    #[case::weekdays_on_a_month("monthly on mondays", PhraseError::Unexpected { token: "mondays".to_string(), offset: 11 })]
+    #[case::nth_of_several_days("monthly on the first monday and tuesday", PhraseError::Unexpected { token: "and".to_string(), offset: 28 })]
+    #[case::bad_ordinal("monthly on the 15nd", PhraseError::Unexpected { token: "15nd".to_string(), offset: 15 })]
+    #[case::incomplete("every 3", PhraseError::UnexpectedEnd)]
+    #[case::empty("", PhraseError::UnexpectedEnd)]
+    #[case::no_such_day("yearly on february 30", PhraseError::NeverMatches)]
+    #[case::day_out_of_range("monthly on the 32nd and last day", PhraseError::InvalidRule(RepeatingDateError::InvalidMonthlyRule))]
+    fn rejects(#[case] phrase: &str, #[case] expected: PhraseError) {
+        assert_eq!(Err(expected), Schedule::from_phrase(phrase, from()));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Repeating, RepeatingDateError, Schedule};
+
+/// A run of consecutive days repeating with a schedule, e.g. a shutdown from July 1 to July 14 every
+/// year: each occurrence of the schedule starts a span, both ends inclusive.
+///
+/// Spans of monthly, quarterly and yearly rules end on the first span's end moved by as many months
+/// as the start was, clamped to the end of shorter months like `chrono::Months` does: January 28-31
+/// repeated monthly covers February 28-29 in 2024. Other rules, and those picking several days per
+/// period, keep the first span's length in days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringSpan {
+    schedule: Schedule,
+    first_end: chrono::NaiveDate
+}
+
+impl RecurringSpan {
+    /// The first span runs from the schedule's start to `first_end`; fails with
+    /// `RepeatingDateError::SpanEndBeforeStart` when that is earlier.
+    pub fn new(schedule: Schedule, first_end: chrono::NaiveDate) -> Result<RecurringSpan, RepeatingDateError> {
+        if first_end < schedule.start_date() {
+            return Err(RepeatingDateError::SpanEndBeforeStart);
+        }
+
+        Ok(RecurringSpan { schedule, first_end })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn first_end(&self) -> chrono::NaiveDate {
+        self.first_end
+    }
+
+    /// The span starting on the occurrence `start`.
+    fn span_from(&self, start: chrono::NaiveDate) -> (chrono::NaiveDate, chrono::NaiveDate) {
+        let first_start = self.schedule.start_date();
+        let by_months = matches!(self.schedule.step().0, Repeating::Monthly | Repeating::Yearly) && !self.schedule.is_expanded();
+
+        let end = if by_months {
+            let months = crate::get_months_since(start, first_start);
+
+            u32::try_from(months).ok().and_then(|months| self.first_end.checked_add_months(chrono::Months::new(months)))
+        } else {
+            start.checked_add_signed(self.first_end - first_start)
+        };
+
+        (start, end.unwrap_or(chrono::NaiveDate::MAX).max(start))
+    }
+
+    /// The first span starting strictly after `from_date`, see `Schedule::next_after`.
+    pub fn next_span_after(&self, from_date: chrono::NaiveDate) -> Result<(chrono::NaiveDate, chrono::NaiveDate), RepeatingDateError> {
+        self.schedule.next_after(from_date).map(|start| self.span_from(start))
+    }
+
+    /// The span `date` falls in. Spans end in the order they start, so the one starting last on or
+    /// before `date` is the only candidate.
+    pub fn span_containing(&self, date: chrono::NaiveDate) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+        let start = if self.schedule.matches(date) { Some(date) } else { self.schedule.previous_before(date) };
+
+        start.map(|start| self.span_from(start)).filter(|(_, end)| date <= *end)
+    }
+
+    /// Whether `date` falls inside any span.
+    pub fn matches(&self, date: chrono::NaiveDate) -> bool {
+        self.span_containing(date).is_some()
+    }
+
+    /// Every span overlapping `start..=end`, in ascending order, including one already running on
+    /// `start`.
+    pub fn spans_between(&self, start: chrono::NaiveDate, end: chrono::NaiveDate) -> Vec<(chrono::NaiveDate, chrono::NaiveDate)> {
+        if start > end {
+            return vec![];
+        }
+
+        let running = self.span_containing(start);
+        let later = self.schedule.occurrences(start).take_while(|occurrence| *occurrence <= end).map(|occurrence| self.span_from(occurrence));
+
+        running.into_iter().chain(later).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    fn spans(rule: &str, first_end: &str) -> RecurringSpan {
+        RecurringSpan::new(Schedule::from_str(rule).unwrap(), date(first_end)).unwrap()
+    }
+
+    fn span(start: &str, end: &str) -> (chrono::NaiveDate, chrono::NaiveDate) {
+        (date(start), date(end))
+    }
+
+    #[rstest]
+    #[case::in_the_middle("2025-07-08", Some(span("2025-07-01", "2025-07-14")))]
+    #[case::first_day("2026-07-01", Some(span("2026-07-01", "2026-07-14")))]
+    #[case::last_day("2026-07-14", Some(span("2026-07-01", "2026-07-14")))]
+    #[case::after("2026-07-15", None)]
+    #[case::before_the_first("2024-06-30", None)]
+    fn yearly_shutdown(#[case] query: &str, #[case] expected: Option<(chrono::NaiveDate, chrono::NaiveDate)>) {
+        let shutdown = spans("yearly;start=2024-07-01", "2024-07-14");
+
+        assert_eq!(expected, shutdown.span_containing(date(query)));
+        assert_eq!(expected.is_some(), shutdown.matches(date(query)));
+    }
+
+    #[test]
+    fn monthly_spans_clamp_to_short_months() {
+        let month_end = spans("monthly;start=2024-01-28", "2024-01-31");
+
+        assert_eq!(Ok(span("2024-02-28", "2024-02-29")), month_end.next_span_after(date("2024-01-28")));
+        assert_eq!(vec![span("2025-02-28", "2025-02-28"), span("2025-03-28", "2025-03-31"), span("2025-04-28", "2025-04-30")], month_end.spans_between(date("2025-02-01"), date("2025-04-30")));
+        assert!(!month_end.matches(date("2025-03-01")));
+    }
+
+    #[test]
+    fn consecutive_spans_touch() {
+        let weeks = spans("weekly;days=MON;start=2024-01-01", "2024-01-07");
+
+        assert_eq!(Some(span("2024-01-01", "2024-01-07")), weeks.span_containing(date("2024-01-07")));
+        assert_eq!(Some(span("2024-01-08", "2024-01-14")), weeks.span_containing(date("2024-01-08")));
+        assert_eq!(vec![span("2024-01-01", "2024-01-07"), span("2024-01-08", "2024-01-14")], weeks.spans_between(date("2024-01-07"), date("2024-01-08")));
+        assert!(weeks.spans_between(date("2024-01-08"), date("2024-01-07")).is_empty());
+    }
+
+    #[test]
+    fn spans_keep_their_length_for_daily_rules() {
+        let every_ten_days = spans("daily;interval=10;start=2024-01-01", "2024-01-03");
+
+        assert_eq!(Ok(span("2024-01-11", "2024-01-13")), every_ten_days.next_span_after(date("2024-01-01")));
+        assert!(!every_ten_days.matches(date("2024-01-04")));
+    }
+
+    #[test]
+    fn rejects_an_end_before_the_start() {
+        assert_eq!(Err(RepeatingDateError::SpanEndBeforeStart), RecurringSpan::new(Schedule::from_str("yearly;start=2024-07-01").unwrap(), date("2024-06-30")));
+    }
+}
@@ -0,0 +1,735 @@
+//! RFC 5545 `RRULE` values, behind the `rfc5545` feature.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use chrono::Datelike;
+
+use crate::{MissingDay, MonthDay, MonthFlags, MonthlyBy, Repeating, RepeatingDateError, Schedule, WeekdayFlags, WeekdayPolicy, WEEKDAY_CODES};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RruleError {
+    /// A part is not of the form `NAME=value`.
+    MalformedPart { part: String },
+    DuplicatePart { name: String },
+    MissingFrequency,
+    /// A part this crate handles whose value does not parse, e.g. `INTERVAL=0`.
+    InvalidValue { name: String, value: String },
+    /// A part, as upper case `NAME=value`, whose meaning a `Schedule` cannot represent; it is
+    /// reported rather than dropped, as dropping it would change the occurrences.
+    UnsupportedPart { part: String },
+    /// `UNTIL` and `COUNT` together, which RFC 5545 forbids.
+    UntilWithCount,
+    /// Every part parsed, but `Schedule` rejected the combination, e.g. a start date the rule does
+    /// not fall on.
+    InvalidRule(RepeatingDateError),
+    /// A `Schedule` feature with no `RRULE` equivalent, named as in its getter.
+    NotExpressible { feature: &'static str }
+}
+
+impl std::fmt::Display for RruleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RruleError::MalformedPart { part } => write!(f, "RRULE part `{}` is not of the form NAME=value", part),
+            RruleError::DuplicatePart { name } => write!(f, "RRULE part {} is given more than once", name),
+            RruleError::MissingFrequency => write!(f, "RRULE has no FREQ"),
+            RruleError::InvalidValue { name, value } => write!(f, "RRULE {} value `{}` is invalid", name, value),
+            RruleError::UnsupportedPart { part } => write!(f, "RRULE part `{}` is not supported", part),
+            RruleError::UntilWithCount => write!(f, "RRULE cannot have both UNTIL and COUNT"),
+            RruleError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            RruleError::NotExpressible { feature } => write!(f, "{} cannot be written as an RRULE", feature),
+        }
+    }
+}
+
+impl std::error::Error for RruleError {}
+
+/// An `RruleError` with the byte range of the rule it is about: the offending `NAME=value` part,
+/// or the whole rule for errors about no part in particular, like `RruleError::MissingFrequency`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedRruleError {
+    pub error: RruleError,
+    pub span: std::ops::Range<usize>
+}
+
+impl std::fmt::Display for SpannedRruleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at bytes {}..{}", self.error, self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for SpannedRruleError {}
+
+fn by_day(weekdays: WeekdayFlags) -> String {
+    weekdays.extract_weekdays().map(|weekday| WEEKDAY_CODES[weekday.num_days_from_monday() as usize]).collect::<Vec<&str>>().join(",")
+}
+
+fn joined<T: ToString>(values: impl IntoIterator<Item = T>) -> String {
+    values.into_iter().map(|value| value.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn weekday_coded(code: &str) -> Option<chrono::Weekday> {
+    let index = WEEKDAY_CODES.iter().position(|candidate| *candidate == code)?;
+
+    Some(chrono::Weekday::try_from(index as u8).unwrap())
+}
+
+/// The parts of a rule, upper cased, in the order given.
+struct Parts(Vec<(String, String)>);
+
+impl Parts {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(other, _)| other == name).map(|(_, value)| value.as_str())
+    }
+
+    fn unsupported(&self, name: &str) -> RruleError {
+        RruleError::UnsupportedPart { part: format!("{}={}", name, self.get(name).unwrap_or_default()) }
+    }
+
+    /// Parses the comma separated values of `name`, if present.
+    fn list<T>(&self, name: &str, parse: impl Fn(&str) -> Option<T>) -> Result<Option<Vec<T>>, RruleError> {
+        let Some(value) = self.get(name) else { return Ok(None) };
+
+        value.split(',')
+            .map(|entry| parse(entry.trim()).ok_or_else(|| RruleError::InvalidValue { name: name.to_string(), value: value.to_string() }))
+            .collect::<Result<Vec<T>, RruleError>>()
+            .map(Some)
+    }
+
+    fn number<T: FromStr>(&self, name: &str, valid: impl Fn(&T) -> bool) -> Result<Option<T>, RruleError> {
+        let Some(value) = self.get(name) else { return Ok(None) };
+
+        T::from_str(value).ok().filter(valid).map(Some)
+            .ok_or_else(|| RruleError::InvalidValue { name: name.to_string(), value: value.to_string() })
+    }
+}
+
+/// The non-empty `;` separated parts of a rule, trimmed, with their byte ranges in `rule`.
+fn split_parts(rule: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let body_start = rule.len() - rule.trim_start().len();
+    let body_start = match rule.get(body_start..body_start + 6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => body_start + 6,
+        _ => body_start,
+    };
+
+    let mut offset = body_start;
+    let mut parts = vec![];
+
+    for piece in rule[body_start..].split(';') {
+        let trimmed = piece.trim();
+        let start = offset + (piece.len() - piece.trim_start().len());
+
+        if !trimmed.is_empty() {
+            parts.push((start..start + trimmed.len(), trimmed));
+        }
+
+        offset += piece.len() + 1;
+    }
+
+    parts
+}
+
+/// A `NAME=value` part, upper cased.
+fn split_part(part: &str) -> Result<(String, String), RruleError> {
+    let (name, value) = part.split_once('=').ok_or_else(|| RruleError::MalformedPart { part: part.to_string() })?;
+
+    Ok((name.trim().to_ascii_uppercase(), value.trim().to_ascii_uppercase()))
+}
+
+/// The errors `Schedule::from_rrule` reports for a part on its own, whatever the others.
+fn check_part(name: &str, value: &str) -> Result<(), RruleError> {
+    let parts = Parts(vec![(name.to_string(), value.to_string())]);
+    let invalid = || RruleError::InvalidValue { name: name.to_string(), value: value.to_string() };
+
+    match name {
+        "FREQ" => match value {
+            "DAILY" | "WEEKLY" | "MONTHLY" | "YEARLY" => Ok(()),
+            "HOURLY" | "MINUTELY" | "SECONDLY" => Err(parts.unsupported(name)),
+            _ => Err(invalid()),
+        },
+        "WKST" => weekday_coded(value).map(|_| ()).ok_or_else(invalid),
+        "INTERVAL" => parts.number::<u64>(name, |interval| *interval > 0).map(|_| ()),
+        "COUNT" => parts.number::<u32>(name, |count| *count > 0).map(|_| ()),
+        "UNTIL" => parse_until(value).map(|_| ()).ok_or_else(invalid),
+        "BYMONTH" => parts.list(name, |entry| u8::from_str(entry).ok().and_then(|month| chrono::Month::try_from(month).ok())).map(|_| ()),
+        "BYMONTHDAY" => parts.list(name, |entry| i8::from_str(entry).ok().and_then(|day| MonthDay::new(day).ok())).map(|_| ()),
+        "BYSETPOS" => parts.list(name, |entry| i16::from_str(entry).ok()).map(|_| ()),
+        "BYDAY" => parts.list(name, parse_by_day).map(|_| ()),
+        _ => Err(parts.unsupported(name)),
+    }
+}
+
+/// The name of the part an error of `Schedule::from_rrule` is about, if any.
+fn part_named(error: &RruleError) -> Option<&str> {
+    match error {
+        RruleError::UnsupportedPart { part } => part.split('=').next(),
+        RruleError::InvalidValue { name, .. } | RruleError::DuplicatePart { name } => Some(name),
+        _ => None,
+    }
+}
+
+/// `UNTIL` as a date or a date-time, local or UTC; only the date is kept.
+fn parse_until(value: &str) -> Option<chrono::NaiveDate> {
+    let date = chrono::NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    let time = value[8..].strip_suffix('Z').unwrap_or(&value[8..]);
+
+    (time.is_empty() || chrono::NaiveTime::parse_from_str(time, "T%H%M%S").is_ok()).then_some(date)
+}
+
+/// A `BYDAY` entry: an optional ordinal and a day code, as in `MO` or `-1FR`.
+fn parse_by_day(entry: &str) -> Option<(Option<i8>, chrono::Weekday)> {
+    let split = entry.len().checked_sub(2)?;
+    let weekday = weekday_coded(entry.get(split..)?)?;
+
+    match &entry[..split] {
+        "" => Some((None, weekday)),
+        ordinal => i8::from_str(ordinal).ok().filter(|ordinal| *ordinal != 0).map(|ordinal| (Some(ordinal), weekday)),
+    }
+}
+
+impl Schedule {
+    /// Reads a RFC 5545 recurrence rule such as `RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH`, with
+    /// `start_date` as its `DTSTART`. Names and values are case insensitive, whitespace around parts
+    /// is ignored and the `RRULE:` prefix is optional.
+    ///
+    /// `FREQ` (daily to yearly), `INTERVAL`, `COUNT`, `UNTIL` (of which only the date is kept),
+    /// `WKST`, `BYDAY`, `BYMONTHDAY`, `BYMONTH` and `BYSETPOS` are read where a `Schedule` means the
+    /// same: a week start other than Monday only where it cannot matter, ordinal `BYDAY` entries
+    /// become a `MonthlyBy::NthWeekday`, `BYMONTHDAY` a `MonthlyBy::Days` and both need `BYMONTH`
+    /// on yearly rules, and plain `BYDAY` on monthly and yearly rules needs `BYMONTHDAY` or
+    /// `BYSETPOS` to narrow it. Anything else, including a week
+    /// start other than Monday on a weekly rule over several days that skips weeks, fails with
+    /// `RruleError::UnsupportedPart`.
+    pub fn from_rrule(rule: &str, start_date: chrono::NaiveDate) -> Result<Schedule, RruleError> {
+        let mut parts = Parts(vec![]);
+
+        for (_, part) in split_parts(rule) {
+            let (name, value) = split_part(part)?;
+
+            if parts.get(&name).is_some() {
+                return Err(RruleError::DuplicatePart { name });
+            }
+
+            parts.0.push((name, value));
+        }
+
+        Schedule::from_rrule_parts(&parts, start_date)
+    }
+
+    /// `from_rrule`, but going on past the errors it would stop at to report them all, each with
+    /// the byte range of its part in `rule`, ordered by position. A part that fails on its own, or
+    /// that the remaining parts cannot be combined with, is left out and the rest read again, so
+    /// the schedule, when there is one, is what the rule means without its bad parts: enough to
+    /// preview what was understood. There is none without a valid `FREQ` or when `Schedule`
+    /// rejects the remaining rule.
+    pub fn parse_rrule_collecting(rule: &str, start_date: chrono::NaiveDate) -> (Option<Schedule>, Vec<SpannedRruleError>) {
+        let mut parts = Parts(vec![]);
+        let mut spans = vec![];
+        let mut errors = vec![];
+        let mut seen = BTreeSet::new();
+
+        for (span, part) in split_parts(rule) {
+            let checked = split_part(part).and_then(|(name, value)| {
+                if !seen.insert(name.clone()) {
+                    return Err(RruleError::DuplicatePart { name });
+                }
+
+                check_part(&name, &value).map(|_| (name, value))
+            });
+
+            match checked {
+                Ok(part) => {
+                    parts.0.push(part);
+                    spans.push(span);
+                },
+                Err(error) => errors.push(SpannedRruleError { error, span }),
+            }
+        }
+
+        let schedule = loop {
+            let error = match Schedule::from_rrule_parts(&parts, start_date) {
+                Ok(schedule) => break Some(schedule),
+                Err(RruleError::MissingFrequency) if errors.iter().any(|error| part_named(&error.error) == Some("FREQ")) => break None,
+                Err(RruleError::UntilWithCount) => (RruleError::UntilWithCount, parts.0.iter().rposition(|(name, _)| name == "UNTIL" || name == "COUNT")),
+                Err(error) => {
+                    let index = part_named(&error).and_then(|name| parts.0.iter().position(|(other, _)| other == name));
+                    (error, index)
+                },
+            };
+
+            match error {
+                (error, Some(index)) => {
+                    parts.0.remove(index);
+                    errors.push(SpannedRruleError { error, span: spans.remove(index) });
+                },
+                (error, None) => {
+                    errors.push(SpannedRruleError { error, span: 0..rule.len() });
+                    break None;
+                },
+            }
+        };
+
+        errors.sort_by_key(|error| error.span.start);
+
+        (schedule, errors)
+    }
+
+    fn from_rrule_parts(parts: &Parts, start_date: chrono::NaiveDate) -> Result<Schedule, RruleError> {
+        if let Some((name, _)) = parts.0.iter().find(|(name, _)| !matches!(name.as_str(), "FREQ" | "INTERVAL" | "COUNT" | "UNTIL" | "WKST" | "BYDAY" | "BYMONTHDAY" | "BYMONTH" | "BYSETPOS")) {
+            return Err(parts.unsupported(name));
+        }
+
+        let repeat = match parts.get("FREQ") {
+            None => return Err(RruleError::MissingFrequency),
+            Some("DAILY") => Repeating::Daily,
+            Some("WEEKLY") => Repeating::Weekly,
+            Some("MONTHLY") => Repeating::Monthly,
+            Some("YEARLY") => Repeating::Yearly,
+            Some("HOURLY" | "MINUTELY" | "SECONDLY") => return Err(parts.unsupported("FREQ")),
+            Some(value) => return Err(RruleError::InvalidValue { name: "FREQ".to_string(), value: value.to_string() }),
+        };
+
+        if parts.get("WKST").is_some_and(|value| weekday_coded(value).is_none()) {
+            return Err(RruleError::InvalidValue { name: "WKST".to_string(), value: parts.get("WKST").unwrap_or_default().to_string() });
+        }
+
+        let interval = parts.number::<u64>("INTERVAL", |interval| *interval > 0)?;
+        let count = parts.number::<u32>("COUNT", |count| *count > 0)?;
+        let until = parts.get("UNTIL")
+            .map(|value| parse_until(value).ok_or_else(|| RruleError::InvalidValue { name: "UNTIL".to_string(), value: value.to_string() }))
+            .transpose()?;
+
+        if until.is_some() && count.is_some() {
+            return Err(RruleError::UntilWithCount);
+        }
+
+        let months = parts.list("BYMONTH", |entry| u8::from_str(entry).ok().and_then(|month| chrono::Month::try_from(month).ok()))?;
+        let month_days = parts.list("BYMONTHDAY", |entry| i8::from_str(entry).ok())?;
+        let set_positions = parts.list("BYSETPOS", |entry| i16::from_str(entry).ok())?;
+        let by_day = parts.list("BYDAY", parse_by_day)?;
+        let in_months = repeat == Repeating::Monthly || (repeat == Repeating::Yearly && months.is_some());
+
+        // The week start only decides which days share a week when weekly rules skip weeks.
+        if parts.get("WKST").is_some_and(|value| value != "MO") && repeat == Repeating::Weekly && interval.is_some_and(|interval| interval > 1) && by_day.as_ref().is_some_and(|entries| entries.len() > 1) {
+            return Err(parts.unsupported("WKST"));
+        }
+
+        if months.is_some() && repeat != Repeating::Yearly {
+            return Err(parts.unsupported("BYMONTH"));
+        }
+
+        if month_days.is_some() && !in_months {
+            return Err(parts.unsupported("BYMONTHDAY"));
+        }
+
+        let mut builder = Schedule::builder().start(start_date).repeat(repeat).interval(interval.unwrap_or(1));
+
+        builder = match by_day {
+            None if repeat == Repeating::Weekly => builder.on(WeekdayFlags::from_weekday(chrono::Datelike::weekday(&start_date))),
+            None => builder,
+            Some(entries) if entries.iter().all(|(ordinal, _)| ordinal.is_none()) => {
+                if matches!(repeat, Repeating::Monthly | Repeating::Yearly) && month_days.is_none() && set_positions.is_none() {
+                    return Err(parts.unsupported("BYDAY"));
+                }
+
+                builder.on(entries.iter().fold(WeekdayFlags::empty(), |weekdays, (_, weekday)| weekdays | WeekdayFlags::from_weekday(*weekday)))
+            },
+            Some(entries) => {
+                let weekday = entries[0].1;
+                let same_weekday = entries.iter().all(|(ordinal, other)| ordinal.is_some() && *other == weekday);
+
+                if !in_months || month_days.is_some() || !same_weekday {
+                    return Err(parts.unsupported("BYDAY"));
+                }
+
+                builder.monthly_by(MonthlyBy::nth_weekday(weekday, entries.iter().filter_map(|(ordinal, _)| *ordinal)))
+            },
+        };
+
+        if let Some(days) = month_days {
+            let rule = MonthlyBy::days(&days).map_err(|_| RruleError::InvalidValue { name: "BYMONTHDAY".to_string(), value: parts.get("BYMONTHDAY").unwrap_or_default().to_string() })?;
+            builder = builder.monthly_by(rule);
+        }
+
+        if let Some(months) = months {
+            builder = builder.months(months.into_iter().fold(MonthFlags::empty(), |flags, month| flags | MonthFlags::from_month(month)));
+        }
+
+        if let Some(positions) = set_positions {
+            builder = builder.set_positions(positions.into_iter().collect::<BTreeSet<i16>>());
+        }
+
+        if let Some(until) = until {
+            builder = builder.until(until);
+        }
+
+        if let Some(count) = count {
+            builder = builder.count(count);
+        }
+
+        builder.build().map_err(RruleError::InvalidRule)
+    }
+
+    /// Writes the rule as an `RRULE` value without the `RRULE:` prefix, the start being the
+    /// event's `DTSTART`, such that `from_rrule` reads it back. Parts come in the order `FREQ`,
+    /// `INTERVAL` (left out when 1), `BYDAY` (days Monday first, always given on weekly rules),
+    /// `BYMONTHDAY`, `BYMONTH`, `BYSETPOS`, then `COUNT` or `UNTIL`.
+    ///
+    /// A quarterly rule is monthly every three intervals. A monthly or yearly weekday filter is
+    /// written as the start's day of month (and month) narrowed by `BYDAY`, and a yearly
+    /// `MonthlyBy` without a month set names the start's month: both read back as an equivalent
+    /// rule rather than an equal one. Weekday policies other than `Skip`, shift patterns, ISO
+    /// weeks, days of the year, clamped month days, a weekday filter on top of nth weekdays,
+    /// exclusions, extra dates and more than one time of day fail with
+    /// `RruleError::NotExpressible`.
+    pub fn to_rrule(&self) -> Result<String, RruleError> {
+        let unsupported = [
+            ("weekday_policy", self.weekday_policy() != WeekdayPolicy::Skip),
+            ("shift_pattern", self.shift_pattern().is_some()),
+            ("iso_weeks", !self.iso_weeks().is_empty()),
+            ("year_days", self.year_days().is_some()),
+            ("exclusions", !self.exclusions().is_empty()),
+            ("extra_dates", !self.extra_dates().is_empty()),
+            ("times_of_day", self.times_of_day().is_some_and(|times| times.times().len() != 1 || WeekdayFlags::ANY.extract_weekdays().any(|weekday| times.weekday_time(weekday).is_some()))),
+        ];
+
+        if let Some((feature, _)) = unsupported.iter().find(|(_, unsupported)| *unsupported) {
+            return Err(RruleError::NotExpressible { feature });
+        }
+
+        let (repeat, interval) = self.step();
+        let mut parts = vec![format!("FREQ={}", match repeat {
+            Repeating::Daily => "DAILY",
+            Repeating::Weekly => "WEEKLY",
+            Repeating::Monthly => "MONTHLY",
+            _ => "YEARLY",
+        })];
+
+        if interval != 1 {
+            parts.push(format!("INTERVAL={}", interval));
+        }
+
+        let filtered = !self.weekdays().is_all();
+        let by_month_day = match self.monthly_by() {
+            Some(MonthlyBy::Days { missing: MissingDay::Clamp, .. }) => return Err(RruleError::NotExpressible { feature: "monthly_by" }),
+            Some(MonthlyBy::Days { days, .. }) => Some(joined(days.iter().map(|day| day.get()))),
+            Some(MonthlyBy::NthWeekday { .. }) if filtered => return Err(RruleError::NotExpressible { feature: "weekdays" }),
+            Some(MonthlyBy::NthWeekday { weekday, ordinals }) => {
+                parts.push(format!("BYDAY={}", joined(ordinals.iter().map(|ordinal| format!("{}{}", ordinal, WEEKDAY_CODES[weekday.num_days_from_monday() as usize])))));
+                None
+            },
+            None if repeat != Repeating::Weekly && repeat != Repeating::Daily && filtered && self.set_positions().is_empty() => Some(self.start_date().day().to_string()),
+            None => None,
+        };
+
+        if filtered || repeat == Repeating::Weekly {
+            parts.push(format!("BYDAY={}", by_day(self.weekdays())));
+        }
+
+        let by_month = match self.months() {
+            Some(months) => Some(joined(months.month_numbers())),
+            None if repeat == Repeating::Yearly && (self.monthly_by().is_some() || by_month_day.is_some()) => Some(self.start_date().month().to_string()),
+            None => None,
+        };
+
+        if let Some(days) = by_month_day {
+            parts.push(format!("BYMONTHDAY={}", days));
+        }
+
+        if let Some(months) = by_month {
+            parts.push(format!("BYMONTH={}", months));
+        }
+
+        if !self.set_positions().is_empty() {
+            parts.push(format!("BYSETPOS={}", joined(self.set_positions())));
+        }
+
+        if let Some(count) = self.count() {
+            parts.push(format!("COUNT={}", count));
+        }
+
+        if let Some(until) = self.until() {
+            parts.push(format!("UNTIL={}", until.format("%Y%m%d")));
+        }
+
+        Ok(parts.join(";"))
+    }
+
+    /// A minimal iCalendar file with this schedule as its one `VEVENT`: `UID` and `SUMMARY` as
+    /// given, `DTSTAMP` (the time the event was created, in UTC) from `stamp`, `DTSTART` from the
+    /// start date, as a date or, with a single time of day, as a floating local date-time, and the
+    /// `RRULE` from `to_rrule`, whose errors it shares. Lines end in CRLF and are folded at 75
+    /// octets.
+    pub fn to_ics_event(&self, uid: &str, summary: &str, stamp: chrono::NaiveDateTime) -> Result<String, RruleError> {
+        let rrule = self.to_rrule()?;
+        let dtstart = match self.times_of_day().and_then(|times| times.times().first()) {
+            Some(time) => format!("DTSTART:{}", self.start_date().and_time(*time).format("%Y%m%dT%H%M%S")),
+            None => format!("DTSTART;VALUE=DATE:{}", self.start_date().format("%Y%m%d")),
+        };
+
+        let lines = [
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//recurring-dates-rs//EN".to_string(),
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", escape_text(uid)),
+            format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")),
+            dtstart,
+            format!("RRULE:{}", rrule),
+            format!("SUMMARY:{}", escape_text(summary)),
+            "END:VEVENT".to_string(),
+            "END:VCALENDAR".to_string(),
+        ];
+
+        Ok(lines.iter().map(|line| fold(line)).collect())
+    }
+}
+
+/// Escapes a `TEXT` value as RFC 5545 section 3.3.11 requires.
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(character);
+            },
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {},
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Ends `line` with CRLF, splitting it into lines of at most 75 octets, continuation lines starting
+/// with a space; multi-byte characters are never split.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+
+    for character in line.chars() {
+        if length + character.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+
+        folded.push(character);
+        length += character.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::{date, dates};
+
+    #[rstest]
+    #[case("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH", "2024-01-02", ["2024-01-02", "2024-01-04", "2024-01-16", "2024-01-18", "2024-01-30"].as_slice())]
+    #[case("FREQ=DAILY;COUNT=3", "2024-01-30", ["2024-01-30", "2024-01-31", "2024-02-01"].as_slice())]
+    #[case("freq=daily;interval=2", "2024-02-27", ["2024-02-27", "2024-02-29", "2024-03-02", "2024-03-04", "2024-03-06"].as_slice())]
+    #[case("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", "2024-03-01", ["2024-03-01", "2024-03-04", "2024-03-05", "2024-03-06", "2024-03-07"].as_slice())]
+    #[case("FREQ=WEEKLY", "2024-03-06", ["2024-03-06", "2024-03-13", "2024-03-20", "2024-03-27", "2024-04-03"].as_slice())]
+    #[case("FREQ=WEEKLY;WKST=MO;BYDAY=MO,WE,FR;UNTIL=20240311T235959Z", "2024-03-04", ["2024-03-04", "2024-03-06", "2024-03-08", "2024-03-11"].as_slice())]
+    #[case("FREQ=WEEKLY;WKST=SU;UNTIL=20240302T025959Z;BYDAY=FR", "2024-02-09", ["2024-02-09", "2024-02-16", "2024-02-23", "2024-03-01"].as_slice())]
+    #[case("FREQ=MONTHLY;BYMONTHDAY=15", "2024-01-15", ["2024-01-15", "2024-02-15", "2024-03-15", "2024-04-15", "2024-05-15"].as_slice())]
+    #[case("FREQ=MONTHLY;BYMONTHDAY=-1", "2024-01-31", ["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30", "2024-05-31"].as_slice())]
+    #[case("FREQ=MONTHLY;BYDAY=2TU", "2024-01-09", ["2024-01-09", "2024-02-13", "2024-03-12", "2024-04-09", "2024-05-14"].as_slice())]
+    #[case("FREQ=MONTHLY;BYDAY=-1FR", "2024-01-26", ["2024-01-26", "2024-02-23", "2024-03-29", "2024-04-26", "2024-05-31"].as_slice())]
+    #[case("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1", "2024-01-31", ["2024-01-31", "2024-02-29", "2024-03-29", "2024-04-30", "2024-05-31"].as_slice())]
+    #[case("FREQ=YEARLY;BYMONTH=5;BYDAY=2SU", "2024-05-12", ["2024-05-12", "2025-05-11", "2026-05-10", "2027-05-09", "2028-05-14"].as_slice())]
+    #[case("FREQ=YEARLY;INTERVAL=4", "2024-02-29", ["2024-02-29", "2028-02-29", "2032-02-29", "2036-02-29", "2040-02-29"].as_slice())]
+    #[case("FREQ=YEARLY;BYMONTH=1,7;BYMONTHDAY=1", "2024-01-01", ["2024-01-01", "2024-07-01", "2025-01-01", "2025-07-01", "2026-01-01"].as_slice())]
+    #[case("  rrule:freq=monthly; interval=3 ; ", "2024-01-31", ["2024-01-31", "2024-07-31", "2024-10-31", "2025-01-31", "2025-07-31"].as_slice())]
+    fn first_five_occurrences(#[case] rule: &str, #[case] start: &str, #[case] expected: &[&str]) {
+        let schedule = Schedule::from_rrule(rule, date(start)).unwrap();
+        let occurrences: Vec<chrono::NaiveDate> = schedule.occurrences(date(start).pred_opt().unwrap()).take(5).collect();
+
+        assert_eq!(dates(expected), occurrences);
+    }
+
+    #[rstest]
+    #[case::daily("daily;start=2024-01-30", "FREQ=DAILY")]
+    #[case::daily_filtered("daily;interval=2;days=MON,TUE,WED,THU,FRI;start=2024-03-01", "FREQ=DAILY;INTERVAL=2;BYDAY=MO,TU,WE,TH,FR")]
+    #[case::weekly("weekly;days=WED;start=2024-03-06", "FREQ=WEEKLY;BYDAY=WE")]
+    #[case::weekly_days_in_order("weekly;interval=2;days=THU,TUE;start=2024-01-02;until=2024-06-30", "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;UNTIL=20240630")]
+    #[case::weekly_every_day("weekly;start=2024-01-02;count=10", "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU;COUNT=10")]
+    #[case::monthly("monthly;start=2024-01-31", "FREQ=MONTHLY")]
+    #[case::monthly_days("monthly;start=2024-01-15;monthdays=-1,15", "FREQ=MONTHLY;BYMONTHDAY=-1,15")]
+    #[case::nth_weekday("monthly;interval=2;start=2024-01-09;nth=-1TUE,2TUE", "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1TU,2TU")]
+    #[case::last_workday("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-31;setpos=-1", "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1")]
+    #[case::yearly("yearly;interval=4;start=2024-02-29", "FREQ=YEARLY;INTERVAL=4")]
+    #[case::yearly_months("yearly;start=2024-01-01;months=JAN,JUL;monthdays=1", "FREQ=YEARLY;BYMONTHDAY=1;BYMONTH=1,7")]
+    #[case::mothers_day("yearly;start=2024-05-12;months=MAY;nth=2SUN", "FREQ=YEARLY;BYDAY=2SU;BYMONTH=5")]
+    fn round_trips(#[case] rule: &str, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let rrule = schedule.to_rrule().unwrap();
+
+        assert_eq!(expected, rrule);
+        assert_eq!(schedule, Schedule::from_rrule(&rrule, schedule.start_date()).unwrap());
+    }
+
+    #[rstest]
+    #[case::quarterly("quarterly;start=2024-01-31", "FREQ=MONTHLY;INTERVAL=3")]
+    #[case::monthly_filtered("monthly;days=FRI;start=2024-09-13", "FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13")]
+    #[case::yearly_filtered("yearly;days=MON,TUE,WED,THU,FRI;start=2024-07-04", "FREQ=YEARLY;BYDAY=MO,TU,WE,TH,FR;BYMONTHDAY=4;BYMONTH=7")]
+    #[case::yearly_days_in_the_start_month("yearly;start=2024-03-01;monthdays=1,-1", "FREQ=YEARLY;BYMONTHDAY=-1,1;BYMONTH=3")]
+    fn round_trips_equivalent(#[case] rule: &str, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+        let rrule = schedule.to_rrule().unwrap();
+
+        assert_eq!(expected, rrule);
+        assert!(schedule.equivalent(&Schedule::from_rrule(&rrule, schedule.start_date()).unwrap()), "{}", rrule);
+    }
+
+    #[rstest]
+    #[case::slide("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-01;policy=slide", "weekday_policy")]
+    #[case::shift("daily;start=2024-01-01;shift=8:0,1,2,3", "shift_pattern")]
+    #[case::iso_weeks("yearly;days=MON;start=2024-04-08;weeks=15", "iso_weeks")]
+    #[case::year_days("yearly;start=2024-04-09;yeardays=100", "year_days")]
+    #[case::clamped("monthly;start=2024-01-31;monthdays=31;missing=clamp", "monthly_by")]
+    #[case::filtered_nth("monthly;days=TUE;start=2024-01-09;nth=2TUE", "weekdays")]
+    #[case::exclusions("daily;start=2024-01-01;exdate=2024-01-02", "exclusions")]
+    #[case::extra_dates("weekly;days=MON;start=2024-01-01;rdate=2024-01-03", "extra_dates")]
+    #[case::two_times("daily;start=2024-01-01;times=08:00,20:00", "times_of_day")]
+    fn not_expressible(#[case] rule: &str, #[case] feature: &'static str) {
+        assert_eq!(Err(RruleError::NotExpressible { feature }), Schedule::from_str(rule).unwrap().to_rrule());
+    }
+
+    fn stamp() -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_str("2024-09-01T12:00:00").unwrap()
+    }
+
+    #[test]
+    fn ics_event_on_dates() {
+        let schedule = Schedule::from_str("weekly;interval=2;days=THU;start=2024-09-12").unwrap();
+        let expected = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//recurring-dates-rs//EN\r\nBEGIN:VEVENT\r\nUID:appointment-1@example.com\r\nDTSTAMP:20240901T120000Z\r\nDTSTART;VALUE=DATE:20240912\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TH\r\nSUMMARY:Appointment\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        assert_eq!(Ok(expected.to_string()), schedule.to_ics_event("appointment-1@example.com", "Appointment", stamp()));
+    }
+
+    #[test]
+    fn ics_event_at_a_time() {
+        let schedule = Schedule::from_str("monthly;start=2024-09-12;times=14:30").unwrap();
+        let ics = schedule.to_ics_event("rent@example.com", "Rent", stamp()).unwrap();
+
+        assert!(ics.contains("\r\nDTSTART:20240912T143000\r\nRRULE:FREQ=MONTHLY\r\n"), "{}", ics);
+    }
+
+    #[test]
+    fn ics_event_escapes_and_folds() {
+        let schedule = Schedule::from_str("daily;start=2024-09-12").unwrap();
+        let summary = "Stand-up; bring notes, questions and a coffee \\ tea for the whole team — every single day\nRoom 4";
+        let ics = schedule.to_ics_event("standup@example.com", summary, stamp()).unwrap();
+        let expected = "SUMMARY:Stand-up\\; bring notes\\, questions and a coffee \\\\ tea for the whol\r\n e team — every single day\\nRoom 4\r\n";
+
+        assert!(ics.contains(expected), "{}", ics);
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(!ics.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn ics_event_folds_on_character_boundaries() {
+        let line = fold(&format!("SUMMARY:{}", "é".repeat(40)));
+
+        assert_eq!(format!("SUMMARY:{}\r\n {}\r\n", "é".repeat(33), "é".repeat(7)), line);
+    }
+
+    #[test]
+    fn ics_event_needs_an_rrule() {
+        let schedule = Schedule::from_str("daily;start=2024-09-12;exdate=2024-09-13").unwrap();
+
+        assert_eq!(Err(RruleError::NotExpressible { feature: "exclusions" }), schedule.to_ics_event("a@example.com", "A", stamp()));
+    }
+
+    #[rstest]
+    #[case::no_frequency("INTERVAL=2", RruleError::MissingFrequency)]
+    #[case::malformed("FREQ=DAILY;INTERVAL", RruleError::MalformedPart { part: "INTERVAL".to_string() })]
+    #[case::duplicate("FREQ=DAILY;freq=WEEKLY", RruleError::DuplicatePart { name: "FREQ".to_string() })]
+    #[case::unknown_frequency("FREQ=FORTNIGHTLY", RruleError::InvalidValue { name: "FREQ".to_string(), value: "FORTNIGHTLY".to_string() })]
+    #[case::zero_interval("FREQ=DAILY;INTERVAL=0", RruleError::InvalidValue { name: "INTERVAL".to_string(), value: "0".to_string() })]
+    #[case::bad_until("FREQ=DAILY;UNTIL=2024-12-31", RruleError::InvalidValue { name: "UNTIL".to_string(), value: "2024-12-31".to_string() })]
+    #[case::bad_day("FREQ=WEEKLY;BYDAY=MO,XX", RruleError::InvalidValue { name: "BYDAY".to_string(), value: "MO,XX".to_string() })]
+    #[case::until_with_count("FREQ=DAILY;COUNT=2;UNTIL=20240105", RruleError::UntilWithCount)]
+    #[case::hourly("FREQ=HOURLY", RruleError::UnsupportedPart { part: "FREQ=HOURLY".to_string() })]
+    #[case::week_numbers("FREQ=YEARLY;BYWEEKNO=20", RruleError::UnsupportedPart { part: "BYWEEKNO=20".to_string() })]
+    #[case::hours("FREQ=DAILY;BYHOUR=9", RruleError::UnsupportedPart { part: "BYHOUR=9".to_string() })]
+    #[case::week_start("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,SU;WKST=SU", RruleError::UnsupportedPart { part: "WKST=SU".to_string() })]
+    #[case::unknown_week_start("FREQ=WEEKLY;WKST=XX", RruleError::InvalidValue { name: "WKST".to_string(), value: "XX".to_string() })]
+    #[case::every_tuesday_of_the_month("FREQ=MONTHLY;BYDAY=TU", RruleError::UnsupportedPart { part: "BYDAY=TU".to_string() })]
+    #[case::mixed_ordinals("FREQ=MONTHLY;BYDAY=1MO,TU", RruleError::UnsupportedPart { part: "BYDAY=1MO,TU".to_string() })]
+    #[case::month_days_every_month_of_the_year("FREQ=YEARLY;BYMONTHDAY=1", RruleError::UnsupportedPart { part: "BYMONTHDAY=1".to_string() })]
+    #[case::months_on_a_monthly_rule("FREQ=MONTHLY;BYMONTH=1", RruleError::UnsupportedPart { part: "BYMONTH=1".to_string() })]
+    #[case::start_off_the_rule("FREQ=WEEKLY;BYDAY=MO", RruleError::InvalidRule(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Tue, allowed: WeekdayFlags::MON }))]
+    fn rejects(#[case] rule: &str, #[case] expected: RruleError) {
+        assert_eq!(Err(expected), Schedule::from_rrule(rule, date("2024-01-02")));
+    }
+
+    fn spanned(error: RruleError, span: std::ops::Range<usize>) -> SpannedRruleError {
+        SpannedRruleError { error, span }
+    }
+
+    fn invalid(name: &str, value: &str) -> RruleError {
+        RruleError::InvalidValue { name: name.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn collecting_reports_every_error() {
+        let rule = " RRULE:FREQ=FORTNIGHTLY;UNTIL=2024-12-31; BYDAY=MO,XX";
+        let (schedule, errors) = Schedule::parse_rrule_collecting(rule, date("2024-01-02"));
+
+        assert_eq!(None, schedule);
+        assert_eq!(vec![
+            spanned(invalid("FREQ", "FORTNIGHTLY"), 7..23),
+            spanned(invalid("UNTIL", "2024-12-31"), 24..40),
+            spanned(invalid("BYDAY", "MO,XX"), 42..53),
+        ], errors);
+        assert_eq!(Err(errors[0].error.clone()), Schedule::from_rrule(rule, date("2024-01-02")));
+    }
+
+    #[test]
+    fn collecting_keeps_what_it_understood() {
+        let rule = "FREQ=WEEKLY;INTERVAL=0;BYDAY=TU,XX;BYHOUR=9;COUNT=3;UNTIL=20240105";
+        let (schedule, errors) = Schedule::parse_rrule_collecting(rule, date("2024-01-02"));
+
+        assert_eq!(Some(Schedule::from_rrule("FREQ=WEEKLY;COUNT=3", date("2024-01-02")).unwrap()), schedule);
+        assert_eq!(vec![
+            spanned(invalid("INTERVAL", "0"), 12..22),
+            spanned(invalid("BYDAY", "TU,XX"), 23..34),
+            spanned(RruleError::UnsupportedPart { part: "BYHOUR=9".to_string() }, 35..43),
+            spanned(RruleError::UntilWithCount, 52..66),
+        ], errors);
+        assert_eq!(Err(RruleError::UnsupportedPart { part: "BYHOUR=9".to_string() }), Schedule::from_rrule(rule, date("2024-01-02")));
+    }
+
+    #[rstest]
+    #[case::no_frequency("INTERVAL=2", vec![spanned(RruleError::MissingFrequency, 0..10)])]
+    #[case::malformed_and_duplicate("FREQ=FORTNIGHTLY;INTERVAL;freq=WEEKLY", vec![
+        spanned(invalid("FREQ", "FORTNIGHTLY"), 0..16),
+        spanned(RruleError::MalformedPart { part: "INTERVAL".to_string() }, 17..25),
+        spanned(RruleError::DuplicatePart { name: "FREQ".to_string() }, 26..37),
+    ])]
+    #[case::start_off_the_rule("FREQ=WEEKLY;BYDAY=MO;WKST=XX", vec![
+        spanned(RruleError::InvalidRule(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Tue, allowed: WeekdayFlags::MON }), 0..28),
+        spanned(invalid("WKST", "XX"), 21..28),
+    ])]
+    fn collecting_rejects(#[case] rule: &str, #[case] expected: Vec<SpannedRruleError>) {
+        let (schedule, errors) = Schedule::parse_rrule_collecting(rule, date("2024-01-02"));
+
+        assert_eq!(None, schedule);
+        assert_eq!(expected, errors);
+        assert!(errors.iter().any(|spanned| Err(&spanned.error) == Schedule::from_rrule(rule, date("2024-01-02")).as_ref()));
+    }
+
+    #[test]
+    fn spanned_error_displays_its_span() {
+        assert_eq!("RRULE has no FREQ at bytes 0..10", spanned(RruleError::MissingFrequency, 0..10).to_string());
+    }
+}
@@ -0,0 +1,954 @@
+//! `Display` and `FromStr` for `Schedule`; the format is documented in the crate docs.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use crate::month_set::month_flag_named;
+use crate::{
+    weekday_flag_named, weekday_name, MissingDay, MonthDay, MonthFlags, MonthlyBy, Repeating, RepeatingDateError, Schedule,
+    ShiftPattern, TimesOfDay, WeekdayFlags, WeekdayPolicy, YearDays,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseScheduleError {
+    /// The leading frequency is not one of the known names.
+    UnknownFrequency { value: String },
+    /// A field is not of the form `key=value`.
+    MalformedField { field: String },
+    UnknownField { key: String },
+    DuplicateField { key: &'static str },
+    /// Two fields that cannot be combined, in the order they appeared.
+    ConflictingFields { first: &'static str, second: &'static str },
+    MissingField { key: &'static str },
+    InvalidInterval { value: String },
+    /// `days` contains an unknown or repeated day name.
+    InvalidWeekday { value: String },
+    InvalidStartDate { value: String },
+    /// `weeks` contains something that is not an ISO week number `1..=53`, or repeats one.
+    InvalidWeekNumber { value: String },
+    /// `yeardays` contains something that is not a day of the year `1..=366`, or repeats one.
+    InvalidYearDay { value: String },
+    /// `setpos` contains something that is not a non-zero integer, or repeats one.
+    InvalidSetPosition { value: String },
+    /// `policy` is not `skip`, `slide` or `count`.
+    InvalidPolicy { value: String },
+    /// `shift` is not `<cycle>:<day>[,...]` with repeated or out of cycle days.
+    InvalidShift { value: String },
+    /// `months` contains an unknown or repeated month name.
+    InvalidMonth { value: String },
+    /// `monthdays` contains something that is not a day of month `MonthDay` accepts, or repeats one.
+    InvalidMonthDay { value: String },
+    /// `nth` contains something other than `<ordinal><DAY>`, repeats an entry or mixes weekdays.
+    InvalidNthWeekday { value: String },
+    /// `missing` is neither `skip` nor `clamp`.
+    InvalidMissingDay { value: String },
+    InvalidUntilDate { value: String },
+    InvalidCount { value: String },
+    /// `exdate` contains something that is not a date.
+    InvalidExclusionDate { value: String },
+    /// `rdate` contains something that is not a date.
+    InvalidExtraDate { value: String },
+    /// `times` contains something that is not a time of day, or repeats one.
+    InvalidTime { value: String },
+    /// Every field parsed, but `Schedule::new` rejected the combination.
+    InvalidRule(RepeatingDateError)
+}
+
+impl std::fmt::Display for ParseScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseScheduleError::UnknownFrequency { value } => write!(f, "unknown frequency `{}`, expected daily, weekly, monthly, quarterly or yearly", value),
+            ParseScheduleError::MalformedField { field } => write!(f, "field `{}` is not of the form key=value", field),
+            ParseScheduleError::UnknownField { key } => write!(f, "unknown field `{}`, expected interval, days, start, policy, shift, months, weeks, yeardays, monthdays, missing, nth, setpos, until, count, exdate, rdate or times", key),
+            ParseScheduleError::DuplicateField { key } => write!(f, "field `{}` is given more than once", key),
+            ParseScheduleError::ConflictingFields { first, second } => write!(f, "field `{}` cannot be combined with `{}`", second, first),
+            ParseScheduleError::MissingField { key } => write!(f, "missing required field `{}`", key),
+            ParseScheduleError::InvalidInterval { value } => write!(f, "interval `{}` is not a positive integer", value),
+            ParseScheduleError::InvalidWeekday { value } => write!(f, "days: `{}` is not a weekday name (MON..SUN) or is repeated", value),
+            ParseScheduleError::InvalidStartDate { value } => write!(f, "start `{}` is not a YYYY-MM-DD date", value),
+            ParseScheduleError::InvalidWeekNumber { value } => write!(f, "weeks: `{}` is not an ISO week number (1..=53) or is repeated", value),
+            ParseScheduleError::InvalidYearDay { value } => write!(f, "yeardays: `{}` is not a day of the year (1..=366) or is repeated", value),
+            ParseScheduleError::InvalidSetPosition { value } => write!(f, "setpos: `{}` is not a non-zero integer or is repeated", value),
+            ParseScheduleError::InvalidPolicy { value } => write!(f, "policy `{}` is not skip, slide or count", value),
+            ParseScheduleError::InvalidShift { value } => write!(f, "shift `{}` is not a cycle length followed by distinct days within it, as in 8:0,1,2,3", value),
+            ParseScheduleError::InvalidMonth { value } => write!(f, "months: `{}` is not a month name (JAN..DEC) or is repeated", value),
+            ParseScheduleError::InvalidMonthDay { value } => write!(f, "monthdays: `{}` is not a day of month (1..=31 or -31..=-1) or is repeated", value),
+            ParseScheduleError::InvalidNthWeekday { value } => write!(f, "nth: `{}` is not an ordinal followed by the same weekday name as the others", value),
+            ParseScheduleError::InvalidMissingDay { value } => write!(f, "missing `{}` is neither skip nor clamp", value),
+            ParseScheduleError::InvalidUntilDate { value } => write!(f, "until `{}` is not a YYYY-MM-DD date", value),
+            ParseScheduleError::InvalidCount { value } => write!(f, "count `{}` is not a positive integer", value),
+            ParseScheduleError::InvalidExclusionDate { value } => write!(f, "exdate: `{}` is not a YYYY-MM-DD date", value),
+            ParseScheduleError::InvalidExtraDate { value } => write!(f, "rdate: `{}` is not a YYYY-MM-DD date", value),
+            ParseScheduleError::InvalidTime { value } => write!(f, "times: `{}` is not a HH:MM[:SS] time of day or is repeated", value),
+            ParseScheduleError::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ParseScheduleError {}
+
+fn frequency_name(repeat: Repeating) -> &'static str {
+    match repeat {
+        Repeating::Daily => "daily",
+        Repeating::Weekly => "weekly",
+        Repeating::Monthly => "monthly",
+        Repeating::Quarterly => "quarterly",
+        Repeating::Yearly => "yearly",
+        Repeating::Hourly | Repeating::Minutely => unreachable!("Schedule::new rejects sub-daily frequencies"),
+    }
+}
+
+pub(crate) fn parse_frequency(value: &str) -> Result<Repeating, ParseScheduleError> {
+    match value {
+        "daily" => Ok(Repeating::Daily),
+        "weekly" => Ok(Repeating::Weekly),
+        "monthly" => Ok(Repeating::Monthly),
+        "quarterly" => Ok(Repeating::Quarterly),
+        "yearly" => Ok(Repeating::Yearly),
+        _ => Err(ParseScheduleError::UnknownFrequency { value: value.to_string() }),
+    }
+}
+
+fn parse_days(value: &str) -> Result<WeekdayFlags, ParseScheduleError> {
+    let mut result = WeekdayFlags::empty();
+
+    if value.is_empty() {
+        return Ok(result);
+    }
+
+    for name in value.split(',') {
+        match weekday_flag_named(name) {
+            Some(weekday) if !result.contains(weekday) => result |= weekday,
+            _ => return Err(ParseScheduleError::InvalidWeekday { value: name.to_string() }),
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_weeks(value: &str) -> Result<BTreeSet<u8>, ParseScheduleError> {
+    let mut weeks = BTreeSet::new();
+
+    for entry in value.split(',') {
+        let week = u8::from_str(entry).ok().filter(|week| (1..=53).contains(week) && !weeks.contains(week))
+            .ok_or_else(|| ParseScheduleError::InvalidWeekNumber { value: entry.to_string() })?;
+
+        weeks.insert(week);
+    }
+
+    Ok(weeks)
+}
+
+fn parse_year_days(value: &str) -> Result<YearDays, ParseScheduleError> {
+    let mut days = BTreeSet::new();
+
+    for entry in value.split(',') {
+        let day = u16::from_str(entry).ok().filter(|day| (1..=366).contains(day) && !days.contains(day))
+            .ok_or_else(|| ParseScheduleError::InvalidYearDay { value: entry.to_string() })?;
+
+        days.insert(day);
+    }
+
+    YearDays::new(days).map_err(|error| ParseScheduleError::InvalidYearDay { value: error.value.to_string() })
+}
+
+fn parse_set_positions(value: &str) -> Result<BTreeSet<i16>, ParseScheduleError> {
+    let mut positions = BTreeSet::new();
+
+    for entry in value.split(',') {
+        let position = i16::from_str(entry).ok().filter(|position| *position != 0 && !positions.contains(position))
+            .ok_or_else(|| ParseScheduleError::InvalidSetPosition { value: entry.to_string() })?;
+
+        positions.insert(position);
+    }
+
+    Ok(positions)
+}
+
+fn parse_policy(value: &str) -> Result<WeekdayPolicy, ParseScheduleError> {
+    match value {
+        "skip" => Ok(WeekdayPolicy::Skip),
+        "slide" => Ok(WeekdayPolicy::Slide),
+        "count" => Ok(WeekdayPolicy::Count),
+        _ => Err(ParseScheduleError::InvalidPolicy { value: value.to_string() }),
+    }
+}
+
+fn parse_shift(value: &str) -> Result<ShiftPattern, ParseScheduleError> {
+    let invalid = || ParseScheduleError::InvalidShift { value: value.to_string() };
+    let (cycle_days, on_days) = value.split_once(':').ok_or_else(invalid)?;
+    let cycle_days = u16::from_str(cycle_days).map_err(|_| invalid())?;
+    let mut days = BTreeSet::new();
+
+    for entry in on_days.split(',') {
+        let day = u16::from_str(entry).map_err(|_| invalid())?;
+
+        if !days.insert(day) {
+            return Err(invalid());
+        }
+    }
+
+    ShiftPattern::new(cycle_days, days).map_err(|_| invalid())
+}
+
+fn parse_months(value: &str) -> Result<MonthFlags, ParseScheduleError> {
+    let mut result = MonthFlags::empty();
+
+    for name in value.split(',') {
+        match month_flag_named(name) {
+            Some(month) if !result.contains(month) => result |= month,
+            _ => return Err(ParseScheduleError::InvalidMonth { value: name.to_string() }),
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_nth(value: &str) -> Result<MonthlyBy, ParseScheduleError> {
+    let mut weekday = None;
+    let mut ordinals = BTreeSet::new();
+
+    for entry in value.split(',') {
+        let error = || ParseScheduleError::InvalidNthWeekday { value: entry.to_string() };
+        let split = entry.find(|character: char| character.is_ascii_alphabetic()).ok_or_else(error)?;
+        let (ordinal, name) = entry.split_at(split);
+
+        let ordinal = i8::from_str(ordinal).map_err(|_| error())?;
+        let named = weekday_flag_named(name).and_then(|flag| chrono::Weekday::try_from(flag).ok()).ok_or_else(error)?;
+
+        if *weekday.get_or_insert(named) != named || !ordinals.insert(ordinal) {
+            return Err(error());
+        }
+    }
+
+    let weekday = weekday.ok_or_else(|| ParseScheduleError::InvalidNthWeekday { value: value.to_string() })?;
+
+    Ok(MonthlyBy::NthWeekday { weekday, ordinals })
+}
+
+fn parse_month_days(value: &str) -> Result<MonthlyBy, ParseScheduleError> {
+    let mut days = BTreeSet::new();
+
+    for entry in value.split(',') {
+        let day = i8::from_str(entry).ok().and_then(|day| MonthDay::new(day).ok()).filter(|day| !days.contains(day))
+            .ok_or_else(|| ParseScheduleError::InvalidMonthDay { value: entry.to_string() })?;
+
+        days.insert(day);
+    }
+
+    Ok(MonthlyBy::Days { days, missing: MissingDay::Skip })
+}
+
+fn parse_missing_day(value: &str) -> Result<MissingDay, ParseScheduleError> {
+    match value {
+        "skip" => Ok(MissingDay::Skip),
+        "clamp" => Ok(MissingDay::Clamp),
+        _ => Err(ParseScheduleError::InvalidMissingDay { value: value.to_string() }),
+    }
+}
+
+fn display_monthly_by(rule: &MonthlyBy) -> String {
+    match rule {
+        MonthlyBy::Days { days, missing } => {
+            let days = days.iter().map(|day| day.get().to_string()).collect::<Vec<String>>();
+
+            match missing {
+                MissingDay::Skip => format!("monthdays={}", days.join(",")),
+                MissingDay::Clamp => format!("monthdays={};missing=clamp", days.join(",")),
+            }
+        },
+        MonthlyBy::NthWeekday { weekday, ordinals } => {
+            let entries = ordinals.iter().map(|ordinal| format!("{}{}", ordinal, weekday_name(*weekday))).collect::<Vec<String>>();
+
+            format!("nth={}", entries.join(","))
+        },
+    }
+}
+
+fn parse_dates(value: &str, error: impl Fn(&str) -> ParseScheduleError) -> Result<BTreeSet<chrono::NaiveDate>, ParseScheduleError> {
+    value.split(',')
+        .map(|date| chrono::NaiveDate::from_str(date).map_err(|_| error(date)))
+        .collect()
+}
+
+fn parse_times(value: &str) -> Result<TimesOfDay, ParseScheduleError> {
+    let mut times = BTreeSet::new();
+    let mut by_weekday = vec![];
+
+    for entry in value.split(',') {
+        let invalid = || ParseScheduleError::InvalidTime { value: entry.to_string() };
+
+        match entry.split_once('@') {
+            Some((day, time)) => {
+                let weekday = weekday_flag_named(day).and_then(|flag| flag.extract_weekdays().next()).ok_or_else(invalid)?;
+                let time = chrono::NaiveTime::from_str(time).map_err(|_| invalid())?;
+
+                if by_weekday.iter().any(|(other, _)| *other == weekday) {
+                    return Err(invalid());
+                }
+
+                by_weekday.push((weekday, time));
+            },
+            None => {
+                let time = chrono::NaiveTime::from_str(entry).ok().filter(|time| !times.contains(time)).ok_or_else(invalid)?;
+
+                times.insert(time);
+            },
+        }
+    }
+
+    let times_of_day = if times.is_empty() { TimesOfDay::per_weekday(by_weekday) } else { TimesOfDay::new(times).map(|shared| by_weekday.into_iter().fold(shared, |times_of_day, (weekday, time)| times_of_day.with_weekday_time(weekday, time))) };
+
+    times_of_day.map_err(|_| ParseScheduleError::InvalidTime { value: value.to_string() })
+}
+
+fn set_once<T>(slot: &mut Option<T>, key: &'static str, value: T) -> Result<(), ParseScheduleError> {
+    if slot.is_some() {
+        return Err(ParseScheduleError::DuplicateField { key });
+    }
+
+    *slot = Some(value);
+    Ok(())
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = self.weekdays().extract_weekdays()
+            .map(weekday_name)
+            .collect::<Vec<&str>>()
+            .join(",");
+
+        write!(f, "{};interval={};days={};start={}", frequency_name(self.repeat()), self.interval(), days, self.start_date())?;
+
+        match self.weekday_policy() {
+            WeekdayPolicy::Skip => {},
+            WeekdayPolicy::Slide => write!(f, ";policy=slide")?,
+            WeekdayPolicy::Count => write!(f, ";policy=count")?,
+        }
+
+        if let Some(pattern) = self.shift_pattern() {
+            write!(f, ";shift={}:{}", pattern.cycle_days(), pattern.on_days().iter().map(|day| day.to_string()).collect::<Vec<String>>().join(","))?;
+        }
+
+        if let Some(months) = self.months() {
+            write!(f, ";months={}", months.names().collect::<Vec<&str>>().join(","))?;
+        }
+
+        if !self.iso_weeks().is_empty() {
+            write!(f, ";weeks={}", self.iso_weeks().iter().map(|week| week.to_string()).collect::<Vec<String>>().join(","))?;
+        }
+
+        if let Some(year_days) = self.year_days() {
+            write!(f, ";yeardays={}", year_days.days().iter().map(|day| day.to_string()).collect::<Vec<String>>().join(","))?;
+
+            if year_days.missing() == MissingDay::Clamp {
+                write!(f, ";missing=clamp")?;
+            }
+        }
+
+        if let Some(rule) = self.monthly_by() {
+            write!(f, ";{}", display_monthly_by(rule))?;
+        }
+
+        if !self.set_positions().is_empty() {
+            write!(f, ";setpos={}", self.set_positions().iter().map(|position| position.to_string()).collect::<Vec<String>>().join(","))?;
+        }
+
+        if let Some(until) = self.until() {
+            write!(f, ";until={}", until)?;
+        }
+
+        if let Some(count) = self.count() {
+            write!(f, ";count={}", count)?;
+        }
+
+        for (key, dates) in [("exdate", self.exclusions()), ("rdate", self.extra_dates())] {
+            if !dates.is_empty() {
+                write!(f, ";{}={}", key, dates.iter().map(|date| date.to_string()).collect::<Vec<String>>().join(","))?;
+            }
+        }
+
+        if let Some(times) = self.times_of_day() {
+            let by_weekday = WeekdayFlags::ANY.extract_weekdays()
+                .filter_map(|weekday| Some(format!("{}@{}", weekday_name(weekday), times.weekday_time(weekday)?)));
+
+            write!(f, ";times={}", times.times().iter().map(|time| time.to_string()).chain(by_weekday).collect::<Vec<String>>().join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists longer than this are cut short in `Schedule::summary`, ending in how many were left out.
+const SUMMARY_LIST_LIMIT: usize = 8;
+
+fn summary_list(items: impl IntoIterator<Item = String>) -> String {
+    let items = items.into_iter().collect::<Vec<String>>();
+
+    if items.len() <= SUMMARY_LIST_LIMIT {
+        return items.join(",");
+    }
+
+    format!("{},+{} more", items[..SUMMARY_LIST_LIMIT].join(","), items.len() - SUMMARY_LIST_LIMIT)
+}
+
+impl Schedule {
+    /// A single line naming the rule for logs, e.g. `weekly*2 on TUE,THU from 2023-09-12 until
+    /// 2025-12-31 except 3 dates`: the frequency with the interval when above 1, the weekdays unless
+    /// all are allowed, the other rule parts by their `Display` field names, the start, the end and
+    /// how many dates are excluded or added. Unlike `Display` it does not parse back, and lists
+    /// of more than eight entries are cut short.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![frequency_name(self.repeat()).to_string()];
+
+        if self.interval() > 1 {
+            parts[0] = format!("{}*{}", parts[0], self.interval());
+        }
+
+        if self.weekdays() & WeekdayFlags::ANY != WeekdayFlags::ANY {
+            parts.push(format!("on {}", self.weekdays()));
+        }
+
+        match self.weekday_policy() {
+            WeekdayPolicy::Skip => {},
+            WeekdayPolicy::Slide => parts.push("policy slide".to_string()),
+            WeekdayPolicy::Count => parts.push("policy count".to_string()),
+        }
+
+        if let Some(pattern) = self.shift_pattern() {
+            parts.push(format!("shift {}:{}", pattern.cycle_days(), summary_list(pattern.on_days().iter().map(|day| day.to_string()))));
+        }
+
+        if let Some(months) = self.months() {
+            parts.push(format!("months {}", months.names().collect::<Vec<&str>>().join(",")));
+        }
+
+        if !self.iso_weeks().is_empty() {
+            parts.push(format!("weeks {}", summary_list(self.iso_weeks().iter().map(|week| week.to_string()))));
+        }
+
+        if let Some(year_days) = self.year_days() {
+            parts.push(format!("yeardays {}", summary_list(year_days.days().iter().map(|day| day.to_string()))));
+
+            if year_days.missing() == MissingDay::Clamp {
+                parts.push("clamp".to_string());
+            }
+        }
+
+        match self.monthly_by() {
+            Some(MonthlyBy::Days { days, missing }) => {
+                parts.push(format!("monthdays {}", summary_list(days.iter().map(|day| day.get().to_string()))));
+
+                if *missing == MissingDay::Clamp {
+                    parts.push("clamp".to_string());
+                }
+            },
+            Some(MonthlyBy::NthWeekday { weekday, ordinals }) => {
+                parts.push(format!("nth {}", summary_list(ordinals.iter().map(|ordinal| format!("{}{}", ordinal, weekday_name(*weekday))))));
+            },
+            None => {},
+        }
+
+        if !self.set_positions().is_empty() {
+            parts.push(format!("setpos {}", summary_list(self.set_positions().iter().map(|position| position.to_string()))));
+        }
+
+        parts.push(format!("from {}", self.start_date()));
+
+        if let Some(until) = self.until() {
+            parts.push(format!("until {}", until));
+        }
+
+        if let Some(count) = self.count() {
+            parts.push(format!("count {}", count));
+        }
+
+        for (word, dates) in [("except", self.exclusions()), ("plus", self.extra_dates())] {
+            match dates.len() {
+                0 => {},
+                1 => parts.push(format!("{} 1 date", word)),
+                count => parts.push(format!("{} {} dates", word, count)),
+            }
+        }
+
+        if let Some(times) = self.times_of_day() {
+            let by_weekday = WeekdayFlags::ANY.extract_weekdays()
+                .filter_map(|weekday| Some(format!("{}@{}", weekday_name(weekday), times.weekday_time(weekday)?)));
+
+            parts.push(format!("at {}", summary_list(times.times().iter().map(|time| time.to_string()).chain(by_weekday))));
+        }
+
+        parts.join(" ")
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ParseScheduleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut fields = value.split(';');
+        let repeat = parse_frequency(fields.next().unwrap_or_default())?;
+
+        let mut interval = None;
+        let mut weekdays = None;
+        let mut start_date = None;
+        let mut policy = None;
+        let mut shift = None;
+        let mut months = None;
+        let mut weeks = None;
+        let mut year_days = None;
+        let mut monthly_by = None;
+        let mut missing = None;
+        let mut set_positions = None;
+        let mut until = None;
+        let mut count = None;
+        let mut exclusions = None;
+        let mut extra_dates = None;
+        let mut times = None;
+
+        for field in fields {
+            let (key, value) = field.split_once('=')
+                .ok_or_else(|| ParseScheduleError::MalformedField { field: field.to_string() })?;
+
+            match key {
+                "interval" => {
+                    let parsed = u64::from_str(value).ok().filter(|interval| *interval > 0)
+                        .ok_or_else(|| ParseScheduleError::InvalidInterval { value: value.to_string() })?;
+                    set_once(&mut interval, "interval", parsed)?;
+                },
+                "days" => set_once(&mut weekdays, "days", parse_days(value)?)?,
+                "start" => {
+                    let parsed = chrono::NaiveDate::from_str(value)
+                        .map_err(|_| ParseScheduleError::InvalidStartDate { value: value.to_string() })?;
+                    set_once(&mut start_date, "start", parsed)?;
+                },
+                "policy" => set_once(&mut policy, "policy", parse_policy(value)?)?,
+                "shift" => set_once(&mut shift, "shift", parse_shift(value)?)?,
+                "weeks" => set_once(&mut weeks, "weeks", parse_weeks(value)?)?,
+                "months" => set_once(&mut months, "months", parse_months(value)?)?,
+                "yeardays" => set_once(&mut year_days, "yeardays", parse_year_days(value)?)?,
+                "monthdays" | "nth" => {
+                    let key = if key == "nth" { "nth" } else { "monthdays" };
+
+                    if let Some((first, _)) = monthly_by {
+                        return Err(if first == key { ParseScheduleError::DuplicateField { key } } else { ParseScheduleError::ConflictingFields { first, second: key } });
+                    }
+
+                    let parsed = if key == "nth" { parse_nth(value)? } else { parse_month_days(value)? };
+                    monthly_by = Some((key, parsed));
+                },
+                "missing" => set_once(&mut missing, "missing", parse_missing_day(value)?)?,
+                "setpos" => set_once(&mut set_positions, "setpos", parse_set_positions(value)?)?,
+                "until" => {
+                    let parsed = chrono::NaiveDate::from_str(value)
+                        .map_err(|_| ParseScheduleError::InvalidUntilDate { value: value.to_string() })?;
+                    set_once(&mut until, "until", parsed)?;
+                },
+                "count" => {
+                    let parsed = u32::from_str(value).ok().filter(|count| *count > 0)
+                        .ok_or_else(|| ParseScheduleError::InvalidCount { value: value.to_string() })?;
+                    set_once(&mut count, "count", parsed)?;
+                },
+                "exdate" => {
+                    let parsed = parse_dates(value, |date| ParseScheduleError::InvalidExclusionDate { value: date.to_string() })?;
+                    set_once(&mut exclusions, "exdate", parsed)?;
+                },
+                "rdate" => {
+                    let parsed = parse_dates(value, |date| ParseScheduleError::InvalidExtraDate { value: date.to_string() })?;
+                    set_once(&mut extra_dates, "rdate", parsed)?;
+                },
+                "times" => set_once(&mut times, "times", parse_times(value)?)?,
+                _ => return Err(ParseScheduleError::UnknownField { key: key.to_string() }),
+            }
+        }
+
+        let start_date = start_date.ok_or(ParseScheduleError::MissingField { key: "start" })?;
+        let (monthly_by, year_days) = match (monthly_by, year_days, missing) {
+            (Some(("nth", _)), None, Some(_)) => return Err(ParseScheduleError::ConflictingFields { first: "nth", second: "missing" }),
+            (None, None, Some(_)) => return Err(ParseScheduleError::MissingField { key: "monthdays" }),
+            (rule, year_days, missing) => {
+                let missing = missing.unwrap_or_default();
+
+                (rule.map(|(_, rule)| rule.with_missing_days(missing)), year_days.map(|year_days: YearDays| year_days.with_missing_days(missing)))
+            },
+        };
+
+        Schedule::new(start_date, weekdays.unwrap_or(WeekdayFlags::ANY), repeat, interval.unwrap_or(1))
+            .and_then(|schedule| schedule.with_weekday_policy(policy.unwrap_or_default()))
+            .and_then(|schedule| schedule.with_shift_pattern(shift))
+            .and_then(|schedule| schedule.with_monthly_by(monthly_by))
+            .and_then(|schedule| schedule.with_months(months))
+            .and_then(|schedule| schedule.with_iso_weeks(weeks.unwrap_or_default()))
+            .and_then(|schedule| schedule.with_year_days(year_days))
+            .and_then(|schedule| schedule.with_set_positions(set_positions.unwrap_or_default()))
+            .and_then(|schedule| schedule.with_end(until, count))
+            .and_then(|schedule| schedule.with_exclusions(exclusions.unwrap_or_default()).with_extra_dates(extra_dates.unwrap_or_default()))
+            .and_then(|schedule| schedule.with_times_of_day(times))
+            .map_err(ParseScheduleError::InvalidRule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    #[rstest]
+    #[case::minimal("daily;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2023-09-12", "daily from 2023-09-12")]
+    #[case::documented("weekly;interval=2;days=TUE,THU;start=2023-09-12;until=2025-12-31;exdate=2023-09-14,2023-09-26,2023-09-28", "weekly*2 on TUE,THU from 2023-09-12 until 2025-12-31 except 3 dates")]
+    #[case::count_and_single_dates("monthly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-31;count=12;exdate=2024-03-31;rdate=2024-02-29", "monthly from 2024-01-31 count 12 except 1 date plus 1 date")]
+    #[case::business_days("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2023-09-11;policy=count", "daily*3 on MIDWEEK policy count from 2023-09-11")]
+    #[case::month_days("monthly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-31;monthdays=15,31;missing=clamp", "monthly monthdays 15,31 clamp from 2024-01-31")]
+    #[case::nth_weekday("yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2023-11-23;nth=4THU", "yearly nth 4THU from 2023-11-23")]
+    #[case::months("yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-03-31;months=MAR,JUN,SEP,DEC", "yearly months MAR,JUN,SEP,DEC from 2024-03-31")]
+    #[case::long_lists(
+        "yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-01;yeardays=1,2,3,4,5,6,7,8,9,10,11,12;\
+            exdate=2024-01-02,2024-01-03,2024-01-04,2024-01-05,2024-01-06,2024-01-07,2024-01-08,2024-01-09,\
+            2024-01-10,2024-01-11,2024-01-12,2024-01-13,2024-01-14,2024-01-15,2024-01-16,2024-01-17,2024-01-18,\
+            2024-01-19,2024-01-20,2024-01-21",
+        "yearly yeardays 1,2,3,4,5,6,7,8,+4 more from 2024-01-01 except 20 dates"
+    )]
+    #[case::times("daily;interval=1;days=SAT,SUN;start=2023-09-16;times=09:00:00,17:30:00", "daily on WEEKEND from 2023-09-16 at 09:00:00,17:30:00")]
+    fn summaries(#[case] rule: &str, #[case] expected: &str) {
+        let schedule = Schedule::from_str(rule).unwrap();
+
+        assert_eq!(expected, schedule.summary());
+        assert!(!schedule.summary().contains('\n'));
+    }
+
+    #[test]
+    fn display_matches_documented_example() {
+        let schedule = Schedule::new(date("2023-09-21"), WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2).unwrap();
+
+        assert_eq!("weekly;interval=2;days=TUE,THU;start=2023-09-21", schedule.to_string());
+    }
+
+    #[test]
+    fn display_appends_until() {
+        let schedule = Schedule::builder().start(date("2023-09-21")).weekly().until(date("2023-12-31")).build().unwrap();
+
+        assert_eq!("weekly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2023-09-21;until=2023-12-31", schedule.to_string());
+    }
+
+    #[test]
+    fn display_appends_count() {
+        let schedule = Schedule::builder().start(date("2023-09-21")).monthly().count(10).build().unwrap();
+
+        assert_eq!("monthly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2023-09-21;count=10", schedule.to_string());
+    }
+
+    #[test]
+    fn display_appends_exclusions() {
+        let schedule = Schedule::builder().start(date("2023-09-21")).exclude(date("2023-10-05")).exclude(date("2023-09-28")).build().unwrap();
+
+        assert_eq!("daily;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2023-09-21;exdate=2023-09-28,2023-10-05", schedule.to_string());
+    }
+
+    #[test]
+    fn display_appends_extra_dates() {
+        let schedule = Schedule::builder().start(date("2023-09-21")).weekly().on(WeekdayFlags::THU).include(date("2023-09-25")).build().unwrap();
+
+        assert_eq!("weekly;interval=1;days=THU;start=2023-09-21;rdate=2023-09-25", schedule.to_string());
+    }
+
+    #[test]
+    fn round_trips_rule_matrix() {
+        let starts = [date("2023-09-18"), date("2024-02-29"), date("1899-12-31"), date("+12345-06-07"), date("-0044-03-15")];
+        let repeats = [Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Quarterly, Repeating::Yearly];
+
+        for start in starts {
+            for repeat in repeats {
+                for interval in [1, 2, 7, i32::MAX as u64] {
+                    // Every weekday set that contains the start's weekday.
+                    for bits in 1..=WeekdayFlags::ANY.bits() {
+                        let weekdays = WeekdayFlags::from_bits_masked(bits);
+
+                        let Ok(schedule) = Schedule::new(start, weekdays, repeat, interval) else { continue };
+                        let text = schedule.to_string();
+
+                        assert_eq!(Ok(schedule.clone()), Schedule::from_str(&text), "{}", text);
+
+                        let bounded = schedule.with_end(start.checked_add_days(chrono::Days::new(30)), Some(interval as u32 % 5 + 1)).unwrap()
+                            .with_exclusions([start, start.checked_add_days(chrono::Days::new(interval % 9)).unwrap()].into())
+                            .with_extra_dates([start.checked_add_days(chrono::Days::new(interval % 11)).unwrap()].into()).unwrap();
+                        let text = bounded.to_string();
+
+                        assert_eq!(Ok(bounded), Schedule::from_str(&text), "{}", text);
+                    }
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::defaults("monthly;start=2023-09-05", Repeating::Monthly, 1, WeekdayFlags::ANY)]
+    #[case::any_order("weekly;start=2023-09-12;days=THU,TUE;interval=3", Repeating::Weekly, 3, WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::quarterly("quarterly;start=2023-09-05;interval=2", Repeating::Quarterly, 2, WeekdayFlags::ANY)]
+    fn parses_optional_and_reordered_fields(#[case] text: &str, #[case] repeat: Repeating, #[case] interval: u64, #[case] weekdays: WeekdayFlags) {
+        let schedule = Schedule::from_str(text).unwrap();
+
+        assert_eq!(repeat, schedule.repeat());
+        assert_eq!(interval, schedule.interval());
+        assert_eq!(weekdays, schedule.weekdays());
+    }
+
+    #[rstest]
+    #[case::empty("", ParseScheduleError::UnknownFrequency { value: "".to_string() })]
+    #[case::frequency("hourly;start=2023-09-12", ParseScheduleError::UnknownFrequency { value: "hourly".to_string() })]
+    #[case::frequency_case("Weekly;start=2023-09-12", ParseScheduleError::UnknownFrequency { value: "Weekly".to_string() })]
+    #[case::no_equals("weekly;start", ParseScheduleError::MalformedField { field: "start".to_string() })]
+    #[case::unknown_key("weekly;byhour=9;start=2023-09-12", ParseScheduleError::UnknownField { key: "byhour".to_string() })]
+    #[case::duplicate("weekly;interval=1;interval=2;start=2023-09-12", ParseScheduleError::DuplicateField { key: "interval" })]
+    #[case::missing_start("weekly;interval=2", ParseScheduleError::MissingField { key: "start" })]
+    #[case::zero_interval("weekly;interval=0;start=2023-09-12", ParseScheduleError::InvalidInterval { value: "0".to_string() })]
+    #[case::negative_interval("weekly;interval=-1;start=2023-09-12", ParseScheduleError::InvalidInterval { value: "-1".to_string() })]
+    #[case::day_name("weekly;days=TUE,THURS;start=2023-09-12", ParseScheduleError::InvalidWeekday { value: "THURS".to_string() })]
+    #[case::repeated_day("weekly;days=TUE,TUE;start=2023-09-12", ParseScheduleError::InvalidWeekday { value: "TUE".to_string() })]
+    #[case::trailing_comma("weekly;days=TUE,;start=2023-09-12", ParseScheduleError::InvalidWeekday { value: "".to_string() })]
+    #[case::start_date("weekly;start=2023-02-30", ParseScheduleError::InvalidStartDate { value: "2023-02-30".to_string() })]
+    #[case::until_date("weekly;start=2023-09-12;until=never", ParseScheduleError::InvalidUntilDate { value: "never".to_string() })]
+    #[case::until_before_start("weekly;start=2023-09-12;until=2023-09-11", ParseScheduleError::InvalidRule(RepeatingDateError::UntilBeforeStart))]
+    #[case::zero_count("weekly;start=2023-09-12;count=0", ParseScheduleError::InvalidCount { value: "0".to_string() })]
+    #[case::duplicate_count("weekly;count=2;start=2023-09-12;count=3", ParseScheduleError::DuplicateField { key: "count" })]
+    #[case::exclusion_date("weekly;start=2023-09-12;exdate=2023-09-19,tomorrow", ParseScheduleError::InvalidExclusionDate { value: "tomorrow".to_string() })]
+    #[case::extra_date("weekly;start=2023-09-12;rdate=2023-09-18,", ParseScheduleError::InvalidExtraDate { value: "".to_string() })]
+    #[case::extra_date_before_start("weekly;start=2023-09-12;rdate=2023-09-11", ParseScheduleError::InvalidRule(RepeatingDateError::ExtraDateBeforeStart))]
+    #[case::empty_days("weekly;days=;start=2023-09-12", ParseScheduleError::InvalidRule(RepeatingDateError::EmptyWeekdaySet))]
+    #[case::wrong_weekday("weekly;days=MON;start=2023-09-12", ParseScheduleError::InvalidRule(RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Tue, allowed: WeekdayFlags::MON }))]
+    fn rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn errors_name_the_field() {
+        let message = Schedule::from_str("daily;interval=x;start=2023-09-12").unwrap_err().to_string();
+
+        assert_eq!("interval `x` is not a positive integer", message);
+    }
+
+    #[test]
+    fn nth_weekday_round_trip() {
+        let schedule = Schedule::from_str("monthly;start=2024-01-01;nth=3MON,1MON").unwrap();
+
+        assert_eq!("monthly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-01;nth=1MON,3MON", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[test]
+    fn yearly_nth_weekday_round_trip() {
+        let schedule = Schedule::from_str("yearly;start=2024-11-28;months=NOV;nth=4THU").unwrap();
+
+        assert_eq!("yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-11-28;months=NOV;nth=4THU", schedule.to_string());
+        assert_eq!(Ok(chrono::NaiveDate::from_ymd_opt(2025, 11, 27).unwrap()), schedule.next_after(schedule.start_date()));
+    }
+
+    #[test]
+    fn nth_counting_from_the_end() {
+        let schedule = Schedule::from_str("monthly;start=2024-01-26;nth=-1FRI").unwrap();
+
+        assert_eq!(Some(&MonthlyBy::last_weekday(chrono::Weekday::Fri)), schedule.monthly_by());
+        assert!(schedule.to_string().ends_with(";nth=-1FRI"));
+    }
+
+    #[test]
+    fn month_days_round_trip() {
+        let schedule = Schedule::from_str("monthly;start=2024-01-31;monthdays=-1").unwrap();
+
+        assert_eq!(Some(&MonthlyBy::last_day()), schedule.monthly_by());
+        assert_eq!("monthly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-31;monthdays=-1", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[test]
+    fn iso_weeks_round_trip() {
+        let schedule = Schedule::from_str("yearly;days=MON;start=2024-08-12;weeks=33").unwrap();
+
+        assert_eq!(&BTreeSet::from([33]), schedule.iso_weeks());
+        assert_eq!("yearly;interval=1;days=MON;start=2024-08-12;weeks=33", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[rstest]
+    #[case::zero("yearly;start=2024-08-12;weeks=0", ParseScheduleError::InvalidWeekNumber { value: "0".to_string() })]
+    #[case::beyond_53("yearly;start=2024-08-12;weeks=33,54", ParseScheduleError::InvalidWeekNumber { value: "54".to_string() })]
+    #[case::repeated("yearly;start=2024-08-12;weeks=33,33", ParseScheduleError::InvalidWeekNumber { value: "33".to_string() })]
+    #[case::not_yearly("monthly;start=2024-08-12;weeks=33", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidWeekNumber))]
+    #[case::with_months("yearly;start=2024-08-12;months=AUG;weeks=33", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidWeekNumber))]
+    #[case::start_outside("yearly;start=2024-08-19;weeks=33", ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule))]
+    fn iso_weeks_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn year_days_round_trip() {
+        let schedule = Schedule::from_str("yearly;start=2024-12-31;yeardays=366,100;missing=clamp").unwrap();
+
+        assert_eq!(Some(&YearDays::new([100, 366]).unwrap().with_missing_days(MissingDay::Clamp)), schedule.year_days());
+        assert_eq!("yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-12-31;yeardays=100,366;missing=clamp", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[rstest]
+    #[case::zero("yearly;start=2024-04-09;yeardays=0", ParseScheduleError::InvalidYearDay { value: "0".to_string() })]
+    #[case::beyond_366("yearly;start=2024-04-09;yeardays=100,367", ParseScheduleError::InvalidYearDay { value: "367".to_string() })]
+    #[case::repeated("yearly;start=2024-04-09;yeardays=100,100", ParseScheduleError::InvalidYearDay { value: "100".to_string() })]
+    #[case::not_yearly("monthly;start=2024-04-09;yeardays=100", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidYearDayRule))]
+    #[case::with_month_days("yearly;start=2024-04-09;yeardays=100;monthdays=9", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidYearDayRule))]
+    #[case::start_outside("yearly;start=2024-04-10;yeardays=100", ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule))]
+    fn year_days_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn set_positions_round_trip() {
+        let schedule = Schedule::from_str("monthly;days=MON,TUE,WED,THU,FRI;start=2024-01-31;setpos=-1").unwrap();
+
+        assert_eq!(&BTreeSet::from([-1]), schedule.set_positions());
+        assert_eq!("monthly;interval=1;days=MON,TUE,WED,THU,FRI;start=2024-01-31;setpos=-1", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+        assert_eq!("weekly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-02;setpos=-1,2", Schedule::from_str("weekly;start=2024-01-02;setpos=2,-1").unwrap().to_string());
+    }
+
+    #[rstest]
+    #[case::zero("weekly;start=2024-01-02;setpos=0", ParseScheduleError::InvalidSetPosition { value: "0".to_string() })]
+    #[case::repeated("weekly;start=2024-01-02;setpos=2,2", ParseScheduleError::InvalidSetPosition { value: "2".to_string() })]
+    #[case::not_a_number("weekly;start=2024-01-02;setpos=last", ParseScheduleError::InvalidSetPosition { value: "last".to_string() })]
+    #[case::daily("daily;start=2024-01-02;setpos=1", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidSetPosition))]
+    #[case::beyond_a_year("yearly;start=2024-01-02;setpos=367", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidSetPosition))]
+    #[case::start_not_picked("weekly;start=2024-01-03;setpos=2", ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule))]
+    fn set_positions_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn times_round_trip() {
+        let schedule = Schedule::from_str("daily;start=2024-03-01;times=20:00,08:00:30").unwrap();
+
+        assert_eq!("daily;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-03-01;times=08:00:30,20:00:00", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+        assert_eq!(Err(ParseScheduleError::InvalidTime { value: "8am".to_string() }), Schedule::from_str("daily;start=2024-03-01;times=08:00,8am"));
+        assert_eq!(Err(ParseScheduleError::InvalidTime { value: "08:00".to_string() }), Schedule::from_str("daily;start=2024-03-01;times=08:00,08:00"));
+    }
+
+    #[test]
+    fn weekday_times_round_trip() {
+        let schedule = Schedule::from_str("weekly;days=MON,THU;start=2024-03-04;times=THU@14:00,MON@09:00").unwrap();
+
+        assert_eq!("weekly;interval=1;days=MON,THU;start=2024-03-04;times=MON@09:00:00,THU@14:00:00", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+        assert_eq!("weekly;interval=1;days=MON,THU;start=2024-03-04;times=08:00:00,THU@14:00:00", Schedule::from_str("weekly;days=MON,THU;start=2024-03-04;times=THU@14:00,08:00").unwrap().to_string());
+        assert_eq!(Err(ParseScheduleError::InvalidTime { value: "MON@10:00".to_string() }), Schedule::from_str("weekly;days=MON;start=2024-03-04;times=MON@09:00,MON@10:00"));
+        assert_eq!(Err(ParseScheduleError::InvalidTime { value: "MO@09:00".to_string() }), Schedule::from_str("weekly;days=MON;start=2024-03-04;times=MO@09:00"));
+        assert_eq!(Err(ParseScheduleError::InvalidRule(RepeatingDateError::MissingWeekdayTime)), Schedule::from_str("weekly;days=MON,THU;start=2024-03-04;times=MON@09:00"));
+    }
+
+    #[test]
+    fn shift_round_trip() {
+        let schedule = Schedule::from_str("daily;start=2024-01-01;shift=8:3,0,1,2").unwrap();
+
+        assert_eq!(Some(&ShiftPattern::on_off(4, 4).unwrap()), schedule.shift_pattern());
+        assert_eq!("daily;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-01;shift=8:0,1,2,3", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[rstest]
+    #[case::no_colon("daily;start=2024-01-01;shift=8", ParseScheduleError::InvalidShift { value: "8".to_string() })]
+    #[case::repeated("daily;start=2024-01-01;shift=8:0,0", ParseScheduleError::InvalidShift { value: "8:0,0".to_string() })]
+    #[case::outside_cycle("daily;start=2024-01-01;shift=4:0,4", ParseScheduleError::InvalidShift { value: "4:0,4".to_string() })]
+    #[case::empty_cycle("daily;start=2024-01-01;shift=0:0", ParseScheduleError::InvalidShift { value: "0:0".to_string() })]
+    #[case::not_daily("weekly;start=2024-01-01;shift=8:0", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidShiftRule))]
+    #[case::start_off("daily;start=2024-01-01;shift=8:1", ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule))]
+    fn shift_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn policy_round_trip() {
+        let schedule = Schedule::from_str("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-01;policy=slide").unwrap();
+
+        assert_eq!(WeekdayPolicy::Slide, schedule.weekday_policy());
+        assert_eq!("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-01;policy=slide", schedule.to_string());
+        assert_eq!("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-01", Schedule::from_str("daily;interval=3;days=MON,TUE,WED,THU,FRI;start=2024-01-01;policy=skip").unwrap().to_string());
+        assert_eq!(Err(ParseScheduleError::InvalidPolicy { value: "next".to_string() }), Schedule::from_str("daily;start=2024-01-01;policy=next"));
+        assert_eq!(Err(ParseScheduleError::InvalidRule(RepeatingDateError::InvalidWeekdayPolicy)), Schedule::from_str("weekly;start=2024-01-01;policy=slide"));
+
+        let counting = Schedule::builder().start(date("2024-01-01")).business_days().interval(5).build().unwrap();
+
+        assert_eq!("daily;interval=5;days=MON,TUE,WED,THU,FRI;start=2024-01-01;policy=count", counting.to_string());
+        assert_eq!(counting, Schedule::from_str(&counting.to_string()).unwrap());
+    }
+
+    #[test]
+    fn months_round_trip() {
+        let schedule = Schedule::from_str("yearly;start=2024-03-12;months=DEC,MAR,SEP,JUN").unwrap();
+
+        assert_eq!(Some(MonthFlags::QUARTER_ENDS), schedule.months());
+        assert_eq!("yearly;interval=1;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-03-12;months=MAR,JUN,SEP,DEC", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+    }
+
+    #[rstest]
+    #[case::unknown("yearly;start=2024-03-12;months=MAR,JUNE", ParseScheduleError::InvalidMonth { value: "JUNE".to_string() })]
+    #[case::repeated("yearly;start=2024-03-12;months=MAR,MAR", ParseScheduleError::InvalidMonth { value: "MAR".to_string() })]
+    #[case::empty("yearly;start=2024-03-12;months=", ParseScheduleError::InvalidMonth { value: "".to_string() })]
+    #[case::not_yearly("monthly;start=2024-03-12;months=MAR", ParseScheduleError::InvalidRule(RepeatingDateError::InvalidMonthSet))]
+    #[case::start_outside("yearly;start=2024-04-12;months=MAR", ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule))]
+    fn months_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[test]
+    fn clamped_month_days_round_trip() {
+        let schedule = Schedule::from_str("monthly;interval=2;start=2024-01-15;missing=clamp;monthdays=31,15,1").unwrap();
+
+        assert_eq!(Some(&MonthlyBy::days(&[1, 15, 31]).unwrap().with_missing_days(MissingDay::Clamp)), schedule.monthly_by());
+        assert_eq!("monthly;interval=2;days=MON,TUE,WED,THU,FRI,SAT,SUN;start=2024-01-15;monthdays=1,15,31;missing=clamp", schedule.to_string());
+        assert_eq!(schedule, Schedule::from_str(&schedule.to_string()).unwrap());
+        assert_eq!(Schedule::from_str("monthly;start=2024-01-01;monthdays=1").unwrap(), Schedule::from_str("monthly;start=2024-01-01;monthdays=1;missing=skip").unwrap());
+    }
+
+    #[rstest]
+    #[case::unknown_policy("monthly;start=2024-01-31;monthdays=31;missing=last", ParseScheduleError::InvalidMissingDay { value: "last".to_string() })]
+    #[case::without_month_days("monthly;start=2024-01-31;missing=clamp", ParseScheduleError::MissingField { key: "monthdays" })]
+    #[case::with_nth("monthly;start=2024-01-01;nth=1MON;missing=clamp", ParseScheduleError::ConflictingFields { first: "nth", second: "missing" })]
+    #[case::duplicate("monthly;start=2024-01-31;monthdays=31;missing=clamp;missing=skip", ParseScheduleError::DuplicateField { key: "missing" })]
+    fn missing_policy_rejects_malformed(#[case] text: &str, #[case] expected: ParseScheduleError) {
+        assert_eq!(Err(expected), Schedule::from_str(text));
+    }
+
+    #[rstest]
+    #[case::zero("0", "0")]
+    #[case::too_large("15,32", "32")]
+    #[case::repeated("-1,-1", "-1")]
+    #[case::not_a_number("last", "last")]
+    fn month_days_rejects_malformed(#[case] days: &str, #[case] value: &str) {
+        let error = Schedule::from_str(&format!("monthly;start=2024-01-31;monthdays={}", days)).unwrap_err();
+
+        assert_eq!(ParseScheduleError::InvalidMonthDay { value: value.to_string() }, error);
+    }
+
+    #[test]
+    fn month_days_and_nth_exclude_each_other() {
+        assert_eq!(Err(ParseScheduleError::ConflictingFields { first: "monthdays", second: "nth" }), Schedule::from_str("monthly;start=2024-01-01;monthdays=1;nth=1MON"));
+        assert_eq!(Err(ParseScheduleError::DuplicateField { key: "nth" }), Schedule::from_str("monthly;start=2024-01-01;nth=1MON;nth=1MON"));
+    }
+
+    #[rstest]
+    #[case::mixed_weekdays("1MON,3TUE", "3TUE")]
+    #[case::repeated("1MON,1MON", "1MON")]
+    #[case::no_ordinal("MON", "MON")]
+    #[case::no_weekday("1", "1")]
+    #[case::unknown_weekday("1MONDAY", "1MONDAY")]
+    fn nth_rejects_malformed(#[case] nth: &str, #[case] value: &str) {
+        let error = Schedule::from_str(&format!("monthly;start=2024-01-01;nth={}", nth)).unwrap_err();
+
+        assert_eq!(ParseScheduleError::InvalidNthWeekday { value: value.to_string() }, error);
+    }
+
+    #[test]
+    fn nth_checks_the_rule() {
+        assert_eq!(Err(ParseScheduleError::InvalidRule(RepeatingDateError::StartNotInRule)), Schedule::from_str("monthly;start=2024-01-02;nth=1MON"));
+        assert_eq!(Err(ParseScheduleError::InvalidRule(RepeatingDateError::InvalidMonthlyRule)), Schedule::from_str("weekly;start=2024-01-01;nth=1MON"));
+    }
+}
@@ -0,0 +1,1561 @@
+use std::collections::BTreeSet;
+
+use chrono::Datelike;
+
+use crate::{
+    check_interval, day_after, find_next_date, get_months_since, is_on_week, match_repeating_date, week_start_day, weeks_between,
+    DEFAULT_MAX_YEAR_SKIP, MissingDay, MonthDay, MonthFlags, MonthlyBy, Repeating, RepeatingDateError, ShiftPattern, TimesOfDay,
+    WeekStart, WeekdayFlags, WeekdayPolicy, YearDays,
+};
+
+/// A recurrence rule: the start date together with the frequency, interval and allowed weekdays
+/// that the free functions otherwise take as separate parameters.
+///
+/// The start date is the first occurrence of the series; `next_after` and `previous_before` walk
+/// the series from any date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Schedule {
+    start_date: chrono::NaiveDate,
+    weekdays: WeekdayFlags,
+    repeat: Repeating,
+    interval: u64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "WeekdayPolicy::is_skip"))]
+    weekday_policy: WeekdayPolicy,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    shift_pattern: Option<ShiftPattern>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    monthly_by: Option<MonthlyBy>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    months: Option<MonthFlags>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeSet::is_empty"))]
+    set_positions: BTreeSet<i16>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeSet::is_empty"))]
+    iso_weeks: BTreeSet<u8>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    year_days: Option<YearDays>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    until: Option<chrono::NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    count: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeSet::is_empty"))]
+    exclusions: BTreeSet<chrono::NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeSet::is_empty"))]
+    extra_dates: BTreeSet<chrono::NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    times_of_day: Option<TimesOfDay>,
+    // The `count`-th generated date and the final one, found once by `with_end` as the rule's
+    // parameters are fixed by then, instead of on every lookup.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    counted_last: Option<chrono::NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    last_generated: Option<chrono::NaiveDate>
+}
+
+/// Step by step construction of a `Schedule`; the frequency defaults to daily, the interval to 1
+/// and the weekdays to `WeekdayFlags::ANY`.
+#[derive(Debug, Clone)]
+pub struct ScheduleBuilder {
+    start_date: Option<chrono::NaiveDate>,
+    weekdays: WeekdayFlags,
+    repeat: Repeating,
+    interval: u64,
+    weekday_policy: WeekdayPolicy,
+    shift_pattern: Option<ShiftPattern>,
+    monthly_by: Option<MonthlyBy>,
+    months: Option<MonthFlags>,
+    set_positions: BTreeSet<i16>,
+    iso_weeks: BTreeSet<u8>,
+    year_days: Option<YearDays>,
+    until: Option<chrono::NaiveDate>,
+    count: Option<u32>,
+    exclusions: BTreeSet<chrono::NaiveDate>,
+    extra_dates: BTreeSet<chrono::NaiveDate>,
+    times_of_day: Option<TimesOfDay>,
+    adjust_start: bool
+}
+
+impl Default for ScheduleBuilder {
+    fn default() -> Self {
+        ScheduleBuilder {
+            start_date: None,
+            weekdays: WeekdayFlags::ANY,
+            repeat: Repeating::Daily,
+            interval: 1,
+            weekday_policy: WeekdayPolicy::Skip,
+            shift_pattern: None,
+            monthly_by: None,
+            months: None,
+            set_positions: BTreeSet::new(),
+            iso_weeks: BTreeSet::new(),
+            year_days: None,
+            until: None,
+            count: None,
+            exclusions: BTreeSet::new(),
+            extra_dates: BTreeSet::new(),
+            times_of_day: None,
+            adjust_start: false,
+        }
+    }
+}
+
+impl ScheduleBuilder {
+    pub fn start(mut self, start_date: chrono::NaiveDate) -> Self {
+        self.start_date = Some(start_date);
+        self
+    }
+
+    /// Moves a start on a disallowed weekday forward to the next allowed one instead of failing with
+    /// `WrongWeekday`; the interval counts from the moved start, which `Schedule::start_date` gives.
+    pub fn adjust_start_to_weekdays(mut self) -> Self {
+        self.adjust_start = true;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeating) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn daily(self) -> Self {
+        self.repeat(Repeating::Daily)
+    }
+
+    pub fn weekly(self) -> Self {
+        self.repeat(Repeating::Weekly)
+    }
+
+    pub fn monthly(self) -> Self {
+        self.repeat(Repeating::Monthly)
+    }
+
+    pub fn quarterly(self) -> Self {
+        self.repeat(Repeating::Quarterly)
+    }
+
+    pub fn yearly(self) -> Self {
+        self.repeat(Repeating::Yearly)
+    }
+
+    pub fn interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn on(mut self, weekdays: WeekdayFlags) -> Self {
+        self.weekdays = weekdays;
+        self
+    }
+
+    /// How a daily schedule treats steps landing on a disallowed weekday, skipping them by default.
+    pub fn weekday_policy(mut self, policy: WeekdayPolicy) -> Self {
+        self.weekday_policy = policy;
+        self
+    }
+
+    /// Repeats a daily schedule in cycles of working days counted from the start, which must be an
+    /// on day; the interval counts cycles.
+    pub fn shift_pattern(mut self, shift_pattern: ShiftPattern) -> Self {
+        self.shift_pattern = Some(shift_pattern);
+        self
+    }
+
+    /// Daily on the `WeekdayFlags::MIDWEEK` working days with the interval counted in working days,
+    /// see `WeekdayPolicy::Count`; `on` changes the working days.
+    pub fn business_days(mut self) -> Self {
+        self.repeat = Repeating::Daily;
+        self.weekdays = WeekdayFlags::MIDWEEK;
+        self.weekday_policy = WeekdayPolicy::Count;
+        self
+    }
+
+    /// Picks the days of each on-month for a monthly or quarterly schedule, or of the start's month
+    /// (or each of `months`) for a yearly one; the start must be one of them.
+    pub fn monthly_by(mut self, monthly_by: MonthlyBy) -> Self {
+        self.monthly_by = Some(monthly_by);
+        self
+    }
+
+    /// Restricts a yearly schedule to the given months, on the start's day of month in each unless a
+    /// `MonthlyBy` picks the days; the start's month must be one of them.
+    pub fn months(mut self, months: MonthFlags) -> Self {
+        self.months = Some(months);
+        self
+    }
+
+    /// Places a yearly schedule in the given ISO 8601 weeks `1..=53` of each ISO year, on the
+    /// allowed weekdays of those weeks; the start must be in one of them.
+    pub fn iso_weeks(mut self, weeks: impl IntoIterator<Item = u8>) -> Self {
+        self.iso_weeks = weeks.into_iter().collect();
+        self
+    }
+
+    /// Places a yearly schedule on the given days of each year instead of the start's month and
+    /// day; the start must be one of them.
+    pub fn year_days(mut self, year_days: YearDays) -> Self {
+        self.year_days = Some(year_days);
+        self
+    }
+
+    /// Keeps only the dates at these one based positions among each week's, month's or year's
+    /// candidates, negative positions counting from the end; see `Schedule::set_positions`.
+    pub fn set_positions(mut self, positions: impl IntoIterator<Item = i16>) -> Self {
+        self.set_positions = positions.into_iter().collect();
+        self
+    }
+
+    /// The last date the schedule may produce, inclusive.
+    pub fn until(mut self, until: chrono::NaiveDate) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Ends the schedule after this many occurrences, the start included.
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Removes a single occurrence from the series; dates that are not occurrences are ignored.
+    pub fn exclude(mut self, date: chrono::NaiveDate) -> Self {
+        self.exclusions.insert(date);
+        self
+    }
+
+    /// Expands every occurrence date into these times of day, see `Schedule::datetimes`.
+    pub fn times_of_day(mut self, times_of_day: TimesOfDay) -> Self {
+        self.times_of_day = Some(times_of_day);
+        self
+    }
+
+    /// Adds a one-off occurrence outside the rule; it must not be before the start.
+    pub fn include(mut self, date: chrono::NaiveDate) -> Self {
+        self.extra_dates.insert(date);
+        self
+    }
+
+    pub fn build(self) -> Result<Schedule, RepeatingDateError> {
+        let mut start_date = self.start_date.ok_or(RepeatingDateError::MissingStartDate)?;
+
+        if self.adjust_start {
+            // Every allowed weekday comes round within a week.
+            start_date = start_date.iter_days().take(7).find(|date| self.weekdays.contains(WeekdayFlags::from_weekday(date.weekday()))).unwrap_or(start_date);
+        }
+
+        let schedule = Schedule::new(start_date, self.weekdays, self.repeat, self.interval)?
+            .with_weekday_policy(self.weekday_policy)?
+            .with_shift_pattern(self.shift_pattern)?
+            .with_monthly_by(self.monthly_by)?
+            .with_months(self.months)?
+            .with_iso_weeks(self.iso_weeks)?
+            .with_year_days(self.year_days)?
+            .with_set_positions(self.set_positions)?
+            .with_end(self.until, self.count)?;
+
+        schedule.with_exclusions(self.exclusions).with_extra_dates(self.extra_dates)?.with_times_of_day(self.times_of_day)
+    }
+}
+
+impl Schedule {
+    pub fn builder() -> ScheduleBuilder {
+        ScheduleBuilder::default()
+    }
+
+    /// Validates the rule once, so that the lookups don't have to report a bad rule on every call.
+    pub fn new(start_date: chrono::NaiveDate, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<Schedule, RepeatingDateError> {
+        if matches!(repeat, Repeating::Hourly | Repeating::Minutely) {
+            return Err(RepeatingDateError::UnsupportedFrequency);
+        }
+
+        if interval == 0 {
+            return Err(RepeatingDateError::ZeroInterval);
+        }
+
+        check_interval(repeat, interval)?;
+
+        if WeekdayFlags::from_bits_masked(weekdays.bits()).is_empty() {
+            return Err(RepeatingDateError::EmptyWeekdaySet);
+        }
+
+        if !weekdays.contains(WeekdayFlags::from_weekday(start_date.weekday())) {
+            return Err(RepeatingDateError::WrongWeekday { start_weekday: start_date.weekday(), allowed: weekdays });
+        }
+
+        Ok(Schedule {
+            start_date,
+            weekdays,
+            repeat,
+            interval,
+            weekday_policy: WeekdayPolicy::Skip,
+            shift_pattern: None,
+            monthly_by: None,
+            months: None,
+            set_positions: BTreeSet::new(),
+            iso_weeks: BTreeSet::new(),
+            year_days: None,
+            until: None,
+            count: None,
+            exclusions: BTreeSet::new(),
+            extra_dates: BTreeSet::new(),
+            times_of_day: None,
+            counted_last: None,
+            last_generated: None,
+        })
+    }
+
+    /// Every day from `start_date` on.
+    pub fn daily(start_date: chrono::NaiveDate) -> Result<Schedule, RepeatingDateError> {
+        Schedule::every_n_days(start_date, 1)
+    }
+
+    /// Every `n`-th day from `start_date` on; `ZeroInterval` for `n = 0`.
+    pub fn every_n_days(start_date: chrono::NaiveDate, n: u64) -> Result<Schedule, RepeatingDateError> {
+        Schedule::new(start_date, WeekdayFlags::ANY, Repeating::Daily, n)
+    }
+
+    /// Every week on `weekdays`, see `every_n_weeks_on`.
+    pub fn weekly_on(start_date: chrono::NaiveDate, weekdays: WeekdayFlags) -> Result<Schedule, RepeatingDateError> {
+        Schedule::every_n_weeks_on(start_date, 1, weekdays)
+    }
+
+    /// Every `n`-th week on `weekdays`, starting on the first of them on or after `start_date` as
+    /// with `ScheduleBuilder::adjust_start_to_weekdays`; `EmptyWeekdaySet` for no weekdays.
+    pub fn every_n_weeks_on(start_date: chrono::NaiveDate, n: u64, weekdays: WeekdayFlags) -> Result<Schedule, RepeatingDateError> {
+        Schedule::builder().start(start_date).weekly().interval(n).on(weekdays).adjust_start_to_weekdays().build()
+    }
+
+    /// Every `interval`-th week on the start's weekday, the most common weekly rule, which lookups
+    /// resolve by plain arithmetic on `interval * 7` day steps.
+    pub fn weekly_same_day(start_date: chrono::NaiveDate, interval: u64) -> Result<Schedule, RepeatingDateError> {
+        Schedule::new(start_date, WeekdayFlags::from_weekday(start_date.weekday()), Repeating::Weekly, interval)
+    }
+
+    /// Every month on `day`, starting on its first occurrence on or after `start_date`. `missing`
+    /// says whether months lacking the day, the 31st in April say, are skipped or fall on their
+    /// last day; there is no default, as either surprises someone.
+    pub fn monthly_on_day(start_date: chrono::NaiveDate, day: MonthDay, missing: MissingDay) -> Result<Schedule, RepeatingDateError> {
+        let rule = MonthlyBy::Days { days: BTreeSet::from([day]), missing };
+        // Any day of month comes round within a few months.
+        let start_date = first_picked(start_date, start_date.with_day(1), 1, &rule)?;
+
+        Schedule::builder().start(start_date).monthly().monthly_by(rule).build()
+    }
+
+    /// Every year on `day` of `month`, starting on its first occurrence on or after `start_date`;
+    /// a February 29 rule only occurs in leap years.
+    pub fn yearly_on(start_date: chrono::NaiveDate, month: chrono::Month, day: MonthDay) -> Result<Schedule, RepeatingDateError> {
+        let rule = MonthlyBy::Days { days: BTreeSet::from([day]), missing: MissingDay::Skip };
+        let start_date = first_picked(start_date, chrono::NaiveDate::from_ymd_opt(start_date.year(), month.number_from_month(), 1), 12, &rule)?;
+
+        Schedule::builder().start(start_date).yearly().monthly_by(rule).build()
+    }
+
+    pub(crate) fn with_weekday_policy(mut self, policy: WeekdayPolicy) -> Result<Schedule, RepeatingDateError> {
+        if policy != WeekdayPolicy::Skip && self.repeat != Repeating::Daily {
+            return Err(RepeatingDateError::InvalidWeekdayPolicy);
+        }
+
+        self.weekday_policy = policy;
+        Ok(self)
+    }
+
+    pub(crate) fn with_shift_pattern(mut self, shift_pattern: Option<ShiftPattern>) -> Result<Schedule, RepeatingDateError> {
+        let Some(pattern) = shift_pattern else { return Ok(self) };
+
+        if self.repeat != Repeating::Daily || self.weekday_policy != WeekdayPolicy::Skip || !pattern.is_valid() {
+            return Err(RepeatingDateError::InvalidShiftRule);
+        }
+
+        if !pattern.on_days().contains(&0) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        self.shift_pattern = Some(pattern);
+        Ok(self)
+    }
+
+    pub(crate) fn with_times_of_day(mut self, times_of_day: Option<TimesOfDay>) -> Result<Schedule, RepeatingDateError> {
+        if let Some(times) = times_of_day.as_ref().filter(|times| times.times().is_empty()) {
+            if times.is_empty() {
+                return Err(RepeatingDateError::InvalidTimesOfDay);
+            }
+
+            let extra_weekdays = self.extra_dates.iter().map(|date| WeekdayFlags::from_weekday(date.weekday()));
+
+            if extra_weekdays.fold(self.weekdays, |weekdays, weekday| weekdays | weekday).extract_weekdays().any(|weekday| times.weekday_time(weekday).is_none()) {
+                return Err(RepeatingDateError::MissingWeekdayTime);
+            }
+        }
+
+        self.times_of_day = times_of_day;
+        Ok(self)
+    }
+
+    pub(crate) fn with_monthly_by(mut self, monthly_by: Option<MonthlyBy>) -> Result<Schedule, RepeatingDateError> {
+        let Some(rule) = monthly_by else { return Ok(self) };
+
+        if !matches!(self.step().0, Repeating::Monthly | Repeating::Yearly) || !rule.is_valid() {
+            return Err(RepeatingDateError::InvalidMonthlyRule);
+        }
+
+        if !rule.dates_in(self.start_date.year(), self.start_date.month()).contains(&self.start_date) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        self.monthly_by = Some(rule);
+        Ok(self)
+    }
+
+    pub(crate) fn with_months(mut self, months: Option<MonthFlags>) -> Result<Schedule, RepeatingDateError> {
+        let Some(months) = months else { return Ok(self) };
+
+        if self.repeat != Repeating::Yearly || (months & MonthFlags::ANY).is_empty() {
+            return Err(RepeatingDateError::InvalidMonthSet);
+        }
+
+        if !months.contains_month(self.start_date.month()) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        self.months = Some(months & MonthFlags::ANY);
+        Ok(self)
+    }
+
+    pub(crate) fn with_iso_weeks(mut self, weeks: BTreeSet<u8>) -> Result<Schedule, RepeatingDateError> {
+        if weeks.is_empty() {
+            return Ok(self);
+        }
+
+        let picks_months = self.months.is_some() || self.monthly_by.is_some();
+
+        if self.repeat != Repeating::Yearly || picks_months || weeks.iter().any(|week| !(1..=53).contains(week)) {
+            return Err(RepeatingDateError::InvalidWeekNumber);
+        }
+
+        if !weeks.contains(&(self.start_date.iso_week().week() as u8)) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        self.iso_weeks = weeks;
+        Ok(self)
+    }
+
+    pub(crate) fn with_year_days(mut self, year_days: Option<YearDays>) -> Result<Schedule, RepeatingDateError> {
+        let Some(year_days) = year_days else { return Ok(self) };
+
+        let picks_others = self.months.is_some() || self.monthly_by.is_some() || !self.iso_weeks.is_empty();
+
+        if self.repeat != Repeating::Yearly || picks_others || year_days.days().is_empty() {
+            return Err(RepeatingDateError::InvalidYearDayRule);
+        }
+
+        if !year_days.dates_in(self.start_date.year()).contains(&self.start_date) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        self.year_days = Some(year_days);
+        Ok(self)
+    }
+
+    pub(crate) fn with_set_positions(mut self, positions: BTreeSet<i16>) -> Result<Schedule, RepeatingDateError> {
+        if positions.is_empty() {
+            return Ok(self);
+        }
+
+        if self.repeat == Repeating::Daily || positions.iter().any(|position| *position == 0 || position.unsigned_abs() > 366) {
+            return Err(RepeatingDateError::InvalidSetPosition);
+        }
+
+        self.set_positions = positions;
+
+        if !self.period_dates(0).is_some_and(|dates| dates.contains(&self.start_date)) {
+            return Err(RepeatingDateError::StartNotInRule);
+        }
+
+        Ok(self)
+    }
+
+    pub(crate) fn with_end(mut self, until: Option<chrono::NaiveDate>, count: Option<u32>) -> Result<Schedule, RepeatingDateError> {
+        if until.is_some_and(|until| until < self.start_date) {
+            return Err(RepeatingDateError::UntilBeforeStart);
+        }
+
+        if count == Some(0) {
+            return Err(RepeatingDateError::ZeroCount);
+        }
+
+        self.until = until;
+        self.count = count;
+        self.counted_last = self.count.and_then(|count| self.nth_unbounded(count as u64 - 1));
+        self.last_generated = self.find_last_generated();
+        Ok(self)
+    }
+
+    pub(crate) fn with_exclusions(mut self, exclusions: BTreeSet<chrono::NaiveDate>) -> Schedule {
+        self.exclusions = exclusions;
+        self
+    }
+
+    pub(crate) fn with_extra_dates(mut self, extra_dates: BTreeSet<chrono::NaiveDate>) -> Result<Schedule, RepeatingDateError> {
+        if extra_dates.first().is_some_and(|first| *first < self.start_date) {
+            return Err(RepeatingDateError::ExtraDateBeforeStart);
+        }
+
+        self.extra_dates = extra_dates;
+        Ok(self)
+    }
+
+    pub fn start_date(&self) -> chrono::NaiveDate {
+        self.start_date
+    }
+
+    pub fn weekdays(&self) -> WeekdayFlags {
+        self.weekdays
+    }
+
+    pub fn repeat(&self) -> Repeating {
+        self.repeat
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// The frequency and interval the lookups run on, see `Repeating::normalized`.
+    pub(crate) fn step(&self) -> (Repeating, u64) {
+        self.repeat.normalized(self.interval)
+    }
+
+    pub fn weekday_policy(&self) -> WeekdayPolicy {
+        self.weekday_policy
+    }
+
+    /// The cycle of working days a daily schedule repeats; `None` for every `interval` days.
+    pub fn shift_pattern(&self) -> Option<&ShiftPattern> {
+        self.shift_pattern.as_ref()
+    }
+
+    /// The days of each on-month a monthly, quarterly or yearly schedule falls on; `None` for the
+    /// start's day of month.
+    pub fn monthly_by(&self) -> Option<&MonthlyBy> {
+        self.monthly_by.as_ref()
+    }
+
+    /// The months a yearly schedule is restricted to; `None` for the start's month only.
+    pub fn months(&self) -> Option<MonthFlags> {
+        self.months
+    }
+
+    /// The ISO weeks a yearly schedule falls in, empty for the start's day of month. Such a
+    /// schedule steps by ISO years, which can begin in late December or end in early January, and
+    /// has no occurrence in week 53 of years with 52 weeks.
+    pub fn iso_weeks(&self) -> &BTreeSet<u8> {
+        &self.iso_weeks
+    }
+
+    /// The days of the year a yearly schedule falls on; `None` for the start's month and day.
+    pub fn year_days(&self) -> Option<&YearDays> {
+        self.year_days.as_ref()
+    }
+
+    /// The positions kept among the candidates of each on-period, empty when all are kept.
+    ///
+    /// The candidates of a period are its allowed weekdays: every day of the week, month or year,
+    /// narrowed to the `MonthlyBy` days, the month set or the ISO weeks when given (a yearly rule
+    /// with a `MonthlyBy` but no month set uses the start's month), then to the weekday filter.
+    /// Position 1 is the first candidate and -1 the last; periods with fewer candidates have no
+    /// occurrence for that position.
+    pub fn set_positions(&self) -> &BTreeSet<i16> {
+        &self.set_positions
+    }
+
+    /// The inclusive end of the series, if any.
+    pub fn until(&self) -> Option<chrono::NaiveDate> {
+        self.until
+    }
+
+    /// The number of occurrences the schedule is limited to, if any.
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Dates removed from the series. They still count towards `count` and occurrence indices, as
+    /// excluded instances do in calendars.
+    pub fn exclusions(&self) -> &BTreeSet<chrono::NaiveDate> {
+        &self.exclusions
+    }
+
+    pub(crate) fn is_excluded(&self, date: chrono::NaiveDate) -> bool {
+        self.exclusions.contains(&date)
+    }
+
+    /// One-off occurrences added to the rule's. They are merged into every lookup, may coincide with
+    /// rule occurrences (the date then occurs once), are not limited by `until` or `count` and are
+    /// not part of occurrence indices; exclusions still remove them.
+    pub fn extra_dates(&self) -> &BTreeSet<chrono::NaiveDate> {
+        &self.extra_dates
+    }
+
+    /// The times of day each occurrence date expands into; `None` for midnight only.
+    pub fn times_of_day(&self) -> Option<&TimesOfDay> {
+        self.times_of_day.as_ref()
+    }
+
+    fn extra_dates_in(&self, range: impl std::ops::RangeBounds<chrono::NaiveDate>) -> impl DoubleEndedIterator<Item = chrono::NaiveDate> + '_ {
+        self.extra_dates.range(range).copied().filter(|date| !self.is_excluded(*date))
+    }
+
+    pub(crate) fn is_past_end(&self, date: chrono::NaiveDate) -> bool {
+        self.until.is_some_and(|until| date > until) || self.counted_last.is_some_and(|last| date > last)
+    }
+
+    /// The start, or the first occurrence after it when the start is excluded.
+    pub fn first_occurrence(&self) -> Option<chrono::NaiveDate> {
+        if self.is_excluded(self.start_date) {
+            return self.next_after(self.start_date).ok();
+        }
+
+        Some(self.start_date)
+    }
+
+    /// The final occurrence of a schedule with an `until` date or a `count`, whichever ends it
+    /// first; `None` for open ended schedules and for ones whose every occurrence is excluded.
+    pub fn last_occurrence(&self) -> Option<chrono::NaiveDate> {
+        let last = self.last_generated()?;
+
+        self.skip_excluded_back(last).max(self.extra_dates_in(..).next_back())
+    }
+
+    fn skip_excluded_back(&self, mut date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        while self.is_excluded(date) {
+            date = self.previous_unbounded(date)?;
+        }
+
+        Some(date)
+    }
+
+    /// The final date the rule generates before exclusions, if it ends.
+    pub(crate) fn last_generated(&self) -> Option<chrono::NaiveDate> {
+        self.last_generated
+    }
+
+    fn find_last_generated(&self) -> Option<chrono::NaiveDate> {
+        let bound = match (self.until, self.counted_last) {
+            (None, None) => return None,
+            (Some(until), None) => until,
+            (None, Some(last)) => last,
+            (Some(until), Some(last)) => until.min(last),
+        };
+
+        if self.index_unbounded(bound).is_some() {
+            return Some(bound);
+        }
+
+        self.previous_unbounded(bound)
+    }
+
+    /// The first occurrence strictly after `from_date` that is not excluded, see `find_next_date`;
+    /// `ScheduleEnded` once that occurrence would be after `until` or beyond `count` occurrences.
+    pub fn next_after(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        let extra = self.extra_dates_in((std::ops::Bound::Excluded(from_date), std::ops::Bound::Unbounded)).next();
+
+        match (self.next_generated(from_date), extra) {
+            (Ok(next), Some(extra)) => Ok(next.min(extra)),
+            (Err(error @ RepeatingDateError::StartDateBeforeBound { .. }), _) => Err(error),
+            (Err(_), Some(extra)) => Ok(extra),
+            (result, None) => result,
+        }
+    }
+
+    /// The first occurrence on or after `from_date`: `from_date` itself when it is one, unlike
+    /// `next_after`, and the first occurrence for any `from_date` up to the start.
+    pub fn next_on_or_after(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        match from_date.pred_opt() {
+            Some(day_before) => self.next_or_first(day_before),
+            None => self.first_occurrence().ok_or(RepeatingDateError::ScheduleEnded),
+        }
+    }
+
+    /// Like `next_after`, but the first occurrence when `from_date` is before the start.
+    pub(crate) fn next_or_first(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        if from_date < self.start_date {
+            return self.first_occurrence().ok_or(RepeatingDateError::ScheduleEnded);
+        }
+
+        self.next_after(from_date)
+    }
+
+    fn next_generated(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        let last = self.last_generated();
+        let mut from_date = from_date;
+
+        // Every excluded date is skipped at most once, so this ends after `exclusions.len()` steps.
+        loop {
+            if last.is_some_and(|last| from_date >= last) {
+                return Err(RepeatingDateError::ScheduleEnded);
+            }
+
+            let next = if self.is_expanded() {
+                self.next_expanded(from_date)?
+            } else if self.slides() {
+                self.next_sliding(from_date)?
+            } else {
+                find_next_date(from_date, self.start_date, self.weekdays, self.repeat, self.interval)?
+            };
+
+            if self.is_past_end(next) {
+                return Err(RepeatingDateError::ScheduleEnded);
+            }
+
+            if !self.is_excluded(next) {
+                return Ok(next);
+            }
+
+            from_date = next;
+        }
+    }
+
+    /// Whether `date` is an occurrence after the start and not past the schedule's end, see
+    /// `match_repeating_date`.
+    pub fn matches(&self, date: chrono::NaiveDate) -> bool {
+        if self.is_excluded(date) {
+            return false;
+        }
+
+        let generated = if self.is_expanded() {
+            self.matches_expanded(date)
+        } else if self.slides() {
+            self.slide_index(date).is_some()
+        } else {
+            match_repeating_date(date, self.start_date, self.weekdays, self.repeat, self.interval)
+        };
+
+        self.extra_dates.contains(&date) || (!self.is_past_end(date) && generated)
+    }
+
+    /// The last occurrence strictly before `from_date` that is neither excluded nor past the
+    /// schedule's end, the start date included; `None` when there is none.
+    pub fn previous_before(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let previous = match self.last_generated() {
+            Some(last) if from_date > last => Some(last),
+            _ => self.previous_unbounded(from_date),
+        };
+
+        previous.and_then(|previous| self.skip_excluded_back(previous)).max(self.extra_dates_in(..from_date).next_back())
+    }
+
+    pub(crate) fn previous_unbounded(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        if from_date <= self.start_date {
+            return None;
+        }
+
+        match (self.step().0, self.is_expanded()) {
+            (_, true) => self.previous_expanded(from_date),
+            (Repeating::Daily, false) if self.slides() => self.previous_sliding(from_date),
+            (Repeating::Daily, false) => self.previous_daily(from_date),
+            (Repeating::Weekly, false) => self.previous_weekly(from_date),
+            (Repeating::Monthly | Repeating::Quarterly, false) => self.previous_monthly(from_date),
+            (Repeating::Yearly, false) => self.previous_yearly(from_date),
+            (Repeating::Hourly | Repeating::Minutely, false) => unreachable!("Schedule::new rejects sub-daily frequencies"),
+        }
+    }
+
+    pub(crate) fn allows(&self, date: chrono::NaiveDate) -> bool {
+        self.weekdays.contains(WeekdayFlags::from_weekday(date.weekday()))
+    }
+
+    fn previous_daily(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let days_elapsed = (from_date - self.start_date).num_days() as u64;
+        let mut steps = (days_elapsed - 1) / self.interval;
+
+        loop {
+            let date = self.start_date.checked_add_days(chrono::Days::new(steps * self.interval))?;
+
+            if self.allows(date) {
+                return Some(date);
+            }
+
+            steps = steps.checked_sub(1)?;
+        }
+    }
+
+    fn previous_weekly(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let day_before = from_date.pred_opt()?;
+        let from_week_start = week_start_day(day_before, WeekStart::Monday);
+
+        if is_on_week(day_before, self.start_date, self.interval, WeekStart::Monday) {
+            let candidate = if self.allows(day_before) {
+                Some(day_before)
+            } else {
+                let days_back = self.weekdays.days_since_previous(day_before.weekday())?;
+                day_before.checked_sub_days(chrono::Days::new(days_back as u64))
+            };
+
+            if let Some(date) = candidate.filter(|date| date.num_days_from_ce() as i64 >= from_week_start && *date >= self.start_date) {
+                return Some(date);
+            }
+        }
+
+        let weeks_elapsed = weeks_between(self.start_date, day_before, WeekStart::Monday) as u64;
+
+        if weeks_elapsed == 0 {
+            return None;
+        }
+
+        let previous_on_week = match weeks_elapsed % self.interval {
+            0 => weeks_elapsed - self.interval,
+            offset => weeks_elapsed - offset,
+        };
+
+        let last_weekday = self.weekdays.last_selected()?;
+
+        day_after(week_start_day(self.start_date, WeekStart::Monday), previous_on_week * 7 + last_weekday.num_days_from_monday() as u64)
+    }
+
+    fn previous_monthly(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let max_month_skip = 4800;
+
+        let interval = i32::try_from(self.step().1).ok()?;
+        let month_diff = get_months_since(from_date, self.start_date);
+
+        let month_day = MonthDay::of(self.start_date);
+        let first_of_start_month = self.start_date.with_day(1)?;
+        let mut months = month_diff - (month_diff % interval);
+
+        let mut counter = 0;
+        while counter < max_month_skip && months >= 0 {
+            let first_of_month = first_of_start_month.checked_add_months(chrono::Months::new(months as u32))?;
+
+            if let Some(date) = month_day.resolve(first_of_month.year(), first_of_month.month()) {
+                if date < from_date && self.allows(date) {
+                    return Some(date);
+                }
+            }
+
+            months -= interval;
+            counter += 1;
+        }
+
+        None
+    }
+
+    fn previous_yearly(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let interval = i32::try_from(self.interval).ok()?;
+        let years_diff = from_date.year() - self.start_date.year();
+
+        let mut year = self.start_date.year() + years_diff - (years_diff % interval);
+
+        let mut counter = 0;
+        while counter < DEFAULT_MAX_YEAR_SKIP && year >= self.start_date.year() {
+            if let Some(date) = self.start_date.with_year(year) {
+                if date < from_date && self.allows(date) {
+                    return Some(date);
+                }
+            }
+
+            year -= interval;
+            counter += 1;
+        }
+
+        None
+    }
+}
+
+/// The first date `rule` picks on or after `start_date`, in the month of `first_of_month` or one
+/// every `step_months` after it, searching far enough for any day of month, a leap day included.
+fn first_picked(start_date: chrono::NaiveDate, first_of_month: Option<chrono::NaiveDate>, step_months: u32, rule: &MonthlyBy) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    let first_of_month = first_of_month.ok_or(RepeatingDateError::DateOutOfRange)?;
+
+    (0..=8).map_while(|steps| first_of_month.checked_add_months(chrono::Months::new(steps * step_months)))
+        .flat_map(|month| rule.dates_in(month.year(), month.month()))
+        .find(|date| *date >= start_date)
+        .ok_or(RepeatingDateError::DateOutOfRange)
+}
+
+/// Deserialization runs the same validation as `Schedule::new`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Schedule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct ScheduleFields {
+            start_date: chrono::NaiveDate,
+            weekdays: WeekdayFlags,
+            repeat: Repeating,
+            interval: u64,
+            #[serde(default)]
+            weekday_policy: WeekdayPolicy,
+            #[serde(default)]
+            shift_pattern: Option<ShiftPattern>,
+            #[serde(default)]
+            monthly_by: Option<MonthlyBy>,
+            #[serde(default)]
+            months: Option<MonthFlags>,
+            #[serde(default)]
+            set_positions: BTreeSet<i16>,
+            #[serde(default)]
+            iso_weeks: BTreeSet<u8>,
+            #[serde(default)]
+            year_days: Option<YearDays>,
+            #[serde(default)]
+            until: Option<chrono::NaiveDate>,
+            #[serde(default)]
+            count: Option<u32>,
+            #[serde(default)]
+            exclusions: BTreeSet<chrono::NaiveDate>,
+            #[serde(default)]
+            extra_dates: BTreeSet<chrono::NaiveDate>,
+            #[serde(default)]
+            times_of_day: Option<TimesOfDay>
+        }
+
+        let fields = ScheduleFields::deserialize(deserializer)?;
+
+        Schedule::new(fields.start_date, fields.weekdays, fields.repeat, fields.interval)
+            .and_then(|schedule| schedule.with_weekday_policy(fields.weekday_policy))
+            .and_then(|schedule| schedule.with_shift_pattern(fields.shift_pattern))
+            .and_then(|schedule| schedule.with_monthly_by(fields.monthly_by))
+            .and_then(|schedule| schedule.with_months(fields.months))
+            .and_then(|schedule| schedule.with_iso_weeks(fields.iso_weeks))
+            .and_then(|schedule| schedule.with_year_days(fields.year_days))
+            .and_then(|schedule| schedule.with_set_positions(fields.set_positions))
+            .and_then(|schedule| schedule.with_end(fields.until, fields.count))
+            .and_then(|schedule| schedule.with_exclusions(fields.exclusions).with_extra_dates(fields.extra_dates))
+            .and_then(|schedule| schedule.with_times_of_day(fields.times_of_day))
+            .map_err(|error| serde::de::Error::custom(format_args!("invalid schedule: {}", error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case::daily(Repeating::Daily, "\"daily\"")]
+    #[case::weekly(Repeating::Weekly, "\"weekly\"")]
+    #[case::monthly(Repeating::Monthly, "\"monthly\"")]
+    #[case::quarterly(Repeating::Quarterly, "\"quarterly\"")]
+    #[case::yearly(Repeating::Yearly, "\"yearly\"")]
+    fn serde_repeating_round_trip(#[case] repeat: Repeating, #[case] expected_json: &str) {
+        assert_eq!(expected_json, serde_json::to_string(&repeat).unwrap());
+        assert_eq!(repeat, serde_json::from_str::<Repeating>(expected_json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case::midweek(WeekdayFlags::MIDWEEK, r#"["MON","TUE","WED","THU","FRI"]"#)]
+    #[case::weekend(WeekdayFlags::WEEKEND, r#"["SAT","SUN"]"#)]
+    #[case::any(WeekdayFlags::ANY, r#"["MON","TUE","WED","THU","FRI","SAT","SUN"]"#)]
+    #[case::combined(WeekdayFlags::MON | WeekdayFlags::WED, r#"["MON","WED"]"#)]
+    #[case::empty(WeekdayFlags::empty(), r#"[]"#)]
+    fn serde_weekdays_round_trip(#[case] weekdays: WeekdayFlags, #[case] expected_json: &str) {
+        assert_eq!(expected_json, serde_json::to_string(&weekdays).unwrap());
+        assert_eq!(weekdays, serde_json::from_str::<WeekdayFlags>(expected_json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_weekdays_out_of_order_input() {
+        assert_eq!(WeekdayFlags::MON | WeekdayFlags::FRI, serde_json::from_str::<WeekdayFlags>(r#"["FRI","MON"]"#).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case::unknown_name(r#"["MON","FUNDAY"]"#, "unknown variant `FUNDAY`")]
+    #[case::lowercase(r#"["mon"]"#, "unknown variant `mon`")]
+    #[case::duplicate(r#"["MON","MON"]"#, "duplicate weekday `MON`")]
+    #[case::not_a_list(r#""MON""#, "a list of weekday names")]
+    #[case::not_strings(r#"[1, 2]"#, "expected a string")]
+    fn serde_weekdays_rejects_malformed(#[case] json: &str, #[case] expected_message: &str) {
+        let error = serde_json::from_str::<WeekdayFlags>(json).unwrap_err().to_string();
+
+        assert!(error.contains(expected_message), "{}", error);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case::empty(WeekdayFlags::empty(), 0x00)]
+    #[case::combined(WeekdayFlags::MON | WeekdayFlags::WED, 0x05)]
+    #[case::any(WeekdayFlags::ANY, 0x7F)]
+    fn serde_weekdays_compact_round_trip(#[case] weekdays: WeekdayFlags, #[case] expected_bits: u8) {
+        let bytes = postcard::to_allocvec(&weekdays).unwrap();
+
+        assert_eq!(vec![expected_bits], bytes);
+        assert_eq!(weekdays, postcard::from_bytes::<WeekdayFlags>(&bytes).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_weekdays_accepts_a_bitmask() {
+        assert_eq!(WeekdayFlags::MON | WeekdayFlags::WED, serde_json::from_str::<WeekdayFlags>("5").unwrap());
+        assert_eq!(WeekdayFlags::ANY, serde_json::from_str::<WeekdayFlags>("127").unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_weekdays_rejects_high_bits() {
+        let error = serde_json::from_str::<WeekdayFlags>("128").unwrap_err().to_string();
+
+        assert!(error.contains("a weekday bitmask up to 0x7F"), "{}", error);
+        assert!(serde_json::from_str::<WeekdayFlags>("256").is_err());
+        assert!(postcard::from_bytes::<WeekdayFlags>(&[0x80]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_repeating_rejects_unknown() {
+        let error = serde_json::from_str::<Repeating>(r#""secondly""#).unwrap_err().to_string();
+
+        assert!(error.contains("unknown variant `secondly`"), "{}", error);
+        assert!(error.contains("`daily`, `weekly`, `monthly`, `quarterly`, `yearly`, `hourly`, `minutely`"), "{}", error);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_schedule_round_trip() {
+        let schedule = Schedule::new(date("2023-09-12"), WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2).unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert_eq!(r#"{"start_date":"2023-09-12","weekdays":["TUE","THU"],"repeat":"weekly","interval":2}"#, json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_schedule_end() {
+        let schedule = Schedule::builder().start(date("2023-09-12")).until(date("2023-12-31")).build().unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert!(json.ends_with(r#","until":"2023-12-31"}"#), "{}", json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+
+        let schedule = Schedule::builder().start(date("2023-09-12")).count(3).build().unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert!(json.ends_with(r#","count":3}"#), "{}", json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+
+        let schedule = Schedule::builder().start(date("2023-09-12")).exclude(date("2023-09-13")).build().unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert!(json.ends_with(r#","exclusions":["2023-09-13"]}"#), "{}", json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+
+        let json = r#"{"start_date":"2023-09-12","weekdays":["TUE"],"repeat":"weekly","interval":1,"until":"2023-09-01"}"#;
+        assert!(serde_json::from_str::<Schedule>(json).unwrap_err().to_string().contains("the until date is before the start date"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_schedule_validates() {
+        let json = r#"{"start_date":"2023-09-13","weekdays":["TUE","THU"],"repeat":"weekly","interval":2}"#;
+        let error = serde_json::from_str::<Schedule>(json).unwrap_err().to_string();
+
+        assert!(error.contains("the start falls on WED, which is not one of the allowed weekdays TUE,THU"), "{}", error);
+
+        let json = r#"{"start_date":"2023-09-12","weekdays":["TUE"],"repeat":"weekly","interval":0}"#;
+        assert!(serde_json::from_str::<Schedule>(json).is_err());
+    }
+
+    #[test]
+    fn new_rejects_start_outside_weekdays() {
+        let result = Schedule::new(date("2023-09-13"), WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2);
+
+        assert!(matches!(result, Err(RepeatingDateError::WrongWeekday { .. })));
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let schedule = Schedule::builder().start(date("2023-09-12")).build().unwrap();
+
+        assert_eq!(date("2023-09-12"), schedule.start_date());
+        assert_eq!(WeekdayFlags::ANY, schedule.weekdays());
+        assert_eq!(Repeating::Daily, schedule.repeat());
+        assert_eq!(1, schedule.interval());
+        assert_eq!(date("2023-09-13"), schedule.next_after(date("2023-09-12")).unwrap());
+    }
+
+    #[test]
+    fn builder_sets_every_field() {
+        let schedule = Schedule::builder().start(date("2023-09-12")).weekly().interval(2).on(WeekdayFlags::TUE | WeekdayFlags::THU).build().unwrap();
+
+        assert_eq!(Schedule::new(date("2023-09-12"), WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2).unwrap(), schedule);
+
+        assert_eq!(Repeating::Monthly, Schedule::builder().start(date("2023-09-12")).monthly().build().unwrap().repeat());
+        assert_eq!(Repeating::Quarterly, Schedule::builder().start(date("2023-09-12")).quarterly().build().unwrap().repeat());
+        assert_eq!(Repeating::Yearly, Schedule::builder().start(date("2023-09-12")).yearly().build().unwrap().repeat());
+        assert_eq!(Repeating::Daily, Schedule::builder().start(date("2023-09-12")).weekly().daily().build().unwrap().repeat());
+        assert_eq!(Repeating::Weekly, Schedule::builder().start(date("2023-09-12")).repeat(Repeating::Weekly).build().unwrap().repeat());
+    }
+
+    #[test]
+    fn builder_adjusts_the_start_to_the_weekdays() {
+        let weekdays = WeekdayFlags::TUE | WeekdayFlags::THU;
+        let adjusted = |repeat: Repeating| Schedule::builder().start(date("2023-09-13")).repeat(repeat).interval(3).on(weekdays).adjust_start_to_weekdays().build().unwrap();
+
+        for repeat in [Repeating::Daily, Repeating::Weekly, Repeating::Monthly, Repeating::Yearly] {
+            assert_eq!(Schedule::new(date("2023-09-14"), weekdays, repeat, 3).unwrap(), adjusted(repeat), "{:?}", repeat);
+        }
+
+        assert_eq!(date("2023-09-14"), adjusted(Repeating::Daily).start_date());
+        assert_eq!(Ok(vec![date("2023-09-26"), date("2023-10-05")]), adjusted(Repeating::Daily).next_n_dates(date("2023-09-14"), 2).map(Vec::from));
+        assert!(matches!(Schedule::builder().start(date("2023-09-13")).weekly().on(weekdays).build(), Err(RepeatingDateError::WrongWeekday { .. })));
+        assert_eq!(date("2023-09-12"), Schedule::builder().start(date("2023-09-12")).on(weekdays).adjust_start_to_weekdays().build().unwrap().start_date());
+        assert!(matches!(Schedule::builder().start(date("2023-09-13")).on(WeekdayFlags::empty()).adjust_start_to_weekdays().build(), Err(RepeatingDateError::EmptyWeekdaySet)));
+    }
+
+    #[rstest]
+    #[case::daily(Repeating::Daily, 1)]
+    #[case::bi_daily(Repeating::Daily, 2)]
+    #[case::weekly(Repeating::Weekly, 1)]
+    #[case::bi_weekly(Repeating::Weekly, 2)]
+    #[case::monthly(Repeating::Monthly, 1)]
+    #[case::bi_monthly(Repeating::Monthly, 2)]
+    #[case::yearly(Repeating::Yearly, 1)]
+    #[case::bi_yearly(Repeating::Yearly, 2)]
+    fn next_on_or_after_includes_the_date(#[case] repeat: Repeating, #[case] interval: u64) {
+        let schedule = Schedule::new(date("2023-09-12"), WeekdayFlags::TUE | WeekdayFlags::THU | WeekdayFlags::SAT, repeat, interval).unwrap();
+        let occurrence = schedule.next_after(schedule.start_date()).unwrap();
+        let following = schedule.next_after(occurrence).unwrap();
+
+        assert_eq!(Ok(schedule.start_date()), schedule.next_on_or_after(schedule.start_date()));
+        assert_eq!(Ok(schedule.start_date()), schedule.next_on_or_after(date("2023-01-01")));
+        assert_eq!(Ok(occurrence), schedule.next_on_or_after(occurrence));
+        assert_eq!(Ok(following), schedule.next_on_or_after(occurrence.succ_opt().unwrap()));
+        assert!(following > occurrence);
+    }
+
+    #[rstest]
+    #[case::daily(Repeating::Daily)]
+    #[case::weekly(Repeating::Weekly)]
+    #[case::monthly(Repeating::Monthly)]
+    #[case::quarterly(Repeating::Quarterly)]
+    #[case::yearly(Repeating::Yearly)]
+    fn matches_its_start(#[case] repeat: Repeating) {
+        let schedule = Schedule::new(date("2024-01-31"), WeekdayFlags::WED | WeekdayFlags::FRI, repeat, 2).unwrap();
+
+        assert_eq!(Some(schedule.start_date()), schedule.first_occurrence());
+        assert!(schedule.matches(schedule.start_date()));
+    }
+
+    #[test]
+    fn builder_rejects_missing_start() {
+        assert!(matches!(Schedule::builder().weekly().build(), Err(RepeatingDateError::MissingStartDate)));
+    }
+
+    #[rstest]
+    #[case::hourly(Repeating::Hourly)]
+    #[case::minutely(Repeating::Minutely)]
+    fn builder_rejects_sub_daily(#[case] repeat: Repeating) {
+        let result = Schedule::builder().start(date("2023-09-12")).repeat(repeat).build();
+
+        assert!(matches!(result, Err(RepeatingDateError::UnsupportedFrequency)));
+        assert_eq!(Err(RepeatingDateError::UnsupportedFrequency), find_next_date(date("2023-09-12"), date("2023-09-12"), WeekdayFlags::ANY, repeat, 1));
+        assert!(!match_repeating_date(date("2023-09-13"), date("2023-09-12"), WeekdayFlags::ANY, repeat, 1));
+    }
+
+    #[test]
+    fn builder_rejects_zero_interval() {
+        let result = Schedule::builder().start(date("2023-09-12")).weekly().interval(0).build();
+
+        assert!(matches!(result, Err(RepeatingDateError::ZeroInterval)));
+    }
+
+    #[test]
+    fn builder_rejects_empty_weekdays() {
+        let result = Schedule::builder().start(date("2023-09-12")).weekly().on(WeekdayFlags::empty()).build();
+        assert!(matches!(result, Err(RepeatingDateError::EmptyWeekdaySet)));
+
+        let result = Schedule::builder().start(date("2023-09-12")).on(WeekdayFlags::from_bits_retain(0x80)).build();
+        assert!(matches!(result, Err(RepeatingDateError::EmptyWeekdaySet)));
+    }
+
+    #[test]
+    fn builder_rejects_start_outside_weekdays() {
+        let result = Schedule::builder().start(date("2023-09-13")).weekly().on(WeekdayFlags::TUE | WeekdayFlags::THU).build();
+
+        assert!(matches!(result, Err(RepeatingDateError::WrongWeekday { .. })));
+    }
+
+    #[test]
+    fn delegates_to_free_functions() {
+        let schedule = Schedule::new(date("2023-09-12"), WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2).unwrap();
+
+        assert_eq!(date("2023-09-12"), schedule.start_date());
+        assert_eq!(WeekdayFlags::TUE | WeekdayFlags::THU, schedule.weekdays());
+        assert_eq!(Repeating::Weekly, schedule.repeat());
+        assert_eq!(2, schedule.interval());
+
+        assert_eq!(date("2023-09-14"), schedule.next_after(date("2023-09-12")).unwrap());
+        assert_eq!(date("2023-09-26"), schedule.next_after(date("2023-09-14")).unwrap());
+        assert!(schedule.matches(date("2023-09-26")));
+        assert!(!schedule.matches(date("2023-09-19")));
+        assert!(matches!(schedule.next_after(date("2023-09-11")), Err(RepeatingDateError::StartDateBeforeBound { .. })));
+    }
+
+    fn bounded(until: &str) -> Schedule {
+        Schedule::builder().start(date("2023-09-12")).weekly().interval(2).on(WeekdayFlags::TUE | WeekdayFlags::THU).until(date(until)).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::between_occurrences("2023-09-20", "2023-09-14", None)]
+    #[case::on_occurrence("2023-09-26", "2023-09-14", Some("2023-09-26"))]
+    #[case::on_start("2023-09-12", "2023-09-11", None)]
+    fn until_ends_next_after(#[case] until: &str, #[case] from: &str, #[case] expected_result: Option<&str>) {
+        let schedule = bounded(until);
+
+        assert_eq!(Some(date(until)), schedule.until());
+
+        match expected_result {
+            Some(expected) => assert_eq!(Ok(date(expected)), schedule.next_after(date(from))),
+            None if date(from) < schedule.start_date() => assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: date(from), start: schedule.start_date() }), schedule.next_after(date(from))),
+            None => assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_after(date(from))),
+        }
+
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_after(date("2023-10-31")));
+    }
+
+    #[test]
+    fn until_bounds_matches_and_previous_before() {
+        let between = bounded("2023-09-20");
+        let on_occurrence = bounded("2023-09-26");
+
+        assert!(between.matches(date("2023-09-14")));
+        assert!(!between.matches(date("2023-09-26")));
+        assert!(on_occurrence.matches(date("2023-09-26")));
+        assert!(!on_occurrence.matches(date("2023-09-28")));
+
+        assert_eq!(Some(date("2023-09-14")), between.previous_before(date("2023-12-01")));
+        assert_eq!(Some(date("2023-09-26")), on_occurrence.previous_before(date("2023-12-01")));
+        assert_eq!(Some(date("2023-09-14")), on_occurrence.previous_before(date("2023-09-26")));
+    }
+
+    #[test]
+    fn until_stops_iteration_on_last_occurrence() {
+        let schedule = bounded("2023-10-10");
+        let mut dates = vec![schedule.start_date()];
+
+        while let Ok(next) = schedule.next_after(*dates.last().unwrap()) {
+            dates.push(next);
+        }
+
+        assert_eq!(vec![date("2023-09-12"), date("2023-09-14"), date("2023-09-26"), date("2023-09-28"), date("2023-10-10")], dates);
+    }
+
+    #[rstest]
+    #[case::daily(Repeating::Daily, 2, WeekdayFlags::TUE | WeekdayFlags::THU, 10, "2023-11-09")]
+    #[case::weekly(Repeating::Weekly, 2, WeekdayFlags::TUE | WeekdayFlags::THU, 5, "2023-10-10")]
+    #[case::monthly_filtered(Repeating::Monthly, 1, WeekdayFlags::TUE | WeekdayFlags::THU, 3, "2023-12-12")]
+    #[case::yearly(Repeating::Yearly, 1, WeekdayFlags::ANY, 1, "2023-09-12")]
+    fn count_ends_series(#[case] repeat: Repeating, #[case] interval: u64, #[case] weekdays: WeekdayFlags, #[case] count: u32, #[case] expected_last: &str) {
+        let schedule = Schedule::builder().start(date("2023-09-12")).repeat(repeat).interval(interval).on(weekdays).count(count).build().unwrap();
+        let mut dates = vec![schedule.start_date()];
+
+        while let Ok(next) = schedule.next_after(*dates.last().unwrap()) {
+            dates.push(next);
+        }
+
+        assert_eq!(count as usize, dates.len());
+        assert_eq!(Some(date(expected_last)), schedule.last_occurrence());
+        assert_eq!(Some(date(expected_last)), dates.last().copied());
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_after(date(expected_last)));
+
+        for (index, occurrence) in dates.iter().enumerate() {
+            assert_eq!(Some(index as u64), schedule.occurrence_index(*occurrence));
+        }
+
+        let unbounded = Schedule::new(schedule.start_date(), weekdays, repeat, interval).unwrap();
+        let beyond = unbounded.next_after(date(expected_last)).unwrap();
+
+        assert!(unbounded.matches(beyond));
+        assert!(!schedule.matches(beyond));
+        assert_eq!(None, schedule.occurrence_index(beyond));
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.nth_occurrence(count as u64));
+        assert_eq!(Some(date(expected_last)), schedule.previous_before(date("2030-01-01")));
+    }
+
+    #[test]
+    fn count_and_until_end_at_the_earlier() {
+        let builder = Schedule::builder().start(date("2023-09-12")).weekly().interval(2).on(WeekdayFlags::TUE | WeekdayFlags::THU);
+
+        assert_eq!(Some(date("2023-09-14")), builder.clone().count(2).until(date("2023-10-31")).build().unwrap().last_occurrence());
+        assert_eq!(Some(date("2023-09-28")), builder.clone().count(20).until(date("2023-10-09")).build().unwrap().last_occurrence());
+        assert_eq!(None, builder.build().unwrap().last_occurrence());
+    }
+
+    #[test]
+    fn long_counted_series_iterate_in_linear_time() {
+        // Finding the counted last date walks the whole series; doing so on every step took
+        // minutes for this one.
+        let days = MonthlyBy::days(&[1, 15]).unwrap();
+        let schedule = Schedule::builder().start(date("2000-01-01")).monthly().monthly_by(days).count(20_000).build().unwrap();
+        let occurrences: Vec<chrono::NaiveDate> = schedule.occurrences(chrono::NaiveDate::MIN).collect();
+
+        assert_eq!(20_000, occurrences.len());
+        assert_eq!(Some(date("2833-04-15")), occurrences.last().copied());
+        assert_eq!(occurrences.last().copied(), schedule.last_occurrence());
+    }
+
+    fn excluding(dates: &[&str]) -> ScheduleBuilder {
+        let builder = Schedule::builder().start(date("2023-09-12")).weekly().on(WeekdayFlags::TUE | WeekdayFlags::THU);
+
+        dates.iter().fold(builder, |builder, excluded| builder.exclude(date(excluded)))
+    }
+
+    #[test]
+    fn exclusions_skip_first_next_occurrence() {
+        let schedule = excluding(&["2023-09-14"]).build().unwrap();
+
+        assert_eq!(Ok(date("2023-09-19")), schedule.next_after(date("2023-09-12")));
+        assert!(!schedule.matches(date("2023-09-14")));
+        assert!(schedule.matches(date("2023-09-19")));
+        assert_eq!(Some(date("2023-09-12")), schedule.previous_before(date("2023-09-19")));
+        assert_eq!(None, schedule.occurrence_index(date("2023-09-14")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2023-09-19")));
+    }
+
+    #[test]
+    fn exclusions_skip_consecutive_occurrences() {
+        let schedule = excluding(&["2023-09-14", "2023-09-19", "2023-09-21", "2023-09-26"]).build().unwrap();
+
+        assert_eq!(Ok(date("2023-09-28")), schedule.next_after(date("2023-09-12")));
+        assert_eq!(Ok(date("2023-09-28")), schedule.next_after(date("2023-09-20")));
+        assert_eq!(Some(date("2023-09-12")), schedule.previous_before(date("2023-09-28")));
+    }
+
+    #[test]
+    fn exclusions_of_every_remaining_occurrence_end_the_schedule() {
+        let schedule = excluding(&["2023-09-19", "2023-09-21", "2023-09-26"]).count(5).build().unwrap();
+
+        assert_eq!(Ok(date("2023-09-14")), schedule.next_after(date("2023-09-12")));
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_after(date("2023-09-14")));
+        assert_eq!(Some(date("2023-09-14")), schedule.last_occurrence());
+        assert_eq!(Some(date("2023-09-14")), schedule.previous_before(date("2023-12-01")));
+    }
+
+    #[test]
+    fn excluded_start() {
+        let schedule = excluding(&["2023-09-12"]).build().unwrap();
+
+        assert_eq!(Some(date("2023-09-14")), schedule.first_occurrence());
+        assert_eq!(None, schedule.previous_before(date("2023-09-14")));
+
+        let everything = excluding(&["2023-09-12", "2023-09-14"]).count(2).build().unwrap();
+
+        assert_eq!(None, everything.first_occurrence());
+        assert_eq!(None, everything.last_occurrence());
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), everything.next_after(date("2023-09-12")));
+    }
+
+    fn thursdays_with_extra_monday() -> ScheduleBuilder {
+        Schedule::builder().start(date("2023-09-21")).weekly().on(WeekdayFlags::THU).include(date("2023-09-25"))
+    }
+
+    #[test]
+    fn extra_dates_interleave_with_occurrences() {
+        let schedule = thursdays_with_extra_monday().build().unwrap();
+        let mut dates = vec![schedule.start_date()];
+
+        while dates.len() < 4 {
+            dates.push(schedule.next_after(*dates.last().unwrap()).unwrap());
+        }
+
+        assert_eq!(["2023-09-21", "2023-09-25", "2023-09-28", "2023-10-05"].map(date).to_vec(), dates);
+        assert!(schedule.matches(date("2023-09-25")));
+        assert!(!schedule.matches(date("2023-10-02")));
+        assert_eq!(Some(date("2023-09-25")), schedule.previous_before(date("2023-09-28")));
+        assert_eq!(Some(date("2023-09-21")), schedule.previous_before(date("2023-09-25")));
+        assert_eq!(None, schedule.occurrence_index(date("2023-09-25")));
+    }
+
+    #[test]
+    fn extra_dates_outlast_the_rule() {
+        let schedule = thursdays_with_extra_monday().include(date("2023-12-25")).count(2).build().unwrap();
+
+        assert_eq!(Ok(date("2023-12-25")), schedule.next_after(date("2023-09-28")));
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_after(date("2023-12-25")));
+        assert_eq!(Some(date("2023-12-25")), schedule.last_occurrence());
+        assert_eq!(Some(date("2023-09-28")), schedule.previous_before(date("2023-12-25")));
+    }
+
+    #[test]
+    fn extra_dates_deduplicate_and_respect_exclusions() {
+        let duplicate = thursdays_with_extra_monday().include(date("2023-09-28")).build().unwrap();
+
+        assert_eq!(Ok(date("2023-09-28")), duplicate.next_after(date("2023-09-25")));
+        assert_eq!(Ok(date("2023-10-05")), duplicate.next_after(date("2023-09-28")));
+
+        let excluded = thursdays_with_extra_monday().exclude(date("2023-09-25")).build().unwrap();
+
+        assert!(!excluded.matches(date("2023-09-25")));
+        assert_eq!(Ok(date("2023-09-28")), excluded.next_after(date("2023-09-21")));
+    }
+
+    #[test]
+    fn builder_rejects_extra_date_before_start() {
+        assert_eq!(Err(RepeatingDateError::ExtraDateBeforeStart), thursdays_with_extra_monday().include(date("2023-09-20")).build());
+    }
+
+    #[test]
+    fn builder_rejects_zero_count() {
+        assert_eq!(Err(RepeatingDateError::ZeroCount), Schedule::builder().start(date("2023-09-12")).count(0).build());
+    }
+
+    #[test]
+    fn builder_rejects_until_before_start() {
+        let result = Schedule::builder().start(date("2023-09-12")).until(date("2023-09-11")).build();
+
+        assert_eq!(Err(RepeatingDateError::UntilBeforeStart), result);
+    }
+
+    #[rstest]
+    #[case::daily("2023-9-18", WeekdayFlags::MIDWEEK, Repeating::Daily, 1, "2023-9-25", Some("2023-9-22"))]
+    #[case::daily_stretched("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Daily, 2, "2023-9-26", Some("2023-9-14"))]
+    #[case::daily_first("2023-9-18", WeekdayFlags::MIDWEEK, Repeating::Daily, 1, "2023-9-19", Some("2023-9-18"))]
+    #[case::weekly_same_week("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2, "2023-9-14", Some("2023-9-12"))]
+    #[case::weekly_off_week("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2, "2023-9-26", Some("2023-9-14"))]
+    #[case::weekly_on_week_later_day("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2, "2023-9-29", Some("2023-9-28"))]
+    #[case::weekly_mid_week_start("2023-10-11", WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI | WeekdayFlags::SAT, Repeating::Weekly, 3, "2023-10-30", Some("2023-10-14"))]
+    #[case::weekly_not_before_start("2023-10-11", WeekdayFlags::MON | WeekdayFlags::WED, Repeating::Weekly, 1, "2023-10-12", Some("2023-10-11"))]
+    #[case::monthly("2023-9-19", WeekdayFlags::ANY, Repeating::Monthly, 1, "2024-1-19", Some("2023-12-19"))]
+    #[case::monthly_day_31("2024-1-31", WeekdayFlags::ANY, Repeating::Monthly, 1, "2024-5-1", Some("2024-3-31"))]
+    #[case::bi_monthly_filtered("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Monthly, 2, "2024-9-1", Some("2024-3-12"))]
+    #[case::yearly("2023-9-12", WeekdayFlags::ANY, Repeating::Yearly, 2, "2026-1-1", Some("2025-9-12"))]
+    #[case::yearly_leap_day("1896-2-29", WeekdayFlags::ANY, Repeating::Yearly, 1, "1904-2-28", Some("1896-2-29"))]
+    #[case::on_start("2023-9-12", WeekdayFlags::ANY, Repeating::Yearly, 1, "2023-9-12", None)]
+    #[case::before_start("2023-9-12", WeekdayFlags::ANY, Repeating::Daily, 1, "2023-9-1", None)]
+    fn previous_before(#[case] start: chrono::NaiveDate, #[case] weekdays: WeekdayFlags, #[case] repeat: Repeating, #[case] interval: u64,
+        #[case] from: chrono::NaiveDate, #[case] expected_result: Option<&str>) {
+        let schedule = Schedule::new(start, weekdays, repeat, interval).unwrap();
+
+        assert_eq!(expected_result.map(date), schedule.previous_before(from));
+    }
+
+    #[rstest]
+    #[case("2023-9-18", WeekdayFlags::MIDWEEK, Repeating::Daily, 1)]
+    #[case("2023-9-18", WeekdayFlags::MIDWEEK, Repeating::Daily, 3)]
+    #[case("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Weekly, 2)]
+    #[case("2023-10-11", WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI | WeekdayFlags::SAT, Repeating::Weekly, 3)]
+    #[case("2023-9-13", WeekdayFlags::ANY, Repeating::Weekly, 2)]
+    #[case("2024-1-31", WeekdayFlags::ANY, Repeating::Monthly, 1)]
+    #[case("2023-9-12", WeekdayFlags::TUE | WeekdayFlags::THU, Repeating::Monthly, 2)]
+    #[case("2023-9-12", WeekdayFlags::MIDWEEK, Repeating::Yearly, 1)]
+    #[case::sparse_leap_day("2000-2-29", WeekdayFlags::TUE, Repeating::Yearly, 1)]
+    fn previous_before_walks_back_forward_iteration(#[case] start: chrono::NaiveDate, #[case] weekdays: WeekdayFlags, #[case] repeat: Repeating, #[case] interval: u64) {
+        let schedule = Schedule::new(start, weekdays, repeat, interval).unwrap();
+
+        let mut forward = vec![start];
+        while forward.len() < 30 {
+            forward.push(schedule.next_after(*forward.last().unwrap()).unwrap());
+        }
+
+        for pair in forward.windows(2) {
+            assert_eq!(Some(pair[0]), schedule.previous_before(pair[1]));
+
+            for day in pair[0].iter_days().skip(1).take_while(|day| *day <= pair[1]) {
+                assert_eq!(Some(pair[0]), schedule.previous_before(day), "previous before {}", day);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::monthly(Repeating::Monthly)]
+    #[case::quarterly(Repeating::Quarterly)]
+    #[case::yearly(Repeating::Yearly)]
+    fn previous_before_with_huge_intervals(#[case] repeat: Repeating) {
+        let start = date("2023-09-12");
+
+        assert_eq!(Err(RepeatingDateError::IntervalTooLarge), Schedule::new(start, WeekdayFlags::ANY, repeat, (1 << 32) + 1));
+
+        let interval = if repeat == Repeating::Quarterly { i32::MAX as u64 / 3 } else { i32::MAX as u64 };
+        let schedule = Schedule::new(start, WeekdayFlags::ANY, repeat, interval).unwrap();
+
+        assert_eq!(Some(start), schedule.previous_before(date("2030-01-01")));
+        assert_eq!(None, schedule.previous_before(start));
+        assert!(schedule.matches(start));
+        assert!(!schedule.matches(date("2029-09-12")));
+    }
+
+    #[test]
+    fn daily_constructors() {
+        let start = date("2024-01-30");
+
+        assert_eq!(Schedule::new(start, WeekdayFlags::ANY, Repeating::Daily, 1), Schedule::daily(start));
+        assert_eq!(Ok(date("2024-02-02")), Schedule::every_n_days(start, 3).and_then(|schedule| schedule.next_after(start)));
+        assert_eq!(Err(RepeatingDateError::ZeroInterval), Schedule::every_n_days(start, 0));
+    }
+
+    #[test]
+    fn weekly_constructors() {
+        let weekdays = WeekdayFlags::TUE | WeekdayFlags::THU;
+        let schedule = Schedule::weekly_on(date("2023-09-12"), weekdays).unwrap();
+
+        assert_eq!(Schedule::new(date("2023-09-12"), weekdays, Repeating::Weekly, 1), Ok(schedule));
+
+        // A Wednesday start moves to that week's Thursday.
+        let schedule = Schedule::every_n_weeks_on(date("2023-09-13"), 2, weekdays).unwrap();
+
+        assert_eq!(date("2023-09-14"), schedule.start_date());
+        assert_eq!(Ok(vec![date("2023-09-26"), date("2023-09-28")]), schedule.next_n_dates(date("2023-09-14"), 2).map(Vec::from));
+        assert_eq!(Err(RepeatingDateError::EmptyWeekdaySet), Schedule::weekly_on(date("2023-09-12"), WeekdayFlags::empty()));
+        assert_eq!(Err(RepeatingDateError::ZeroInterval), Schedule::every_n_weeks_on(date("2023-09-12"), 0, weekdays));
+    }
+
+    #[test]
+    fn weeks_at_the_start_of_the_calendar() {
+        let start = chrono::NaiveDate::MIN;
+        let days = |days: &[u64]| days.iter().map(|days| start + chrono::Days::new(*days)).collect::<Vec<chrono::NaiveDate>>();
+        let schedule = Schedule::every_n_weeks_on(start, 2, WeekdayFlags::THU | WeekdayFlags::SAT | WeekdayFlags::SUN).unwrap();
+
+        assert_eq!(days(&[0, 2, 3, 14, 16, 17]), std::iter::once(start).chain(schedule.occurrences(start).take(5)).collect::<Vec<chrono::NaiveDate>>());
+        assert_eq!(Some(start + chrono::Days::new(3)), schedule.previous_before(start + chrono::Days::new(14)));
+        assert_eq!(Ok(start + chrono::Days::new(14)), schedule.nth_occurrence(3));
+        assert_eq!(Some(5), schedule.occurrence_index(start + chrono::Days::new(17)));
+
+        let last_of_the_week = Schedule::builder().start(start + chrono::Days::new(3)).weekly().interval(2).on(WeekdayFlags::THU | WeekdayFlags::SAT | WeekdayFlags::SUN).set_positions([-1]).build().unwrap();
+
+        assert_eq!(days(&[3, 17, 31]), last_of_the_week.occurrences(start).take(3).collect::<Vec<chrono::NaiveDate>>());
+    }
+
+    #[test]
+    fn weekly_same_day() {
+        let schedule = Schedule::weekly_same_day(date("2023-09-21"), 3).unwrap();
+        let every_21_days = Schedule::every_n_days(date("2023-09-21"), 21).unwrap();
+
+        assert_eq!(Schedule::new(date("2023-09-21"), WeekdayFlags::THU, Repeating::Weekly, 3), Ok(schedule.clone()));
+        assert!(schedule.occurrences(chrono::NaiveDate::MIN).take(2000).eq(every_21_days.occurrences(chrono::NaiveDate::MIN).take(2000)));
+        assert_eq!(Ok(date("2123-10-07")), schedule.next_after(date("2123-09-20")));
+        assert_eq!(Err(RepeatingDateError::ZeroInterval), Schedule::weekly_same_day(date("2023-09-21"), 0));
+    }
+
+    #[rstest]
+    #[case::skip(MissingDay::Skip, "2024-01-31", ["2024-01-31", "2024-03-31", "2024-05-31"])]
+    #[case::clamp(MissingDay::Clamp, "2024-01-31", ["2024-01-31", "2024-02-29", "2024-03-31"])]
+    #[case::skip_moves_the_start(MissingDay::Skip, "2024-02-01", ["2024-03-31", "2024-05-31", "2024-07-31"])]
+    #[case::clamp_moves_the_start(MissingDay::Clamp, "2024-02-01", ["2024-02-29", "2024-03-31", "2024-04-30"])]
+    fn monthly_on_day(#[case] missing: MissingDay, #[case] start: chrono::NaiveDate, #[case] expected: [&str; 3]) {
+        let schedule = Schedule::monthly_on_day(start, MonthDay::new(31).unwrap(), missing).unwrap();
+
+        assert_eq!(expected.map(date).to_vec(), schedule.occurrences(chrono::NaiveDate::MIN).take(3).collect::<Vec<chrono::NaiveDate>>());
+    }
+
+    #[test]
+    fn monthly_on_the_last_day() {
+        let schedule = Schedule::monthly_on_day(date("2023-12-15"), MonthDay::LAST, MissingDay::Skip).unwrap();
+
+        assert_eq!(vec![date("2023-12-31"), date("2024-01-31"), date("2024-02-29")], schedule.occurrences(chrono::NaiveDate::MIN).take(3).collect::<Vec<chrono::NaiveDate>>());
+    }
+
+    #[rstest]
+    #[case::this_year("2023-09-12", chrono::Month::December, 25, ["2023-12-25", "2024-12-25"])]
+    #[case::on_the_start("2023-12-25", chrono::Month::December, 25, ["2023-12-25", "2024-12-25"])]
+    #[case::next_year("2023-12-26", chrono::Month::December, 25, ["2024-12-25", "2025-12-25"])]
+    #[case::leap_day("2025-01-01", chrono::Month::February, 29, ["2028-02-29", "2032-02-29"])]
+    fn yearly_on(#[case] start: chrono::NaiveDate, #[case] month: chrono::Month, #[case] day: i8, #[case] expected: [&str; 2]) {
+        let schedule = Schedule::yearly_on(start, month, MonthDay::new(day).unwrap()).unwrap();
+
+        assert_eq!(expected.map(date).to_vec(), schedule.occurrences(chrono::NaiveDate::MIN).take(2).collect::<Vec<chrono::NaiveDate>>());
+    }
+}
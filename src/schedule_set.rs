@@ -0,0 +1,202 @@
+use crate::{RepeatingDateError, Schedule, ScheduleBuilder};
+
+/// Several schedules treated as one: a date occurs when any member has it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleSet {
+    members: Vec<Schedule>
+}
+
+/// A member that failed to build, with its position in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleSetError {
+    pub index: usize,
+    pub error: RepeatingDateError
+}
+
+impl std::fmt::Display for ScheduleSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "schedule {} is invalid: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for ScheduleSetError {}
+
+impl ScheduleSet {
+    pub fn new(members: Vec<Schedule>) -> ScheduleSet {
+        ScheduleSet { members }
+    }
+
+    /// Builds every member, reporting each one that fails rather than only the first.
+    pub fn from_builders(builders: impl IntoIterator<Item = ScheduleBuilder>) -> Result<ScheduleSet, Vec<ScheduleSetError>> {
+        let mut members = vec![];
+        let mut errors = vec![];
+
+        for (index, builder) in builders.into_iter().enumerate() {
+            match builder.build() {
+                Ok(schedule) => members.push(schedule),
+                Err(error) => errors.push(ScheduleSetError { index, error }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(ScheduleSet { members })
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn members(&self) -> &[Schedule] {
+        &self.members
+    }
+
+    /// The earliest member occurrence strictly after `from_date`; `ScheduleEnded` once every
+    /// member has ended.
+    pub fn next_after(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        let mut error = RepeatingDateError::ScheduleEnded;
+
+        self.members.iter()
+            .filter_map(|member| match member.next_or_first(from_date) {
+                Ok(date) => Some(date),
+                Err(RepeatingDateError::ScheduleEnded) => None,
+                Err(member_error) => {
+                    error = member_error;
+                    None
+                },
+            })
+            .min()
+            .ok_or(error)
+    }
+
+    /// Whether any member matches `date`, see `Schedule::matches`.
+    pub fn matches(&self, date: chrono::NaiveDate) -> bool {
+        self.members.iter().any(|member| member.matches(date))
+    }
+
+    /// Every occurrence of every member in ascending order, dates shared by several members once.
+    pub fn iter(&self) -> ScheduleSetIter<'_> {
+        ScheduleSetIter {
+            members: &self.members,
+            upcoming: self.members.iter().map(|member| member.first_occurrence()).collect()
+        }
+    }
+}
+
+impl From<Vec<Schedule>> for ScheduleSet {
+    fn from(members: Vec<Schedule>) -> Self {
+        ScheduleSet::new(members)
+    }
+}
+
+impl<'a> IntoIterator for &'a ScheduleSet {
+    type Item = chrono::NaiveDate;
+    type IntoIter = ScheduleSetIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Merges the members' occurrence streams, see `ScheduleSet::iter`.
+#[derive(Debug, Clone)]
+pub struct ScheduleSetIter<'a> {
+    members: &'a [Schedule],
+    upcoming: Vec<Option<chrono::NaiveDate>>
+}
+
+impl Iterator for ScheduleSetIter<'_> {
+    type Item = chrono::NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self.upcoming.iter().flatten().min().copied()?;
+
+        for (member, upcoming) in self.members.iter().zip(self.upcoming.iter_mut()) {
+            if *upcoming == Some(date) {
+                *upcoming = member.next_after(date).ok();
+            }
+        }
+
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::WeekdayFlags;
+    use crate::test_util::date;
+
+    fn rule(value: &str) -> Schedule {
+        Schedule::from_str(value).unwrap()
+    }
+
+    fn tuesdays_and_firsts() -> ScheduleSet {
+        ScheduleSet::new(vec![rule("weekly;days=TUE;start=2023-08-01"), rule("monthly;start=2023-08-01")])
+    }
+
+    #[test]
+    fn iter_merges_and_deduplicates() {
+        let dates: Vec<chrono::NaiveDate> = tuesdays_and_firsts().iter().take(7).collect();
+
+        // 2023-08-01 is both a Tuesday and the 1st.
+        let expected = ["2023-08-01", "2023-08-08", "2023-08-15", "2023-08-22", "2023-08-29", "2023-09-01", "2023-09-05"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+    }
+
+    #[test]
+    fn next_after_is_earliest_member() {
+        let set = tuesdays_and_firsts();
+
+        assert_eq!(Ok(date("2023-09-01")), set.next_after(date("2023-08-29")));
+        assert_eq!(Ok(date("2023-09-05")), set.next_after(date("2023-09-01")));
+        assert_eq!(Ok(date("2023-08-01")), set.next_after(date("2023-07-01")));
+    }
+
+    #[test]
+    fn matches_any_member() {
+        let set = tuesdays_and_firsts();
+
+        assert!(set.matches(date("2023-08-15")));
+        assert!(set.matches(date("2023-10-01")));
+        assert!(!set.matches(date("2023-10-02")));
+    }
+
+    #[test]
+    fn members_with_different_starts() {
+        let set = ScheduleSet::new(vec![rule("daily;interval=10;start=2023-09-20"), rule("weekly;days=MON;start=2023-09-04")]);
+        let dates: Vec<chrono::NaiveDate> = set.iter().take(6).collect();
+
+        let expected = ["2023-09-04", "2023-09-11", "2023-09-18", "2023-09-20", "2023-09-25", "2023-09-30"];
+        assert_eq!(expected.map(date).to_vec(), dates);
+        assert_eq!(Ok(date("2023-09-18")), set.next_after(date("2023-09-11")));
+        assert_eq!(Ok(date("2023-09-20")), set.next_after(date("2023-09-18")));
+    }
+
+    #[test]
+    fn iter_stops_when_every_member_ended() {
+        let set = ScheduleSet::new(vec![rule("weekly;days=TUE;start=2023-08-01;count=2"), rule("monthly;start=2023-08-01;until=2023-09-30")]);
+        let dates: Vec<chrono::NaiveDate> = set.iter().collect();
+
+        assert_eq!(["2023-08-01", "2023-08-08", "2023-09-01"].map(date).to_vec(), dates);
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), set.next_after(date("2023-09-01")));
+    }
+
+    #[test]
+    fn from_builders_reports_every_failing_member() {
+        let start = date("2023-09-12");
+        let builders = vec![
+            Schedule::builder().start(start).weekly().on(WeekdayFlags::TUE),
+            Schedule::builder().start(start).weekly().on(WeekdayFlags::MON),
+            Schedule::builder().start(start),
+            Schedule::builder().start(start).interval(0),
+        ];
+
+        assert_eq!(Err(vec![
+            ScheduleSetError { index: 1, error: RepeatingDateError::WrongWeekday { start_weekday: chrono::Weekday::Tue, allowed: WeekdayFlags::MON } },
+            ScheduleSetError { index: 3, error: RepeatingDateError::ZeroInterval },
+        ]), ScheduleSet::from_builders(builders));
+
+        assert_eq!(2, ScheduleSet::from_builders(vec![Schedule::builder().start(start), Schedule::builder().start(start).yearly()]).unwrap().members().len());
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+/// A rotating pattern of working days such as four on, four off: a cycle of `cycle_days` days
+/// counted from the schedule's start, of which the zero based `on_days` are occurrences. The cycle
+/// stays aligned to the start however far a lookup goes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShiftPattern {
+    cycle_days: u16,
+    on_days: BTreeSet<u16>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidShiftPattern {
+    EmptyCycle,
+    /// `on_off` was given more than `u16::MAX` days in total.
+    CycleTooLong,
+    /// An on day that is not before the cycle's length.
+    DayOutsideCycle { day: u16 }
+}
+
+impl std::fmt::Display for InvalidShiftPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidShiftPattern::EmptyCycle => write!(f, "a shift cycle must last at least one day"),
+            InvalidShiftPattern::CycleTooLong => write!(f, "a shift cycle lasts at most {} days", u16::MAX),
+            InvalidShiftPattern::DayOutsideCycle { day } => write!(f, "shift day {} is outside the cycle", day),
+        }
+    }
+}
+
+impl std::error::Error for InvalidShiftPattern {}
+
+impl ShiftPattern {
+    pub fn new(cycle_days: u16, on_days: impl IntoIterator<Item = u16>) -> Result<ShiftPattern, InvalidShiftPattern> {
+        if cycle_days == 0 {
+            return Err(InvalidShiftPattern::EmptyCycle);
+        }
+
+        let on_days: BTreeSet<u16> = on_days.into_iter().collect();
+
+        if let Some(day) = on_days.last().filter(|day| **day >= cycle_days) {
+            return Err(InvalidShiftPattern::DayOutsideCycle { day: *day });
+        }
+
+        Ok(ShiftPattern { cycle_days, on_days })
+    }
+
+    /// `on` days on, starting with the schedule's start, followed by `off` days off.
+    pub fn on_off(on: u16, off: u16) -> Result<ShiftPattern, InvalidShiftPattern> {
+        let cycle_days = on.checked_add(off).ok_or(InvalidShiftPattern::CycleTooLong)?;
+
+        ShiftPattern::new(cycle_days, 0..on)
+    }
+
+    pub fn cycle_days(&self) -> u16 {
+        self.cycle_days
+    }
+
+    pub fn on_days(&self) -> &BTreeSet<u16> {
+        &self.on_days
+    }
+
+    /// Whether a deserialized pattern would have passed `new`.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.cycle_days > 0 && self.on_days.last().is_none_or(|day| *day < self.cycle_days)
+    }
+
+    /// The on days of the cycle beginning on `cycle_start`, ascending.
+    pub(crate) fn dates_in(&self, cycle_start: chrono::NaiveDate) -> Vec<chrono::NaiveDate> {
+        self.on_days.iter()
+            .filter_map(|day| cycle_start.checked_add_days(chrono::Days::new(*day as u64)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{RepeatingDateError, Schedule, WeekdayFlags, WeekdayPolicy};
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    fn four_on_four_off(weekdays: WeekdayFlags) -> Schedule {
+        Schedule::builder().start(date("2024-01-01")).on(weekdays).shift_pattern(ShiftPattern::on_off(4, 4).unwrap()).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::empty_cycle(ShiftPattern::new(0, []), InvalidShiftPattern::EmptyCycle)]
+    #[case::day_outside(ShiftPattern::new(8, [0, 8]), InvalidShiftPattern::DayOutsideCycle { day: 8 })]
+    #[case::too_long(ShiftPattern::on_off(u16::MAX, 1), InvalidShiftPattern::CycleTooLong)]
+    fn rejects_malformed(#[case] result: Result<ShiftPattern, InvalidShiftPattern>, #[case] expected: InvalidShiftPattern) {
+        assert_eq!(Err(expected), result);
+    }
+
+    #[test]
+    fn four_on_four_off_cycle() {
+        let schedule = four_on_four_off(WeekdayFlags::ANY);
+
+        assert_eq!(["2024-01-02", "2024-01-03", "2024-01-04", "2024-01-09", "2024-01-10"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 5).unwrap().into_vec());
+        assert_eq!(Ok(date("2024-01-09")), schedule.next_after(date("2024-01-05")));
+        assert_eq!(Some(date("2024-01-04")), schedule.previous_before(date("2024-01-09")));
+        assert!(schedule.matches(date("2024-01-12")));
+        assert!(!schedule.matches(date("2024-01-13")));
+    }
+
+    #[test]
+    fn stays_in_phase_a_year_out() {
+        let schedule = four_on_four_off(WeekdayFlags::ANY);
+
+        // 2025-01-03 is 368 days, 46 whole cycles, after the start.
+        assert_eq!(Ok(date("2025-01-03")), schedule.next_after(date("2024-12-30")));
+        assert_eq!(Some(184), schedule.occurrence_index(date("2025-01-03")));
+        assert_eq!(Ok(date("2025-01-03")), schedule.nth_occurrence(184));
+
+        for day in date("2024-12-01").iter_days().take(60) {
+            let on = (day - date("2024-01-01")).num_days() % 8 < 4;
+
+            assert_eq!(on, schedule.matches(day), "{}", day);
+        }
+    }
+
+    #[test]
+    fn weekday_filter_drops_sundays() {
+        let schedule = four_on_four_off(WeekdayFlags::ANY - WeekdayFlags::SUN);
+
+        // The fourth cycle runs Thursday to Sunday, January 25 to 28.
+        assert_eq!(Ok(date("2024-02-02")), schedule.next_after(date("2024-01-27")));
+        assert!(!schedule.matches(date("2024-01-28")));
+        assert_eq!(Some(date("2024-01-27")), schedule.previous_before(date("2024-02-02")));
+    }
+
+    #[test]
+    fn interval_skips_whole_cycles() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).interval(2).shift_pattern(ShiftPattern::new(3, [0, 2]).unwrap()).build().unwrap();
+
+        assert_eq!(["2024-01-03", "2024-01-07", "2024-01-09"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 3).unwrap().into_vec());
+        assert!(!schedule.matches(date("2024-01-04")));
+    }
+
+    #[test]
+    fn builder_validates_shift_pattern() {
+        let builder = Schedule::builder().start(date("2024-01-01"));
+
+        assert_eq!(Err(RepeatingDateError::InvalidShiftRule), builder.clone().weekly().shift_pattern(ShiftPattern::on_off(4, 4).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::InvalidShiftRule), builder.clone().weekday_policy(WeekdayPolicy::Slide).shift_pattern(ShiftPattern::on_off(4, 4).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), builder.clone().shift_pattern(ShiftPattern::new(8, [1, 2]).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), builder.shift_pattern(ShiftPattern::new(8, []).unwrap()).build());
+    }
+
+    #[test]
+    fn equivalent_to_an_every_other_day_rule() {
+        let shifts = Schedule::builder().start(date("2024-01-01")).shift_pattern(ShiftPattern::new(4, [0, 2]).unwrap()).build().unwrap();
+
+        assert!(shifts.equivalent(&Schedule::from_str("daily;interval=2;start=2024-01-01").unwrap()));
+        assert!(!four_on_four_off(WeekdayFlags::ANY).equivalent(&Schedule::from_str("daily;interval=2;start=2024-01-01").unwrap()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_validates_the_cycle() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).shift_pattern(ShiftPattern::on_off(2, 1).unwrap()).build().unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+
+        assert!(json.ends_with(r#","shift_pattern":{"cycle_days":3,"on_days":[0,1]}}"#), "{}", json);
+        assert_eq!(schedule, serde_json::from_str::<Schedule>(&json).unwrap());
+
+        let json = json.replace("[0,1]", "[0,3]");
+        assert!(serde_json::from_str::<Schedule>(&json).unwrap_err().to_string().contains("a shift pattern needs"));
+    }
+}
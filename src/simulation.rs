@@ -0,0 +1,198 @@
+//! A clock for testing reminder flows against a schedule, behind the `test-support` feature.
+
+use std::collections::BTreeSet;
+
+use crate::{OccurrenceList, Schedule};
+
+/// A schedule seen from a synthetic "today" that only moves forward, with the occurrences marked
+/// done so far. Occurrences up to today that are not done are due; the ones before today are
+/// missed as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    schedule: Schedule,
+    today: chrono::NaiveDate,
+    completed: BTreeSet<chrono::NaiveDate>
+}
+
+impl Simulation {
+    pub fn new(schedule: Schedule, clock_start: chrono::NaiveDate) -> Simulation {
+        Simulation { schedule, today: clock_start, completed: BTreeSet::new() }
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn today(&self) -> chrono::NaiveDate {
+        self.today
+    }
+
+    /// The occurrences marked done, whether or not they were due when completed.
+    pub fn completed(&self) -> &BTreeSet<chrono::NaiveDate> {
+        &self.completed
+    }
+
+    /// Moves today to `date`.
+    ///
+    /// # Panics
+    ///
+    /// When `date` is before today: the clock never goes back.
+    pub fn advance_to(&mut self, date: chrono::NaiveDate) {
+        assert!(date >= self.today, "cannot move the clock back from {} to {}", self.today, date);
+
+        self.today = date;
+    }
+
+    /// Moves today `days` days forward.
+    ///
+    /// # Panics
+    ///
+    /// When that is past `NaiveDate::MAX`.
+    pub fn advance_days(&mut self, days: u64) {
+        let date = self.today.checked_add_days(chrono::Days::new(days)).expect("the clock ran past NaiveDate::MAX");
+
+        self.advance_to(date);
+    }
+
+    /// The occurrences up to and including today that are not done, oldest first.
+    pub fn due_now(&self) -> OccurrenceList {
+        let due = self.schedule.occurrences_between(chrono::NaiveDate::MIN, self.today).into_iter().filter(|date| !self.completed.contains(date));
+
+        OccurrenceList::from_sorted_unchecked(due.collect())
+    }
+
+    /// The occurrences before today that are not done, oldest first.
+    pub fn missed(&self) -> OccurrenceList {
+        let mut missed: Vec<chrono::NaiveDate> = self.schedule.occurrences_before(self.today).filter(|date| !self.completed.contains(date)).collect();
+        missed.reverse();
+
+        OccurrenceList::from_sorted_unchecked(missed)
+    }
+
+    /// The first occurrence on or after today that is not done; `None` once the schedule ends.
+    pub fn next_due(&self) -> Option<chrono::NaiveDate> {
+        let mut next = self.schedule.next_on_or_after(self.today).ok()?;
+
+        while self.completed.contains(&next) {
+            next = self.schedule.next_after(next).ok()?;
+        }
+
+        Some(next)
+    }
+
+    /// Marks the occurrence on `date` done. Whether it was due: dates that are not occurrences,
+    /// lie after today or are done already are left as they are.
+    pub fn complete(&mut self, date: chrono::NaiveDate) -> bool {
+        date <= self.today && self.schedule.matches(date) && self.completed.insert(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeekdayFlags;
+    use crate::test_util::{date, dates};
+
+    fn daily() -> Simulation {
+        Simulation::new(Schedule::daily(date("2024-03-01")).unwrap(), date("2024-03-01"))
+    }
+
+    #[test]
+    fn ignoring_two_weeks_then_catching_up() {
+        let mut simulation = daily();
+
+        assert_eq!(dates(&["2024-03-01"]).as_slice(), simulation.due_now().as_slice());
+        assert!(simulation.missed().is_empty());
+
+        simulation.advance_days(14);
+
+        assert_eq!(15, simulation.due_now().len());
+        assert_eq!(14, simulation.missed().len());
+        assert_eq!(Some(date("2024-03-01")), simulation.missed().first().copied());
+        assert_eq!(Some(date("2024-03-15")), simulation.next_due());
+
+        for missed in simulation.due_now() {
+            assert!(simulation.complete(missed));
+        }
+
+        assert!(simulation.due_now().is_empty());
+        assert_eq!(Some(date("2024-03-16")), simulation.next_due());
+
+        simulation.advance_days(1);
+
+        assert_eq!(dates(&["2024-03-16"]).as_slice(), simulation.due_now().as_slice());
+        assert!(simulation.missed().is_empty());
+    }
+
+    #[test]
+    fn completes_only_due_occurrences() {
+        let mut simulation = Simulation::new(Schedule::weekly_on(date("2024-03-04"), WeekdayFlags::MON | WeekdayFlags::THU).unwrap(), date("2024-03-07"));
+
+        assert!(!simulation.complete(date("2024-03-05")));
+        assert!(!simulation.complete(date("2024-03-11")));
+        assert!(!simulation.complete(date("2024-03-03")));
+        assert!(simulation.complete(date("2024-03-07")));
+        assert!(!simulation.complete(date("2024-03-07")));
+        assert_eq!(&BTreeSet::from([date("2024-03-07")]), simulation.completed());
+        assert_eq!(dates(&["2024-03-04"]).as_slice(), simulation.due_now().as_slice());
+        assert_eq!(Some(date("2024-03-11")), simulation.next_due());
+    }
+
+    /// The habit from `examples/habits.rs`: Monday, Wednesday and Friday, with 2023-10-11 forgotten.
+    #[test]
+    fn habit_with_a_forgotten_day() {
+        let weekdays = WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI;
+        let mut simulation = Simulation::new(Schedule::weekly_on(date("2023-10-02"), weekdays).unwrap(), date("2023-10-02"));
+
+        for done in dates(&["2023-10-02", "2023-10-04", "2023-10-06", "2023-10-09", "2023-10-13", "2023-10-16", "2023-10-18"]) {
+            simulation.advance_to(done);
+            assert!(simulation.complete(done));
+        }
+
+        simulation.advance_to(date("2023-10-20"));
+
+        assert_eq!(dates(&["2023-10-11"]).as_slice(), simulation.missed().as_slice());
+        assert_eq!(dates(&["2023-10-11", "2023-10-20"]).as_slice(), simulation.due_now().as_slice());
+
+        assert!(simulation.complete(date("2023-10-20")));
+        simulation.advance_days(1);
+
+        assert_eq!(dates(&["2023-10-11"]).as_slice(), simulation.due_now().as_slice());
+        assert_eq!(Some(date("2023-10-23")), simulation.next_due());
+
+        // A day off is excluded from the schedule instead of staying due.
+        let day_off = Schedule::builder().start(date("2023-10-02")).weekly().on(weekdays).exclude(date("2023-10-11")).build().unwrap();
+        let mut with_day_off = Simulation::new(day_off, date("2023-10-21"));
+
+        for done in simulation.completed().clone() {
+            assert!(with_day_off.complete(done));
+        }
+
+        assert!(with_day_off.due_now().is_empty());
+    }
+
+    #[test]
+    fn stops_when_the_schedule_ends() {
+        let mut simulation = Simulation::new(Schedule::builder().start(date("2024-03-01")).daily().count(3).build().unwrap(), date("2024-02-20"));
+
+        assert!(simulation.due_now().is_empty());
+        assert_eq!(Some(date("2024-03-01")), simulation.next_due());
+
+        simulation.advance_to(date("2024-03-10"));
+
+        assert_eq!(dates(&["2024-03-01", "2024-03-02", "2024-03-03"]).as_slice(), simulation.missed().as_slice());
+        assert_eq!(None, simulation.next_due());
+
+        assert!(simulation.complete(date("2024-03-03")));
+
+        assert_eq!(dates(&["2024-03-01", "2024-03-02"]).as_slice(), simulation.due_now().as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot move the clock back")]
+    fn never_goes_back() {
+        let mut simulation = daily();
+        simulation.advance_days(3);
+        simulation.advance_to(date("2024-03-02"));
+    }
+}
@@ -0,0 +1,284 @@
+use chrono::Datelike;
+
+use crate::{Repeating, RepeatingDateError, Schedule, WeekdayFlags, WEEKDAYS};
+
+/// What a daily schedule does when stepping by its interval lands on a weekday it does not allow, or
+/// whether those weekdays count towards the interval at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum WeekdayPolicy {
+    /// Keep stepping by the interval until an allowed weekday, so the occurrences stay every
+    /// `interval` days from the start and "every 3 days on weekdays" can leave gaps of 6 or 9 days.
+    #[default]
+    Skip,
+    /// Move forward one day at a time to the next allowed weekday and count the next interval from
+    /// there, as in "every 3 days, but only on weekdays". Only daily schedules can slide.
+    Slide,
+    /// Count the interval over the allowed weekdays only, as in "every 5th business day": each
+    /// occurrence is the `interval`th allowed day after the previous one, and disallowed days never
+    /// use up the count. Only daily schedules count, see `ScheduleBuilder::business_days`.
+    Count
+}
+
+impl WeekdayPolicy {
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_skip(&self) -> bool {
+        *self == WeekdayPolicy::Skip
+    }
+}
+
+/// Day offsets from the start of a sliding rule's occurrences. The weekday of an occurrence
+/// decides the next one, so after at most seven occurrences the weekdays, and with them the gaps,
+/// repeat.
+struct SlideSteps {
+    /// Offsets up to the first occurrence whose weekday repeats an earlier one, excluded.
+    offsets: Vec<u64>,
+    /// Index of that earlier occurrence; the gaps from there on recur.
+    cycle_start: usize,
+    /// Days spanned by one round of the recurring gaps, a multiple of seven.
+    cycle_days: u64
+}
+
+impl SlideSteps {
+    fn cycle_len(&self) -> u64 {
+        (self.offsets.len() - self.cycle_start) as u64
+    }
+
+    /// Offset of the occurrence at zero based position `index`.
+    fn offset(&self, index: u64) -> Option<u64> {
+        if let Some(offset) = self.offsets.get(usize::try_from(index).ok()?) {
+            return Some(*offset);
+        }
+
+        let since_cycle = index - self.cycle_start as u64;
+        let rounds = since_cycle / self.cycle_len();
+        let position = self.cycle_start + (since_cycle % self.cycle_len()) as usize;
+
+        rounds.checked_mul(self.cycle_days)?.checked_add(self.offsets[position])
+    }
+
+    /// Position of the first occurrence at least `offset` days after the start.
+    fn first_at_or_after(&self, offset: u64) -> Option<u64> {
+        if let Some(index) = self.offsets.iter().position(|candidate| *candidate >= offset) {
+            return Some(index as u64);
+        }
+
+        let base = self.offsets[self.cycle_start];
+        let rounds = (offset - base) / self.cycle_days;
+        let within = offset - base - rounds * self.cycle_days;
+        let cycle = &self.offsets[self.cycle_start..];
+
+        let (rounds, position) = match cycle.iter().position(|candidate| candidate - base >= within) {
+            Some(position) => (rounds, position),
+            None => (rounds + 1, 0),
+        };
+
+        rounds.checked_mul(self.cycle_len())?.checked_add((self.cycle_start + position) as u64)
+    }
+}
+
+impl Schedule {
+    /// Whether the weekday of each occurrence decides the next one: `WeekdayPolicy::Slide` and
+    /// `WeekdayPolicy::Count` on a daily rule.
+    pub(crate) fn slides(&self) -> bool {
+        self.repeat() == Repeating::Daily && self.weekday_policy() != WeekdayPolicy::Skip
+    }
+
+    fn slide_steps(&self) -> SlideSteps {
+        let start_weekday = self.start_date().weekday().num_days_from_monday() as u64;
+        let weekday_at = |offset: u64| (start_weekday + offset % 7) % 7;
+        let allowed = |weekday: u64| self.weekdays().contains(WeekdayFlags::from_weekday(WEEKDAYS[(weekday % 7) as usize]));
+
+        let per_week = (0..7).filter(|weekday| allowed(*weekday)).count() as u64;
+        let mut offsets: Vec<u64> = vec![0];
+
+        loop {
+            let last = offsets[offsets.len() - 1];
+            let next = match self.weekday_policy() {
+                WeekdayPolicy::Count => {
+                    let weeks = (self.interval() - 1) / per_week;
+                    let remaining = ((self.interval() - 1) % per_week) as usize;
+                    let within = (1..=7).filter(|days| allowed(weekday_at(last) + days)).nth(remaining).unwrap_or(7);
+
+                    last.saturating_add(weeks.saturating_mul(7)).saturating_add(within)
+                },
+                _ => {
+                    let stepped = last.saturating_add(self.interval());
+                    let slide = (0..7).find(|days| allowed(weekday_at(stepped) + days)).unwrap_or_default();
+
+                    stepped.saturating_add(slide)
+                },
+            };
+
+            if let Some(cycle_start) = offsets.iter().position(|offset| weekday_at(*offset) == weekday_at(next)) {
+                let cycle_days = next - offsets[cycle_start];
+
+                return SlideSteps { offsets, cycle_start, cycle_days };
+            }
+
+            offsets.push(next);
+        }
+    }
+
+    /// The occurrence at zero based position `index` of a sliding or counting rule.
+    pub(crate) fn slide_date(&self, index: u64) -> Option<chrono::NaiveDate> {
+        let offset = self.slide_steps().offset(index)?;
+
+        self.start_date().checked_add_days(chrono::Days::new(offset))
+    }
+
+    /// Zero based position of `date` in a sliding or counting rule; `None` unless it is an occurrence.
+    pub(crate) fn slide_index(&self, date: chrono::NaiveDate) -> Option<u64> {
+        let offset = u64::try_from((date - self.start_date()).num_days()).ok()?;
+        let steps = self.slide_steps();
+        let index = steps.first_at_or_after(offset)?;
+
+        Some(index).filter(|index| steps.offset(*index) == Some(offset))
+    }
+
+    /// The date from which the rule's gaps repeat with `period_days`: the start, except for sliding
+    /// and counting rules that first settle into their weekday pattern.
+    pub(crate) fn pattern_start(&self) -> chrono::NaiveDate {
+        if !self.slides() {
+            return self.start_date();
+        }
+
+        let steps = self.slide_steps();
+        let date = self.start_date().checked_add_days(chrono::Days::new(steps.offsets[steps.cycle_start]));
+
+        date.unwrap_or(chrono::NaiveDate::MAX)
+    }
+
+    pub(crate) fn slide_period_days(&self) -> u64 {
+        self.slide_steps().cycle_days
+    }
+
+    /// `find_next_date` for a sliding or counting rule.
+    pub(crate) fn next_sliding(&self, from_date: chrono::NaiveDate) -> Result<chrono::NaiveDate, RepeatingDateError> {
+        if from_date < self.start_date() {
+            return Err(RepeatingDateError::StartDateBeforeBound { from: from_date, start: self.start_date() });
+        }
+
+        let offset = (from_date - self.start_date()).num_days() as u64 + 1;
+        let index = self.slide_steps().first_at_or_after(offset).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+        self.slide_date(index).ok_or(RepeatingDateError::DateOutOfRange)
+    }
+
+    /// The latest occurrence of a sliding or counting rule before `from_date`, which is after the
+    /// start.
+    pub(crate) fn previous_sliding(&self, from_date: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        let offset = (from_date - self.start_date()).num_days() as u64;
+        let index = self.slide_steps().first_at_or_after(offset)?;
+
+        self.slide_date(index.checked_sub(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use rstest::rstest;
+    use crate::test_util::{date, dates};
+
+    fn every_three_weekdays(policy: WeekdayPolicy) -> Schedule {
+        Schedule::builder().start(date("2024-01-01")).daily().interval(3).on(WeekdayFlags::MIDWEEK).weekday_policy(policy).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::skip(WeekdayPolicy::Skip, &["2024-01-04", "2024-01-10", "2024-01-16", "2024-01-19", "2024-01-22", "2024-01-25"])]
+    #[case::slide(WeekdayPolicy::Slide, &["2024-01-04", "2024-01-08", "2024-01-11", "2024-01-15", "2024-01-18", "2024-01-22"])]
+    fn policies_on_the_same_rule(#[case] policy: WeekdayPolicy, #[case] expected: &[&str]) {
+        let schedule = every_three_weekdays(policy);
+        let expected = dates(expected);
+
+        assert_eq!(expected, schedule.occurrences(date("2024-01-01")).take(6).collect::<Vec<chrono::NaiveDate>>());
+        assert!(expected.iter().all(|date| schedule.matches(*date)));
+    }
+
+    #[test]
+    fn sliding_matches_and_walks_back() {
+        let schedule = every_three_weekdays(WeekdayPolicy::Slide);
+
+        assert!(schedule.matches(date("2024-01-08")));
+        assert!(!schedule.matches(date("2024-01-07")));
+        assert!(!schedule.matches(date("2024-01-10")));
+        assert_eq!(Ok(date("2024-01-11")), schedule.next_after(date("2024-01-09")));
+        assert_eq!(Some(date("2024-01-08")), schedule.previous_before(date("2024-01-11")));
+        assert_eq!(Some(date("2024-01-01")), schedule.previous_before(date("2024-01-02")));
+    }
+
+    #[test]
+    fn sliding_settles_into_a_weekly_pattern() {
+        // Friday, then Monday and Thursday every week.
+        let schedule = Schedule::builder().start(date("2024-01-05")).daily().interval(3).on(WeekdayFlags::MIDWEEK).weekday_policy(WeekdayPolicy::Slide).build().unwrap();
+
+        assert_eq!(["2024-01-08", "2024-01-11", "2024-01-15", "2024-01-18"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-05"), 4).unwrap().into_vec());
+        assert_eq!(Ok(date("2025-01-02")), schedule.nth_occurrence(104));
+        assert_eq!(Some(104), schedule.occurrence_index(date("2025-01-02")));
+        assert_eq!(Ok(date("2025-01-06")), schedule.next_after(date("2025-01-02")));
+        assert_eq!(date("2024-01-08"), schedule.pattern_start());
+    }
+
+    #[test]
+    fn sliding_equivalent_to_weekly() {
+        let weekly = Schedule::from_str("weekly;days=MON,THU;start=2024-01-01").unwrap();
+
+        assert!(every_three_weekdays(WeekdayPolicy::Slide).equivalent(&weekly));
+        assert!(!every_three_weekdays(WeekdayPolicy::Skip).equivalent(&weekly));
+    }
+
+    #[test]
+    fn every_third_business_day() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).business_days().interval(3).build().unwrap();
+
+        assert_eq!(WeekdayFlags::MIDWEEK, schedule.weekdays());
+        assert_eq!(["2024-01-04", "2024-01-09", "2024-01-12", "2024-01-17", "2024-01-22"].map(date).to_vec(), schedule.next_n_dates(date("2024-01-01"), 5).unwrap().into_vec());
+        assert!(schedule.matches(date("2024-01-22")));
+        assert!(!schedule.matches(date("2024-01-20")));
+        assert!(!schedule.matches(date("2024-01-23")));
+        assert_eq!(Some(5), schedule.occurrence_index(date("2024-01-22")));
+        assert_eq!(Ok(date("2024-01-09")), schedule.next_after(date("2024-01-06")));
+        assert_eq!(Some(date("2024-01-12")), schedule.previous_before(date("2024-01-15")));
+    }
+
+    #[rstest]
+    #[case::across_a_weekend("2024-01-05", 7, WeekdayFlags::MIDWEEK, "2024-01-16")]
+    #[case::longer_than_a_week("2024-01-01", 11, WeekdayFlags::MIDWEEK, "2024-01-16")]
+    #[case::custom_working_days("2024-01-01", 2, WeekdayFlags::MON | WeekdayFlags::WED | WeekdayFlags::FRI, "2024-01-05")]
+    fn weekends_never_count(#[case] start: &str, #[case] interval: u64, #[case] weekdays: WeekdayFlags, #[case] expected: &str) {
+        let schedule = Schedule::builder().start(date(start)).business_days().on(weekdays).interval(interval).build().unwrap();
+
+        assert_eq!(Ok(date(expected)), schedule.next_after(date(start)));
+    }
+
+    #[test]
+    fn every_fifth_business_day_is_weekly() {
+        let schedule = Schedule::builder().start(date("2024-01-01")).business_days().interval(5).build().unwrap();
+
+        assert_eq!(Ok(date("2024-12-30")), schedule.nth_occurrence(52));
+        assert!(schedule.equivalent(&Schedule::from_str("weekly;days=MON;start=2024-01-01").unwrap()));
+        assert!(!schedule.equivalent(&every_three_weekdays(WeekdayPolicy::Slide)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_writes_sliding_only() {
+        let json = serde_json::to_string(&every_three_weekdays(WeekdayPolicy::Slide)).unwrap();
+
+        assert!(json.contains(r#""weekday_policy":"slide""#), "{}", json);
+        assert_eq!(every_three_weekdays(WeekdayPolicy::Slide), serde_json::from_str::<Schedule>(&json).unwrap());
+        assert!(!serde_json::to_string(&every_three_weekdays(WeekdayPolicy::Skip)).unwrap().contains("weekday_policy"));
+    }
+
+    #[test]
+    fn only_daily_schedules_slide() {
+        let builder = Schedule::builder().start(date("2024-01-01")).weekday_policy(WeekdayPolicy::Slide);
+
+        assert_eq!(Err(RepeatingDateError::InvalidWeekdayPolicy), builder.clone().weekly().build());
+        assert_eq!(WeekdayPolicy::Slide, builder.build().unwrap().weekday_policy());
+        assert_eq!(Err(RepeatingDateError::InvalidWeekdayPolicy), Schedule::builder().start(date("2024-01-01")).business_days().monthly().build());
+    }
+}
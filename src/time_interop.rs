@@ -0,0 +1,121 @@
+//! `time` crate dates and weekdays for the date functions, behind the `time` feature. Dates
+//! convert by year and day of the year, so each one maps to the same calendar date.
+
+use crate::{find_next_date, match_repeating_date, Repeating, RepeatingDateError, WeekdayFlags};
+
+fn from_time_date(date: time::Date) -> Result<chrono::NaiveDate, RepeatingDateError> {
+    chrono::NaiveDate::from_yo_opt(date.year(), date.ordinal() as u32).ok_or(RepeatingDateError::DateOutOfRange)
+}
+
+fn to_time_date(date: chrono::NaiveDate) -> Result<time::Date, RepeatingDateError> {
+    time::Date::from_ordinal_date(chrono::Datelike::year(&date), chrono::Datelike::ordinal(&date) as u16).map_err(|_| RepeatingDateError::DateOutOfRange)
+}
+
+impl WeekdayFlags {
+    pub fn from_time_weekday(weekday: time::Weekday) -> WeekdayFlags {
+        match weekday {
+            time::Weekday::Monday => WeekdayFlags::MON,
+            time::Weekday::Tuesday => WeekdayFlags::TUE,
+            time::Weekday::Wednesday => WeekdayFlags::WED,
+            time::Weekday::Thursday => WeekdayFlags::THU,
+            time::Weekday::Friday => WeekdayFlags::FRI,
+            time::Weekday::Saturday => WeekdayFlags::SAT,
+            time::Weekday::Sunday => WeekdayFlags::SUN,
+        }
+    }
+}
+
+/// `find_next_date` on `time::Date`s; an occurrence past `time::Date::MAX` is
+/// `RepeatingDateError::DateOutOfRange`.
+pub fn find_next_time_date(from_date: time::Date, start_date: time::Date, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<time::Date, RepeatingDateError> {
+    to_time_date(find_next_date(from_time_date(from_date)?, from_time_date(start_date)?, weekdays, repeat, interval)?)
+}
+
+/// `match_repeating_date` on `time::Date`s.
+pub fn match_repeating_time_date(date_to_check: time::Date, start_date: time::Date, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
+    match (from_time_date(date_to_check), from_time_date(start_date)) {
+        (Ok(date_to_check), Ok(start_date)) => match_repeating_date(date_to_check, start_date, weekdays, repeat, interval),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn date(year: i32, month: u8, day: u8) -> time::Date {
+        time::Date::from_calendar_date(year, time::Month::try_from(month).unwrap(), day).unwrap()
+    }
+
+    #[rstest]
+    #[case::new_years_eve(date(2023, 12, 31), "2023-12-31")]
+    #[case::new_years_day(date(2024, 1, 1), "2024-01-01")]
+    #[case::leap_day(date(2024, 2, 29), "2024-02-29")]
+    #[case::after_a_leap_day(date(2024, 3, 1), "2024-03-01")]
+    #[case::end_of_a_leap_year(date(2024, 12, 31), "2024-12-31")]
+    #[case::century_leap_day(date(2000, 2, 29), "2000-02-29")]
+    #[case::century_without_one(date(1900, 3, 1), "1900-03-01")]
+    #[case::epoch(date(1970, 1, 1), "1970-01-01")]
+    #[case::first(time::Date::MIN, "-9999-01-01")]
+    #[case::last(time::Date::MAX, "9999-12-31")]
+    fn converts_exactly(#[case] date: time::Date, #[case] expected: &str) {
+        let converted = from_time_date(date).unwrap();
+
+        assert_eq!(expected.parse::<chrono::NaiveDate>().unwrap(), converted);
+        assert_eq!(Ok(date), to_time_date(converted));
+    }
+
+    #[test]
+    fn converts_every_day_of_a_leap_cycle() {
+        let mut day = date(1999, 12, 1);
+
+        while day < date(2004, 3, 1) {
+            let converted = from_time_date(day).unwrap();
+
+            assert_eq!((day.year(), day.month() as u32, day.day() as u32), (chrono::Datelike::year(&converted), chrono::Datelike::month(&converted), chrono::Datelike::day(&converted)));
+            assert_eq!(WeekdayFlags::from_time_weekday(day.weekday()), WeekdayFlags::from_weekday(chrono::Datelike::weekday(&converted)));
+            day = day.next_day().unwrap();
+        }
+    }
+
+    #[test]
+    fn out_of_time_range() {
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), to_time_date(chrono::NaiveDate::from_ymd_opt(10000, 1, 1).unwrap()));
+        assert_eq!(Err(RepeatingDateError::DateOutOfRange), find_next_time_date(time::Date::MAX, date(9999, 1, 1), WeekdayFlags::ANY, Repeating::Yearly, 1));
+    }
+
+    #[rstest]
+    #[case::across_new_year(date(2023, 12, 31), date(2023, 12, 1), WeekdayFlags::ANY, Repeating::Daily, 1, date(2024, 1, 1))]
+    #[case::weekly_across_new_year(date(2023, 12, 29), date(2023, 12, 1), WeekdayFlags::FRI, Repeating::Weekly, 1, date(2024, 1, 5))]
+    #[case::leap_day(date(2024, 2, 28), date(2024, 1, 1), WeekdayFlags::ANY, Repeating::Daily, 1, date(2024, 2, 29))]
+    #[case::month_end(date(2024, 1, 31), date(2024, 1, 31), WeekdayFlags::ANY, Repeating::Monthly, 1, date(2024, 3, 31))]
+    #[case::yearly_leap_day(date(2024, 2, 29), date(2024, 2, 29), WeekdayFlags::ANY, Repeating::Yearly, 1, date(2028, 2, 29))]
+    fn finds_next(#[case] from: time::Date, #[case] start: time::Date, #[case] weekdays: WeekdayFlags, #[case] repeat: Repeating, #[case] interval: u64, #[case] expected: time::Date) {
+        let chrono = find_next_date(from_time_date(from).unwrap(), from_time_date(start).unwrap(), weekdays, repeat, interval).unwrap();
+
+        assert_eq!(Ok(expected), find_next_time_date(from, start, weekdays, repeat, interval));
+        assert_eq!(Ok(expected), to_time_date(chrono));
+        assert!(match_repeating_time_date(expected, start, weekdays, repeat, interval));
+    }
+
+    #[rstest]
+    #[case(date(2024, 3, 1), date(2024, 2, 29), Repeating::Daily, true)]
+    #[case(date(2025, 2, 28), date(2024, 2, 29), Repeating::Yearly, false)]
+    #[case(date(2025, 1, 1), date(2024, 1, 1), Repeating::Yearly, true)]
+    #[case(date(2023, 12, 31), date(2024, 1, 1), Repeating::Daily, false)]
+    fn matches(#[case] date_to_check: time::Date, #[case] start: time::Date, #[case] repeat: Repeating, #[case] expected: bool) {
+        assert_eq!(expected, match_repeating_time_date(date_to_check, start, WeekdayFlags::ANY, repeat, 1));
+    }
+
+    #[test]
+    fn weekdays() {
+        let expected = [WeekdayFlags::MON, WeekdayFlags::TUE, WeekdayFlags::WED, WeekdayFlags::THU, WeekdayFlags::FRI, WeekdayFlags::SAT, WeekdayFlags::SUN];
+        let mut weekday = time::Weekday::Monday;
+
+        for flag in expected {
+            assert_eq!(flag, WeekdayFlags::from_time_weekday(weekday));
+            weekday = weekday.next();
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeSet;
+
+use chrono::Datelike;
+
+use crate::{RepeatingDateError, Schedule};
+
+/// The times of day each occurrence date of a `Schedule` expands into, e.g. 08:00 and 20:00 for a
+/// twice daily medication; see `Schedule::datetimes`.
+///
+/// A weekday can have its own time instead, e.g. Mondays at 09:00 and Thursdays at 14:00. Dates on
+/// weekdays without one fall back to the shared times; with no shared times (`per_weekday`),
+/// building a schedule fails with `RepeatingDateError::MissingWeekdayTime` unless every weekday it
+/// can fall on has one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimesOfDay {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeSet::is_empty"))]
+    times: BTreeSet<chrono::NaiveTime>,
+    /// Indexed by days from Monday.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "weekday_times::is_empty", with = "weekday_times"))]
+    by_weekday: [Option<chrono::NaiveTime>; 7]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyTimesOfDay;
+
+impl std::fmt::Display for EmptyTimesOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at least one time of day is required")
+    }
+}
+
+impl std::error::Error for EmptyTimesOfDay {}
+
+impl TimesOfDay {
+    pub fn new(times: impl IntoIterator<Item = chrono::NaiveTime>) -> Result<TimesOfDay, EmptyTimesOfDay> {
+        let times: BTreeSet<chrono::NaiveTime> = times.into_iter().collect();
+
+        if times.is_empty() {
+            return Err(EmptyTimesOfDay);
+        }
+
+        Ok(TimesOfDay { times, by_weekday: [None; 7] })
+    }
+
+    /// One time per weekday and no shared times; a later entry for the same weekday replaces an
+    /// earlier one.
+    pub fn per_weekday(times: impl IntoIterator<Item = (chrono::Weekday, chrono::NaiveTime)>) -> Result<TimesOfDay, EmptyTimesOfDay> {
+        let times_of_day = times.into_iter()
+            .fold(TimesOfDay { times: BTreeSet::new(), by_weekday: [None; 7] }, |times_of_day, (weekday, time)| times_of_day.with_weekday_time(weekday, time));
+
+        if times_of_day.is_empty() {
+            return Err(EmptyTimesOfDay);
+        }
+
+        Ok(times_of_day)
+    }
+
+    /// Dates on `weekday` occur at `time` only, in place of the shared times.
+    pub fn with_weekday_time(mut self, weekday: chrono::Weekday, time: chrono::NaiveTime) -> TimesOfDay {
+        self.by_weekday[weekday.num_days_from_monday() as usize] = Some(time);
+        self
+    }
+
+    /// The shared times, for weekdays without their own.
+    pub fn times(&self) -> &BTreeSet<chrono::NaiveTime> {
+        &self.times
+    }
+
+    pub fn weekday_time(&self, weekday: chrono::Weekday) -> Option<chrono::NaiveTime> {
+        self.by_weekday[weekday.num_days_from_monday() as usize]
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.times.is_empty() && weekday_times::is_empty(&self.by_weekday)
+    }
+
+    /// The times `date` expands into, ascending; empty on a weekday without its own time when there
+    /// are no shared ones.
+    pub(crate) fn on(&self, date: chrono::NaiveDate) -> Vec<chrono::NaiveTime> {
+        match self.weekday_time(date.weekday()) {
+            Some(time) => vec![time],
+            None => self.times.iter().copied().collect(),
+        }
+    }
+}
+
+mod weekday_times {
+    pub(super) fn is_empty(by_weekday: &[Option<chrono::NaiveTime>; 7]) -> bool {
+        by_weekday.iter().all(Option::is_none)
+    }
+
+    /// As a map from day names to times, Monday first.
+    #[cfg(feature = "serde")]
+    pub(super) fn serialize<S: serde::Serializer>(by_weekday: &[Option<chrono::NaiveTime>; 7], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(crate::WEEKDAY_NAMES.iter().zip(by_weekday).filter_map(|(name, time)| Some((name, (*time)?))))
+    }
+
+    #[cfg(feature = "serde")]
+    pub(super) fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<[Option<chrono::NaiveTime>; 7], D::Error> {
+        let named = <std::collections::BTreeMap<String, chrono::NaiveTime> as serde::Deserialize>::deserialize(deserializer)?;
+        let mut by_weekday = [None; 7];
+
+        for (name, time) in named {
+            let index = crate::WEEKDAY_NAMES.iter().position(|candidate| *candidate == name)
+                .ok_or_else(|| serde::de::Error::unknown_variant(&name, &crate::WEEKDAY_NAMES))?;
+
+            by_weekday[index] = Some(time);
+        }
+
+        Ok(by_weekday)
+    }
+}
+
+impl Schedule {
+    /// The times of day occurrences happen at, ascending; midnight when the schedule has no
+    /// `TimesOfDay`.
+    fn times_on(&self, date: chrono::NaiveDate) -> Vec<chrono::NaiveTime> {
+        match self.times_of_day() {
+            Some(times) => times.on(date),
+            None => vec![chrono::NaiveTime::MIN],
+        }
+    }
+
+    /// The first occurrence strictly after `from`: a later time on the same date when `from` is
+    /// on an occurrence date, otherwise the first time of the next one, see `next_after`.
+    pub fn next_datetime_after(&self, from: chrono::NaiveDateTime) -> Result<chrono::NaiveDateTime, RepeatingDateError> {
+        if self.matches(from.date()) {
+            if let Some(time) = self.times_on(from.date()).into_iter().find(|time| *time > from.time()) {
+                return Ok(from.date().and_time(time));
+            }
+        }
+
+        let date = self.next_after(from.date())?;
+
+        Ok(date.and_time(self.times_on(date)[0]))
+    }
+
+    /// The last occurrence strictly before `from`: an earlier time on the same date when `from` is
+    /// on an occurrence date, otherwise the last time of the previous one, see `previous_before`.
+    pub fn previous_datetime_before(&self, from: chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> {
+        if self.matches(from.date()) {
+            if let Some(time) = self.times_on(from.date()).into_iter().rfind(|time| *time < from.time()) {
+                return Some(from.date().and_time(time));
+            }
+        }
+
+        let date = self.previous_before(from.date())?;
+
+        self.times_on(date).last().map(|time| date.and_time(*time))
+    }
+
+    /// Whether `datetime` falls on an occurrence date at one of its times, see `matches`.
+    pub fn matches_datetime(&self, datetime: chrono::NaiveDateTime) -> bool {
+        self.matches(datetime.date()) && self.times_on(datetime.date()).contains(&datetime.time())
+    }
+
+    /// The occurrences strictly after `from` in ascending order, each date expanded into its times
+    /// of day, starting with the first occurrence when `from` is before the start.
+    pub fn datetimes(&self, from: chrono::NaiveDateTime) -> Datetimes<'_> {
+        Datetimes { schedule: self, from: Some(from), done: false }
+    }
+}
+
+/// Iterator over a schedule's occurrences as datetimes, see `Schedule::datetimes`.
+#[derive(Debug, Clone)]
+pub struct Datetimes<'a> {
+    schedule: &'a Schedule,
+    /// The previous datetime yielded, `None` before the first occurrence.
+    from: Option<chrono::NaiveDateTime>,
+    done: bool
+}
+
+impl Iterator for Datetimes<'_> {
+    type Item = chrono::NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = match self.from {
+            Some(from) if from.date() >= self.schedule.start_date() => self.schedule.next_datetime_after(from),
+            _ => self.schedule.first_occurrence()
+                .map(|date| date.and_time(self.schedule.times_on(date)[0]))
+                .ok_or(RepeatingDateError::ScheduleEnded),
+        };
+
+        match next {
+            Ok(datetime) => {
+                self.from = Some(datetime);
+                Some(datetime)
+            },
+            Err(_) => {
+                self.done = true;
+                None
+            },
+        }
+    }
+}
+
+impl std::iter::FusedIterator for Datetimes<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::WeekdayFlags;
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    fn datetime(value: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_str(value).unwrap()
+    }
+
+    fn time(value: &str) -> chrono::NaiveTime {
+        chrono::NaiveTime::from_str(value).unwrap()
+    }
+
+    fn twice_daily() -> Schedule {
+        Schedule::builder().start(date("2024-03-01")).times_of_day(TimesOfDay::new([time("20:00"), time("08:00")]).unwrap()).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::before_the_first("2024-03-05T07:00:00", "2024-03-05T08:00:00")]
+    #[case::on_the_first("2024-03-05T08:00:00", "2024-03-05T20:00:00")]
+    #[case::between("2024-03-05T12:00:00", "2024-03-05T20:00:00")]
+    #[case::on_the_last("2024-03-05T20:00:00", "2024-03-06T08:00:00")]
+    #[case::before_the_start("2024-02-20T12:00:00", "2024-03-01T08:00:00")]
+    fn next_twice_daily(#[case] from: &str, #[case] expected: &str) {
+        assert_eq!(datetime(expected), twice_daily().datetimes(datetime(from)).next().unwrap());
+    }
+
+    #[test]
+    fn iteration_interleaves_days() {
+        let schedule = Schedule::builder().start(date("2024-03-01")).on(WeekdayFlags::FRI | WeekdayFlags::MON).weekly().times_of_day(TimesOfDay::new([time("09:00"), time("17:30")]).unwrap()).count(2).build().unwrap();
+        let datetimes: Vec<chrono::NaiveDateTime> = schedule.datetimes(datetime("2024-03-01T00:00:00")).collect();
+
+        assert_eq!(["2024-03-01T09:00:00", "2024-03-01T17:30:00", "2024-03-04T09:00:00", "2024-03-04T17:30:00"].map(datetime).to_vec(), datetimes);
+        assert_eq!(Err(RepeatingDateError::ScheduleEnded), schedule.next_datetime_after(datetime("2024-03-04T17:30:00")));
+    }
+
+    #[rstest]
+    #[case::after_the_last("2024-03-05T21:00:00", Some("2024-03-05T20:00:00"))]
+    #[case::on_the_last("2024-03-05T20:00:00", Some("2024-03-05T08:00:00"))]
+    #[case::on_the_first("2024-03-05T08:00:00", Some("2024-03-04T20:00:00"))]
+    #[case::first_occurrence("2024-03-01T08:00:00", None)]
+    fn previous_twice_daily(#[case] from: &str, #[case] expected: Option<&str>) {
+        assert_eq!(expected.map(datetime), twice_daily().previous_datetime_before(datetime(from)));
+    }
+
+    #[rstest]
+    #[case::first_time("2024-03-05T08:00:00", true)]
+    #[case::second_time("2024-03-05T20:00:00", true)]
+    #[case::other_time("2024-03-05T12:00:00", false)]
+    #[case::before_the_start("2024-02-29T08:00:00", false)]
+    fn matches_date_and_time(#[case] check: &str, #[case] expected: bool) {
+        assert_eq!(expected, twice_daily().matches_datetime(datetime(check)));
+    }
+
+    #[test]
+    fn midnight_without_times() {
+        let schedule = Schedule::builder().start(date("2024-03-01")).on(WeekdayFlags::FRI).weekly().build().unwrap();
+
+        assert_eq!(Ok(datetime("2024-03-08T00:00:00")), schedule.next_datetime_after(datetime("2024-03-01T00:00:00")));
+        assert!(schedule.matches_datetime(datetime("2024-03-08T00:00:00")));
+    }
+
+    #[test]
+    fn rejects_empty_times() {
+        assert_eq!(Err(EmptyTimesOfDay), TimesOfDay::new([]));
+        assert_eq!(Err(EmptyTimesOfDay), TimesOfDay::per_weekday([]));
+    }
+
+    fn evening_then_morning() -> Schedule {
+        let times = TimesOfDay::per_weekday([(chrono::Weekday::Mon, time("18:00")), (chrono::Weekday::Tue, time("08:00"))]).unwrap();
+
+        Schedule::builder().start(date("2024-03-04")).weekly().on(WeekdayFlags::MON | WeekdayFlags::TUE).times_of_day(times).build().unwrap()
+    }
+
+    #[rstest]
+    #[case::monday_morning("2024-03-11T07:00:00", "2024-03-11T18:00:00")]
+    #[case::monday_evening("2024-03-11T18:00:00", "2024-03-12T08:00:00")]
+    #[case::tuesday_after("2024-03-12T08:00:00", "2024-03-18T18:00:00")]
+    fn adjacent_weekday_times_keep_date_order(#[case] from: &str, #[case] expected: &str) {
+        assert_eq!(Ok(datetime(expected)), evening_then_morning().next_datetime_after(datetime(from)));
+    }
+
+    #[test]
+    fn weekday_times_iterate_and_match() {
+        let schedule = evening_then_morning();
+
+        assert_eq!(["2024-03-04T18:00:00", "2024-03-05T08:00:00", "2024-03-11T18:00:00"].map(datetime).to_vec(), schedule.datetimes(datetime("2024-03-01T00:00:00")).take(3).collect::<Vec<_>>());
+        assert!(schedule.matches_datetime(datetime("2024-03-05T08:00:00")));
+        assert!(!schedule.matches_datetime(datetime("2024-03-05T18:00:00")));
+    }
+
+    #[test]
+    fn weekday_time_replaces_the_shared_times() {
+        let times = TimesOfDay::new([time("09:00"), time("13:00")]).unwrap().with_weekday_time(chrono::Weekday::Thu, time("14:00"));
+        let schedule = Schedule::builder().start(date("2024-03-04")).weekly().on(WeekdayFlags::MON | WeekdayFlags::THU).times_of_day(times).build().unwrap();
+
+        assert_eq!(["2024-03-04T09:00:00", "2024-03-04T13:00:00", "2024-03-07T14:00:00", "2024-03-11T09:00:00"].map(datetime).to_vec(), schedule.datetimes(datetime("2024-03-01T00:00:00")).take(4).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn builder_requires_every_weekday_time() {
+        let monday_only = TimesOfDay::per_weekday([(chrono::Weekday::Mon, time("09:00"))]).unwrap();
+        let builder = Schedule::builder().start(date("2024-03-04")).weekly();
+
+        assert_eq!(Err(RepeatingDateError::MissingWeekdayTime), builder.clone().on(WeekdayFlags::MON | WeekdayFlags::THU).times_of_day(monday_only.clone()).build());
+        assert_eq!(Err(RepeatingDateError::MissingWeekdayTime), builder.clone().on(WeekdayFlags::MON).include(date("2024-03-06")).times_of_day(monday_only.clone()).build());
+        assert!(builder.on(WeekdayFlags::MON).times_of_day(monday_only).build().is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_empty_times() {
+        let json = serde_json::to_string(&twice_daily()).unwrap();
+
+        assert!(json.ends_with(r#","times_of_day":{"times":["08:00:00","20:00:00"]}}"#), "{}", json);
+        assert_eq!(twice_daily(), serde_json::from_str::<Schedule>(&json).unwrap());
+
+        let json = json.replace(r#"["08:00:00","20:00:00"]"#, "[]");
+        assert!(serde_json::from_str::<Schedule>(&json).unwrap_err().to_string().contains("times of day need at least one time"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_weekday_times() {
+        let json = serde_json::to_string(&evening_then_morning()).unwrap();
+
+        assert!(json.ends_with(r#","times_of_day":{"by_weekday":{"MON":"18:00:00","TUE":"08:00:00"}}}"#), "{}", json);
+        assert_eq!(evening_then_morning(), serde_json::from_str::<Schedule>(&json).unwrap());
+        assert!(serde_json::from_str::<Schedule>(&json.replace("TUE", "TU")).unwrap_err().to_string().contains("unknown variant `TU`"));
+        assert!(serde_json::from_str::<Schedule>(&json.replace(r#","TUE":"08:00:00""#, "")).unwrap_err().to_string().contains("lack a time for a weekday"));
+    }
+}
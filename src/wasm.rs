@@ -0,0 +1,160 @@
+//! A small `wasm-bindgen` API for JavaScript, behind the `wasm` feature: a `Rule` built from
+//! plain values, with dates as ISO 8601 `YYYY-MM-DD` strings both ways.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rule_format::parse_frequency;
+use crate::{weekday_flag_named, RepeatingDateError, Schedule, WeekdayFlags};
+
+fn parse_date(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("invalid date `{}`, expected YYYY-MM-DD", value))
+}
+
+fn parse_weekdays(names: &[String]) -> Result<WeekdayFlags, String> {
+    if names.is_empty() {
+        return Ok(WeekdayFlags::ANY);
+    }
+
+    names.iter().try_fold(WeekdayFlags::empty(), |weekdays, name| {
+        weekday_flag_named(&name.to_ascii_uppercase()).map(|flag| weekdays | flag).ok_or_else(|| format!("invalid weekday `{}`, expected MON to SUN", name))
+    })
+}
+
+/// The variant name, so JavaScript can tell errors apart without parsing their prose.
+fn variant_name(error: &RepeatingDateError) -> &'static str {
+    match error {
+        RepeatingDateError::StartDateBeforeBound { .. } => "StartDateBeforeBound",
+        RepeatingDateError::WrongWeekday { .. } => "WrongWeekday",
+        RepeatingDateError::NoOccurrenceFound => "NoOccurrenceFound",
+        RepeatingDateError::MissingStartDate => "MissingStartDate",
+        RepeatingDateError::ZeroInterval => "ZeroInterval",
+        RepeatingDateError::EmptyWeekdaySet => "EmptyWeekdaySet",
+        RepeatingDateError::ScheduleEnded => "ScheduleEnded",
+        RepeatingDateError::UntilBeforeStart => "UntilBeforeStart",
+        RepeatingDateError::ZeroCount => "ZeroCount",
+        RepeatingDateError::ExtraDateBeforeStart => "ExtraDateBeforeStart",
+        RepeatingDateError::HorizonExceeded => "HorizonExceeded",
+        RepeatingDateError::DateOutOfRange => "DateOutOfRange",
+        RepeatingDateError::IntervalTooLarge => "IntervalTooLarge",
+        RepeatingDateError::YearSearchExhausted { .. } => "YearSearchExhausted",
+        RepeatingDateError::InvalidMonthlyRule => "InvalidMonthlyRule",
+        RepeatingDateError::StartNotInRule => "StartNotInRule",
+        RepeatingDateError::InvalidMonthSet => "InvalidMonthSet",
+        RepeatingDateError::InvalidWeekdayPolicy => "InvalidWeekdayPolicy",
+        RepeatingDateError::InvalidSetPosition => "InvalidSetPosition",
+        RepeatingDateError::InvalidWeekNumber => "InvalidWeekNumber",
+        RepeatingDateError::InvalidYearDayRule => "InvalidYearDayRule",
+        RepeatingDateError::InvalidShiftRule => "InvalidShiftRule",
+        RepeatingDateError::InvalidTimesOfDay => "InvalidTimesOfDay",
+        RepeatingDateError::MissingWeekdayTime => "MissingWeekdayTime",
+        RepeatingDateError::InvalidDuration => "InvalidDuration",
+        RepeatingDateError::SpanEndBeforeStart => "SpanEndBeforeStart",
+        RepeatingDateError::UnsupportedFrequency => "UnsupportedFrequency",
+    }
+}
+
+/// `ZeroInterval: the interval must be at least 1`.
+fn error_message(error: RepeatingDateError) -> String {
+    format!("{}: {}", variant_name(&error), error)
+}
+
+fn format_date(date: chrono::NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn build(start: &str, frequency: &str, interval: u32, weekdays: &[String]) -> Result<Schedule, String> {
+    let repeat = parse_frequency(&frequency.to_ascii_lowercase()).map_err(|error| error.to_string())?;
+
+    Schedule::new(parse_date(start)?, parse_weekdays(weekdays)?, repeat, interval as u64).map_err(error_message)
+}
+
+/// A rule as in `Schedule::new`; errors are thrown as `Error`s whose message is the
+/// `RepeatingDateError` variant name followed by its message, or says which argument is invalid.
+#[wasm_bindgen(js_name = Rule)]
+pub struct WasmRule {
+    schedule: Schedule
+}
+
+#[wasm_bindgen(js_class = Rule)]
+impl WasmRule {
+    /// `new Rule("2024-01-02", "weekly", 2, ["TUE", "THU"])`: the frequency is daily, weekly,
+    /// monthly, quarterly or yearly and weekdays are `MON` to `SUN`, both in any case. No weekdays
+    /// allow every day.
+    #[wasm_bindgen(constructor)]
+    pub fn new(start: &str, frequency: &str, interval: u32, weekdays: Vec<String>) -> Result<WasmRule, JsError> {
+        build(start, frequency, interval, &weekdays).map(|schedule| WasmRule { schedule }).map_err(|message| JsError::new(&message))
+    }
+
+    /// The first occurrence strictly after `date`, see `Schedule::next_after`.
+    #[wasm_bindgen(js_name = nextAfter)]
+    pub fn next_after(&self, date: &str) -> Result<String, JsError> {
+        let date = parse_date(date).map_err(|message| JsError::new(&message))?;
+
+        self.schedule.next_after(date).map(format_date).map_err(|error| JsError::new(&error_message(error)))
+    }
+
+    pub fn matches(&self, date: &str) -> Result<bool, JsError> {
+        parse_date(date).map(|date| self.schedule.matches(date)).map_err(|message| JsError::new(&message))
+    }
+
+    /// The `n` occurrences strictly after `date`, see `Schedule::next_n_dates`.
+    #[wasm_bindgen(js_name = nextN)]
+    pub fn next_n(&self, date: &str, n: usize) -> Result<Vec<String>, JsError> {
+        let date = parse_date(date).map_err(|message| JsError::new(&message))?;
+
+        self.schedule.next_n_dates(date, n).map(|dates| dates.into_iter().map(format_date).collect()).map_err(|error| JsError::new(&error_message(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn builds_from_plain_values() {
+        let schedule = build("2024-01-02", "Weekly", 2, &names(&["tue", "THU"])).unwrap();
+
+        assert_eq!(Ok(vec!["2024-01-04".to_string(), "2024-01-16".to_string()]), schedule.next_n_dates(parse_date("2024-01-02").unwrap(), 2).map(|dates| dates.into_iter().map(format_date).collect::<Vec<String>>()));
+        assert_eq!(Ok(WeekdayFlags::ANY), build("2024-01-31", "monthly", 1, &[]).map(|schedule| schedule.weekdays()));
+    }
+
+    #[rstest]
+    #[case::date("2024-13-01", "daily", 1, &[], "invalid date `2024-13-01`, expected YYYY-MM-DD")]
+    #[case::frequency("2024-01-01", "hourly", 1, &[], "unknown frequency `hourly`, expected daily, weekly, monthly, quarterly or yearly")]
+    #[case::weekday("2024-01-01", "daily", 1, &["MONDAY"], "invalid weekday `MONDAY`, expected MON to SUN")]
+    #[case::rule("2024-01-02", "weekly", 1, &["MON"], "WrongWeekday: the start falls on TUE, which is not one of the allowed weekdays MON")]
+    #[case::zero_interval("2024-01-01", "daily", 0, &[], "ZeroInterval: the interval must be at least 1")]
+    fn reports(#[case] start: &str, #[case] frequency: &str, #[case] interval: u32, #[case] weekdays: &[&str], #[case] expected: &str) {
+        assert_eq!(Err(expected.to_string()), build(start, frequency, interval, &names(weekdays)));
+    }
+
+    #[rstest]
+    #[case::fieldless(RepeatingDateError::ScheduleEnded)]
+    #[case::with_fields(RepeatingDateError::YearSearchExhausted { searched_years: 100 })]
+    #[case::with_dates(RepeatingDateError::StartDateBeforeBound { from: parse_date("2024-01-01").unwrap(), start: parse_date("2024-01-02").unwrap() })]
+    fn names_the_variant(#[case] error: RepeatingDateError) {
+        assert!(format!("{:?}", error).starts_with(variant_name(&error)), "{:?}", error);
+        assert_eq!(format!("{}: {}", variant_name(&error), error), error_message(error));
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn happy_path() {
+        let rule = WasmRule::new("2024-01-02", "weekly", 2, vec!["TUE".to_string(), "THU".to_string()]).unwrap();
+
+        assert_eq!("2024-01-04", rule.next_after("2024-01-02").unwrap());
+        assert!(rule.matches("2024-01-16").unwrap());
+        assert!(!rule.matches("2024-01-09").unwrap());
+        assert_eq!(vec!["2024-01-04", "2024-01-16", "2024-01-18"], rule.next_n("2024-01-02", 3).unwrap());
+    }
+}
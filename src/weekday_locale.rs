@@ -0,0 +1,238 @@
+//! Weekday names in a handful of languages, behind the `locales` feature.
+
+use crate::{WeekdayFlags, WEEKDAYS};
+
+/// A language `WeekdayFlags::parse_localized` reads weekday names in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeekdayLocale {
+    English,
+    BrazilianPortuguese,
+    German,
+    Spanish
+}
+
+impl WeekdayLocale {
+    /// The locale of a BCP 47 language tag such as `pt-BR` or `de_AT`, by its language alone, in
+    /// any case; Portuguese is read as spoken in Brazil.
+    pub fn from_tag(tag: &str) -> Option<WeekdayLocale> {
+        let language = tag.split(['-', '_']).next()?;
+
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(WeekdayLocale::English),
+            "pt" => Some(WeekdayLocale::BrazilianPortuguese),
+            "de" => Some(WeekdayLocale::German),
+            "es" => Some(WeekdayLocale::Spanish),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            WeekdayLocale::English => "en",
+            WeekdayLocale::BrazilianPortuguese => "pt-BR",
+            WeekdayLocale::German => "de",
+            WeekdayLocale::Spanish => "es",
+        }
+    }
+
+    /// Lower case full names, first, and abbreviations of each weekday, Monday first, with
+    /// unaccented spellings too.
+    fn names(self) -> &'static [&'static [&'static str]; 7] {
+        match self {
+            WeekdayLocale::English => &ENGLISH,
+            WeekdayLocale::BrazilianPortuguese => &BRAZILIAN_PORTUGUESE,
+            WeekdayLocale::German => &GERMAN,
+            WeekdayLocale::Spanish => &SPANISH,
+        }
+    }
+}
+
+impl std::fmt::Display for WeekdayLocale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+const ENGLISH: [&[&str]; 7] = [
+    &["monday", "mon", "mo"],
+    &["tuesday", "tue", "tues", "tu"],
+    &["wednesday", "wed", "we"],
+    &["thursday", "thu", "thur", "thurs", "th"],
+    &["friday", "fri", "fr"],
+    &["saturday", "sat", "sa"],
+    &["sunday", "sun", "su"],
+];
+
+const BRAZILIAN_PORTUGUESE: [&[&str]; 7] = [
+    &["segunda-feira", "segunda", "seg"],
+    &["terça-feira", "terca-feira", "terça", "terca", "ter"],
+    &["quarta-feira", "quarta", "qua"],
+    &["quinta-feira", "quinta", "qui"],
+    &["sexta-feira", "sexta", "sex"],
+    &["sábado", "sabado", "sáb", "sab"],
+    &["domingo", "dom"],
+];
+
+const GERMAN: [&[&str]; 7] = [
+    &["montag", "mon", "mo"],
+    &["dienstag", "die", "di"],
+    &["mittwoch", "mit", "mi"],
+    &["donnerstag", "don", "do"],
+    &["freitag", "fre", "fr"],
+    &["samstag", "sonnabend", "sam", "sa"],
+    &["sonntag", "son", "so"],
+];
+
+/// The single letters are the usual calendar headings, `x` marking Wednesday apart from Tuesday.
+const SPANISH: [&[&str]; 7] = [
+    &["lunes", "lun", "lu", "l"],
+    &["martes", "mar", "ma", "m"],
+    &["miércoles", "miercoles", "mié", "mie", "mi", "x"],
+    &["jueves", "jue", "ju", "j"],
+    &["viernes", "vie", "vi", "v"],
+    &["sábado", "sabado", "sáb", "sab", "sa", "s"],
+    &["domingo", "dom", "do", "d"],
+];
+
+/// A token of a localized weekday list that `WeekdayFlags::parse_localized` cannot read, as
+/// written, with its zero based index among the comma separated ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalizedWeekdayError {
+    /// Neither a name or abbreviation of the locale nor the start of a name.
+    Unknown { token: String, position: usize, locale: WeekdayLocale },
+    /// The start of names of several weekdays, as `qu` of quarta-feira and quinta-feira.
+    Ambiguous { token: String, position: usize, candidates: WeekdayFlags }
+}
+
+impl std::fmt::Display for LocalizedWeekdayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalizedWeekdayError::Unknown { token, position, locale } => write!(f, "`{}` at position {} is not a weekday in {}", token, position, locale),
+            LocalizedWeekdayError::Ambiguous { token, position, candidates } => write!(f, "`{}` at position {} could be any of {}", token, position, candidates),
+        }
+    }
+}
+
+impl std::error::Error for LocalizedWeekdayError {}
+
+/// The weekdays a lower cased token names: those with it as a name or abbreviation, or else those
+/// with a name starting with it.
+fn weekdays_named(locale: WeekdayLocale, token: &str) -> WeekdayFlags {
+    let matching = |matches: &dyn Fn(&str) -> bool| locale.names().iter().zip(WEEKDAYS)
+        .filter(|(names, _)| names.iter().any(|name| matches(name)))
+        .map(|(_, weekday)| weekday)
+        .collect::<WeekdayFlags>();
+
+    let exact = matching(&|name| name == token);
+
+    if !exact.is_empty() {
+        return exact;
+    }
+
+    matching(&|name| name.starts_with(token))
+}
+
+impl WeekdayFlags {
+    /// Comma separated weekday names of `locale` in any case and order, each a full name, an
+    /// abbreviation with or without a trailing period, or the start of a name of only one weekday:
+    /// `"seg, qua, sex"` in `pt-BR` or `"Mo, Mi, Fr"` in `de`. Repeats are allowed and an empty
+    /// string is no weekday.
+    pub fn parse_localized(value: &str, locale: WeekdayLocale) -> Result<WeekdayFlags, LocalizedWeekdayError> {
+        if value.trim().is_empty() {
+            return Ok(WeekdayFlags::empty());
+        }
+
+        value.split(',').enumerate().try_fold(WeekdayFlags::empty(), |weekdays, (position, token)| {
+            let token = token.trim();
+            let name = token.strip_suffix('.').unwrap_or(token).split_whitespace().collect::<Vec<&str>>().join("-").to_lowercase();
+
+            let candidates = if name.is_empty() { WeekdayFlags::empty() } else { weekdays_named(locale, &name) };
+
+            match candidates.bits().count_ones() {
+                0 => Err(LocalizedWeekdayError::Unknown { token: token.to_string(), position, locale }),
+                1 => Ok(weekdays | candidates),
+                _ => Err(LocalizedWeekdayError::Ambiguous { token: token.to_string(), position, candidates }),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const MON_WED_FRI: WeekdayFlags = WeekdayFlags::MON.union(WeekdayFlags::WED).union(WeekdayFlags::FRI);
+
+    #[rstest]
+    #[case::english_full(WeekdayLocale::English, "Monday, Wednesday, Friday", MON_WED_FRI)]
+    #[case::english_short(WeekdayLocale::English, "mon, wed., FRI", MON_WED_FRI)]
+    #[case::english_prefix(WeekdayLocale::English, "tuesd, thurs", WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::portuguese_full(WeekdayLocale::BrazilianPortuguese, "segunda-feira, quarta-feira, sexta-feira", MON_WED_FRI)]
+    #[case::portuguese_short(WeekdayLocale::BrazilianPortuguese, "seg, qua, sex", MON_WED_FRI)]
+    #[case::portuguese_accents(WeekdayLocale::BrazilianPortuguese, "Terça, terca feira, SÁBADO, sab.", WeekdayFlags::TUE | WeekdayFlags::SAT)]
+    #[case::portuguese_weekend(WeekdayLocale::BrazilianPortuguese, "sáb, dom", WeekdayFlags::WEEKEND)]
+    #[case::german_full(WeekdayLocale::German, "Montag, Mittwoch, Freitag", MON_WED_FRI)]
+    #[case::german_short(WeekdayLocale::German, "Mo, Mi, Fr", MON_WED_FRI)]
+    #[case::german_weekend(WeekdayLocale::German, "Sa., So.", WeekdayFlags::WEEKEND)]
+    #[case::german_sonnabend(WeekdayLocale::German, "Sonnabend", WeekdayFlags::SAT)]
+    #[case::spanish_full(WeekdayLocale::Spanish, "lunes, miércoles, viernes", MON_WED_FRI)]
+    #[case::spanish_short(WeekdayLocale::Spanish, "lun, mié, vie", MON_WED_FRI)]
+    #[case::spanish_letters(WeekdayLocale::Spanish, "L, X, V", MON_WED_FRI)]
+    #[case::spanish_tuesday_letter(WeekdayLocale::Spanish, "M, J", WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::spanish_unaccented(WeekdayLocale::Spanish, "miercoles, sabado", WeekdayFlags::WED | WeekdayFlags::SAT)]
+    #[case::repeats(WeekdayLocale::German, "Mo, Montag", WeekdayFlags::MON)]
+    #[case::empty(WeekdayLocale::English, " ", WeekdayFlags::empty())]
+    fn parses_localized_names(#[case] locale: WeekdayLocale, #[case] value: &str, #[case] expected: WeekdayFlags) {
+        assert_eq!(Ok(expected), WeekdayFlags::parse_localized(value, locale));
+    }
+
+    #[rstest]
+    #[case::english_s(WeekdayLocale::English, "mon, s", "s", 1, WeekdayFlags::WEEKEND)]
+    #[case::english_t(WeekdayLocale::English, "T", "T", 0, WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::portuguese_qu(WeekdayLocale::BrazilianPortuguese, "qu", "qu", 0, WeekdayFlags::WED | WeekdayFlags::THU)]
+    #[case::portuguese_s(WeekdayLocale::BrazilianPortuguese, "dom, s", "s", 1, WeekdayFlags::MON | WeekdayFlags::FRI | WeekdayFlags::SAT)]
+    #[case::german_d(WeekdayLocale::German, "D", "D", 0, WeekdayFlags::TUE | WeekdayFlags::THU)]
+    #[case::german_sonn(WeekdayLocale::German, "Mo, Sonn", "Sonn", 1, WeekdayFlags::WEEKEND)]
+    fn ambiguous_names(#[case] locale: WeekdayLocale, #[case] value: &str, #[case] token: &str, #[case] position: usize, #[case] candidates: WeekdayFlags) {
+        assert_eq!(Err(LocalizedWeekdayError::Ambiguous { token: token.to_string(), position, candidates }), WeekdayFlags::parse_localized(value, locale));
+    }
+
+    #[rstest]
+    #[case::english_in_german(WeekdayLocale::German, "Mo, Wed", "Wed", 1)]
+    #[case::portuguese_unknown(WeekdayLocale::BrazilianPortuguese, "seg,,qua", "", 1)]
+    #[case::spanish_unknown(WeekdayLocale::Spanish, "lunes, friday", "friday", 1)]
+    fn rejects_unknown_names(#[case] locale: WeekdayLocale, #[case] value: &str, #[case] token: &str, #[case] position: usize) {
+        assert_eq!(Err(LocalizedWeekdayError::Unknown { token: token.to_string(), position, locale }), WeekdayFlags::parse_localized(value, locale));
+    }
+
+    #[test]
+    fn every_locale_names_every_weekday() {
+        for locale in [WeekdayLocale::English, WeekdayLocale::BrazilianPortuguese, WeekdayLocale::German, WeekdayLocale::Spanish] {
+            for (names, weekday) in locale.names().iter().zip(WEEKDAYS) {
+                for name in names.iter() {
+                    assert_eq!(Ok(WeekdayFlags::from_weekday(weekday)), WeekdayFlags::parse_localized(name, locale), "{} in {}", name, locale);
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case("en", Some(WeekdayLocale::English))]
+    #[case("en-US", Some(WeekdayLocale::English))]
+    #[case("pt-BR", Some(WeekdayLocale::BrazilianPortuguese))]
+    #[case("pt", Some(WeekdayLocale::BrazilianPortuguese))]
+    #[case("DE_at", Some(WeekdayLocale::German))]
+    #[case("es-MX", Some(WeekdayLocale::Spanish))]
+    #[case("fr", None)]
+    #[case("", None)]
+    fn locales_from_tags(#[case] tag: &str, #[case] expected: Option<WeekdayLocale>) {
+        assert_eq!(expected, WeekdayLocale::from_tag(tag));
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!("`qu` at position 0 could be any of WED,THU", WeekdayFlags::parse_localized("qu", WeekdayLocale::BrazilianPortuguese).unwrap_err().to_string());
+        assert_eq!("`Wed` at position 1 is not a weekday in de", WeekdayFlags::parse_localized("Mo, Wed", WeekdayLocale::German).unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use crate::MissingDay;
+
+/// Ordinal days of the year `1..=366` a yearly schedule falls on, in place of the start's month
+/// and day. Day 366 only exists in leap years; `missing` decides whether other years skip it or
+/// fall on December 31 instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct YearDays {
+    days: BTreeSet<u16>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MissingDay::is_skip"))]
+    missing: MissingDay
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidYearDay {
+    pub value: u16
+}
+
+impl std::fmt::Display for InvalidYearDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid day of year {}, expected 1..=366", self.value)
+    }
+}
+
+impl std::error::Error for InvalidYearDay {}
+
+impl YearDays {
+    /// The given days of every year, e.g. `[100]`; day 366 is skipped outside leap years, see
+    /// `with_missing_days` to clamp it instead.
+    pub fn new(days: impl IntoIterator<Item = u16>) -> Result<YearDays, InvalidYearDay> {
+        let days = days.into_iter()
+            .map(|value| if (1..=366).contains(&value) { Ok(value) } else { Err(InvalidYearDay { value }) })
+            .collect::<Result<BTreeSet<u16>, InvalidYearDay>>()?;
+
+        Ok(YearDays { days, missing: MissingDay::Skip })
+    }
+
+    pub fn with_missing_days(mut self, policy: MissingDay) -> YearDays {
+        self.missing = policy;
+        self
+    }
+
+    pub fn days(&self) -> &BTreeSet<u16> {
+        &self.days
+    }
+
+    pub fn missing(&self) -> MissingDay {
+        self.missing
+    }
+
+    /// The dates the days fall on in `year`, ascending; day 366 and a clamped December 31 occur once.
+    pub(crate) fn dates_in(&self, year: i32) -> Vec<chrono::NaiveDate> {
+        let mut dates: Vec<chrono::NaiveDate> = self.days.iter()
+            .filter_map(|day| match chrono::NaiveDate::from_yo_opt(year, *day as u32) {
+                None if self.missing == MissingDay::Clamp => chrono::NaiveDate::from_ymd_opt(year, 12, 31),
+                date => date,
+            })
+            .collect();
+
+        dates.dedup();
+        dates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RepeatingDateError, Schedule, WeekdayFlags};
+    use rstest::rstest;
+    use crate::test_util::date;
+
+    fn yearly_on(start: &str, interval: u64, year_days: YearDays) -> Schedule {
+        Schedule::builder().start(date(start)).yearly().interval(interval).year_days(year_days).build().unwrap()
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(367)]
+    fn rejects_out_of_range(#[case] value: u16) {
+        assert_eq!(Err(InvalidYearDay { value }), YearDays::new([1, value]));
+    }
+
+    #[test]
+    fn day_100_across_a_leap_year() {
+        let schedule = yearly_on("2023-04-10", 1, YearDays::new([100]).unwrap());
+
+        assert_eq!(["2024-04-09", "2025-04-10"].map(date).to_vec(), schedule.next_n_dates(date("2023-04-10"), 2).unwrap().into_vec());
+        assert_eq!(Ok(date("2024-04-09")), schedule.next_after(date("2023-12-01")));
+        assert!(schedule.matches(date("2024-04-09")));
+        assert!(!schedule.matches(date("2024-04-10")));
+        assert_eq!(Some(date("2024-04-09")), schedule.previous_before(date("2025-04-10")));
+    }
+
+    #[test]
+    fn day_366_skips_or_clamps() {
+        let skipping = yearly_on("2024-12-31", 1, YearDays::new([366]).unwrap());
+        let clamping = yearly_on("2024-12-31", 1, YearDays::new([366]).unwrap().with_missing_days(MissingDay::Clamp));
+
+        assert_eq!(Ok(date("2028-12-31")), skipping.next_after(date("2024-12-31")));
+        assert!(!skipping.matches(date("2025-12-31")));
+        assert_eq!(["2025-12-31", "2026-12-31"].map(date).to_vec(), clamping.next_n_dates(date("2024-12-31"), 2).unwrap().into_vec());
+        assert_eq!(vec![date("2025-12-31")], YearDays::new([365, 366]).unwrap().with_missing_days(MissingDay::Clamp).dates_in(2025));
+    }
+
+    #[test]
+    fn every_other_year_with_a_weekday_filter() {
+        let schedule = Schedule::builder().start(date("2024-04-09")).yearly().interval(2).on(WeekdayFlags::MIDWEEK).year_days(YearDays::new([100]).unwrap()).build().unwrap();
+
+        // Day 100 of 2028 is Sunday, April 9.
+        assert_eq!(["2026-04-10", "2030-04-10"].map(date).to_vec(), schedule.next_n_dates(date("2024-04-09"), 2).unwrap().into_vec());
+        assert!(!schedule.matches(date("2025-04-10")));
+        assert_eq!(Some(2), schedule.occurrence_index(date("2030-04-10")));
+    }
+
+    #[test]
+    fn builder_validates_year_days() {
+        let builder = Schedule::builder().start(date("2024-04-09"));
+
+        assert_eq!(Err(RepeatingDateError::InvalidYearDayRule), builder.clone().monthly().year_days(YearDays::new([100]).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::InvalidYearDayRule), builder.clone().yearly().year_days(YearDays::new([]).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::InvalidYearDayRule), builder.clone().yearly().iso_weeks([15]).year_days(YearDays::new([100]).unwrap()).build());
+        assert_eq!(Err(RepeatingDateError::StartNotInRule), builder.yearly().year_days(YearDays::new([101]).unwrap()).build());
+    }
+}
@@ -0,0 +1,123 @@
+//! Rules defined by a local start datetime in a `chrono_tz::Tz`, evaluated from instants in any
+//! zone. The rule itself runs on local wall-clock time through the datetime functions, so weekdays
+//! and dates are those of the rule's zone.
+
+use chrono::{DateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{find_next_datetime, match_repeating_datetime, Repeating, RepeatingDateError, WeekdayFlags};
+
+/// Longest run of local occurrences that can resolve to instants at or before the bound: one per
+/// reading of an hour repeated by a backward transition.
+const MAX_RESOLVE_SKIP: u32 = 120;
+
+/// The instant a local occurrence happens at: the earlier one when a backward transition repeats
+/// the local time, and for a time a forward transition skips, the time read with the offset in
+/// effect before the transition, so it moves forward by the length of the gap.
+fn resolve(zone: Tz, local: chrono::NaiveDateTime) -> Option<DateTime<Tz>> {
+    match zone.from_local_datetime(&local) {
+        chrono::LocalResult::Single(instant) | chrono::LocalResult::Ambiguous(instant, _) => Some(instant),
+        chrono::LocalResult::None => {
+            let before = zone.offset_from_utc_datetime(&local.checked_sub_days(chrono::Days::new(1))?).fix();
+
+            local.checked_sub_offset(before).map(|utc| zone.from_utc_datetime(&utc))
+        },
+    }
+}
+
+/// The first occurrence after `from` of a rule starting at the local `start_datetime` in `zone`,
+/// as an instant in `zone`; `.with_timezone(&chrono::Utc)` gives it in UTC. `from` is read as a
+/// local time in `zone` and the rule is evaluated as by `find_next_datetime`, so "every Monday"
+/// means Monday in `zone` whatever day it is in UTC.
+pub fn find_next_in_zone<Z: TimeZone>(from: &DateTime<Z>, start_datetime: chrono::NaiveDateTime, zone: Tz, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> Result<DateTime<Tz>, RepeatingDateError> {
+    let from = from.with_timezone(&zone);
+    let mut local = from.naive_local();
+
+    for _ in 0..MAX_RESOLVE_SKIP {
+        local = find_next_datetime(local, start_datetime, weekdays, repeat, interval)?;
+
+        let instant = resolve(zone, local).ok_or(RepeatingDateError::DateOutOfRange)?;
+
+        if instant > from {
+            return Ok(instant);
+        }
+    }
+
+    Err(RepeatingDateError::NoOccurrenceFound)
+}
+
+/// Whether `instant` is an occurrence of the rule starting at the local `start_datetime` in
+/// `zone`, as `find_next_in_zone` resolves them: only the earlier reading of a repeated local time
+/// matches, and a time moved out of a gap matches at the instant it was moved to.
+pub fn match_repeating_in_zone<Z: TimeZone>(instant: &DateTime<Z>, start_datetime: chrono::NaiveDateTime, zone: Tz, weekdays: WeekdayFlags, repeat: Repeating, interval: u64) -> bool {
+    let instant = instant.with_timezone(&zone);
+    let utc = instant.naive_utc();
+    let offset_before = utc.checked_sub_days(chrono::Days::new(1)).map(|earlier| zone.offset_from_utc_datetime(&earlier).fix());
+
+    [Some(instant.offset().fix()), offset_before].into_iter().flatten()
+        .filter_map(|offset| utc.checked_add_offset(offset))
+        .any(|local| match_repeating_datetime(local, start_datetime, weekdays, repeat, interval) && resolve(zone, local) == Some(instant))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use chrono::Utc;
+    use chrono_tz::America::Sao_Paulo;
+    use rstest::rstest;
+
+    fn datetime(value: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_str(value).unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        datetime(value).and_utc()
+    }
+
+    #[rstest]
+    // Monday 21:30 in São Paulo is already Tuesday in UTC.
+    #[case::before_the_time("2024-01-09T00:30:00", "2024-01-09T01:00:00")]
+    #[case::at_the_time("2024-01-09T01:00:00", "2024-01-16T01:00:00")]
+    // Monday 23:00 UTC is Monday 20:00 locally, before the occurrence.
+    #[case::utc_monday_evening("2024-01-08T23:00:00", "2024-01-09T01:00:00")]
+    fn mondays_are_local_mondays(#[case] from: &str, #[case] expected: &str) {
+        let next = find_next_in_zone(&utc(from), datetime("2024-01-01T22:00:00"), Sao_Paulo, WeekdayFlags::MON, Repeating::Weekly, 1).unwrap();
+
+        assert_eq!(utc(expected), next.with_timezone(&Utc));
+        assert_eq!(chrono::Weekday::Mon, chrono::Datelike::weekday(&next.naive_local()));
+        assert!(match_repeating_in_zone(&next, datetime("2024-01-01T22:00:00"), Sao_Paulo, WeekdayFlags::MON, Repeating::Weekly, 1));
+    }
+
+    #[test]
+    fn midnight_skipped_by_the_forward_transition() {
+        // On 2018-11-04 São Paulo clocks jumped from 00:00 to 01:00.
+        let start = datetime("2018-11-01T00:30:00");
+        let next = find_next_in_zone(&utc("2018-11-03T03:30:00"), start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1).unwrap();
+
+        assert_eq!(datetime("2018-11-04T01:30:00"), next.naive_local());
+        assert_eq!(utc("2018-11-04T03:30:00"), next.with_timezone(&Utc));
+        assert!(match_repeating_in_zone(&next, start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1));
+        assert_eq!(utc("2018-11-05T02:30:00"), find_next_in_zone(&next, start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1).unwrap().with_timezone(&Utc));
+    }
+
+    #[test]
+    fn hour_repeated_by_the_backward_transition() {
+        // On 2019-02-17 São Paulo clocks went back from 00:00 to 23:00 of February 16.
+        let start = datetime("2019-02-10T23:30:00");
+        let first = find_next_in_zone(&utc("2019-02-16T12:00:00"), start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1).unwrap();
+
+        assert_eq!(utc("2019-02-17T01:30:00"), first.with_timezone(&Utc));
+        assert_eq!(utc("2019-02-18T02:30:00"), find_next_in_zone(&first, start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1).unwrap().with_timezone(&Utc));
+        assert!(match_repeating_in_zone(&first, start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1));
+        assert!(!match_repeating_in_zone(&utc("2019-02-17T02:30:00"), start, Sao_Paulo, WeekdayFlags::ANY, Repeating::Daily, 1));
+    }
+
+    #[test]
+    fn rejects_a_bound_before_the_start() {
+        let start = datetime("2024-01-01T22:00:00");
+
+        assert_eq!(Err(RepeatingDateError::StartDateBeforeBound { from: start.date(), start: start.date() }), find_next_in_zone(&utc("2024-01-02T00:59:00"), datetime("2024-01-01T22:00:00"), Sao_Paulo, WeekdayFlags::MON, Repeating::Weekly, 1));
+    }
+}
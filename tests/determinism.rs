@@ -0,0 +1,82 @@
+// Expansions of a fixed set of rules, compared byte for byte against a checked-in fixture.
+// The same fixture is checked on every CI target, so any platform or dependency dependent
+// behavior shows up as a diff. Run with `UPDATE_FIXTURES=1` to regenerate after an
+// intentional behavior change.
+
+use core::str::FromStr;
+
+use recurring_dates_rs::{find_next_date, match_repeating_date, Repeating, WeekdayFlags};
+
+const FIXTURE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/determinism.txt");
+const OCCURRENCES: usize = 24;
+
+const RULES: [(Repeating, u64, WeekdayFlags, &str); 14] = [
+    (Repeating::Daily, 1, WeekdayFlags::MIDWEEK, "2023-09-18"),
+    (Repeating::Daily, 2, WeekdayFlags::TUE.union(WeekdayFlags::THU), "2023-09-12"),
+    (Repeating::Daily, 3, WeekdayFlags::ANY, "2024-02-27"),
+    (Repeating::Weekly, 1, WeekdayFlags::MON.union(WeekdayFlags::WED).union(WeekdayFlags::FRI), "2023-10-02"),
+    (Repeating::Weekly, 2, WeekdayFlags::TUE.union(WeekdayFlags::THU), "2023-09-12"),
+    (Repeating::Weekly, 2, WeekdayFlags::ANY, "2023-09-13"),
+    (Repeating::Weekly, 3, WeekdayFlags::MON.union(WeekdayFlags::SAT).union(WeekdayFlags::SUN), "2023-10-14"),
+    (Repeating::Weekly, 1, WeekdayFlags::SUN, "1799-12-29"),
+    (Repeating::Monthly, 1, WeekdayFlags::ANY, "2024-01-31"),
+    (Repeating::Monthly, 2, WeekdayFlags::TUE.union(WeekdayFlags::THU), "2023-09-12"),
+    (Repeating::Monthly, 1, WeekdayFlags::ANY, "1850-01-31"),
+    (Repeating::Yearly, 1, WeekdayFlags::ANY, "1896-02-29"),
+    (Repeating::Yearly, 2, WeekdayFlags::ANY, "2023-09-12"),
+    (Repeating::Yearly, 1, WeekdayFlags::MIDWEEK, "2023-09-12"),
+];
+
+fn repeat_name(repeat: Repeating) -> String {
+    match repeat {
+        Repeating::Daily => "daily".to_string(),
+        Repeating::Weekly => "weekly".to_string(),
+        Repeating::Monthly => "monthly".to_string(),
+        Repeating::Quarterly => "quarterly".to_string(),
+        Repeating::Yearly => "yearly".to_string(),
+        Repeating::Hourly => "hourly".to_string(),
+        Repeating::Minutely => "minutely".to_string(),
+        // `Repeating` is non-exhaustive; a variant added later is named after its `Debug` form
+        // until it gets an entry above.
+        repeat => format!("{:?}", repeat).to_lowercase(),
+    }
+}
+
+fn expand_rules() -> String {
+    let mut output = String::new();
+
+    for (repeat, interval, weekdays, start) in RULES {
+        let start_date = chrono::NaiveDate::from_str(start).unwrap();
+
+        output.push_str(&format!("{} interval={} weekdays={:#04x} start={}:", repeat_name(repeat), interval, weekdays.bits(), start_date));
+
+        let mut date = start_date;
+        for _ in 0..OCCURRENCES {
+            date = find_next_date(date, start_date, weekdays, repeat, interval).unwrap();
+            assert!(match_repeating_date(date, start_date, weekdays, repeat, interval));
+
+            output.push_str(&format!(" {}", date));
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+#[test]
+fn expansions_match_fixture() {
+    let output = expand_rules();
+
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        std::fs::write(FIXTURE_PATH, &output).unwrap();
+    }
+
+    let expected = std::fs::read_to_string(FIXTURE_PATH).unwrap();
+    assert_eq!(expected, output);
+}
+
+#[test]
+fn expansions_are_repeatable() {
+    assert_eq!(expand_rules(), expand_rules());
+}
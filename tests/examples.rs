@@ -0,0 +1,33 @@
+// The examples end with assertions, so running them here turns any behavior
+// regression into a test failure instead of a silently wrong printout. They need the features
+// their Cargo.toml entries require.
+
+#[cfg(feature = "serde")]
+#[path = "../examples/billing.rs"]
+mod billing;
+
+#[cfg(feature = "rfc5545")]
+#[path = "../examples/calendar_export.rs"]
+mod calendar_export;
+
+#[cfg(feature = "serde")]
+#[path = "../examples/habits.rs"]
+mod habits;
+
+#[cfg(feature = "serde")]
+#[test]
+fn billing_example_runs() {
+    billing::main();
+}
+
+#[cfg(feature = "rfc5545")]
+#[test]
+fn calendar_export_example_runs() {
+    calendar_export::main();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn habits_example_runs() {
+    habits::main();
+}